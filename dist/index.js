@@ -75,6 +75,9 @@ var normalizeItem = (compact) => {
     method: raw.method ?? "",
     partialAddr: raw.partialAddr ?? "",
     ...raw.formArg === undefined ? {} : { formArg: raw.formArg },
+    ...raw.handlers === undefined ? {} : { handlers: raw.handlers },
+    ...raw.keyCombo === undefined ? {} : { keyCombo: raw.keyCombo },
+    ...raw.preventDefault === undefined ? {} : { preventDefault: raw.preventDefault },
     payload: normalizePayload(raw.payload ?? { type: "none" })
   };
 };
@@ -264,6 +267,157 @@ var mountCustomComponent = (element, item, payload, ctx) => {
   });
 };
 
+// ts/devtools.ts
+var panel;
+var treeView;
+var eventsView;
+var batchesView;
+var itemChildren = (item) => {
+  const payload = item.payload;
+  switch (payload.type) {
+    case "layout":
+    case "form":
+    case "modal":
+    case "connectionStatus":
+      return payload.body;
+    case "table":
+    case "thead":
+    case "tbody":
+    case "tr":
+      return payload.items;
+    case "th":
+    case "td":
+      return [payload.item];
+    default:
+      return [];
+  }
+};
+var itemLabel = (item) => {
+  const payload = item.payload;
+  let label = payload.type;
+  if (item.id) {
+    label += ` #${item.id}`;
+  }
+  if (item.inx) {
+    label += `[${item.inx}]`;
+  }
+  if (payload.type === "text") {
+    label += ` "${payload.value}"`;
+  }
+  return label;
+};
+var formatTree = (item, depth, lines) => {
+  lines.push(`${"  ".repeat(depth)}${itemLabel(item)}`);
+  for (const child of itemChildren(item)) {
+    formatTree(child, depth + 1, lines);
+  }
+};
+var formatEvent = (event) => {
+  const actions = event.actions.length > 0 ? ` -> ${event.actions.join(", ")}` : "";
+  return `${event.atMs}ms  ${event.kind}  ${event.bytes}B${actions}`;
+};
+var formatBatch = (batch) => {
+  const elapsed = batch.elapsedMs != null ? `  ${batch.elapsedMs.toFixed(2)}ms` : "";
+  return `${batch.atMs}ms  ${batch.kinds.length} action(s)  ${batch.bytes}B${elapsed}  ${batch.kinds.join(", ")}`;
+};
+var section = (parent, title) => {
+  const details = document.createElement("details");
+  details.open = true;
+  const summary = document.createElement("summary");
+  summary.textContent = title;
+  summary.style.cursor = "pointer";
+  const body = document.createElement("pre");
+  body.style.margin = "4px 0 8px 0";
+  body.style.whiteSpace = "pre";
+  details.append(summary, body);
+  parent.appendChild(details);
+  return body;
+};
+var ensurePanel = () => {
+  if (panel) {
+    return;
+  }
+  panel = document.createElement("details");
+  panel.dataset.wguiDevtools = "true";
+  const style = panel.style;
+  style.position = "fixed";
+  style.right = "8px";
+  style.bottom = "8px";
+  style.maxWidth = "480px";
+  style.maxHeight = "60vh";
+  style.overflow = "auto";
+  style.padding = "6px 10px";
+  style.background = "rgba(20, 20, 24, 0.92)";
+  style.color = "#e6e6e6";
+  style.font = "11px/1.4 monospace";
+  style.borderRadius = "6px";
+  style.zIndex = "2147483647";
+  const summary = document.createElement("summary");
+  summary.textContent = "wgui devtools";
+  summary.style.cursor = "pointer";
+  panel.appendChild(summary);
+  treeView = section(panel, "Item tree");
+  eventsView = section(panel, "Client events");
+  batchesView = section(panel, "Action batches");
+  document.documentElement.appendChild(panel);
+};
+var updateDevtools = (message) => {
+  ensurePanel();
+  if (message.tree && treeView) {
+    const lines = [];
+    formatTree(normalizeItem(message.tree), 0, lines);
+    treeView.textContent = lines.join("\n");
+  }
+  if (eventsView) {
+    eventsView.textContent = message.events.slice().reverse().map(formatEvent).join("\n");
+  }
+  if (batchesView) {
+    batchesView.textContent = message.batches.slice().reverse().map(formatBatch).join("\n");
+  }
+};
+
+// ts/diagnostics_overlay.ts
+var overlay;
+var showTemplateDiagnostics = (message) => {
+  if (message.diagnostics.length === 0) {
+    overlay?.remove();
+    overlay = undefined;
+    return;
+  }
+  if (!overlay) {
+    overlay = document.createElement("div");
+    overlay.dataset.wguiDiagnostics = "true";
+    const style = overlay.style;
+    style.position = "fixed";
+    style.inset = "0";
+    style.overflow = "auto";
+    style.padding = "24px";
+    style.background = "rgba(24, 0, 0, 0.88)";
+    style.color = "#ffd7d7";
+    style.font = "13px/1.5 monospace";
+    style.whiteSpace = "pre-wrap";
+    style.zIndex = "2147483646";
+    document.documentElement.appendChild(overlay);
+  }
+  overlay.textContent = [
+    "WUI template failed to parse",
+    "",
+    ...message.diagnostics.map((diag) => `${diag.file}:${diag.line}:${diag.column} [${diag.module}] ${diag.message}`)
+  ].join("\n");
+};
+var reloadAssets = (message) => {
+  if (!message.stylesheetsOnly) {
+    location.reload();
+    return;
+  }
+  const links = document.querySelectorAll("link[rel=stylesheet]");
+  for (const link of links) {
+    const url = new URL(link.href);
+    url.searchParams.set("wgui-reload", String(Date.now()));
+    link.href = url.toString();
+  }
+};
+
 // ts/path.ts
 var getPathItem = (path, element) => {
   const p = path[0];
@@ -277,6 +431,110 @@ var getPathItem = (path, element) => {
   return getPathItem(path.slice(1), child);
 };
 
+// ts/keys.ts
+var modifierNames = {
+  ctrl: "ctrl",
+  control: "ctrl",
+  alt: "alt",
+  option: "alt",
+  shift: "shift",
+  meta: "meta",
+  cmd: "meta",
+  command: "meta"
+};
+var keyAliases = {
+  esc: "escape",
+  up: "arrowup",
+  down: "arrowdown",
+  left: "arrowleft",
+  right: "arrowright",
+  del: "delete"
+};
+var parseKeyCombo = (spec) => {
+  const combo = { ctrl: false, alt: false, shift: false, meta: false, key: "" };
+  for (const raw of spec.split("+")) {
+    const part = raw.trim().toLowerCase();
+    const modifier = modifierNames[part];
+    if (modifier) {
+      combo[modifier] = true;
+      continue;
+    }
+    if (!part || combo.key) {
+      return;
+    }
+    combo.key = keyAliases[part] ?? part;
+  }
+  return combo.key ? combo : undefined;
+};
+var matchesKeyCombo = (combo, event) => {
+  if (combo.ctrl !== event.ctrlKey || combo.alt !== event.altKey || combo.shift !== event.shiftKey || combo.meta !== event.metaKey) {
+    return false;
+  }
+  const code = event.code.toLowerCase();
+  const key = event.key.toLowerCase();
+  if (combo.key.length === 1) {
+    return code === `key${combo.key}` || code === `digit${combo.key}` || code === `numpad${combo.key}` || key === combo.key;
+  }
+  if (combo.key === "space") {
+    return code === "space" || key === " ";
+  }
+  if (combo.key === "enter") {
+    return code === "enter" || code === "numpadenter";
+  }
+  return code === combo.key || key === combo.key;
+};
+var itemMatchesKey = (item, event) => {
+  if (!item.keyCombo) {
+    return true;
+  }
+  const combo = parseKeyCombo(item.keyCombo);
+  return !!combo && matchesKeyCombo(combo, event);
+};
+var handledKeyEvents = new WeakSet;
+var sendKeyEvent = (ctx, type, handler, event) => {
+  ctx.sender.send({
+    type: type === "keyDown" ? "onKeyDown" : "onKeyUp",
+    id: String(handler.id),
+    keycode: event.code || event.key,
+    inx: handler.inx,
+    ctrl: event.ctrlKey,
+    alt: event.altKey,
+    shift: event.shiftKey,
+    meta: event.metaKey
+  });
+  ctx.sender.sendNow();
+};
+var handleItemKey = (item, type, event, ctx) => {
+  const handler = item.handlers?.[type];
+  if (!handler || event.repeat || handledKeyEvents.has(event) || !itemMatchesKey(item, event)) {
+    return false;
+  }
+  handledKeyEvents.add(event);
+  if (item.preventDefault) {
+    event.preventDefault();
+  }
+  sendKeyEvent(ctx, type, handler, event);
+  return true;
+};
+var shortcuts = new Map;
+var registerShortcut = (element, item, ctx) => {
+  shortcuts.set(element, { item, ctx });
+};
+var dispatchShortcuts = (event, type, typing) => {
+  for (const [element, { item, ctx }] of shortcuts) {
+    if (!element.isConnected) {
+      shortcuts.delete(element);
+      continue;
+    }
+    if (typing && !event.ctrlKey && !event.altKey && !event.metaKey) {
+      continue;
+    }
+    if (handleItemKey(item, type, event, ctx)) {
+      return;
+    }
+  }
+};
+
 // ts/render.ts
 var pendingFormSubmissions = new Map;
 var latestFormSubmissionIds = new WeakMap;
@@ -429,7 +687,12 @@ var hasFileDragPayload = (event) => {
   return false;
 };
 var buttonHoldStates = new WeakMap;
+var handlerInx = (item, event) => item.handlers?.[event]?.inx ?? item.inx ?? undefined;
 var buttonEventId = (item, events, name) => {
+  const handler = item.handlers?.[name];
+  if (handler) {
+    return handler.id;
+  }
   if (events && typeof events[name] === "number") {
     return events[name];
   }
@@ -442,10 +705,11 @@ var sendButtonEvent = (type, id, item, ctx) => {
   if (!id) {
     return;
   }
+  const event = type.charAt(2).toLowerCase() + type.slice(3);
   ctx.sender.send({
     type,
     id,
-    inx: item.inx ?? undefined
+    inx: handlerInx(item, event)
   });
   ctx.sender.sendNow();
 };
@@ -519,14 +783,15 @@ var configureButtonEvents = (button, item, events, ctx) => {
 };
 var bindAutoClick = (element, item, ctx) => {
   const autoKey = "1";
-  if (item.id) {
+  const id = item.handlers?.click?.id ?? item.id;
+  if (id) {
     if (!element.onclick) {
       element.dataset.wguiAutoClick = autoKey;
       element.onclick = () => {
         ctx.sender.send({
           type: "onClick",
-          id: item.id,
-          inx: item.inx
+          id,
+          inx: handlerInx(item, "click")
         });
         ctx.sender.sendNow();
       };
@@ -538,11 +803,35 @@ var bindAutoClick = (element, item, ctx) => {
     delete element.dataset.wguiAutoClick;
   }
 };
+var bindHandlers = (element, item, ctx) => {
+  const send = (type, event) => {
+    const handler = item.handlers?.[event];
+    if (!handler) {
+      return null;
+    }
+    return () => {
+      ctx.sender.send({ type, id: handler.id, inx: handler.inx });
+      ctx.sender.sendNow();
+    };
+  };
+  if (!(element instanceof HTMLButtonElement)) {
+    element.onpointerdown = send("onPress", "press");
+    element.onpointerup = send("onRelease", "release");
+  }
+  const focusable = item.payload.type !== "shortcut";
+  const keyDown = focusable && item.handlers?.keyDown;
+  const keyUp = focusable && item.handlers?.keyUp;
+  element.onkeydown = keyDown ? (event) => handleItemKey(item, "keyDown", event, ctx) : null;
+  element.onkeyup = keyUp ? (event) => handleItemKey(item, "keyUp", event, ctx) : null;
+  if ((keyDown || keyUp) && element.tabIndex < 0) {
+    element.tabIndex = 0;
+  }
+};
 var layoutScrollStates = new WeakMap;
 var scrollNearBottomThreshold = 240;
 var scrollNearBottomThrottleMs = 250;
 var configureLayoutEvents = (element, item, payload, ctx) => {
-  const id = payload.events?.scrollNearBottom;
+  const id = item.handlers?.scrollNearBottom?.id ?? payload.events?.scrollNearBottom;
   if (!id) {
     element.onscroll = null;
     layoutScrollStates.delete(element);
@@ -574,7 +863,7 @@ var configureLayoutEvents = (element, item, payload, ctx) => {
     ctx.sender.send({
       type: "onScrollNearBottom",
       id,
-      inx: item.inx ?? undefined
+      inx: handlerInx(item, "scrollNearBottom")
     });
     ctx.sender.sendNow();
   };
@@ -635,7 +924,8 @@ var navigateLink = (event, anchor, ctx) => {
   ctx.sender.send({
     type: "pathChanged",
     path: location.pathname,
-    query: pathQuery(location.search)
+    query: pathQuery(location.search),
+    queryString: location.search.slice(1)
   });
   ctx.sender.sendNow();
 };
@@ -658,7 +948,7 @@ var renderPayload = (item, ctx, old) => {
         ctx.sender.send({
           type: "onClick",
           id: item.id,
-          inx: item.inx
+          inx: handlerInx(item, "click")
         });
         ctx.sender.sendNow();
       };
@@ -1203,7 +1493,7 @@ var renderPayload = (item, ctx, old) => {
         ctx.sender.send({
           type: "onClick",
           id: item.id,
-          inx: item.inx
+          inx: handlerInx(item, "click")
         });
         ctx.sender.sendNow();
       };
@@ -1228,6 +1518,19 @@ var renderPayload = (item, ctx, old) => {
     element.dataset.wguiId = item.id ? item.id.toString() : "";
     return element;
   }
+  if (payload.type === "shortcut") {
+    let element;
+    if (old instanceof HTMLSpanElement && old.hidden) {
+      element = old;
+    } else {
+      element = document.createElement("span");
+      if (old)
+        old.replaceWith(element);
+    }
+    element.hidden = true;
+    registerShortcut(element, item, ctx);
+    return element;
+  }
   if (payload.type === "modal") {
     let overlay;
     if (old instanceof HTMLDivElement && old.dataset.modal === "overlay") {
@@ -1262,7 +1565,7 @@ var renderPayload = (item, ctx, old) => {
           ctx.sender.send({
             type: "onClick",
             id: item.id,
-            inx: item.inx
+            inx: handlerInx(item, "click")
           });
           ctx.sender.sendNow();
         }
@@ -1423,6 +1726,7 @@ var renderItem = (item, ctx, old) => {
   if (!(element instanceof HTMLInputElement) && !(element instanceof HTMLSelectElement) && !(element instanceof HTMLTextAreaElement)) {
     bindAutoClick(element, item, ctx);
   }
+  bindHandlers(element, item, ctx);
   return element;
 };
 
@@ -2290,10 +2594,15 @@ window.onload = () => {
             history.pushState({}, "", message.url);
           }
           clearModalOverlays(res);
+          const query = {};
+          new URLSearchParams(location.search).forEach((value, key) => {
+            query[key] = value;
+          });
           sender2.send({
             type: "pathChanged",
             path: location.pathname,
-            query: {}
+            query,
+            queryString: location.search.slice(1)
           });
           sender2.sendNow();
           continue;
@@ -2314,7 +2623,7 @@ window.onload = () => {
               params.set(key, value);
             }
           }
-          history.replaceState({}, "", `${params.toString()}`);
+          history.replaceState({}, "", `?${params.toString()}`);
           continue;
         }
         if (message.type === "setTitle") {
@@ -2337,6 +2646,18 @@ window.onload = () => {
           });
           continue;
         }
+        if (message.type === "devtools") {
+          updateDevtools(message);
+          continue;
+        }
+        if (message.type === "templateDiagnostics") {
+          showTemplateDiagnostics(message);
+          continue;
+        }
+        if (message.type === "reloadAssets") {
+          reloadAssets(message);
+          continue;
+        }
         if (message.type === "customData") {
           sendCustomData(res, message.id, message.inx, message.name, message.payload);
           continue;
@@ -2406,6 +2727,7 @@ window.onload = () => {
         type: "pathChanged",
         path: location.pathname,
         query,
+        queryString: location.search.slice(1),
         ssrHydrationId
       });
       if (initialRoot) {
@@ -2423,6 +2745,7 @@ window.onload = () => {
     onConnectionChange: setConnectionStatus
   });
   window.addEventListener("keydown", (event) => {
+    dispatchShortcuts(event, "keyDown", shouldIgnoreKeyboardEvent(event));
     if (event.repeat || shouldIgnoreKeyboardEvent(event)) {
       return;
     }
@@ -2433,11 +2756,16 @@ window.onload = () => {
     activeKeyboardKeys.add(keycode);
     sender.send({
       type: "onKeyDown",
-      keycode
+      keycode,
+      ctrl: event.ctrlKey,
+      alt: event.altKey,
+      shift: event.shiftKey,
+      meta: event.metaKey
     });
     sender.sendNow();
   });
   window.addEventListener("keyup", (event) => {
+    dispatchShortcuts(event, "keyUp", shouldIgnoreKeyboardEvent(event));
     const keycode = event.code || event.key;
     if (shouldIgnoreKeyboardEvent(event) && !activeKeyboardKeys.has(keycode)) {
       return;
@@ -2448,7 +2776,11 @@ window.onload = () => {
     activeKeyboardKeys.delete(keycode);
     sender.send({
       type: "onKeyUp",
-      keycode
+      keycode,
+      ctrl: event.ctrlKey,
+      alt: event.altKey,
+      shift: event.shiftKey,
+      meta: event.metaKey
     });
     sender.sendNow();
   });
@@ -2475,7 +2807,8 @@ window.onload = () => {
     sender.send({
       type: "pathChanged",
       path: location.pathname,
-      query
+      query,
+      queryString: location.search.slice(1)
     });
     sender.sendNow();
   });
@@ -147,10 +147,8 @@ async fn main() {
 					state.date_value = t.value;
 				}
 			}
-			ClientEvent::OnSliderChange(s) => {
-				if s.id == SLIDER {
-					state.slider_value = s.value;
-				}
+			ClientEvent::OnSliderChange(s) if s.id == SLIDER => {
+				state.slider_value = s.value;
 			}
 			ClientEvent::OnSelect(o) if o.id == SELECT => {
 				state.option = o.value;
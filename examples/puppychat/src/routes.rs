@@ -301,16 +301,15 @@ pub async fn login(ctx: &Ctx<SharedContext, PuppyDb>, form: LoginForm) -> RouteR
 
 #[route("/logout", method = "POST")]
 pub async fn logout(ctx: &Ctx<SharedContext, PuppyDb>) -> RouteResult {
-	if let Some(session_key) = ctx.session_id() {
-		if let Some(session) = ctx
+	if let Some(session_key) = ctx.session_id()
+		&& let Some(session) = ctx
 			.db()
 			.sessions
 			.snapshot()
 			.into_iter()
 			.find(|session| session.session_key == session_key)
-		{
-			ctx.db().sessions.delete(session.id).await;
-		}
+	{
+		ctx.db().sessions.delete(session.id).await;
 	}
 	update_session(ctx, |session| {
 		session.user_name.clear();
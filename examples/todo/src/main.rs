@@ -101,11 +101,9 @@ async fn main() {
 				}
 				_ => {}
 			},
-			ClientEvent::OnTextChanged(t) => {
-				if t.id == NEW_TODO_TEXT_ID {
-					log::info!("new_todo_name {:?}", t.value);
-					state.new_todo_name = t.value;
-				}
+			ClientEvent::OnTextChanged(t) if t.id == NEW_TODO_TEXT_ID => {
+				log::info!("new_todo_name {:?}", t.value);
+				state.new_todo_name = t.value;
 			}
 			ClientEvent::OnSliderChange(s) => {
 				state.slider_value = s.value;
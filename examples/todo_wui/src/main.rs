@@ -31,5 +31,8 @@ async fn main() {
 	wgui.add_route(routes::create_todo_route);
 	wgui.add_route(routes::toggle_todo_route);
 	wgui.add_route(routes::page_not_found_route);
+	if let Err(err) = wgui.enable_hot_reload(concat!(env!("CARGO_MANIFEST_DIR"), "/wui")) {
+		log::warn!("template hot reload disabled: {err}");
+	}
 	wgui.run().await;
 }
@@ -1,6 +1,9 @@
 import { Deboncer } from "./debouncer.ts";
 import { normalizeItem, normalizeServerMessage } from "./compact_item.ts";
 import { disposeCustomComponentTree, sendCustomData } from "./custom_components.ts";
+import { updateDevtools } from "./devtools.ts";
+import { reloadAssets, showTemplateDiagnostics } from "./diagnostics_overlay.ts";
+import { dispatchShortcuts } from "./keys.ts";
 import { getPathItem } from "./path.ts";
 import { formSubmissionSucceeded, renderItem, setConnectionStatus } from "./render.ts";
 import { Context, Item, PropValue, SetPropSet, SrvMessage } from "./types.ts";
@@ -244,10 +247,15 @@ window.onload = () => {
                     }
                     clearModalOverlays(res)
 
+                    const query: { [key: string]: string } = {}
+                    new URLSearchParams(location.search).forEach((value, key) => {
+                        query[key] = value
+                    })
                     sender.send({
                         type: "pathChanged",
                         path: location.pathname,
-                        query: {}
+                        query,
+                        queryString: location.search.slice(1),
                     })
                     sender.sendNow()
 
@@ -273,7 +281,7 @@ window.onload = () => {
                             params.set(key, value)
                         }
                     }
-                    history.replaceState({}, "", `${params.toString()}`)
+                    history.replaceState({}, "", `?${params.toString()}`)
                     continue   
                 }
 
@@ -301,6 +309,21 @@ window.onload = () => {
 					continue
 				}
 
+				if (message.type === "devtools") {
+					updateDevtools(message)
+					continue
+				}
+
+				if (message.type === "templateDiagnostics") {
+					showTemplateDiagnostics(message)
+					continue
+				}
+
+				if (message.type === "reloadAssets") {
+					reloadAssets(message)
+					continue
+				}
+
 				if (message.type === "customData") {
 					sendCustomData(res, message.id, message.inx, message.name, message.payload)
 					continue
@@ -388,6 +411,7 @@ window.onload = () => {
                 type: "pathChanged",
                 path: location.pathname,
                 query: query,
+                queryString: location.search.slice(1),
 				ssrHydrationId,
             })
 			if (initialRoot) {
@@ -407,6 +431,7 @@ window.onload = () => {
     })
 
     window.addEventListener("keydown", (event) => {
+        dispatchShortcuts(event, "keyDown", shouldIgnoreKeyboardEvent(event))
         if (event.repeat || shouldIgnoreKeyboardEvent(event)) {
             return
         }
@@ -418,11 +443,16 @@ window.onload = () => {
         sender.send({
             type: "onKeyDown",
             keycode,
+            ctrl: event.ctrlKey,
+            alt: event.altKey,
+            shift: event.shiftKey,
+            meta: event.metaKey,
         })
         sender.sendNow()
     })
 
     window.addEventListener("keyup", (event) => {
+        dispatchShortcuts(event, "keyUp", shouldIgnoreKeyboardEvent(event))
         const keycode = event.code || event.key
         if (shouldIgnoreKeyboardEvent(event) && !activeKeyboardKeys.has(keycode)) {
             return
@@ -434,6 +464,10 @@ window.onload = () => {
         sender.send({
             type: "onKeyUp",
             keycode,
+            ctrl: event.ctrlKey,
+            alt: event.altKey,
+            shift: event.shiftKey,
+            meta: event.metaKey,
         })
         sender.sendNow()
     })
@@ -463,6 +497,7 @@ window.onload = () => {
             type: "pathChanged",
             path: location.pathname,
             query,
+            queryString: location.search.slice(1),
         })
 
         sender.sendNow()
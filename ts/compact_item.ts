@@ -43,6 +43,9 @@ export const normalizeItem = (compact: Item): Item => {
 		method: raw.method ?? "",
 		partialAddr: raw.partialAddr ?? "",
 		...(raw.formArg === undefined ? {} : { formArg: raw.formArg }),
+		...(raw.handlers === undefined ? {} : { handlers: raw.handlers }),
+		...(raw.keyCombo === undefined ? {} : { keyCombo: raw.keyCombo }),
+		...(raw.preventDefault === undefined ? {} : { preventDefault: raw.preventDefault }),
 		payload: normalizePayload(raw.payload ?? { type: "none" }),
 	}
 }
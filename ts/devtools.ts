@@ -0,0 +1,122 @@
+import { normalizeItem } from "./compact_item.ts";
+import { Devtools, DevtoolsBatch, DevtoolsEvent, Item } from "./types.ts";
+
+// The server only sends devtools frames when dev mode is enabled, so the panel
+// is created lazily on the first one. It lives on <html> rather than <body>
+// because the app root is always the body's first element.
+
+let panel: HTMLElement | undefined
+let treeView: HTMLElement | undefined
+let eventsView: HTMLElement | undefined
+let batchesView: HTMLElement | undefined
+
+const itemChildren = (item: Item): Item[] => {
+	const payload = item.payload
+	switch (payload.type) {
+		case "layout":
+		case "form":
+		case "modal":
+		case "connectionStatus":
+			return payload.body
+		case "table":
+		case "thead":
+		case "tbody":
+		case "tr":
+			return payload.items
+		case "th":
+		case "td":
+			return [payload.item]
+		default:
+			return []
+	}
+}
+
+const itemLabel = (item: Item): string => {
+	const payload = item.payload
+	let label = payload.type
+	if (item.id) {
+		label += ` #${item.id}`
+	}
+	if (item.inx) {
+		label += `[${item.inx}]`
+	}
+	if (payload.type === "text") {
+		label += ` "${payload.value}"`
+	}
+	return label
+}
+
+const formatTree = (item: Item, depth: number, lines: string[]) => {
+	lines.push(`${"  ".repeat(depth)}${itemLabel(item)}`)
+	for (const child of itemChildren(item)) {
+		formatTree(child, depth + 1, lines)
+	}
+}
+
+const formatEvent = (event: DevtoolsEvent): string => {
+	const actions = event.actions.length > 0 ? ` -> ${event.actions.join(", ")}` : ""
+	return `${event.atMs}ms  ${event.kind}  ${event.bytes}B${actions}`
+}
+
+const formatBatch = (batch: DevtoolsBatch): string => {
+	const elapsed = batch.elapsedMs != null ? `  ${batch.elapsedMs.toFixed(2)}ms` : ""
+	return `${batch.atMs}ms  ${batch.kinds.length} action(s)  ${batch.bytes}B${elapsed}  ${batch.kinds.join(", ")}`
+}
+
+const section = (parent: HTMLElement, title: string): HTMLElement => {
+	const details = document.createElement("details")
+	details.open = true
+	const summary = document.createElement("summary")
+	summary.textContent = title
+	summary.style.cursor = "pointer"
+	const body = document.createElement("pre")
+	body.style.margin = "4px 0 8px 0"
+	body.style.whiteSpace = "pre"
+	details.append(summary, body)
+	parent.appendChild(details)
+	return body
+}
+
+const ensurePanel = () => {
+	if (panel) {
+		return
+	}
+	panel = document.createElement("details")
+	panel.dataset.wguiDevtools = "true"
+	const style = panel.style
+	style.position = "fixed"
+	style.right = "8px"
+	style.bottom = "8px"
+	style.maxWidth = "480px"
+	style.maxHeight = "60vh"
+	style.overflow = "auto"
+	style.padding = "6px 10px"
+	style.background = "rgba(20, 20, 24, 0.92)"
+	style.color = "#e6e6e6"
+	style.font = "11px/1.4 monospace"
+	style.borderRadius = "6px"
+	style.zIndex = "2147483647"
+	const summary = document.createElement("summary")
+	summary.textContent = "wgui devtools"
+	summary.style.cursor = "pointer"
+	panel.appendChild(summary)
+	treeView = section(panel, "Item tree")
+	eventsView = section(panel, "Client events")
+	batchesView = section(panel, "Action batches")
+	document.documentElement.appendChild(panel)
+}
+
+export const updateDevtools = (message: Devtools) => {
+	ensurePanel()
+	if (message.tree && treeView) {
+		const lines: string[] = []
+		formatTree(normalizeItem(message.tree), 0, lines)
+		treeView.textContent = lines.join("\n")
+	}
+	if (eventsView) {
+		eventsView.textContent = message.events.slice().reverse().map(formatEvent).join("\n")
+	}
+	if (batchesView) {
+		batchesView.textContent = message.batches.slice().reverse().map(formatBatch).join("\n")
+	}
+}
@@ -0,0 +1,50 @@
+import { ReloadAssets, TemplateDiagnostics } from "./types.ts";
+
+// Parse errors from a hot reloaded template are shown over the page until the
+// server reports the template parses again (an empty diagnostics list).
+
+let overlay: HTMLElement | undefined
+
+export const showTemplateDiagnostics = (message: TemplateDiagnostics) => {
+	if (message.diagnostics.length === 0) {
+		overlay?.remove()
+		overlay = undefined
+		return
+	}
+	if (!overlay) {
+		overlay = document.createElement("div")
+		overlay.dataset.wguiDiagnostics = "true"
+		const style = overlay.style
+		style.position = "fixed"
+		style.inset = "0"
+		style.overflow = "auto"
+		style.padding = "24px"
+		style.background = "rgba(24, 0, 0, 0.88)"
+		style.color = "#ffd7d7"
+		style.font = "13px/1.5 monospace"
+		style.whiteSpace = "pre-wrap"
+		style.zIndex = "2147483646"
+		document.documentElement.appendChild(overlay)
+	}
+	overlay.textContent = [
+		"WUI template failed to parse",
+		"",
+		...message.diagnostics.map((diag) =>
+			`${diag.file}:${diag.line}:${diag.column} [${diag.module}] ${diag.message}`),
+	].join("\n")
+}
+
+// A mounted static file changed: re-fetch the stylesheets in place, or reload
+// the page when anything but CSS changed.
+export const reloadAssets = (message: ReloadAssets) => {
+	if (!message.stylesheetsOnly) {
+		location.reload()
+		return
+	}
+	const links = document.querySelectorAll<HTMLLinkElement>("link[rel=stylesheet]")
+	for (const link of links) {
+		const url = new URL(link.href)
+		url.searchParams.set("wgui-reload", String(Date.now()))
+		link.href = url.toString()
+	}
+}
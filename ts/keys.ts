@@ -0,0 +1,157 @@
+import { Context, EventHandler, Item } from "./types.ts"
+
+type KeyCombo = {
+	ctrl: boolean
+	alt: boolean
+	shift: boolean
+	meta: boolean
+	key: string
+}
+
+type Modifier = "ctrl" | "alt" | "shift" | "meta"
+
+const modifierNames: Record<string, Modifier> = {
+	ctrl: "ctrl",
+	control: "ctrl",
+	alt: "alt",
+	option: "alt",
+	shift: "shift",
+	meta: "meta",
+	cmd: "meta",
+	command: "meta",
+}
+
+const keyAliases: Record<string, string> = {
+	esc: "escape",
+	up: "arrowup",
+	down: "arrowdown",
+	left: "arrowleft",
+	right: "arrowright",
+	del: "delete",
+}
+
+// Mirrors wui_core::keys::KeyCombo::parse, lower-casing the key. Templates
+// have already rejected malformed combinations on the server.
+const parseKeyCombo = (spec: string): KeyCombo | undefined => {
+	const combo: KeyCombo = { ctrl: false, alt: false, shift: false, meta: false, key: "" }
+	for (const raw of spec.split("+")) {
+		const part = raw.trim().toLowerCase()
+		const modifier = modifierNames[part]
+		if (modifier) {
+			combo[modifier] = true
+			continue
+		}
+		if (!part || combo.key) {
+			return undefined
+		}
+		combo.key = keyAliases[part] ?? part
+	}
+	return combo.key ? combo : undefined
+}
+
+const matchesKeyCombo = (combo: KeyCombo, event: KeyboardEvent): boolean => {
+	if (
+		combo.ctrl !== event.ctrlKey ||
+		combo.alt !== event.altKey ||
+		combo.shift !== event.shiftKey ||
+		combo.meta !== event.metaKey
+	) {
+		return false
+	}
+	const code = event.code.toLowerCase()
+	const key = event.key.toLowerCase()
+	if (combo.key.length === 1) {
+		return (
+			code === `key${combo.key}` ||
+			code === `digit${combo.key}` ||
+			code === `numpad${combo.key}` ||
+			key === combo.key
+		)
+	}
+	if (combo.key === "space") {
+		return code === "space" || key === " "
+	}
+	if (combo.key === "enter") {
+		return code === "enter" || code === "numpadenter"
+	}
+	return code === combo.key || key === combo.key
+}
+
+// Whether the item's keyDown/keyUp handlers should see this event.
+const itemMatchesKey = (item: Item, event: KeyboardEvent): boolean => {
+	if (!item.keyCombo) {
+		return true
+	}
+	const combo = parseKeyCombo(item.keyCombo)
+	return !!combo && matchesKeyCombo(combo, event)
+}
+
+// Events an element handler already sent, so shortcuts and handlers on
+// ancestors do not send them again.
+const handledKeyEvents = new WeakSet<KeyboardEvent>()
+
+const sendKeyEvent = (
+	ctx: Context,
+	type: "keyDown" | "keyUp",
+	handler: EventHandler,
+	event: KeyboardEvent,
+) => {
+	ctx.sender.send({
+		type: type === "keyDown" ? "onKeyDown" : "onKeyUp",
+		id: String(handler.id),
+		keycode: event.code || event.key,
+		inx: handler.inx,
+		ctrl: event.ctrlKey,
+		alt: event.altKey,
+		shift: event.shiftKey,
+		meta: event.metaKey,
+	})
+	ctx.sender.sendNow()
+}
+
+// Handles a key event for one item; returns whether it reached a handler.
+export const handleItemKey = (
+	item: Item,
+	type: "keyDown" | "keyUp",
+	event: KeyboardEvent,
+	ctx: Context,
+): boolean => {
+	const handler = item.handlers?.[type]
+	if (!handler || event.repeat || handledKeyEvents.has(event) || !itemMatchesKey(item, event)) {
+		return false
+	}
+	handledKeyEvents.add(event)
+	if (item.preventDefault) {
+		event.preventDefault()
+	}
+	sendKeyEvent(ctx, type, handler, event)
+	return true
+}
+
+type RegisteredShortcut = {
+	item: Item
+	ctx: Context
+}
+
+const shortcuts = new Map<HTMLElement, RegisteredShortcut>()
+
+export const registerShortcut = (element: HTMLElement, item: Item, ctx: Context) => {
+	shortcuts.set(element, { item, ctx })
+}
+
+// Runs the page-wide <Shortcut> handlers. While the user types into a field,
+// only combinations with Ctrl, Alt or Meta fire.
+export const dispatchShortcuts = (event: KeyboardEvent, type: "keyDown" | "keyUp", typing: boolean) => {
+	for (const [element, { item, ctx }] of shortcuts) {
+		if (!element.isConnected) {
+			shortcuts.delete(element)
+			continue
+		}
+		if (typing && !event.ctrlKey && !event.altKey && !event.metaKey) {
+			continue
+		}
+		if (handleItemKey(item, type, event, ctx)) {
+			return
+		}
+	}
+}
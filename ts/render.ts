@@ -1,5 +1,6 @@
 import { ButtonEvents, Context, Item, ItemPayload } from "./types.ts";
 import { disposeCustomComponentTree, mountCustomComponent } from "./custom_components.ts";
+import { handleItemKey, registerShortcut } from "./keys.ts";
 
 let nextFormSubmissionId = 1
 const pendingFormSubmissions = new Map<number, HTMLFormElement>()
@@ -185,7 +186,15 @@ type ButtonHoldState = {
 
 const buttonHoldStates = new WeakMap<HTMLButtonElement, ButtonHoldState>()
 
+// The inx sent with `event`: the handler's own arg, else the item's.
+const handlerInx = (item: Item, event: string): number | undefined =>
+	item.handlers?.[event]?.inx ?? item.inx ?? undefined
+
 const buttonEventId = (item: Item, events: ButtonEvents | undefined, name: keyof ButtonEvents): number | undefined => {
+	const handler = item.handlers?.[name]
+	if (handler) {
+		return handler.id
+	}
 	if (events && typeof events[name] === "number") {
 		return events[name] as number
 	}
@@ -204,10 +213,11 @@ const sendButtonEvent = (
 	if (!id) {
 		return
 	}
+	const event = type.charAt(2).toLowerCase() + type.slice(3)
 	ctx.sender.send({
 		type,
 		id,
-		inx: item.inx ?? undefined,
+		inx: handlerInx(item, event),
 	})
 	ctx.sender.sendNow()
 }
@@ -285,14 +295,15 @@ const configureButtonEvents = (button: HTMLButtonElement, item: Item, events: Bu
 
 const bindAutoClick = (element: HTMLElement, item: Item, ctx: Context) => {
 	const autoKey = "1"
-	if (item.id) {
+	const id = item.handlers?.click?.id ?? item.id
+	if (id) {
 		if (!element.onclick) {
 			element.dataset.wguiAutoClick = autoKey
 			element.onclick = () => {
 				ctx.sender.send({
 					type: "onClick",
-					id: item.id,
-					inx: item.inx,
+					id,
+					inx: handlerInx(item, "click"),
 				})
 				ctx.sender.sendNow()
 			}
@@ -305,6 +316,34 @@ const bindAutoClick = (element: HTMLElement, item: Item, ctx: Context) => {
 	}
 }
 
+// Press, release and key handlers on elements other than buttons, which
+// wire press and release through their hold state instead.
+const bindHandlers = (element: HTMLElement, item: Item, ctx: Context) => {
+	const send = (type: "onPress" | "onRelease", event: string) => {
+		const handler = item.handlers?.[event]
+		if (!handler) {
+			return null
+		}
+		return () => {
+			ctx.sender.send({ type, id: handler.id, inx: handler.inx })
+			ctx.sender.sendNow()
+		}
+	}
+	if (!(element instanceof HTMLButtonElement)) {
+		element.onpointerdown = send("onPress", "press")
+		element.onpointerup = send("onRelease", "release")
+	}
+	// Shortcuts listen on the whole page instead; see dispatchShortcuts.
+	const focusable = item.payload.type !== "shortcut"
+	const keyDown = focusable && item.handlers?.keyDown
+	const keyUp = focusable && item.handlers?.keyUp
+	element.onkeydown = keyDown ? (event: KeyboardEvent) => handleItemKey(item, "keyDown", event, ctx) : null
+	element.onkeyup = keyUp ? (event: KeyboardEvent) => handleItemKey(item, "keyUp", event, ctx) : null
+	if ((keyDown || keyUp) && element.tabIndex < 0) {
+		element.tabIndex = 0
+	}
+}
+
 type LayoutScrollState = {
 	nearBottom: boolean
 	lastSentAt: number
@@ -320,7 +359,7 @@ const configureLayoutEvents = (
 	payload: Extract<ItemPayload, { type: "layout" }>,
 	ctx: Context,
 ) => {
-	const id = payload.events?.scrollNearBottom
+	const id = item.handlers?.scrollNearBottom?.id ?? payload.events?.scrollNearBottom
 	if (!id) {
 		element.onscroll = null
 		layoutScrollStates.delete(element)
@@ -352,7 +391,7 @@ const configureLayoutEvents = (
 		ctx.sender.send({
 			type: "onScrollNearBottom",
 			id,
-			inx: item.inx ?? undefined,
+			inx: handlerInx(item, "scrollNearBottom"),
 		})
 		ctx.sender.sendNow()
 	}
@@ -435,6 +474,7 @@ const navigateLink = (event: MouseEvent, anchor: HTMLAnchorElement, ctx: Context
 		type: "pathChanged",
 		path: location.pathname,
 		query: pathQuery(location.search),
+		queryString: location.search.slice(1),
 	})
 	ctx.sender.sendNow()
 }
@@ -457,7 +497,7 @@ const renderPayload = (item: Item, ctx: Context, old?: Element | null) => {
 				ctx.sender.send({
 					type: "onClick",
 					id: item.id,
-					inx: item.inx,
+					inx: handlerInx(item, "click"),
 				})
 				ctx.sender.sendNow()
 			}
@@ -1012,7 +1052,7 @@ const renderPayload = (item: Item, ctx: Context, old?: Element | null) => {
 				ctx.sender.send({
 					type: "onClick",
 					id: item.id,
-					inx: item.inx,
+					inx: handlerInx(item, "click"),
 				})
 				ctx.sender.sendNow()
 			}
@@ -1038,6 +1078,19 @@ const renderPayload = (item: Item, ctx: Context, old?: Element | null) => {
 		return element
 	}
 
+	if (payload.type === "shortcut") {
+		let element: HTMLSpanElement
+		if (old instanceof HTMLSpanElement && old.hidden) {
+			element = old
+		} else {
+			element = document.createElement("span")
+			if (old) old.replaceWith(element)
+		}
+		element.hidden = true
+		registerShortcut(element, item, ctx)
+		return element
+	}
+
 	if (payload.type === "modal") {
 		let overlay: HTMLDivElement
 		if (old instanceof HTMLDivElement && old.dataset.modal === "overlay") {
@@ -1074,7 +1127,7 @@ const renderPayload = (item: Item, ctx: Context, old?: Element | null) => {
 					ctx.sender.send({
 						type: "onClick",
 						id: item.id,
-						inx: item.inx,
+						inx: handlerInx(item, "click"),
 					})
 					ctx.sender.sendNow()
 				}
@@ -1245,5 +1298,6 @@ export const renderItem = (item: Item, ctx: Context, old?: Element | null) => {
 	) {
 		bindAutoClick(element, item, ctx)
 	}
+	bindHandlers(element, item, ctx)
 	return element
 }
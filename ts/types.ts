@@ -192,6 +192,11 @@ export type ConnectionStatus = {
 	body: Item[]
 }
 
+// A page-wide keyboard shortcut; renders a hidden placeholder.
+export type Shortcut = {
+	type: "shortcut"
+}
+
 export type None = {
 	type: "none"
 }
@@ -255,11 +260,22 @@ export type ItemPayload = Text |
  	FloatingLayout |
  	Modal |
 	ConnectionStatus |
+	Shortcut |
  	None 
 
+export type EventHandler = {
+	id: number
+	inx?: number
+}
+
 export type Item = {
 	id: number
 	inx?: number
+	// Per-event handlers keyed by event name (click, press, keyDown, ...).
+	handlers?: Record<string, EventHandler>
+	// Limits the keyDown and keyUp handlers to one combination, e.g. "Ctrl+S".
+	keyCombo?: string
+	preventDefault?: boolean
 	typ: number
 	height: number
 	width: number
@@ -442,6 +458,45 @@ export type WebPushDisable = {
 	serviceWorkerPath: string
 }
 
+export type TemplateDiagnostic = {
+	module: string
+	file: string
+	line: number
+	column: number
+	message: string
+}
+
+export type TemplateDiagnostics = {
+	type: "templateDiagnostics"
+	diagnostics: TemplateDiagnostic[]
+}
+
+export type ReloadAssets = {
+	type: "reloadAssets"
+	stylesheetsOnly: boolean
+}
+
+export type DevtoolsEvent = {
+	atMs: number
+	kind: string
+	bytes: number
+	actions: string[]
+}
+
+export type DevtoolsBatch = {
+	atMs: number
+	kinds: string[]
+	bytes: number
+	elapsedMs?: number | null
+}
+
+export type Devtools = {
+	type: "devtools"
+	events: DevtoolsEvent[]
+	batches: DevtoolsBatch[]
+	tree?: Item | null
+}
+
 export type SrvMessage = Replace |
     ReplaceAt |
     AddBack |
@@ -459,7 +514,10 @@ export type SrvMessage = Replace |
 	WebRtcSignal |
 	WebPushEnable |
 	WebPushDisable |
-	CustomData
+	CustomData |
+	Devtools |
+	TemplateDiagnostics |
+	ReloadAssets
 
 export type OnClick = {
     type: "onClick"
@@ -496,12 +554,22 @@ export type OnKeyDown = {
     type: "onKeyDown"
     id?: string
     keycode: string
+    inx?: number
+    ctrl?: boolean
+    alt?: boolean
+    shift?: boolean
+    meta?: boolean
 }
 
 export type OnKeyUp = {
     type: "onKeyUp"
     id?: string
     keycode: string
+    inx?: number
+    ctrl?: boolean
+    alt?: boolean
+    shift?: boolean
+    meta?: boolean
 }
 
 export type PathChanged = {
@@ -510,6 +578,7 @@ export type PathChanged = {
     query: {
         [key: string]: string
     }
+	queryString?: string
 	ssrHydrationId?: string
 	initialRoot?: Item
 }
@@ -519,6 +588,7 @@ export type FormSubmit = {
 	path: string
 	submissionId: number
 	query: { [key: string]: string }
+	queryString?: string
 	fields: { [key: string]: string }
 }
 
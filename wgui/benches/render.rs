@@ -0,0 +1,75 @@
+//! Render time of a runtime WUI template over a 10k-row list.
+//!
+//! Run with `cargo bench -p wgui --bench render`. The same rows are rendered
+//! from a derived model, which templates walk in place, and from a prebuilt
+//! `WuiValue` tree.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use wgui::wui::runtime::{Template, WuiValueConvert};
+use wgui::WguiModel;
+
+const ROWS: usize = 10_000;
+const RUNS: u32 = 20;
+
+#[derive(WguiModel)]
+struct Row {
+	id: u32,
+	label: String,
+	done: bool,
+}
+
+#[derive(WguiModel)]
+struct ListState {
+	title: String,
+	rows: Vec<Row>,
+}
+
+const TEMPLATE: &str = r#"
+<VStack>
+	<Text value={state.title} />
+	<For each={state.rows} itemAs="row" key={row.id}>
+		<HStack spacing=4>
+			<Text value={row.id} />
+			<Text value={row.label} />
+			<If test={row.done}><Text value="done" /></If>
+		</HStack>
+	</For>
+</VStack>
+"#;
+
+fn main() {
+	let template = Template::parse(TEMPLATE, "bench").expect("bench template parses");
+	let state = ListState {
+		title: "Rows".to_string(),
+		rows: (0..ROWS as u32)
+			.map(|id| Row {
+				id,
+				label: format!("row {id}"),
+				done: id % 3 == 0,
+			})
+			.collect(),
+	};
+	let value = state.to_wui_value();
+
+	report("derived model", || template.render(&state));
+	report("WuiValue tree", || template.render(&value));
+}
+
+fn report<T>(name: &str, mut render: impl FnMut() -> T) {
+	black_box(render());
+	let mut best = Duration::MAX;
+	let mut total = Duration::ZERO;
+	for _ in 0..RUNS {
+		let start = Instant::now();
+		black_box(render());
+		let elapsed = start.elapsed();
+		best = best.min(elapsed);
+		total += elapsed;
+	}
+	println!(
+		"{name}: {ROWS} rows, mean {:.2?}, best {:.2?} over {RUNS} runs",
+		total / RUNS,
+		best
+	);
+}
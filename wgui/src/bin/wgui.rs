@@ -15,7 +15,11 @@ use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::UnixListener;
 use tokio_tungstenite::connect_async;
 use tokio_tungstenite::tungstenite::Message;
+use wgui::i18n::Catalog;
 use wgui::wui::compiler::ir::{ActionPayload, EventKind};
+use wgui::wui::routing::RouteTable;
+use wgui::wui::schema::StateSchema;
+use wgui::wui::testing::{self, Outcome};
 use wgui::{schema_diff::diff_schemas, wdb};
 use wgui::{ClientAction, Item, ItemPayload, PropKey, SetProp, Value};
 
@@ -57,6 +61,8 @@ enum TopCommand {
 	},
 	Generate(GenerateArgs),
 	Check(CheckArgs),
+	Fmt(FmtArgs),
+	TestTemplates(TestTemplatesArgs),
 }
 
 #[derive(Subcommand, Debug)]
@@ -171,6 +177,23 @@ struct CheckArgs {
 	quiet: bool,
 }
 
+#[derive(Args, Debug)]
+struct FmtArgs {
+	paths: Vec<PathBuf>,
+	/// Report unformatted files instead of rewriting them.
+	#[arg(long)]
+	check: bool,
+}
+
+#[derive(Args, Debug)]
+struct TestTemplatesArgs {
+	paths: Vec<PathBuf>,
+	/// Write missing snapshots and rewrite ones that no longer match, as
+	/// `WGUI_UPDATE_SNAPSHOTS=1` does.
+	#[arg(long)]
+	update: bool,
+}
+
 #[derive(Args, Debug)]
 struct ControllersListArgs {
 	#[arg(default_value = ".")]
@@ -209,6 +232,8 @@ enum ControllerEventKind {
 	SliderChange,
 	Select,
 	ScrollNearBottom,
+	KeyDown,
+	KeyUp,
 }
 
 #[derive(Args, Debug)]
@@ -298,6 +323,8 @@ fn run() -> Result<(), String> {
 		TopCommand::Session { command } => run_session(command),
 		TopCommand::Generate(args) => run_generate(args),
 		TopCommand::Check(args) => run_check(args),
+		TopCommand::Fmt(args) => run_fmt(args),
+		TopCommand::TestTemplates(args) => run_test_templates(args),
 	}
 }
 
@@ -386,9 +413,25 @@ fn run_check(args: CheckArgs) -> Result<(), String> {
 		return Err("no .wui files found".to_string());
 	}
 
+	let mut schemas = HashMap::new();
+	let mut route_tables = HashMap::new();
+	let mut catalogs = HashMap::new();
 	let mut failures = 0usize;
 	for (base, file) in &files {
-		match check_wui_file(base, file) {
+		let schema = schemas
+			.entry(base.clone())
+			.or_insert_with(|| project_state_schema(base));
+		let routes = route_tables
+			.entry(base.clone())
+			.or_insert_with(|| project_routes(base));
+		let catalogs = catalogs.entry(base.clone()).or_insert_with(|| {
+			project_catalogs(base).unwrap_or_else(|err| {
+				failures += 1;
+				eprintln!("{err}");
+				Vec::new()
+			})
+		});
+		match check_wui_file(base, file, schema, routes, catalogs) {
 			Ok(()) => {
 				if !args.quiet {
 					println!("ok {}", file.display());
@@ -414,6 +457,145 @@ fn run_check(args: CheckArgs) -> Result<(), String> {
 	}
 }
 
+fn run_fmt(args: FmtArgs) -> Result<(), String> {
+	let paths = if args.paths.is_empty() {
+		vec![PathBuf::from(".")]
+	} else {
+		args.paths
+	};
+	let files = discover_wui_check_files(&paths)?;
+	if files.is_empty() {
+		return Err("no .wui files found".to_string());
+	}
+
+	let mut failures = 0usize;
+	let mut unformatted = 0usize;
+	for (_, file) in &files {
+		match format_wui_file(file, args.check) {
+			Ok(false) => {}
+			Ok(true) if args.check => {
+				unformatted += 1;
+				println!("not formatted {}", file.display());
+			}
+			Ok(true) => println!("formatted {}", file.display()),
+			Err(err) => {
+				failures += 1;
+				eprintln!("{err}");
+			}
+		}
+	}
+
+	if failures > 0 {
+		Err(format!(
+			"wui fmt failed for {failures} of {} file(s)",
+			files.len()
+		))
+	} else if unformatted > 0 {
+		Err(format!(
+			"{unformatted} of {} WUI file(s) need formatting",
+			files.len()
+		))
+	} else {
+		Ok(())
+	}
+}
+
+fn run_test_templates(args: TestTemplatesArgs) -> Result<(), String> {
+	let paths = if args.paths.is_empty() {
+		vec![PathBuf::from(".")]
+	} else {
+		args.paths
+	};
+	let files = discover_wui_check_files(&paths)?;
+
+	let mut tested = 0usize;
+	let mut failures = 0usize;
+	for (base, file) in &files {
+		let result = module_name_for_wui_file(base, file)
+			.and_then(|module| testing::test_template(file, &module, args.update));
+		match result {
+			Ok(None) => continue,
+			Ok(Some(Outcome::Matched)) => println!("ok {}", file.display()),
+			Ok(Some(Outcome::Written)) => {
+				println!("wrote {}", testing::snapshot_path(file).display())
+			}
+			Ok(Some(Outcome::Missing { actual })) => {
+				failures += 1;
+				eprintln!(
+					"missing snapshot {}\n{}",
+					testing::snapshot_path(file).display(),
+					snapshot_diff("", &actual)
+				);
+			}
+			Ok(Some(Outcome::Changed { expected, actual })) => {
+				failures += 1;
+				eprintln!(
+					"snapshot changed {}\n{}",
+					file.display(),
+					snapshot_diff(&expected, &actual)
+				);
+			}
+			Err(err) => {
+				failures += 1;
+				eprintln!("{err}");
+			}
+		}
+		tested += 1;
+	}
+
+	if tested == 0 {
+		Err("no .wui files with fixtures found".to_string())
+	} else if failures > 0 {
+		Err(format!(
+			"{failures} of {tested} template(s) failed; rerun with --update or WGUI_UPDATE_SNAPSHOTS=1 to accept the snapshots"
+		))
+	} else {
+		Ok(())
+	}
+}
+
+/// The lines between the common start and end of two snapshots, marked `-`
+/// for the stored snapshot and `+` for the new one.
+fn snapshot_diff(expected: &str, actual: &str) -> String {
+	let expected = expected.lines().collect::<Vec<_>>();
+	let actual = actual.lines().collect::<Vec<_>>();
+	let start = expected
+		.iter()
+		.zip(&actual)
+		.take_while(|(left, right)| left == right)
+		.count();
+	let end = expected[start..]
+		.iter()
+		.rev()
+		.zip(actual[start..].iter().rev())
+		.take_while(|(left, right)| left == right)
+		.count();
+	let removed = expected[start..expected.len() - end]
+		.iter()
+		.map(|line| format!("-{line}"));
+	let added = actual[start..actual.len() - end]
+		.iter()
+		.map(|line| format!("+{line}"));
+	removed.chain(added).collect::<Vec<_>>().join("\n")
+}
+
+/// Formats `file` in place, or only compares it when `check` is set. Returns
+/// whether the file was not formatted.
+fn format_wui_file(file: &std::path::Path, check: bool) -> Result<bool, String> {
+	let source = std::fs::read_to_string(file)
+		.map_err(|e| format!("failed reading {}: {e}", file.display()))?;
+	let formatted =
+		wgui::wui::format::format(&source).map_err(|diags| format_wui_diagnostics(file, &diags))?;
+	if formatted == source {
+		return Ok(false);
+	}
+	if !check {
+		std::fs::write(file, formatted)
+			.map_err(|e| format!("failed writing {}: {e}", file.display()))?;
+	}
+	Ok(true)
+}
+
 fn discover_wui_check_files(paths: &[PathBuf]) -> Result<Vec<(PathBuf, PathBuf)>, String> {
 	let mut files = Vec::new();
 	for path in paths {
@@ -448,12 +630,65 @@ fn discover_wui_check_files(paths: &[PathBuf]) -> Result<Vec<(PathBuf, PathBuf)>
 	Ok(files)
 }
 
-fn check_wui_file(base: &std::path::Path, file: &std::path::Path) -> Result<(), String> {
+/// Models derived in the `src` directory next to a project's `wui` directory,
+/// for checking templates that declare `<Route state="..">`.
+fn project_state_schema(base: &std::path::Path) -> StateSchema {
+	let src = match base.parent() {
+		Some(project) if base.file_name().is_some_and(|name| name == "wui") => project.join("src"),
+		_ => return StateSchema::new(),
+	};
+	StateSchema::scan_dir(&src).unwrap_or_default()
+}
+
+/// `#[route]` and `#[partial]` handlers in the `src` directory next to a
+/// project's `wui` directory, for checking `url("name", ...)` calls.
+fn project_routes(base: &std::path::Path) -> RouteTable {
+	let src = match base.parent() {
+		Some(project) if base.file_name().is_some_and(|name| name == "wui") => project.join("src"),
+		_ => return RouteTable::new(),
+	};
+	RouteTable::scan_dir(&src).unwrap_or_default()
+}
+
+/// Message catalogs in the `i18n` directory next to a project's `wui`
+/// directory, for reporting `t("key")` calls missing a translation.
+fn project_catalogs(base: &std::path::Path) -> Result<Vec<Catalog>, String> {
+	let dir = match base.parent() {
+		Some(project) if base.file_name().is_some_and(|name| name == "wui") => project.join("i18n"),
+		_ => base.join("i18n"),
+	};
+	if !dir.is_dir() {
+		return Ok(Vec::new());
+	}
+	wgui::i18n::read_dir(&dir)
+}
+
+fn check_wui_file(
+	base: &std::path::Path,
+	file: &std::path::Path,
+	schema: &StateSchema,
+	routes: &RouteTable,
+	catalogs: &[Catalog],
+) -> Result<(), String> {
 	let module = module_name_for_wui_file(base, file)?;
 	let source = std::fs::read_to_string(file)
 		.map_err(|e| format!("failed reading {}: {e}", file.display()))?;
 	wgui::wui::compiler::compile_with_dir(&source, &module, file.parent())
-		.map(|_| ())
+		.and_then(|_| {
+			wgui::wui::compiler::check_state(&source, &module, file.parent(), schema, None)
+		})
+		.and_then(|()| {
+			let mut diags = wgui::i18n::check_keys(&source, catalogs);
+			// Without a crate to scan, there is nothing to check `url()` against.
+			if !routes.is_empty() {
+				diags.extend(wgui::wui::routing::check_urls(&source, routes));
+			}
+			if diags.is_empty() {
+				Ok(())
+			} else {
+				Err(diags)
+			}
+		})
 		.map_err(|diags| format_wui_diagnostics(file, &diags))
 }
 
@@ -1092,7 +1327,10 @@ fn apply_client_action(snapshot: &mut SessionSnapshot, action: ClientAction) {
 		| ClientAction::WebRtcSignal { .. }
 		| ClientAction::WebPushEnable { .. }
 		| ClientAction::WebPushDisable { .. }
-		| ClientAction::CustomData(_) => {}
+		| ClientAction::CustomData(_)
+		| ClientAction::Devtools(_)
+		| ClientAction::TemplateDiagnostics { .. }
+		| ClientAction::ReloadAssets { .. } => {}
 	}
 }
 
@@ -1414,6 +1652,9 @@ fn controller_event_json(
 			ActionPayload::U32I32 if arg.is_none() || value.is_none() => {
 				return Err("this action requires --arg <u32> and --value <i32>".to_string());
 			}
+			ActionPayload::U32String if arg.is_none() || value.is_none() => {
+				return Err("this action requires --arg <u32> and --value <key>".to_string());
+			}
 			ActionPayload::Json => {
 				return Err("custom events cannot be called with --kind".to_string());
 			}
@@ -1478,6 +1719,21 @@ fn controller_event_json(
 			"id": id,
 			"value": value.unwrap_or_default(),
 		})),
+		ControllerEventKind::KeyDown | ControllerEventKind::KeyUp => {
+			let event_type = match kind {
+				ControllerEventKind::KeyDown => "onKeyDown",
+				_ => "onKeyUp",
+			};
+			let mut event = json!({
+				"type": event_type,
+				"id": id.to_string(),
+				"keycode": value.unwrap_or_default(),
+			});
+			if let Some(arg) = arg {
+				event["inx"] = json!(arg);
+			}
+			Ok(event)
+		}
 	}
 }
 
@@ -1491,6 +1747,8 @@ fn controller_event_kind_from_event(kind: &EventKind) -> Option<ControllerEventK
 		EventKind::SliderChange => Some(ControllerEventKind::SliderChange),
 		EventKind::Select => Some(ControllerEventKind::Select),
 		EventKind::ScrollNearBottom => Some(ControllerEventKind::ScrollNearBottom),
+		EventKind::KeyDown => Some(ControllerEventKind::KeyDown),
+		EventKind::KeyUp => Some(ControllerEventKind::KeyUp),
 		EventKind::Custom(_) => None,
 	}
 }
@@ -1505,6 +1763,8 @@ fn event_kind_name(kind: &EventKind) -> String {
 		EventKind::SliderChange => "slider-change".to_string(),
 		EventKind::Select => "select".to_string(),
 		EventKind::ScrollNearBottom => "scroll-near-bottom".to_string(),
+		EventKind::KeyDown => "key-down".to_string(),
+		EventKind::KeyUp => "key-up".to_string(),
 		EventKind::Custom(name) => format!("custom:{name}"),
 	}
 }
@@ -1516,6 +1776,7 @@ fn action_payload_name(payload: &ActionPayload) -> &'static str {
 		ActionPayload::String => "string",
 		ActionPayload::I32 => "i32",
 		ActionPayload::U32I32 => "u32,i32",
+		ActionPayload::U32String => "u32,string",
 		ActionPayload::Json => "json",
 	}
 }
@@ -2172,7 +2433,7 @@ mod tests {
 		)
 		.unwrap();
 
-		let result = check_wui_file(&dir, &file);
+		let result = check_wui_file(&dir, &file, &StateSchema::new(), &RouteTable::new(), &[]);
 
 		let _ = std::fs::remove_dir_all(dir.parent().unwrap());
 		assert!(result.is_ok(), "{result:?}");
@@ -2193,12 +2454,138 @@ mod tests {
 		)
 		.unwrap();
 
-		let err = check_wui_file(&dir, &file).unwrap_err();
+		let err =
+			check_wui_file(&dir, &file, &StateSchema::new(), &RouteTable::new(), &[]).unwrap_err();
 
 		let _ = std::fs::remove_dir_all(dir.parent().unwrap());
 		assert!(err.contains("conflicting payloads"), "{err}");
 	}
 
+	#[test]
+	fn check_wui_file_reports_unknown_state_fields() {
+		let project = make_temp_dir("typed");
+		let wui_dir = project.join("wui");
+		let src_dir = project.join("src");
+		std::fs::create_dir_all(&wui_dir).unwrap();
+		std::fs::create_dir_all(&src_dir).unwrap();
+		std::fs::write(
+			src_dir.join("main.rs"),
+			"#[derive(WguiModel)]\nstruct HomeState { title: String }\n",
+		)
+		.unwrap();
+		let file = wui_dir.join("home.wui");
+		std::fs::write(
+			&file,
+			r#"<Route path="/" state="HomeState"><Text value={state.titel} /></Route>"#,
+		)
+		.unwrap();
+
+		let err = check_wui_file(
+			&wui_dir,
+			&file,
+			&project_state_schema(&wui_dir),
+			&RouteTable::new(),
+			&[],
+		)
+		.unwrap_err();
+
+		let _ = std::fs::remove_dir_all(project.parent().unwrap());
+		assert!(err.contains("unknown field `titel` on `state`"), "{err}");
+	}
+
+	#[test]
+	fn check_wui_file_reports_missing_translations() {
+		let project = make_temp_dir("i18n");
+		let wui_dir = project.join("wui");
+		let i18n_dir = project.join("i18n");
+		std::fs::create_dir_all(&wui_dir).unwrap();
+		std::fs::create_dir_all(&i18n_dir).unwrap();
+		std::fs::write(
+			i18n_dir.join("en.ftl"),
+			"title = Orders\nempty = No orders\n",
+		)
+		.unwrap();
+		std::fs::write(i18n_dir.join("fi.ftl"), "title = Tilaukset\n").unwrap();
+		let file = wui_dir.join("home.wui");
+		std::fs::write(
+			&file,
+			r#"<VStack><Text value={t("title")} /><Text value={t("empty")} /></VStack>"#,
+		)
+		.unwrap();
+
+		let catalogs = project_catalogs(&wui_dir).unwrap();
+		let err = check_wui_file(
+			&wui_dir,
+			&file,
+			&StateSchema::new(),
+			&RouteTable::new(),
+			&catalogs,
+		)
+		.unwrap_err();
+
+		let _ = std::fs::remove_dir_all(project.parent().unwrap());
+		assert!(err.contains("missing translation \"empty\" in fi"), "{err}");
+		assert!(!err.contains("\"title\""), "{err}");
+	}
+
+	#[test]
+	fn check_wui_file_reports_unknown_route_urls() {
+		let project = make_temp_dir("routes");
+		let wui_dir = project.join("wui");
+		let src_dir = project.join("src");
+		std::fs::create_dir_all(&wui_dir).unwrap();
+		std::fs::create_dir_all(&src_dir).unwrap();
+		std::fs::write(
+			src_dir.join("main.rs"),
+			"#[route(\"/todos/:id\")]\nfn todo(ctx: &Ctx<App>, id: u32) -> View { todo!() }\n",
+		)
+		.unwrap();
+		let file = wui_dir.join("home.wui");
+		std::fs::write(
+			&file,
+			r#"<VStack><Link href={url("todo", 1)} /><Link href={url("todos", 1)} /></VStack>"#,
+		)
+		.unwrap();
+
+		let err = check_wui_file(
+			&wui_dir,
+			&file,
+			&StateSchema::new(),
+			&project_routes(&wui_dir),
+			&[],
+		)
+		.unwrap_err();
+
+		let _ = std::fs::remove_dir_all(project.parent().unwrap());
+		assert!(err.contains("unknown route \"todos\""), "{err}");
+		assert!(!err.contains("\"todo\""), "{err}");
+	}
+
+	#[test]
+	fn format_wui_file_checks_then_rewrites() {
+		let dir = make_temp_dir("fmt");
+		let file = dir.join("home.wui");
+		std::fs::write(&file, "<VStack>\n  <Text value=\"Hi\"/>\n</VStack>").unwrap();
+
+		let needs_format = format_wui_file(&file, true).unwrap();
+		let unchanged = std::fs::read_to_string(&file).unwrap();
+		let rewritten = format_wui_file(&file, false).unwrap();
+		let formatted = std::fs::read_to_string(&file).unwrap();
+		let clean = format_wui_file(&file, true).unwrap();
+
+		let _ = std::fs::remove_dir_all(dir.parent().unwrap());
+		assert!(needs_format && rewritten && !clean);
+		assert!(unchanged.contains("  <Text"));
+		assert_eq!(formatted, "<VStack>\n\t<Text value=\"Hi\" />\n</VStack>\n");
+	}
+
+	#[test]
+	fn snapshot_diff_shows_only_changed_lines() {
+		let diff = snapshot_diff("== a\ntext \"One\"\nend\n", "== a\ntext \"Two\"\nend\n");
+
+		assert_eq!(diff, "-text \"One\"\n+text \"Two\"");
+	}
+
 	#[test]
 	fn discover_wui_check_files_uses_project_wui_dir() {
 		let project = make_temp_dir("project");
@@ -36,7 +36,7 @@ impl<T> std::fmt::Debug for DbTable<T> {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		#[cfg(feature = "sqlite")]
 		{
-			return f.write_str("DbTable<sqlite>");
+			f.write_str("DbTable<sqlite>")
 		}
 		#[cfg(not(feature = "sqlite"))]
 		{
@@ -179,10 +179,10 @@ impl<S: WdbSchema> Db<S> {
 		#[cfg(feature = "sqlite")]
 		{
 			crate::apply_sqlite_migrations(&database_path, migrations_path)?;
-			return Ok(Self {
+			Ok(Self {
 				sqlite: SQLLiteDB::<S>::open(database_path)?,
 				_schema: std::marker::PhantomData,
-			});
+			})
 		}
 		#[cfg(not(feature = "sqlite"))]
 		{
@@ -197,10 +197,10 @@ impl<S: WdbSchema> Db<S> {
 	pub fn new() -> Self {
 		#[cfg(feature = "sqlite")]
 		{
-			return Self {
+			Self {
 				sqlite: SQLLiteDB::<S>::new().expect("open sqlite db"),
 				_schema: std::marker::PhantomData,
-			};
+			}
 		}
 		#[cfg(not(feature = "sqlite"))]
 		{
@@ -216,9 +216,7 @@ impl<S: WdbSchema> Db<S> {
 	{
 		#[cfg(feature = "sqlite")]
 		{
-			return DbTable::from_sqlite(
-				self.sqlite.table::<T>().expect("create/open sqlite table"),
-			);
+			DbTable::from_sqlite(self.sqlite.table::<T>().expect("create/open sqlite table"))
 		}
 		#[cfg(not(feature = "sqlite"))]
 		{
@@ -242,7 +240,7 @@ impl<S: WdbSchema> Db<S> {
 			{
 				table.replace(rows);
 			}
-			return table;
+			table
 		}
 		#[cfg(not(feature = "sqlite"))]
 		{
@@ -0,0 +1,181 @@
+//! Recording for the in-browser devtools overlay.
+//!
+//! Only compiled into debug builds, and only active for clients of a `Wgui`
+//! that has called [`Wgui::enable_devtools`](crate::Wgui::enable_devtools).
+//! Each websocket worker keeps the last [`HISTORY`] client events and action
+//! batches and pushes a [`ClientAction::Devtools`] frame to its browser after
+//! every change, which the client renders as a collapsible panel.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use crate::gui::Item;
+use crate::types::{ClientAction, Devtools, DevtoolsBatch, DevtoolsEvent};
+
+/// How many events and batches each worker keeps.
+pub const HISTORY: usize = 50;
+
+tokio::task_local! {
+	static DECODED_ACTIONS: RefCell<Vec<String>>;
+}
+
+/// Note a WUI action decoded while handling the current client event.
+pub(crate) fn record_decoded_action(name: &str) {
+	let _ = DECODED_ACTIONS.try_with(|actions| actions.borrow_mut().push(name.to_string()));
+}
+
+/// Run `fut`, collecting the names of every WUI action decoded inside it.
+pub(crate) async fn capture_decoded_actions<F: Future>(fut: F) -> (F::Output, Vec<String>) {
+	DECODED_ACTIONS
+		.scope(RefCell::new(Vec::new()), async {
+			let output = fut.await;
+			let actions = DECODED_ACTIONS.with(|actions| actions.take());
+			(output, actions)
+		})
+		.await
+}
+
+fn action_kind(action: &ClientAction) -> &'static str {
+	match action {
+		ClientAction::Replace(_) => "replace",
+		ClientAction::ReplaceAt(_) => "replaceAt",
+		ClientAction::AddBack(_) => "addBack",
+		ClientAction::AddFront(_) => "addFront",
+		ClientAction::InsertAt(_) => "insertAt",
+		ClientAction::RemoveInx(_) => "removeInx",
+		ClientAction::PushState(_) => "pushState",
+		ClientAction::Navigate(_) => "navigate",
+		ClientAction::FormSucceeded(_) => "formSucceeded",
+		ClientAction::ReplaceState(_) => "replaceState",
+		ClientAction::SetQuery(_) => "setQuery",
+		ClientAction::SetProp { .. } => "setProp",
+		ClientAction::SetTitle { .. } => "setTitle",
+		ClientAction::WebRtcRoomState { .. } => "webRtcRoomState",
+		ClientAction::WebRtcSignal { .. } => "webRtcSignal",
+		ClientAction::WebPushEnable { .. } => "webPushEnable",
+		ClientAction::WebPushDisable { .. } => "webPushDisable",
+		ClientAction::CustomData(_) => "customData",
+		ClientAction::Devtools(_) => "devtools",
+		ClientAction::TemplateDiagnostics { .. } => "templateDiagnostics",
+		ClientAction::ReloadAssets { .. } => "reloadAssets",
+	}
+}
+
+pub(crate) struct Recorder {
+	connected_at: Instant,
+	events: VecDeque<DevtoolsEvent>,
+	batches: VecDeque<DevtoolsBatch>,
+	tree: Option<Item>,
+}
+
+impl Recorder {
+	pub(crate) fn new() -> Self {
+		Self {
+			connected_at: Instant::now(),
+			events: VecDeque::new(),
+			batches: VecDeque::new(),
+			tree: None,
+		}
+	}
+
+	fn now_ms(&self) -> u64 {
+		self.connected_at.elapsed().as_millis() as u64
+	}
+
+	pub(crate) fn record_event(&mut self, kind: &str, bytes: usize) {
+		if self.events.len() == HISTORY {
+			self.events.pop_front();
+		}
+		self.events.push_back(DevtoolsEvent {
+			at_ms: self.now_ms(),
+			kind: kind.to_string(),
+			bytes,
+			actions: Vec::new(),
+		});
+	}
+
+	/// Attach decoded WUI action names to the most recent event.
+	pub(crate) fn record_decoded_actions(&mut self, names: Vec<String>) {
+		if let Some(event) = self.events.back_mut() {
+			event.actions.extend(names);
+		}
+	}
+
+	pub(crate) fn record_batch(
+		&mut self,
+		actions: &[ClientAction],
+		bytes: usize,
+		elapsed: Option<Duration>,
+	) {
+		if self.batches.len() == HISTORY {
+			self.batches.pop_front();
+		}
+		self.batches.push_back(DevtoolsBatch {
+			at_ms: self.now_ms(),
+			kinds: actions
+				.iter()
+				.map(|action| action_kind(action).to_string())
+				.collect(),
+			bytes,
+			elapsed_ms: elapsed.map(|elapsed| elapsed.as_secs_f64() * 1000.0),
+		});
+	}
+
+	/// Remember a new root; it goes out with the next snapshot.
+	pub(crate) fn record_tree(&mut self, root: &Item) {
+		self.tree = Some(root.clone());
+	}
+
+	pub(crate) fn snapshot(&mut self) -> ClientAction {
+		ClientAction::Devtools(Box::new(Devtools {
+			events: self.events.iter().cloned().collect(),
+			batches: self.batches.iter().cloned().collect(),
+			tree: self.tree.take(),
+		}))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn recorder_keeps_only_recent_history() {
+		let mut recorder = Recorder::new();
+		for _ in 0..HISTORY + 5 {
+			recorder.record_event("OnClick", 10);
+		}
+		recorder.record_decoded_actions(vec!["Save".to_string()]);
+		recorder.record_batch(
+			&[ClientAction::SetTitle {
+				title: "Todos".to_string(),
+			}],
+			32,
+			Some(Duration::from_millis(2)),
+		);
+
+		let ClientAction::Devtools(snapshot) = recorder.snapshot() else {
+			panic!("expected a devtools snapshot");
+		};
+		assert_eq!(snapshot.events.len(), HISTORY);
+		assert_eq!(snapshot.events.last().unwrap().actions, vec!["Save"]);
+		assert_eq!(snapshot.batches[0].kinds, vec!["setTitle"]);
+		assert_eq!(snapshot.batches[0].elapsed_ms, Some(2.0));
+		assert!(snapshot.tree.is_none());
+	}
+
+	#[tokio::test]
+	async fn decoded_actions_are_captured_per_task() {
+		let ((), actions) = capture_decoded_actions(async {
+			record_decoded_action("Toggle");
+			record_decoded_action("Remove");
+		})
+		.await;
+		assert_eq!(actions, vec!["Toggle", "Remove"]);
+
+		// Outside a capture scope recording is a no-op.
+		record_decoded_action("Ignored");
+	}
+}
@@ -37,6 +37,10 @@ fn inner_diff(changes: &mut Vec<ClientAction>, old: &Item, new: &Item, path: Ite
 				|| old_layout.vresize != new_layout.vresize
 				|| old_layout.hresize != new_layout.hresize
 				|| old_layout.pos != new_layout.pos
+				|| old_layout.events != new_layout.events
+				|| old.handlers != new.handlers
+				|| old.key_combo != new.key_combo
+				|| old.prevent_default != new.prevent_default
 			{
 				changes.push(ClientAction::Replace(Replace {
 					path: path.clone(),
@@ -46,7 +50,7 @@ fn inner_diff(changes: &mut Vec<ClientAction>, old: &Item, new: &Item, path: Ite
 			}
 
 			if old_layout.spacing != new_layout.spacing {
-				println!("{:?} spacing is different", path);
+				log::trace!("{:?} spacing is different", path);
 				sets.push(SetProp {
 					key: PropKey::Spacing,
 					value: Value::Number(new_layout.spacing),
@@ -118,7 +122,12 @@ fn inner_diff(changes: &mut Vec<ClientAction>, old: &Item, new: &Item, path: Ite
 				..
 			},
 		) => {
-			if old_connected != new_connected || old_spacing != new_spacing || old_wrap != new_wrap
+			if old_connected != new_connected
+				|| old_spacing != new_spacing
+				|| old_wrap != new_wrap
+				|| old.handlers != new.handlers
+				|| old.key_combo != new.key_combo
+				|| old.prevent_default != new.prevent_default
 			{
 				changes.push(ClientAction::Replace(Replace {
 					path: path.clone(),
@@ -340,6 +349,9 @@ fn diff_children(
 }
 
 pub fn diff(old: &Item, new: &Item) -> Vec<ClientAction> {
+	#[cfg(feature = "metrics")]
+	let started = std::time::Instant::now();
+	let _span = tracing::debug_span!("diff").entered();
 	log::trace!("diff");
 	log::trace!("{:?}", old);
 	log::trace!("{:?}", new);
@@ -347,6 +359,8 @@ pub fn diff(old: &Item, new: &Item) -> Vec<ClientAction> {
 	let path = Vec::new();
 	inner_diff(&mut changes, old, new, path);
 	log::debug!("diff changes: {:?}", changes);
+	#[cfg(feature = "metrics")]
+	crate::metrics::observe_diff(started.elapsed());
 	changes
 }
 
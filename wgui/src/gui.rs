@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 fn is_default<T: Default + PartialEq>(value: &T) -> bool {
 	value == &T::default()
@@ -161,6 +161,8 @@ pub enum ItemPayload {
 		#[serde(default, skip_serializing_if = "HashMap::is_empty")]
 		events: HashMap<String, u32>,
 	},
+	/// Renders nothing; its key handlers listen on the whole page.
+	Shortcut,
 	#[default]
 	None,
 }
@@ -175,6 +177,15 @@ pub struct ButtonEvents {
 	pub repeat_interval: Option<u32>,
 }
 
+/// One entry of [`Item::handlers`]: the id sent back for the event and the
+/// `inx` sent with it, which overrides [`Item::inx`].
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EventHandler {
+	pub id: u32,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub inx: Option<u32>,
+}
+
 #[derive(Debug, PartialEq, Clone, Default, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[serde(default)]
@@ -252,6 +263,18 @@ pub struct Item {
 	pub form_arg: Option<u32>,
 	#[serde(skip_serializing_if = "is_default")]
 	pub partial_addr: String,
+	/// Handlers keyed by event: `click`, `press`, `release`, `repeat`,
+	/// `keyDown`, `keyUp`, `scrollNearBottom`, or a custom component event.
+	#[serde(skip_serializing_if = "is_default")]
+	pub handlers: BTreeMap<String, EventHandler>,
+	/// The key combination, such as `Ctrl+S`, that the `keyDown` and `keyUp`
+	/// handlers are limited to. Empty means any key.
+	#[serde(skip_serializing_if = "is_default")]
+	pub key_combo: String,
+	/// Whether the browser's default action is suppressed for key events
+	/// that reach a handler.
+	#[serde(skip_serializing_if = "is_default")]
+	pub prevent_default: bool,
 }
 
 pub fn checkbox() -> Item {
@@ -561,6 +584,16 @@ pub fn img(src: &str, alt: &str) -> Item {
 	}
 }
 
+/// A page-wide keyboard shortcut, e.g.
+/// `shortcut("Ctrl+S").prevent_default(true).on_key_down(SAVE)`.
+pub fn shortcut(key_combo: &str) -> Item {
+	Item {
+		payload: ItemPayload::Shortcut,
+		key_combo: key_combo.to_string(),
+		..Default::default()
+	}
+}
+
 pub fn folder_picker() -> Item {
 	Item {
 		payload: ItemPayload::FolderPicker,
@@ -630,6 +663,33 @@ impl Item {
 		self.set_layout_event(|events| events.scroll_near_bottom = Some(id))
 	}
 
+	/// Listen to `event` on any kind of item, so one element can carry
+	/// several handlers, each with its own `inx`.
+	pub fn on(mut self, event: impl Into<String>, id: u32, inx: Option<u32>) -> Self {
+		self.handlers.insert(event.into(), EventHandler { id, inx });
+		self
+	}
+
+	/// Fires while the item or one of its children has focus; use
+	/// [`Item::key_combo`] to react to one combination only.
+	pub fn on_key_down(self, id: u32) -> Self {
+		self.on("keyDown", id, None)
+	}
+
+	pub fn on_key_up(self, id: u32) -> Self {
+		self.on("keyUp", id, None)
+	}
+
+	pub fn key_combo(mut self, combo: &str) -> Self {
+		self.key_combo = combo.to_string();
+		self
+	}
+
+	pub fn prevent_default(mut self, prevent: bool) -> Self {
+		self.prevent_default = prevent;
+		self
+	}
+
 	pub fn custom_event(mut self, name: impl Into<String>, id: u32) -> Self {
 		if let ItemPayload::Custom { events, .. } = &mut self.payload {
 			events.insert(name.into(), id);
@@ -1143,4 +1203,15 @@ mod tests {
 
 		assert_eq!(value["className"], "sidebar compact");
 	}
+
+	#[test]
+	fn shortcut_serializes_key_combo_and_handler() {
+		let item = shortcut("Ctrl+S").prevent_default(true).on_key_down(4);
+		let value = serde_json::to_value(&item).unwrap();
+
+		assert_eq!(value["payload"]["type"], "shortcut");
+		assert_eq!(value["keyCombo"], "Ctrl+S");
+		assert_eq!(value["preventDefault"], true);
+		assert_eq!(value["handlers"]["keyDown"]["id"], 4);
+	}
 }
@@ -0,0 +1,190 @@
+//! Translations and the locale templates render in.
+//!
+//! Catalogs are `i18n/<locale>.ftl` files in the project dir (see
+//! [`wui_core::i18n`] for the format), loaded once at startup:
+//!
+//! ```no_run
+//! wgui::i18n::load_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/i18n")).unwrap();
+//! ```
+//!
+//! Each WebSocket client gets the locale its `Accept-Language` header
+//! negotiates to, which [`set_client_locale`] can override. Renders for a
+//! client run with that locale current, so `t(...)`, `format_number` and
+//! `format_date` in templates follow it. Messages missing from the current
+//! locale fall back to the default locale, then to the key itself.
+
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, LazyLock, RwLock};
+
+pub use wui_core::i18n::{
+	check_keys, negotiate as negotiate_from, plural_category, Arg, Catalog, CatalogError,
+};
+
+use crate::wui::builtins::WuiDate;
+use crate::wui::runtime::WuiValue;
+
+const FALLBACK_LOCALE: &str = "en";
+
+static CATALOGS: RwLock<BTreeMap<String, Arc<Catalog>>> = RwLock::new(BTreeMap::new());
+static DEFAULT_LOCALE: RwLock<Option<String>> = RwLock::new(None);
+static CLIENT_LOCALES: LazyLock<RwLock<HashMap<usize, String>>> = LazyLock::new(Default::default);
+
+tokio::task_local! {
+	static LOCALE: String;
+}
+
+/// Loads every `<locale>.ftl` in `dir`, replacing catalogs of the same
+/// locales, and returns the loaded locales.
+pub fn load_dir(dir: impl AsRef<Path>) -> Result<Vec<String>, String> {
+	let catalogs = read_dir(dir.as_ref())?;
+	let locales = catalogs
+		.iter()
+		.map(|catalog| catalog.locale().to_string())
+		.collect();
+	for catalog in catalogs {
+		add_catalog(catalog);
+	}
+	Ok(locales)
+}
+
+/// Parses the `<locale>.ftl` catalogs in `dir`. Errors name the file and
+/// line.
+pub fn read_dir(dir: &Path) -> Result<Vec<Catalog>, String> {
+	let entries = std::fs::read_dir(dir).map_err(|err| format!("{}: {err}", dir.display()))?;
+	let mut paths = entries
+		.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+		.filter(|path| path.extension().is_some_and(|ext| ext == "ftl"))
+		.collect::<Vec<_>>();
+	paths.sort();
+	let mut catalogs = Vec::new();
+	let mut errors = Vec::new();
+	for path in paths {
+		let Some(locale) = path.file_stem().and_then(|stem| stem.to_str()) else {
+			continue;
+		};
+		let src =
+			std::fs::read_to_string(&path).map_err(|err| format!("{}: {err}", path.display()))?;
+		match Catalog::parse(locale, &src) {
+			Ok(catalog) => catalogs.push(catalog),
+			Err(catalog_errors) => errors.extend(
+				catalog_errors
+					.into_iter()
+					.map(|error| format!("{}:{}: {}", path.display(), error.line, error.message)),
+			),
+		}
+	}
+	if errors.is_empty() {
+		Ok(catalogs)
+	} else {
+		Err(errors.join("\n"))
+	}
+}
+
+pub fn add_catalog(catalog: Catalog) {
+	CATALOGS
+		.write()
+		.unwrap()
+		.insert(catalog.locale().to_string(), Arc::new(catalog));
+}
+
+/// Locales with a loaded catalog.
+pub fn locales() -> Vec<String> {
+	CATALOGS.read().unwrap().keys().cloned().collect()
+}
+
+/// The locale used when negotiation finds no match, `"en"` unless set.
+pub fn default_locale() -> String {
+	DEFAULT_LOCALE
+		.read()
+		.unwrap()
+		.clone()
+		.unwrap_or_else(|| FALLBACK_LOCALE.to_string())
+}
+
+pub fn set_default_locale(locale: impl Into<String>) {
+	*DEFAULT_LOCALE.write().unwrap() = Some(locale.into());
+}
+
+/// The loaded locale best matching an `Accept-Language` header, otherwise
+/// the default locale.
+pub fn negotiate(accept_language: Option<&str>) -> String {
+	let locales = locales();
+	let available = locales.iter().map(String::as_str).collect::<Vec<_>>();
+	accept_language
+		.and_then(|header| negotiate_from(header, &available))
+		.map_or_else(default_locale, str::to_string)
+}
+
+pub fn set_client_locale(client_id: usize, locale: impl Into<String>) {
+	CLIENT_LOCALES
+		.write()
+		.unwrap()
+		.insert(client_id, locale.into());
+}
+
+pub fn client_locale(client_id: usize) -> String {
+	CLIENT_LOCALES
+		.read()
+		.unwrap()
+		.get(&client_id)
+		.cloned()
+		.unwrap_or_else(default_locale)
+}
+
+pub(crate) fn forget_client(client_id: usize) {
+	CLIENT_LOCALES.write().unwrap().remove(&client_id);
+}
+
+/// The locale of the render in progress, the default locale outside one.
+pub fn current_locale() -> String {
+	LOCALE
+		.try_with(Clone::clone)
+		.unwrap_or_else(|_| default_locale())
+}
+
+/// Runs `f` with `locale` current.
+pub fn with_locale<R>(locale: impl Into<String>, f: impl FnOnce() -> R) -> R {
+	LOCALE.sync_scope(locale.into(), f)
+}
+
+/// Polls `future` with `locale` current, across await points.
+pub async fn scope<F: Future>(locale: impl Into<String>, future: F) -> F::Output {
+	LOCALE.scope(locale.into(), future).await
+}
+
+/// Runs `f` with the locale of `client_id` current.
+pub(crate) fn with_client_locale<R>(client_id: usize, f: impl FnOnce() -> R) -> R {
+	with_locale(client_locale(client_id), f)
+}
+
+/// The message `key` in the current locale; the `t` built-in.
+pub fn t(key: &str, args: &[(&str, WuiValue)]) -> String {
+	let args = args
+		.iter()
+		.map(|(name, value)| {
+			let arg = match value {
+				WuiValue::Number(number) => Arg::Number(*number),
+				value => Arg::Text(crate::wui::runtime::value_as_string(value)),
+			};
+			(*name, arg)
+		})
+		.collect::<Vec<_>>();
+	let locale = current_locale();
+	let catalogs = CATALOGS.read().unwrap();
+	[locale, default_locale()]
+		.iter()
+		.find_map(|locale| catalogs.get(locale)?.format(key, &args))
+		.unwrap_or_else(|| key.to_string())
+}
+
+/// `format_number` in the current locale.
+pub fn format_number(value: f64, decimals: Option<f64>) -> String {
+	wui_core::i18n::format_number(&current_locale(), value, decimals)
+}
+
+/// `format_date` in the current locale.
+pub fn format_date<D: WuiDate + ?Sized>(value: &D, pattern: &str) -> String {
+	wui_core::i18n::format_date(&current_locale(), value, pattern)
+}
@@ -1,3 +1,4 @@
+use futures_util::FutureExt;
 #[cfg(feature = "hyper")]
 use server::Server;
 use std::any::{Any, TypeId};
@@ -5,6 +6,7 @@ use std::collections::{BTreeSet, HashMap, HashSet};
 use std::future::Future;
 #[cfg(feature = "hyper")]
 use std::net::SocketAddr;
+use std::panic::AssertUnwindSafe;
 #[cfg(feature = "hyper")]
 use std::path::PathBuf;
 use std::pin::Pin;
@@ -13,12 +15,18 @@ use tokio::sync::mpsc;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
+use tracing::Instrument;
 
 pub mod db_table;
+#[cfg(debug_assertions)]
+mod devtools;
 pub mod diff;
 pub mod dist;
 pub mod edit_distance;
 pub mod gui;
+pub mod i18n;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod pubsub;
 pub mod schema_diff;
 #[cfg(feature = "hyper")]
@@ -34,14 +42,16 @@ pub mod ws;
 pub mod wui;
 
 pub use pubsub::PubSub;
+pub use wui::query::{encode_query, Query, QueryError};
 pub use wui::route_handler::{
 	DynRouteHandler, FromParam, HttpMethod, ParamError, PathParams, Redirect, RouteFormData,
-	RouteFuture, RouteHandler, RouteResult, RuntimeContext, View,
+	RouteFuture, RouteHandler, RouteResult, RuntimeContext, SsrLoading, View,
 };
 pub use wui::runtime::{WdbModel, WdbSchema, WguiModel};
 pub use wui_derive::{partial, route, view, wgui_controller, Wdb, WguiModel};
 
 use crate::ui_client::UiWsWorker;
+use crate::wui::error_pages::{catch_render, SharedErrorPages};
 use crate::wui::routing::{best_route_index, RoutePattern};
 use crate::wui::runtime::{MountResult, RouteContext};
 
@@ -52,7 +62,7 @@ pub use serde_json;
 #[cfg(feature = "hyper")]
 pub use server::{
 	FormData, FromHttpRequest, HttpCtx, HttpHandler, HttpRequest, HttpResponse, HttpRouteSpec,
-	Json, StaticAsset,
+	Json, ServerListener, StaticAsset,
 };
 #[cfg(feature = "sqlite")]
 pub use sqlite::{
@@ -68,6 +78,10 @@ pub use ws::TungsteniteWs;
 pub use ws::{next_client_id, WsMessage, WsStream};
 
 pub(crate) type Sessions = Arc<RwLock<HashMap<usize, Option<String>>>>;
+
+/// Query parameter carrying the content version of fingerprinted assets.
+pub(crate) const STATIC_ASSET_VERSION_PARAM: &str = "wgui-v";
+#[cfg(feature = "hyper")]
 pub(crate) type SsrHydrationRoots = Arc<RwLock<HashMap<String, SsrHydrationRoot>>>;
 type BoxedController = Box<dyn crate::wui::runtime::WuiController + Send>;
 pub(crate) type SharedRouteHandler = Arc<dyn crate::wui::route_handler::DynRouteHandler>;
@@ -91,6 +105,7 @@ type SharedRoutes = Arc<std::sync::RwLock<Vec<RouteEntry>>>;
 /// let router = Router::new().merge(todos_router()).merge(auth_router());
 /// wgui.add_router(router);
 /// ```
+#[derive(Default)]
 pub struct Router {
 	routes: Vec<SharedRouteHandler>,
 	partials: Vec<SharedRouteHandler>,
@@ -121,6 +136,9 @@ impl Router {
 		if handler.method() != HttpMethod::Get {
 			panic!("#[partial] handlers must use GET semantics");
 		}
+		if let Some(name) = handler.name() {
+			crate::wui::route_handler::register_route_url(name, handler.path());
+		}
 		self.partials.push(Arc::new(handler));
 		self
 	}
@@ -137,15 +155,6 @@ impl Router {
 	}
 }
 
-impl Default for Router {
-	fn default() -> Self {
-		Self {
-			routes: Vec::new(),
-			partials: Vec::new(),
-		}
-	}
-}
-
 struct RouteEntry {
 	pattern: RoutePattern,
 	handler: SharedRouteHandler,
@@ -169,6 +178,40 @@ struct PartialCache {
 	tree: Item,
 	last_acked_version: u64,
 }
+
+/// The latest dispatch of each client's page (`None`) and partials. A
+/// [`RouteLoaded`] from an older generation was superseded and is dropped.
+#[derive(Default)]
+struct RouteLoads {
+	latest: HashMap<(usize, Option<String>), u64>,
+	next_generation: u64,
+}
+
+impl RouteLoads {
+	fn begin(&mut self, client_id: usize, partial_addr: Option<String>) -> u64 {
+		self.next_generation += 1;
+		self.latest
+			.insert((client_id, partial_addr), self.next_generation);
+		self.next_generation
+	}
+
+	fn is_current(&self, loaded: &RouteLoaded, client_id: usize) -> bool {
+		self.latest
+			.get(&(client_id, loaded.partial_addr.clone()))
+			.is_some_and(|generation| *generation == loaded.generation)
+	}
+
+	/// A navigation supersedes the page and every partial in it.
+	fn navigated(&mut self, client_id: usize) -> u64 {
+		self.forget_client(client_id);
+		self.begin(client_id, None)
+	}
+
+	fn forget_client(&mut self, client_id: usize) {
+		self.latest.retain(|(id, _), _| *id != client_id);
+	}
+}
+
 type ControllerFuture = Pin<Box<dyn Future<Output = BoxedController> + Send>>;
 type ControllerFactory = Arc<dyn Fn() -> ControllerFuture + Send + Sync>;
 type ControllerProcessFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
@@ -185,6 +228,7 @@ type HttpControllerFactory = Arc<
 		+ Send
 		+ Sync,
 >;
+#[cfg(feature = "hyper")]
 pub(crate) type SsrRenderer =
 	Arc<dyn Fn(RouteContext, Option<String>) -> Option<SsrResponse> + Send + Sync>;
 type SsrComponentFactories = Arc<std::sync::RwLock<Vec<(String, ControllerFactory)>>>;
@@ -198,20 +242,34 @@ enum PageMount {
 	Redirect(String),
 }
 
+#[cfg(feature = "hyper")]
 pub(crate) struct SsrHydrationRoot {
 	pub path: String,
 	pub item: Item,
 	pub title: Option<String>,
 }
 
+#[cfg(feature = "hyper")]
 pub(crate) enum SsrResponse {
 	Render {
 		item: Box<Item>,
 		title: Option<String>,
+		status: u16,
 	},
 	Redirect(String),
 }
 
+#[cfg(feature = "hyper")]
+impl From<View> for SsrResponse {
+	fn from(view: View) -> Self {
+		SsrResponse::Render {
+			item: Box::new(view.item),
+			title: view.title,
+			status: view.status,
+		}
+	}
+}
+
 pub fn custom_component_entry_for_path(path: &str) -> String {
 	let trimmed = path.trim().trim_matches('/');
 	format!("/fs/wgui-controllers/{trimmed}/controller.js")
@@ -250,11 +308,9 @@ fn custom_component_asset_for_type<C>() -> String {
 
 fn resolve_custom_component_entries(item: &mut Item, entries: &CustomComponentEntries) {
 	match &mut item.payload {
-		ItemPayload::Custom { name, entry, .. } => {
-			if entry.is_empty() {
-				if let Some(resolved) = entries.get(name) {
-					*entry = resolved.clone();
-				}
+		ItemPayload::Custom { name, entry, .. } if entry.is_empty() => {
+			if let Some(resolved) = entries.get(name) {
+				*entry = resolved.clone();
 			}
 		}
 		ItemPayload::Layout(layout) => {
@@ -498,29 +554,96 @@ fn page_route_context(
 	pattern: &RoutePattern,
 	path: &str,
 	query: &HashMap<String, String>,
+	query_string: &str,
 ) -> Option<RouteContext> {
 	let matched = pattern.match_path(path)?;
 	Some(RouteContext {
 		path: path.to_string(),
 		params: matched.params,
 		query: query.clone(),
+		query_string: query_string.to_string(),
 	})
 }
 
-fn component_route_context(path: &str, query: &HashMap<String, String>) -> RouteContext {
+fn component_route_context(
+	path: &str,
+	query: &HashMap<String, String>,
+	query_string: &str,
+) -> RouteContext {
 	RouteContext {
 		path: path.to_string(),
 		params: HashMap::new(),
 		query: query.clone(),
+		query_string: query_string.to_string(),
+	}
+}
+
+/// Renders `controller`, or the error page in its place if the render
+/// panics.
+fn render_controller(
+	controller: &(dyn crate::wui::runtime::WuiController + Send),
+	route: &RouteContext,
+	error_pages: &SharedErrorPages,
+) -> Item {
+	catch_render(controller, route)
+		.unwrap_or_else(|message| error_pages.read().unwrap().error_view(&message, route).item)
+}
+
+/// Await a controller's event handler, forwarding the WUI actions it decoded
+/// to the client's devtools overlay when dev mode is on. A handler that
+/// panics shows the client the error page and counts as unhandled.
+async fn dispatch_controller_event(
+	handle: &WguiHandle,
+	client_id: usize,
+	route: &RouteContext,
+	error_pages: &SharedErrorPages,
+	dispatch: impl Future<Output = bool>,
+) -> bool {
+	let dispatch = AssertUnwindSafe(dispatch).catch_unwind();
+	#[cfg(debug_assertions)]
+	let result = if handle.devtools_enabled() {
+		let (result, actions) = devtools::capture_decoded_actions(dispatch).await;
+		if !actions.is_empty() {
+			handle.send_decoded_actions(client_id, actions).await;
+		}
+		result
+	} else {
+		dispatch.await
+	};
+	#[cfg(not(debug_assertions))]
+	let result = dispatch.await;
+	match result {
+		Ok(handled) => handled,
+		Err(payload) => {
+			let message = crate::wui::error_pages::panic_message(payload.as_ref());
+			let view = error_pages.read().unwrap().error_view(&message, route);
+			handle.render(client_id, view.item).await;
+			false
+		}
 	}
 }
 
-fn route_target(url: &str) -> (String, HashMap<String, String>) {
-	let (path, query) = url.split_once('?').unwrap_or((url, ""));
-	let query = form_urlencoded::parse(query.as_bytes())
+fn route_target(url: &str) -> (String, HashMap<String, String>, String) {
+	let (path, query_string) = url.split_once('?').unwrap_or((url, ""));
+	let query = form_urlencoded::parse(query_string.as_bytes())
 		.into_owned()
 		.collect::<HashMap<_, _>>();
-	(path.to_string(), query)
+	(path.to_string(), query, query_string.to_string())
+}
+
+/// The raw query string of the page a form was submitted from: the one the
+/// client sent, or the current route's when it shows the same query.
+fn form_submit_query_string(
+	submit: &crate::types::FormSubmit,
+	current_route: Option<&RouteContext>,
+) -> String {
+	if !submit.query_string.is_empty() {
+		return submit.query_string.clone();
+	}
+	current_route
+		.filter(|route| route.query == submit.query)
+		.map(|route| route.query_string.clone())
+		.unwrap_or_default()
 }
 
 #[derive(Clone)]
@@ -528,6 +651,9 @@ pub struct WguiHandle {
 	event_tx: mpsc::UnboundedSender<ClientMessage>,
 	clients: Clients,
 	sessions: Sessions,
+	/// Set by [`Wgui::enable_devtools`]; shared by every clone of the handle.
+	#[cfg(debug_assertions)]
+	devtools: Arc<std::sync::atomic::AtomicBool>,
 }
 
 impl WguiHandle {
@@ -540,43 +666,73 @@ impl WguiHandle {
 			event_tx,
 			clients,
 			sessions,
+			#[cfg(debug_assertions)]
+			devtools: Arc::new(std::sync::atomic::AtomicBool::new(false)),
 		}
 	}
 
+	#[cfg(debug_assertions)]
+	fn devtools_enabled(&self) -> bool {
+		self.devtools.load(std::sync::atomic::Ordering::Relaxed)
+	}
+
 	pub async fn handle_ws<S>(&self, ws: S) -> usize
 	where
 		S: WsStream + 'static,
 	{
-		let id = next_client_id();
-		let event_tx = self.event_tx.clone();
-		let clients = self.clients.clone();
-		log::info!("websocket worker created {}", id);
-		tokio::spawn(async move {
-			let worker = UiWsWorker::new(id, ws, event_tx, clients).await;
-			worker.run().await;
-		});
+		self.accept_ws(ws, None, None).await
+	}
 
-		let mut sessions = self.sessions.write().await;
-		sessions.insert(id, None);
+	pub async fn handle_ws_with_session<S>(&self, ws: S, session: Option<String>) -> usize
+	where
+		S: WsStream + 'static,
+	{
+		self.accept_ws(ws, session, None).await
+	}
 
-		id
+	/// [`WguiHandle::handle_ws_with_session`] for a client whose renders use
+	/// `locale` from the first one on.
+	pub async fn handle_ws_with_locale<S>(
+		&self,
+		ws: S,
+		session: Option<String>,
+		locale: String,
+	) -> usize
+	where
+		S: WsStream + 'static,
+	{
+		self.accept_ws(ws, session, Some(locale)).await
 	}
 
-	pub async fn handle_ws_with_session<S>(&self, ws: S, session: Option<String>) -> usize
+	/// Registers the client's session and locale before its worker starts,
+	/// since the worker reports `Connected` right away.
+	async fn accept_ws<S>(&self, ws: S, session: Option<String>, locale: Option<String>) -> usize
 	where
 		S: WsStream + 'static,
 	{
-		let id = self.handle_ws(ws).await;
-		let mut sessions = self.sessions.write().await;
-		sessions.insert(id, session);
+		let id = next_client_id();
+		if let Some(locale) = locale {
+			crate::i18n::set_client_locale(id, locale);
+		}
+		self.sessions.write().await.insert(id, session);
+		let event_tx = self.event_tx.clone();
+		let clients = self.clients.clone();
+		#[cfg(debug_assertions)]
+		let devtools = self.devtools_enabled();
+		#[cfg(not(debug_assertions))]
+		let devtools = false;
+		log::info!("websocket worker created {}", id);
+		tokio::spawn(async move {
+			let worker = UiWsWorker::new(id, ws, event_tx, clients, devtools).await;
+			worker.run().await;
+		});
 		id
 	}
 
 	pub fn refresh(&self, client_id: usize) {
-		let _ = self.event_tx.send(ClientMessage {
-			client_id,
-			event: ClientEvent::Refresh,
-		});
+		let _ = self
+			.event_tx
+			.send(ClientMessage::new(client_id, ClientEvent::Refresh));
 	}
 
 	pub async fn render(&self, client_id: usize, item: Item) {
@@ -589,7 +745,7 @@ impl WguiHandle {
 				return;
 			}
 		};
-		sender.send(Command::Render(item)).unwrap();
+		sender.send(Command::Render(item).into()).unwrap();
 	}
 
 	pub async fn replace_root(&self, client_id: usize, item: Item) {
@@ -602,7 +758,7 @@ impl WguiHandle {
 				return;
 			}
 		};
-		sender.send(Command::ReplaceRoot(item)).unwrap();
+		sender.send(Command::ReplaceRoot(item).into()).unwrap();
 	}
 
 	pub async fn hydrate_root(&self, client_id: usize, item: Item) {
@@ -615,7 +771,7 @@ impl WguiHandle {
 				return;
 			}
 		};
-		sender.send(Command::HydrateRoot(item)).unwrap();
+		sender.send(Command::HydrateRoot(item).into()).unwrap();
 	}
 
 	pub async fn set_title(&self, client_id: usize, title: &str) {
@@ -627,7 +783,9 @@ impl WguiHandle {
 				return;
 			}
 		};
-		sender.send(Command::SetTitle(title.to_string())).unwrap();
+		sender
+			.send(Command::SetTitle(title.to_string()).into())
+			.unwrap();
 	}
 
 	pub async fn push_state(&self, client_id: usize, url: &str) {
@@ -639,7 +797,9 @@ impl WguiHandle {
 				return;
 			}
 		};
-		sender.send(Command::PushState(url.to_string())).unwrap();
+		sender
+			.send(Command::PushState(url.to_string()).into())
+			.unwrap();
 	}
 
 	pub async fn navigate(&self, client_id: usize, url: &str) {
@@ -651,7 +811,9 @@ impl WguiHandle {
 				return;
 			}
 		};
-		sender.send(Command::Navigate(url.to_string())).unwrap();
+		sender
+			.send(Command::Navigate(url.to_string()).into())
+			.unwrap();
 	}
 
 	pub async fn enable_web_push(
@@ -688,6 +850,52 @@ impl WguiHandle {
 		.await;
 	}
 
+	/// Show the reload's diagnostics (or clear them) on every client and
+	/// queue a re-render check for clients viewing a reloaded template.
+	#[cfg(feature = "hot-reload")]
+	pub(crate) async fn templates_reloaded(&self, report: crate::wui::hot_reload::ReloadReport) {
+		let clients = self.clients.read().await;
+		for (client_id, sender) in clients.iter() {
+			let _ = sender.send(
+				Command::Actions(vec![ClientAction::TemplateDiagnostics {
+					diagnostics: report.diagnostics.clone(),
+				}])
+				.into(),
+			);
+			if !report.reloaded.is_empty() {
+				let _ = self.event_tx.send(ClientMessage::new(
+					*client_id,
+					ClientEvent::TemplatesReloaded {
+						modules: report.reloaded.clone(),
+					},
+				));
+			}
+		}
+	}
+
+	/// Have every client pick up the `changed` static files.
+	#[cfg(feature = "hot-reload")]
+	pub(crate) async fn assets_changed(&self, changed: &[PathBuf]) {
+		let stylesheets_only = changed
+			.iter()
+			.all(|path| path.extension().is_some_and(|ext| ext == "css"));
+		log::info!("static assets changed: {changed:?}");
+		let clients = self.clients.read().await;
+		for sender in clients.values() {
+			let _ = sender.send(
+				Command::Actions(vec![ClientAction::ReloadAssets { stylesheets_only }]).into(),
+			);
+		}
+	}
+
+	#[cfg(debug_assertions)]
+	async fn send_decoded_actions(&self, client_id: usize, actions: Vec<String>) {
+		let clients = self.clients.read().await;
+		if let Some(sender) = clients.get(&client_id) {
+			let _ = sender.send(Command::DecodedActions(actions).into());
+		}
+	}
+
 	pub async fn send_actions(&self, client_id: usize, actions: Vec<ClientAction>) {
 		if actions.is_empty() {
 			return;
@@ -700,7 +908,7 @@ impl WguiHandle {
 				return;
 			}
 		};
-		sender.send(Command::Actions(actions)).unwrap();
+		sender.send(Command::Actions(actions).into()).unwrap();
 	}
 
 	pub async fn session_for_client(&self, client_id: usize) -> Option<String> {
@@ -869,6 +1077,10 @@ where
 		self.inner.route_title(path)
 	}
 
+	fn wui_template(&self) -> Option<&'static crate::wui::runtime::Template> {
+		self.inner.wui_template()
+	}
+
 	fn set_runtime_context(&mut self, client_id: Option<usize>, session: Option<String>) {
 		self.ctx.set_current_client(client_id);
 		self.ctx.set_current_session(session.clone());
@@ -898,6 +1110,7 @@ pub struct Wgui<DB = ()> {
 	partials: SharedRoutes,
 	db: Arc<DB>,
 	contexts: SharedContexts,
+	error_pages: SharedErrorPages,
 	#[cfg(feature = "hyper")]
 	http_handler: server::SharedHttpHandler,
 	#[cfg(feature = "hyper")]
@@ -908,11 +1121,35 @@ pub struct Wgui<DB = ()> {
 	static_mounts: server::SharedStaticMounts,
 	#[cfg(feature = "hyper")]
 	ssr_hydration_roots: SsrHydrationRoots,
+	#[cfg(all(feature = "hyper", feature = "metrics"))]
+	metrics_path: server::SharedMetricsPath,
 }
 
 impl Wgui<()> {
+	/// Bind `addr` and start serving.
+	///
+	/// Panics if the address cannot be bound; use [`Wgui::try_new`] to handle
+	/// bind errors instead.
 	#[cfg(feature = "hyper")]
 	pub fn new(addr: SocketAddr) -> Self {
+		Self::try_new(addr).unwrap_or_else(|err| panic!("failed to bind {addr}: {err}"))
+	}
+
+	/// Bind `addr` and start serving, returning bind errors such as a port
+	/// conflict to the caller.
+	#[cfg(feature = "hyper")]
+	pub fn try_new(addr: SocketAddr) -> std::io::Result<Self> {
+		Ok(Self::new_with_listener(ServerListener::bind(addr)?))
+	}
+
+	/// Serve on an already bound listener.
+	///
+	/// Accepts a `tokio::net::TcpListener`, for example one inherited through
+	/// systemd socket activation, or a `tokio::net::UnixListener` placed behind
+	/// a reverse proxy.
+	#[cfg(feature = "hyper")]
+	pub fn new_with_listener(listener: impl Into<ServerListener>) -> Self {
+		let listener = listener.into();
 		let (events_tx, events_rx) = mpsc::unbounded_channel();
 		let clients: Clients = Arc::new(RwLock::new(HashMap::new()));
 		let sessions: Sessions = Arc::new(RwLock::new(HashMap::new()));
@@ -923,9 +1160,14 @@ impl Wgui<()> {
 		let app_css = Arc::new(std::sync::RwLock::new(None));
 		let static_mounts = Arc::new(std::sync::RwLock::new(Vec::new()));
 		let ssr_hydration_roots = Arc::new(RwLock::new(HashMap::new()));
+		#[cfg(feature = "metrics")]
+		let metrics_path = Arc::new(std::sync::RwLock::new(
+			crate::metrics::DEFAULT_METRICS_PATH.to_string(),
+		));
 		let routes: SharedRoutes = Arc::new(std::sync::RwLock::new(Vec::new()));
 		let partials: SharedRoutes = Arc::new(std::sync::RwLock::new(Vec::new()));
 		let contexts: SharedContexts = Arc::new(std::sync::RwLock::new(HashMap::new()));
+		let error_pages: SharedErrorPages = Arc::default();
 
 		{
 			let clients = clients.clone();
@@ -938,8 +1180,11 @@ impl Wgui<()> {
 			let app_css = app_css.clone();
 			let static_mounts = static_mounts.clone();
 			let ssr_hydration_roots = ssr_hydration_roots.clone();
+			#[cfg(feature = "metrics")]
+			let metrics_path = metrics_path.clone();
 			let routes = routes.clone();
 			let contexts = contexts.clone();
+			let error_pages = error_pages.clone();
 			let ssr: Option<SsrRenderer> = Some(Arc::new(
 				move |route: RouteContext, session: Option<String>| {
 					if let Some((handler, state_type_id, params)) = {
@@ -975,35 +1220,62 @@ impl Wgui<()> {
 					} {
 						let ctx_any = contexts.read().unwrap().get(&state_type_id).cloned()?;
 						let result = tokio::task::block_in_place(|| {
-							tokio::runtime::Handle::current().block_on(handler.call_dyn(
-								ctx_any,
-								params,
-								crate::wui::route_handler::RouteFormData::default(),
-								RuntimeContext {
-									client_id: None,
-									session: session.clone(),
-									route: Some(route.clone()),
-								},
-							))
+							tokio::runtime::Handle::current().block_on(async {
+								let result = handler
+									.call_dyn(
+										ctx_any,
+										params,
+										crate::wui::route_handler::RouteFormData::default(),
+										RuntimeContext {
+											client_id: None,
+											session: session.clone(),
+											route: Some(route.clone()),
+										},
+									)
+									.await;
+								// A fallback document carries the placeholder; the
+								// client loads the data once it connects.
+								match handler.ssr_loading() {
+									SsrLoading::Wait => {
+										crate::wui::route_handler::resolve_loading(result).await
+									}
+									SsrLoading::Fallback => result,
+								}
+							})
 						});
-						return match result {
-							RouteResult::View(view) => Some(SsrResponse::Render {
-								item: Box::new(view.item),
-								title: view.title,
-							}),
+						match result {
+							RouteResult::View(view) => return Some((*view).into()),
 							RouteResult::Redirect(redirect) => {
-								Some(SsrResponse::Redirect(redirect.0))
+								return Some(SsrResponse::Redirect(redirect.0));
 							}
-							RouteResult::NotFound => None,
-						};
+							RouteResult::BadRequest(message) => {
+								let view = error_pages
+									.read()
+									.unwrap()
+									.bad_request_view(&message, &route);
+								return Some(view.into());
+							}
+							RouteResult::Error(message) => {
+								let view = error_pages.read().unwrap().error_view(&message, &route);
+								return Some(view.into());
+							}
+							// Pages and components get a turn, as with websocket
+							// navigation.
+							RouteResult::NotFound => {}
+						}
 					}
 					let page = {
 						let pages = ssr_pages.read().unwrap();
 						best_route_index(&pages, &route.path, |(pattern, _)| pattern).and_then(
 							|index| {
 								let pattern = &pages[index].0;
-								page_route_context(pattern, &route.path, &route.query)
-									.map(|route| (pages[index].1.clone(), route))
+								page_route_context(
+									pattern,
+									&route.path,
+									&route.query,
+									&route.query_string,
+								)
+								.map(|route| (pages[index].1.clone(), route))
 							},
 						)
 					};
@@ -1022,9 +1294,16 @@ impl Wgui<()> {
 								let title = controller
 									.title()
 									.or_else(|| controller.route_title(&route.path));
-								Some(SsrResponse::Render {
-									item: Box::new(controller.render_with_route(&route)),
-									title,
+								Some(match catch_render(&*controller, &route) {
+									Ok(item) => SsrResponse::Render {
+										item: Box::new(item),
+										title,
+										status: 200,
+									},
+									Err(message) => {
+										let pages = error_pages.read().unwrap();
+										pages.error_view(&message, &route).into()
+									}
 								})
 							}
 							PageMount::Redirect(url) => Some(SsrResponse::Redirect(url)),
@@ -1033,14 +1312,20 @@ impl Wgui<()> {
 
 					let factory = {
 						let factories = ssr_components.read().unwrap();
-						let index = best_component_route_index(
+						let Some(index) = best_component_route_index(
 							&factories,
 							&route.path,
 							|(route_path, _)| route_path.as_str(),
-						)?;
+						) else {
+							let pages = error_pages.read().unwrap();
+							return pages
+								.configured_not_found_view(&route)
+								.map(SsrResponse::from);
+						};
 						factories[index].1.clone()
 					};
-					let route = component_route_context(&route.path, &route.query);
+					let route =
+						component_route_context(&route.path, &route.query, &route.query_string);
 					let mut controller = tokio::task::block_in_place(|| {
 						tokio::runtime::Handle::current().block_on((factory)())
 					});
@@ -1049,15 +1334,23 @@ impl Wgui<()> {
 					let title = controller
 						.title()
 						.or_else(|| controller.route_title(&route.path));
-					Some(SsrResponse::Render {
-						item: Box::new(controller.render_with_route(&route)),
-						title,
+					Some(match catch_render(&*controller, &route) {
+						Ok(item) => SsrResponse::Render {
+							item: Box::new(item),
+							title,
+							status: 200,
+						},
+						Err(message) => error_pages
+							.read()
+							.unwrap()
+							.error_view(&message, &route)
+							.into(),
 					})
 				},
 			));
 			tokio::spawn(async move {
 				Server::new(server::ServerConfig {
-					addr,
+					listener,
 					event_tx,
 					clients,
 					sessions,
@@ -1067,8 +1360,9 @@ impl Wgui<()> {
 					app_css,
 					static_mounts,
 					ssr_hydration_roots,
+					#[cfg(feature = "metrics")]
+					metrics_path,
 				})
-				.await
 				.run()
 				.await;
 			});
@@ -1087,19 +1381,38 @@ impl Wgui<()> {
 			partials,
 			db: Arc::new(()),
 			contexts,
+			error_pages,
 			http_handler,
 			http_routes,
 			app_css,
 			static_mounts,
 			ssr_hydration_roots,
+			#[cfg(feature = "metrics")]
+			metrics_path,
 		}
 	}
 
+	/// Bind `addr` and serve with `renderer` as the server-rendered first
+	/// paint.
+	///
+	/// Panics if the address cannot be bound; use [`Wgui::try_new_with_ssr`]
+	/// to handle bind errors instead.
 	#[cfg(feature = "hyper")]
 	pub fn new_with_ssr(
 		addr: SocketAddr,
 		renderer: std::sync::Arc<dyn Fn() -> Item + Send + Sync>,
 	) -> Self {
+		Self::try_new_with_ssr(addr, renderer)
+			.unwrap_or_else(|err| panic!("failed to bind {addr}: {err}"))
+	}
+
+	/// Like [`Wgui::new_with_ssr`], returning bind errors to the caller.
+	#[cfg(feature = "hyper")]
+	pub fn try_new_with_ssr(
+		addr: SocketAddr,
+		renderer: std::sync::Arc<dyn Fn() -> Item + Send + Sync>,
+	) -> std::io::Result<Self> {
+		let listener = ServerListener::bind(addr)?;
 		let (events_tx, events_rx) = mpsc::unbounded_channel();
 		let clients: Clients = Arc::new(RwLock::new(HashMap::new()));
 		let sessions: Sessions = Arc::new(RwLock::new(HashMap::new()));
@@ -1110,9 +1423,14 @@ impl Wgui<()> {
 		let app_css = Arc::new(std::sync::RwLock::new(None));
 		let static_mounts = Arc::new(std::sync::RwLock::new(Vec::new()));
 		let ssr_hydration_roots = Arc::new(RwLock::new(HashMap::new()));
+		#[cfg(feature = "metrics")]
+		let metrics_path = Arc::new(std::sync::RwLock::new(
+			crate::metrics::DEFAULT_METRICS_PATH.to_string(),
+		));
 		let routes: SharedRoutes = Arc::new(std::sync::RwLock::new(Vec::new()));
 		let partials: SharedRoutes = Arc::new(std::sync::RwLock::new(Vec::new()));
 		let contexts: SharedContexts = Arc::new(std::sync::RwLock::new(HashMap::new()));
+		let error_pages: SharedErrorPages = Arc::default();
 
 		{
 			let clients = clients.clone();
@@ -1123,17 +1441,20 @@ impl Wgui<()> {
 			let app_css = app_css.clone();
 			let static_mounts = static_mounts.clone();
 			let ssr_hydration_roots = ssr_hydration_roots.clone();
+			#[cfg(feature = "metrics")]
+			let metrics_path = metrics_path.clone();
 			let ssr: Option<SsrRenderer> = Some(Arc::new(
 				move |_route: RouteContext, _session: Option<String>| {
 					Some(SsrResponse::Render {
 						item: Box::new((renderer)()),
 						title: None,
+						status: 200,
 					})
 				},
 			));
 			tokio::spawn(async move {
 				Server::new(server::ServerConfig {
-					addr,
+					listener,
 					event_tx,
 					clients,
 					sessions,
@@ -1143,14 +1464,15 @@ impl Wgui<()> {
 					app_css,
 					static_mounts,
 					ssr_hydration_roots,
+					#[cfg(feature = "metrics")]
+					metrics_path,
 				})
-				.await
 				.run()
 				.await;
 			});
 		}
 
-		Self {
+		Ok(Self {
 			events_rx,
 			handle: WguiHandle::new(events_tx, clients, sessions),
 			components: Vec::new(),
@@ -1163,12 +1485,15 @@ impl Wgui<()> {
 			partials,
 			db: Arc::new(()),
 			contexts,
+			error_pages,
 			http_handler,
 			http_routes,
 			app_css,
 			static_mounts,
 			ssr_hydration_roots,
-		}
+			#[cfg(feature = "metrics")]
+			metrics_path,
+		})
 	}
 
 	pub fn new_without_server() -> Self {
@@ -1180,6 +1505,7 @@ impl Wgui<()> {
 		let routes: SharedRoutes = Arc::new(std::sync::RwLock::new(Vec::new()));
 		let partials: SharedRoutes = Arc::new(std::sync::RwLock::new(Vec::new()));
 		let contexts: SharedContexts = Arc::new(std::sync::RwLock::new(HashMap::new()));
+		let error_pages: SharedErrorPages = Arc::default();
 		#[cfg(feature = "hyper")]
 		let http_handler = Arc::new(std::sync::RwLock::new(None));
 		#[cfg(feature = "hyper")]
@@ -1190,6 +1516,10 @@ impl Wgui<()> {
 		let static_mounts = Arc::new(std::sync::RwLock::new(Vec::new()));
 		#[cfg(feature = "hyper")]
 		let ssr_hydration_roots = Arc::new(RwLock::new(HashMap::new()));
+		#[cfg(all(feature = "hyper", feature = "metrics"))]
+		let metrics_path = Arc::new(std::sync::RwLock::new(
+			crate::metrics::DEFAULT_METRICS_PATH.to_string(),
+		));
 
 		Self {
 			events_rx,
@@ -1204,6 +1534,7 @@ impl Wgui<()> {
 			partials,
 			db: Arc::new(()),
 			contexts,
+			error_pages,
 			#[cfg(feature = "hyper")]
 			http_handler,
 			#[cfg(feature = "hyper")]
@@ -1214,6 +1545,8 @@ impl Wgui<()> {
 			static_mounts,
 			#[cfg(feature = "hyper")]
 			ssr_hydration_roots,
+			#[cfg(all(feature = "hyper", feature = "metrics"))]
+			metrics_path,
 		}
 	}
 }
@@ -1239,6 +1572,7 @@ where
 			partials: self.partials,
 			db: Arc::new(db),
 			contexts: Arc::new(std::sync::RwLock::new(HashMap::new())),
+			error_pages: self.error_pages,
 			#[cfg(feature = "hyper")]
 			http_handler: self.http_handler,
 			#[cfg(feature = "hyper")]
@@ -1249,6 +1583,8 @@ where
 			static_mounts: self.static_mounts,
 			#[cfg(feature = "hyper")]
 			ssr_hydration_roots: self.ssr_hydration_roots,
+			#[cfg(all(feature = "hyper", feature = "metrics"))]
+			metrics_path: self.metrics_path,
 		}
 	}
 
@@ -1269,6 +1605,7 @@ where
 						path: request.path.clone(),
 						params: ctx.params.clone(),
 						query: request.query.clone(),
+						query_string: request.query_string.clone(),
 					};
 					match handler_factory(route, ctx.session.clone(), route_id, request, ctx).await
 					{
@@ -1321,6 +1658,62 @@ where
 		*self.app_css.write().unwrap() = Some(css.into());
 	}
 
+	/// Render `template` (usually `wui/error.wui`) with status 500 when a
+	/// route handler returns `Err` or panics, or a controller panics. Its
+	/// state has `status`, `message` and `path`; `message` is left empty in
+	/// release builds.
+	pub fn set_error_page(&self, template: crate::wui::runtime::Template) {
+		self.error_pages.write().unwrap().error = Some(template);
+	}
+
+	/// Render `template` (usually `wui/not_found.wui`) with status 404 for
+	/// paths no route, page or component claims. Its state is like the
+	/// error page's. Without one, such paths get the client shell as before.
+	pub fn set_not_found_page(&self, template: crate::wui::runtime::Template) {
+		self.error_pages.write().unwrap().not_found = Some(template);
+	}
+
+	/// Turn on the in-browser devtools overlay for clients that connect from
+	/// now on. Only available in debug builds.
+	#[cfg(debug_assertions)]
+	pub fn enable_devtools(&self) {
+		self.handle
+			.devtools
+			.store(true, std::sync::atomic::Ordering::Relaxed);
+	}
+
+	/// Watch `dir` (usually the crate's `wui/` directory) and hot reload
+	/// runtime templates when they or anything they import change. Clients
+	/// viewing a reloaded template are re-rendered; parse errors are shown as
+	/// an overlay in the browser until the template parses again.
+	///
+	/// Files and directories mounted before this call are watched too: a
+	/// changed stylesheet is re-fetched in place, any other asset reloads the
+	/// page.
+	#[cfg(feature = "hot-reload")]
+	pub fn enable_hot_reload(&self, dir: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+		#[cfg(feature = "hyper")]
+		let (assets, assets_changed) = {
+			let mounts = self.static_mounts.clone();
+			(
+				server::static_mount_paths(&mounts),
+				move |changed: &[PathBuf]| server::refresh_static_versions(&mounts, changed),
+			)
+		};
+		#[cfg(not(feature = "hyper"))]
+		let (assets, assets_changed) = (Vec::new(), |_: &[PathBuf]| {});
+		crate::wui::hot_reload::watch(dir.as_ref(), assets, assets_changed, self.handle())
+	}
+
+	/// Serve Prometheus metrics at `path` instead of `/metrics`.
+	///
+	/// The registry is process-wide: every `Wgui` in the process serves the
+	/// same numbers, summed over all of them.
+	#[cfg(all(feature = "hyper", feature = "metrics"))]
+	pub fn set_metrics_path(&self, path: impl Into<String>) {
+		*self.metrics_path.write().unwrap() = path.into();
+	}
+
 	#[cfg(feature = "hyper")]
 	pub fn set_http_handler<F, Fut>(&self, handler: F)
 	where
@@ -1568,10 +1961,8 @@ where
 			while let Some(command) = command_rx.recv().await {
 				match command {
 					crate::wui::runtime::RuntimeCommand::RenderPartial { topic } => {
-						let _ = event_tx.send(ClientMessage {
-							client_id: 0,
-							event: ClientEvent::RenderPartial { topic },
-						});
+						let _ = event_tx
+							.send(ClientMessage::new(0, ClientEvent::RenderPartial { topic }));
 					}
 					crate::wui::runtime::RuntimeCommand::Refresh { client_id } => {
 						handle.refresh(client_id);
@@ -1785,6 +2176,9 @@ where
 	}
 
 	fn add_route_handler(&mut self, handler: SharedRouteHandler) {
+		if let Some(name) = handler.name() {
+			crate::wui::route_handler::register_route_url(name, handler.path());
+		}
 		let pattern = RoutePattern::parse(handler.path());
 		let state_type_id = handler.state_type_id();
 		let method = handler.method();
@@ -1798,6 +2192,7 @@ where
 			let contexts = self.contexts.clone();
 			let handler_arc = handler.clone();
 			let app_css = self.app_css.clone();
+			let error_pages = self.error_pages.clone();
 			let handler_path = handler_arc.path().to_string();
 			let handler_method = method.as_str().to_string();
 			let http_handler: server::HttpRouteHandler =
@@ -1805,6 +2200,7 @@ where
 					let handler_arc = handler_arc.clone();
 					let contexts = contexts.clone();
 					let app_css = app_css.clone();
+					let error_pages = error_pages.clone();
 					let path_str = path_str.clone();
 					Box::pin(async move {
 						let state_type_id = handler_arc.state_type_id();
@@ -1822,11 +2218,12 @@ where
 							path: http_ctx.path.clone(),
 							params: params_map.clone(),
 							query: http_ctx.query.clone(),
+							query_string: request.query_string.clone(),
 						};
 						let runtime = crate::wui::route_handler::RuntimeContext {
 							client_id: None,
 							session: http_ctx.session.clone(),
-							route: Some(route),
+							route: Some(route.clone()),
 						};
 						let params = crate::wui::route_handler::PathParams(params_map);
 						let form = if request
@@ -1846,7 +2243,14 @@ where
 						} else {
 							crate::wui::route_handler::RouteFormData::from_urlencoded(&request.body)
 						};
-						let result = handler_arc.call_dyn(ctx_any, params, form, runtime).await;
+						let locale = crate::i18n::negotiate(
+							request.headers.get("accept-language").map(String::as_str),
+						);
+						let result = crate::i18n::scope(locale, async {
+							let result = handler_arc.call_dyn(ctx_any, params, form, runtime).await;
+							crate::wui::route_handler::resolve_loading(result).await
+						})
+						.await;
 						match result {
 							crate::wui::route_handler::RouteResult::Redirect(redirect) => {
 								if redirect.0.is_empty() {
@@ -1862,7 +2266,19 @@ where
 								Self::route_view_http_response(&view, &app_css)
 							}
 							crate::wui::route_handler::RouteResult::NotFound => {
-								HttpResponse::new(404, "not found")
+								let view = error_pages.read().unwrap().not_found_view(&route);
+								Self::route_view_http_response(&view, &app_css)
+							}
+							crate::wui::route_handler::RouteResult::BadRequest(message) => {
+								let view = error_pages
+									.read()
+									.unwrap()
+									.bad_request_view(&message, &route);
+								Self::route_view_http_response(&view, &app_css)
+							}
+							crate::wui::route_handler::RouteResult::Error(message) => {
+								let view = error_pages.read().unwrap().error_view(&message, &route);
+								Self::route_view_http_response(&view, &app_css)
 							}
 						}
 					})
@@ -1988,7 +2404,16 @@ where
 			.get(&state_type_id)
 			.cloned()
 			.expect("missing Ctx<T> for #[route] handler; call wgui.set_ctx(...) first");
-		handler
+		let span = tracing::info_span!(
+			"route_dispatch",
+			client_id,
+			session = session.as_deref(),
+			method = handler.method().as_str(),
+			route = handler.path(),
+			path = %route.path,
+		);
+		let error_route = route.clone();
+		let future = handler
 			.call_dyn(
 				ctx_any,
 				route_match.params,
@@ -1999,7 +2424,120 @@ where
 					route: Some(route),
 				},
 			)
-			.await
+			.instrument(span);
+		let result = match client_id {
+			Some(client_id) => {
+				crate::i18n::scope(crate::i18n::client_locale(client_id), future).await
+			}
+			None => future.await,
+		};
+		self.render_route_error(result, &error_route)
+	}
+
+	/// Replaces a failed handler's result with the error page, so callers only
+	/// handle views, redirects and `NotFound`.
+	fn render_route_error(&self, result: RouteResult, route: &RouteContext) -> RouteResult {
+		match result {
+			RouteResult::BadRequest(message) => {
+				let view = self
+					.error_pages
+					.read()
+					.unwrap()
+					.bad_request_view(&message, route);
+				RouteResult::View(Box::new(view))
+			}
+			RouteResult::Error(message) => {
+				let view = self.error_pages.read().unwrap().error_view(&message, route);
+				RouteResult::View(Box::new(view))
+			}
+			result => result,
+		}
+	}
+
+	/// Drives the data of a loading view off the event loop. Each view it
+	/// resolves to comes back as [`ClientEvent::RouteLoaded`] tagged with
+	/// `generation`, so the loop can drop results nobody wants anymore.
+	fn spawn_route_load(
+		&self,
+		client_id: usize,
+		generation: u64,
+		view: &mut crate::wui::route_handler::View,
+		route: RouteContext,
+	) {
+		let Some(mut data) = view.take_pending() else {
+			return;
+		};
+		let partial_addr = view.partial_addr.clone();
+		let event_tx = self.handle.event_tx.clone();
+		let error_pages = self.error_pages.clone();
+		let span = tracing::info_span!("route_load", client_id, generation, path = %route.path);
+		let load = async move {
+			loop {
+				let (outcome, next) = match data.await {
+					RouteResult::View(mut view) => {
+						let next = view.take_pending();
+						(
+							LoadedRoute::View {
+								title: view.title,
+								item: Box::new(view.item),
+							},
+							next,
+						)
+					}
+					RouteResult::Redirect(redirect) => (LoadedRoute::Redirect(redirect.0), None),
+					RouteResult::NotFound => {
+						let view = error_pages.read().unwrap().not_found_view(&route);
+						(
+							LoadedRoute::View {
+								title: view.title,
+								item: Box::new(view.item),
+							},
+							None,
+						)
+					}
+					RouteResult::BadRequest(message) => {
+						let view = error_pages
+							.read()
+							.unwrap()
+							.bad_request_view(&message, &route);
+						(
+							LoadedRoute::View {
+								title: view.title,
+								item: Box::new(view.item),
+							},
+							None,
+						)
+					}
+					RouteResult::Error(message) => {
+						let view = error_pages.read().unwrap().error_view(&message, &route);
+						(
+							LoadedRoute::View {
+								title: view.title,
+								item: Box::new(view.item),
+							},
+							None,
+						)
+					}
+				};
+				let loaded = RouteLoaded {
+					generation,
+					partial_addr: partial_addr.clone(),
+					outcome,
+				};
+				let message =
+					ClientMessage::new(client_id, ClientEvent::RouteLoaded(Box::new(loaded)));
+				if event_tx.send(message).is_err() {
+					return;
+				}
+				match next {
+					Some(pending) => data = pending,
+					None => return,
+				}
+			}
+		};
+		tokio::spawn(
+			crate::i18n::scope(crate::i18n::client_locale(client_id), load).instrument(span),
+		);
 	}
 
 	async fn dispatch_partial(
@@ -2022,18 +2560,31 @@ where
 			.cloned()
 			.expect("missing Ctx<T> for #[partial] handler; call wgui.set_ctx(...) first");
 		let partial_addr = route.path.clone();
-		let mut result = handler
-			.call_dyn(
-				ctx_any,
-				route_match.params,
-				RouteFormData::default(),
-				RuntimeContext {
-					client_id: Some(client_id),
-					session,
-					route: Some(route),
-				},
-			)
-			.await;
+		let error_route = route.clone();
+		let span = tracing::info_span!(
+			"partial_dispatch",
+			client_id,
+			session = session.as_deref(),
+			route = handler.path(),
+			path = %route.path,
+		);
+		let mut result = crate::i18n::scope(
+			crate::i18n::client_locale(client_id),
+			handler
+				.call_dyn(
+					ctx_any,
+					route_match.params,
+					RouteFormData::default(),
+					RuntimeContext {
+						client_id: Some(client_id),
+						session,
+						route: Some(route),
+					},
+				)
+				.instrument(span),
+		)
+		.await;
+		result = self.render_route_error(result, &error_route);
 		if let RouteResult::View(view) = &mut result {
 			if view.partial_addr.is_none() {
 				view.partial_addr = Some(partial_addr);
@@ -2068,6 +2619,30 @@ where
 		}
 	}
 
+	/// Swaps a partial's region in the client's page tree for `item` and
+	/// diffs the page.
+	async fn render_partial_view(
+		&mut self,
+		client_id: usize,
+		client_session: &mut ClientSession,
+		addr: &str,
+		item: Item,
+		custom_component_entries: &CustomComponentEntries,
+	) {
+		if !replace_partial_region(&mut client_session.page_tree, addr, &item) {
+			return;
+		}
+		if let Some(cache) = client_session.partials.get_mut(addr) {
+			cache.tree = item;
+			cache.last_acked_version += 1;
+		}
+		let mut rendered = client_session.page_tree.clone();
+		resolve_custom_component_entries(&mut rendered, custom_component_entries);
+		self.handle.render(client_id, rendered).await;
+		self.sync_custom_components(client_id, &client_session.page_tree)
+			.await;
+	}
+
 	fn client_session_for_route(&self, route: RouteContext, page_tree: Item) -> ClientSession {
 		let mut partials = HashMap::new();
 		let mut regions = Vec::new();
@@ -2266,528 +2841,738 @@ where
 		let mut rtc_rooms: HashMap<String, BTreeSet<usize>> = HashMap::new();
 		let mut rtc_client_rooms: HashMap<usize, BTreeSet<String>> = HashMap::new();
 		let mut rtc_room_names: HashMap<String, HashMap<usize, String>> = HashMap::new();
+		let mut route_loads = RouteLoads::default();
 
 		while let Some(message) = self.next().await {
-			let client_id = message.client_id;
-			let custom_component_entries = self.custom_component_entries();
-			match &message.event {
-				ClientEvent::Connected { id: _ } => {}
-				ClientEvent::Disconnected { id: _ } => {
-					selected_pages.remove(&client_id);
-					routes.remove(&client_id);
-					client_sessions.remove(&client_id);
-					if let Some(rooms) = rtc_client_rooms.remove(&client_id) {
-						for room in rooms {
-							let mut room_peers = Vec::new();
-							let remove_room = if let Some(participants) = rtc_rooms.get_mut(&room) {
-								participants.remove(&client_id);
-								room_peers = participants.iter().copied().collect::<Vec<_>>();
-								participants.is_empty()
-							} else {
-								false
-							};
-							if let Some(names) = rtc_room_names.get_mut(&room) {
-								names.remove(&client_id);
-								if names.is_empty() {
-									rtc_room_names.remove(&room);
+			// Handle each event inside the span it was sent from, so route
+			// and controller spans, and the commands they send, nest under
+			// the websocket message that caused them.
+			let span = message.span.clone();
+			async {
+				let client_id = message.client_id;
+				let custom_component_entries = self.custom_component_entries();
+				match &message.event {
+					ClientEvent::Connected { id: _ } => {}
+					ClientEvent::Disconnected { id: _ } => {
+						crate::i18n::forget_client(client_id);
+						route_loads.forget_client(client_id);
+						selected_pages.remove(&client_id);
+						routes.remove(&client_id);
+						client_sessions.remove(&client_id);
+						if let Some(rooms) = rtc_client_rooms.remove(&client_id) {
+							for room in rooms {
+								let mut room_peers = Vec::new();
+								let remove_room = if let Some(participants) =
+									rtc_rooms.get_mut(&room)
+								{
+									participants.remove(&client_id);
+									room_peers = participants.iter().copied().collect::<Vec<_>>();
+									participants.is_empty()
+								} else {
+									false
+								};
+								if let Some(names) = rtc_room_names.get_mut(&room) {
+									names.remove(&client_id);
+									if names.is_empty() {
+										rtc_room_names.remove(&room);
+									}
 								}
-							}
-
-							if remove_room {
-								rtc_rooms.remove(&room);
-								rtc_room_names.remove(&room);
-								continue;
-							}
-							let room_participants = room_peers
-								.iter()
-								.map(|peer_id| WebRtcParticipant {
-									client_id: *peer_id,
-									display_name: rtc_room_names
-										.get(&room)
-										.and_then(|names| names.get(peer_id))
-										.cloned()
-										.unwrap_or_else(|| format!("user {}", peer_id)),
-								})
-								.collect::<Vec<_>>();
 
-							for peer_id in &room_peers {
-								handle
-									.send_actions(
-										*peer_id,
-										vec![ClientAction::WebRtcRoomState {
-											room: room.clone(),
-											self_client_id: *peer_id,
-											peers: room_peers.clone(),
-											participants: room_participants.clone(),
-										}],
-									)
-									.await;
+								if remove_room {
+									rtc_rooms.remove(&room);
+									rtc_room_names.remove(&room);
+									continue;
+								}
+								let room_participants = room_peers
+									.iter()
+									.map(|peer_id| WebRtcParticipant {
+										client_id: *peer_id,
+										display_name: rtc_room_names
+											.get(&room)
+											.and_then(|names| names.get(peer_id))
+											.cloned()
+											.unwrap_or_else(|| format!("user {}", peer_id)),
+									})
+									.collect::<Vec<_>>();
+
+								for peer_id in &room_peers {
+									handle
+										.send_actions(
+											*peer_id,
+											vec![ClientAction::WebRtcRoomState {
+												room: room.clone(),
+												self_client_id: *peer_id,
+												peers: room_peers.clone(),
+												participants: room_participants.clone(),
+											}],
+										)
+										.await;
+								}
 							}
 						}
-					}
 
-					for component in self.components.iter_mut() {
-						component.unmount(client_id);
-					}
-					for page in self.pages.iter_mut() {
-						page.unmount(client_id);
-					}
-					self.unmount_custom_components_for_client(client_id).await;
-					routes.remove(&client_id);
-					handle.clear_session(client_id).await;
-				}
-				ClientEvent::WebRtcJoin(join) => {
-					if join.room.is_empty() {
-						continue;
+						for component in self.components.iter_mut() {
+							component.unmount(client_id);
+						}
+						for page in self.pages.iter_mut() {
+							page.unmount(client_id);
+						}
+						self.unmount_custom_components_for_client(client_id).await;
+						routes.remove(&client_id);
+						handle.clear_session(client_id).await;
 					}
-					let display_name = join
-						.display_name
-						.clone()
-						.map(|name| name.trim().to_string())
-						.filter(|name| !name.is_empty())
-						.unwrap_or_else(|| format!("user {}", client_id));
-
-					let peers = {
-						let participants = rtc_rooms.entry(join.room.clone()).or_default();
-						participants.insert(client_id);
-						participants.iter().copied().collect::<Vec<_>>()
-					};
-					rtc_room_names
-						.entry(join.room.clone())
-						.or_default()
-						.insert(client_id, display_name);
-					rtc_client_rooms
-						.entry(client_id)
-						.or_default()
-						.insert(join.room.clone());
-					let room_participants = peers
-						.iter()
-						.map(|peer_id| WebRtcParticipant {
-							client_id: *peer_id,
-							display_name: rtc_room_names
-								.get(&join.room)
-								.and_then(|names| names.get(peer_id))
-								.cloned()
-								.unwrap_or_else(|| format!("user {}", peer_id)),
-						})
-						.collect::<Vec<_>>();
+					ClientEvent::WebRtcJoin(join) => {
+						if join.room.is_empty() {
+							return;
+						}
+						let display_name = join
+							.display_name
+							.clone()
+							.map(|name| name.trim().to_string())
+							.filter(|name| !name.is_empty())
+							.unwrap_or_else(|| format!("user {}", client_id));
+
+						let peers = {
+							let participants = rtc_rooms.entry(join.room.clone()).or_default();
+							participants.insert(client_id);
+							participants.iter().copied().collect::<Vec<_>>()
+						};
+						rtc_room_names
+							.entry(join.room.clone())
+							.or_default()
+							.insert(client_id, display_name);
+						rtc_client_rooms
+							.entry(client_id)
+							.or_default()
+							.insert(join.room.clone());
+						let room_participants = peers
+							.iter()
+							.map(|peer_id| WebRtcParticipant {
+								client_id: *peer_id,
+								display_name: rtc_room_names
+									.get(&join.room)
+									.and_then(|names| names.get(peer_id))
+									.cloned()
+									.unwrap_or_else(|| format!("user {}", peer_id)),
+							})
+							.collect::<Vec<_>>();
 
-					for peer_id in &peers {
-						handle
-							.send_actions(
-								*peer_id,
-								vec![ClientAction::WebRtcRoomState {
-									room: join.room.clone(),
-									self_client_id: *peer_id,
-									peers: peers.clone(),
-									participants: room_participants.clone(),
-								}],
-							)
-							.await;
-					}
-				}
-				ClientEvent::WebRtcLeave(leave) => {
-					let mut peers = Vec::new();
-					let remove_room = if let Some(participants) = rtc_rooms.get_mut(&leave.room) {
-						participants.remove(&client_id);
-						peers = participants.iter().copied().collect::<Vec<_>>();
-						participants.is_empty()
-					} else {
-						false
-					};
-					if let Some(names) = rtc_room_names.get_mut(&leave.room) {
-						names.remove(&client_id);
-						if names.is_empty() {
-							rtc_room_names.remove(&leave.room);
+						for peer_id in &peers {
+							handle
+								.send_actions(
+									*peer_id,
+									vec![ClientAction::WebRtcRoomState {
+										room: join.room.clone(),
+										self_client_id: *peer_id,
+										peers: peers.clone(),
+										participants: room_participants.clone(),
+									}],
+								)
+								.await;
 						}
 					}
+					ClientEvent::WebRtcLeave(leave) => {
+						let mut peers = Vec::new();
+						let remove_room = if let Some(participants) = rtc_rooms.get_mut(&leave.room)
+						{
+							participants.remove(&client_id);
+							peers = participants.iter().copied().collect::<Vec<_>>();
+							participants.is_empty()
+						} else {
+							false
+						};
+						if let Some(names) = rtc_room_names.get_mut(&leave.room) {
+							names.remove(&client_id);
+							if names.is_empty() {
+								rtc_room_names.remove(&leave.room);
+							}
+						}
 
-					if let Some(rooms) = rtc_client_rooms.get_mut(&client_id) {
-						rooms.remove(&leave.room);
-						if rooms.is_empty() {
-							rtc_client_rooms.remove(&client_id);
+						if let Some(rooms) = rtc_client_rooms.get_mut(&client_id) {
+							rooms.remove(&leave.room);
+							if rooms.is_empty() {
+								rtc_client_rooms.remove(&client_id);
+							}
 						}
-					}
 
-					if remove_room {
-						rtc_rooms.remove(&leave.room);
-						rtc_room_names.remove(&leave.room);
-						continue;
-					}
-					let room_participants = peers
-						.iter()
-						.map(|peer_id| WebRtcParticipant {
-							client_id: *peer_id,
-							display_name: rtc_room_names
-								.get(&leave.room)
-								.and_then(|names| names.get(peer_id))
-								.cloned()
-								.unwrap_or_else(|| format!("user {}", peer_id)),
-						})
-						.collect::<Vec<_>>();
+						if remove_room {
+							rtc_rooms.remove(&leave.room);
+							rtc_room_names.remove(&leave.room);
+							return;
+						}
+						let room_participants = peers
+							.iter()
+							.map(|peer_id| WebRtcParticipant {
+								client_id: *peer_id,
+								display_name: rtc_room_names
+									.get(&leave.room)
+									.and_then(|names| names.get(peer_id))
+									.cloned()
+									.unwrap_or_else(|| format!("user {}", peer_id)),
+							})
+							.collect::<Vec<_>>();
 
-					for peer_id in &peers {
-						handle
-							.send_actions(
-								*peer_id,
-								vec![ClientAction::WebRtcRoomState {
-									room: leave.room.clone(),
-									self_client_id: *peer_id,
-									peers: peers.clone(),
-									participants: room_participants.clone(),
-								}],
-							)
-							.await;
-					}
-				}
-				ClientEvent::WebRtcSignal(signal) => {
-					let participants = rtc_rooms
-						.get(&signal.room)
-						.map(|ids| ids.iter().copied().collect::<Vec<_>>())
-						.unwrap_or_default();
-					if !participants.contains(&client_id) {
-						continue;
+						for peer_id in &peers {
+							handle
+								.send_actions(
+									*peer_id,
+									vec![ClientAction::WebRtcRoomState {
+										room: leave.room.clone(),
+										self_client_id: *peer_id,
+										peers: peers.clone(),
+										participants: room_participants.clone(),
+									}],
+								)
+								.await;
+						}
 					}
+					ClientEvent::WebRtcSignal(signal) => {
+						let participants = rtc_rooms
+							.get(&signal.room)
+							.map(|ids| ids.iter().copied().collect::<Vec<_>>())
+							.unwrap_or_default();
+						if !participants.contains(&client_id) {
+							return;
+						}
 
-					let recipients = if let Some(target_id) = signal.target_client_id {
-						if participants.contains(&target_id) {
-							vec![target_id]
+						let recipients = if let Some(target_id) = signal.target_client_id {
+							if participants.contains(&target_id) {
+								vec![target_id]
+							} else {
+								Vec::new()
+							}
 						} else {
-							Vec::new()
-						}
-					} else {
-						participants
-							.into_iter()
-							.filter(|id| *id != client_id)
-							.collect::<Vec<_>>()
-					};
+							participants
+								.into_iter()
+								.filter(|id| *id != client_id)
+								.collect::<Vec<_>>()
+						};
 
-					for target_id in recipients {
-						handle
-							.send_actions(
-								target_id,
-								vec![ClientAction::WebRtcSignal {
-									room: signal.room.clone(),
-									from_client_id: client_id,
-									payload: signal.payload.clone(),
-								}],
-							)
-							.await;
-					}
-				}
-				ClientEvent::RenderPartial { topic } => {
-					let targets = client_sessions
-						.iter()
-						.filter_map(|(client_id, session)| {
-							session.partials.get(topic).map(|cache| {
-								(
-									*client_id,
-									session.current_route.clone(),
-									cache.params.clone(),
+						for target_id in recipients {
+							handle
+								.send_actions(
+									target_id,
+									vec![ClientAction::WebRtcSignal {
+										room: signal.room.clone(),
+										from_client_id: client_id,
+										payload: signal.payload.clone(),
+									}],
 								)
+								.await;
+						}
+					}
+					ClientEvent::RenderPartial { topic } => {
+						let targets = client_sessions
+							.iter()
+							.filter_map(|(client_id, session)| {
+								session.partials.get(topic).map(|cache| {
+									(
+										*client_id,
+										session.current_route.clone(),
+										cache.params.clone(),
+									)
+								})
 							})
-						})
-						.collect::<Vec<_>>();
-					for (target_client_id, parent_route, params) in targets {
-						let Some(partial_match) = self.match_partial(topic) else {
-							continue;
-						};
-						let session = handle.session_for_client(target_client_id).await;
-						let result = self
-							.dispatch_partial(
-								partial_match,
+							.collect::<Vec<_>>();
+						for (target_client_id, parent_route, params) in targets {
+							let Some(partial_match) = self.match_partial(topic) else {
+								continue;
+							};
+							let session = handle.session_for_client(target_client_id).await;
+							let partial_route = RouteContext {
+								path: topic.clone(),
+								params: params.0,
+								query: parent_route.query,
+								query_string: parent_route.query_string,
+							};
+							let result = self
+								.dispatch_partial(
+									partial_match,
+									target_client_id,
+									session,
+									partial_route.clone(),
+								)
+								.await;
+							let RouteResult::View(mut view) = result else {
+								continue;
+							};
+							let generation =
+								route_loads.begin(target_client_id, Some(topic.clone()));
+							self.spawn_route_load(
+								target_client_id,
+								generation,
+								&mut view,
+								partial_route,
+							);
+							let Some(client_session) = client_sessions.get_mut(&target_client_id)
+							else {
+								continue;
+							};
+							self.render_partial_view(
 								target_client_id,
-								session,
-								RouteContext {
-									path: topic.clone(),
-									params: params.0,
-									query: parent_route.query,
-								},
+								client_session,
+								topic,
+								view.item,
+								&custom_component_entries,
 							)
 							.await;
-						let RouteResult::View(view) = result else {
-							continue;
-						};
-						let Some(client_session) = client_sessions.get_mut(&target_client_id)
-						else {
-							continue;
-						};
-						if !replace_partial_region(&mut client_session.page_tree, topic, &view.item)
-						{
-							continue;
 						}
-						if let Some(cache) = client_session.partials.get_mut(topic) {
-							cache.tree = view.item;
-							cache.last_acked_version += 1;
+					}
+					ClientEvent::RouteLoaded(loaded) => {
+						if !route_loads.is_current(loaded, client_id) {
+							return;
 						}
-						let mut rendered = client_session.page_tree.clone();
-						resolve_custom_component_entries(&mut rendered, &custom_component_entries);
-						handle.render(target_client_id, rendered).await;
-						self.sync_custom_components(target_client_id, &client_session.page_tree)
+						let (title, item) = match &loaded.outcome {
+							LoadedRoute::View { title, item } => (title.clone(), (**item).clone()),
+							LoadedRoute::Redirect(url) => {
+								if !url.is_empty() {
+									handle.push_state(client_id, url).await;
+								}
+								return;
+							}
+						};
+						if let Some(addr) = &loaded.partial_addr {
+							if let Some(client_session) = client_sessions.get_mut(&client_id) {
+								self.render_partial_view(
+									client_id,
+									client_session,
+									addr,
+									item,
+									&custom_component_entries,
+								)
+								.await;
+							}
+							return;
+						}
+						let Some(route) = routes.get(&client_id).cloned() else {
+							return;
+						};
+						client_sessions.insert(
+							client_id,
+							self.client_session_for_route(route, item.clone()),
+						);
+						let mut view = crate::wui::route_handler::View::untitled(item);
+						view.title = title;
+						self.render_route_view(client_id, view, &custom_component_entries, false)
 							.await;
 					}
-				}
-				ClientEvent::FormSubmit(submit) => {
-					let session = handle.session_for_client(client_id).await;
-					let Some(route_match) =
-						self.match_route(&submit.path, crate::wui::route_handler::HttpMethod::Post)
-					else {
-						continue;
-					};
-					let action_route = RouteContext {
-						path: submit.path.clone(),
-						params: route_match.params.0.clone(),
-						query: submit.query.clone(),
-					};
-					let result = self
-						.dispatch_route(
-							route_match,
-							crate::wui::route_handler::RouteFormData::from_fields(
-								submit.fields.clone(),
-							),
-							Some(client_id),
-							session.clone(),
-							action_route,
-						)
-						.await;
-					match result {
-						crate::wui::route_handler::RouteResult::View(view) => {
-							self.render_route_view(
-								client_id,
-								*view,
-								&custom_component_entries,
-								true,
+					ClientEvent::FormSubmit(submit) => {
+						let session = handle.session_for_client(client_id).await;
+						let Some(route_match) = self
+							.match_route(&submit.path, crate::wui::route_handler::HttpMethod::Post)
+						else {
+							return;
+						};
+						let action_route = RouteContext {
+							path: submit.path.clone(),
+							params: route_match.params.0.clone(),
+							query: submit.query.clone(),
+							query_string: form_submit_query_string(submit, routes.get(&client_id)),
+						};
+						#[cfg(feature = "metrics")]
+						let (started, route_pattern) = (
+							std::time::Instant::now(),
+							self.routes.read().unwrap()[route_match.index]
+								.handler
+								.path()
+								.to_string(),
+						);
+						let result = self
+							.dispatch_route(
+								route_match,
+								crate::wui::route_handler::RouteFormData::from_fields(
+									submit.fields.clone(),
+								),
+								Some(client_id),
+								session.clone(),
+								action_route.clone(),
 							)
 							.await;
-						}
-						crate::wui::route_handler::RouteResult::Redirect(redirect) => {
-							handle
-								.send_actions(
+						#[cfg(feature = "metrics")]
+						crate::metrics::observe_post_route(&route_pattern, started.elapsed());
+						match result {
+							crate::wui::route_handler::RouteResult::View(mut view) => {
+								let generation = route_loads.begin(client_id, None);
+								self.spawn_route_load(
 									client_id,
-									vec![ClientAction::FormSucceeded(
-										crate::types::FormSucceeded {
-											submission_id: submit.submission_id,
-										},
-									)],
+									generation,
+									&mut view,
+									action_route,
+								);
+								self.render_route_view(
+									client_id,
+									*view,
+									&custom_component_entries,
+									true,
 								)
 								.await;
-							let current_route = routes.get(&client_id).cloned();
-							let target = if redirect.0.is_empty() {
-								current_route.unwrap_or_else(|| {
-									component_route_context("/", &HashMap::new())
-								})
-							} else {
-								let (path, query) = route_target(&redirect.0);
-								let Some(target_match) = self
-									.match_route(&path, crate::wui::route_handler::HttpMethod::Get)
-								else {
-									if best_component_route_index(
-										&self.components,
-										&path,
-										|component| component.route_path.as_str(),
+							}
+							crate::wui::route_handler::RouteResult::Redirect(redirect) => {
+								handle
+									.send_actions(
+										client_id,
+										vec![ClientAction::FormSucceeded(
+											crate::types::FormSucceeded {
+												submission_id: submit.submission_id,
+											},
+										)],
 									)
-									.is_some()
+									.await;
+								let current_route = routes.get(&client_id).cloned();
+								let target = if redirect.0.is_empty() {
+									current_route.unwrap_or_else(|| {
+										component_route_context("/", &HashMap::new(), "")
+									})
+								} else {
+									let (path, query, query_string) = route_target(&redirect.0);
+									let Some(target_match) = self.match_route(
+										&path,
+										crate::wui::route_handler::HttpMethod::Get,
+									) else {
+										if best_component_route_index(
+											&self.components,
+											&path,
+											|component| component.route_path.as_str(),
+										)
+										.is_some()
+										{
+											// Component-backed paths are rendered by the normal
+											// PathChanged flow. Keep this as client-side navigation
+											// so its next render is diffed rather than reloading.
+											handle.push_state(client_id, &redirect.0).await;
+										} else {
+											handle.navigate(client_id, &redirect.0).await;
+										}
+										return;
+									};
+									let target_route = RouteContext {
+										path: path.clone(),
+										params: target_match.params.0.clone(),
+										query,
+										query_string,
+									};
+									let rendered = self
+										.dispatch_route(
+											target_match,
+											crate::wui::route_handler::RouteFormData::default(),
+											Some(client_id),
+											session.clone(),
+											target_route.clone(),
+										)
+										.await;
+									if let crate::wui::route_handler::RouteResult::View(mut view) =
+										rendered
 									{
-										// Component-backed paths are rendered by the normal
-										// PathChanged flow. Keep this as client-side navigation
-										// so its next render is diffed rather than reloading.
-										handle.push_state(client_id, &redirect.0).await;
-									} else {
-										handle.navigate(client_id, &redirect.0).await;
+										if current_route.as_ref() != Some(&target_route) {
+											handle.push_state(client_id, &redirect.0).await;
+										}
+										let generation = route_loads.navigated(client_id);
+										self.spawn_route_load(
+											client_id,
+											generation,
+											&mut view,
+											target_route.clone(),
+										);
+										routes.insert(client_id, target_route.clone());
+										selected_pages.insert(client_id, None);
+										client_sessions.insert(
+											client_id,
+											self.client_session_for_route(
+												target_route,
+												view.item.clone(),
+											),
+										);
+										self.render_route_view(
+											client_id,
+											*view,
+											&custom_component_entries,
+											false,
+										)
+										.await;
 									}
-									continue;
+									return;
 								};
-								let target_route = RouteContext {
-									path: path.clone(),
-									params: target_match.params.0.clone(),
-									query,
-								};
-								let rendered = self
-									.dispatch_route(
-										target_match,
-										crate::wui::route_handler::RouteFormData::default(),
-										Some(client_id),
-										session.clone(),
-										target_route.clone(),
-									)
-									.await;
-								if let crate::wui::route_handler::RouteResult::View(view) = rendered
-								{
-									if current_route.as_ref() != Some(&target_route) {
-										handle.push_state(client_id, &redirect.0).await;
+								if let Some(target_match) = self.match_route(
+									&target.path,
+									crate::wui::route_handler::HttpMethod::Get,
+								) {
+									let target = RouteContext {
+										path: target.path.clone(),
+										params: target_match.params.0.clone(),
+										query: target.query.clone(),
+										query_string: target.query_string.clone(),
+									};
+									if let crate::wui::route_handler::RouteResult::View(mut view) =
+										self.dispatch_route(
+											target_match,
+											crate::wui::route_handler::RouteFormData::default(),
+											Some(client_id),
+											session,
+											target.clone(),
+										)
+										.await
+									{
+										let generation = route_loads.navigated(client_id);
+										self.spawn_route_load(
+											client_id,
+											generation,
+											&mut view,
+											target.clone(),
+										);
+										routes.insert(client_id, target.clone());
+										selected_pages.insert(client_id, None);
+										client_sessions.insert(
+											client_id,
+											self.client_session_for_route(
+												target,
+												view.item.clone(),
+											),
+										);
+										self.render_route_view(
+											client_id,
+											*view,
+											&custom_component_entries,
+											false,
+										)
+										.await;
 									}
-									routes.insert(client_id, target_route.clone());
-									selected_pages.insert(client_id, None);
+								}
+							}
+							// `dispatch_route` turns errors into the error page view.
+							crate::wui::route_handler::RouteResult::NotFound
+							| crate::wui::route_handler::RouteResult::BadRequest(_)
+							| crate::wui::route_handler::RouteResult::Error(_) => {}
+						}
+					}
+					ClientEvent::PathChanged(change) => {
+						let page_load = route_loads.navigated(client_id);
+						let session = handle.session_for_client(client_id).await;
+						// Only SSR hydration, which needs the server, replaces these.
+						#[cfg_attr(not(feature = "hyper"), allow(unused_mut))]
+						let mut initial_root = change.initial_root.clone();
+						#[cfg_attr(not(feature = "hyper"), allow(unused_mut))]
+						let mut hydrated_title: Option<String> = None;
+						#[cfg(feature = "hyper")]
+						if initial_root.is_none() {
+							if let Some(hydration_id) = &change.ssr_hydration_id {
+								let mut roots = self.ssr_hydration_roots.write().await;
+								if let Some(root) = roots.remove(hydration_id) {
+									if root.path == change.path {
+										hydrated_title = root.title;
+										initial_root = Some(root.item);
+									}
+								}
+							}
+						}
+
+						// ── #[route] handler dispatch (GET pages) ──────────────
+						// Check new-style free-fn routes before the legacy
+						// `add_page` registry. If a matching GET handler is
+						// found, run it and render/diff directly — no
+						// WuiController mount, no process() lifecycle.
+						if let Some(route_match) = self
+							.match_route(&change.path, crate::wui::route_handler::HttpMethod::Get)
+						{
+							let active_route = RouteContext {
+								path: change.path.clone(),
+								params: route_match.params.0.clone(),
+								query: change.query.clone(),
+								query_string: change.query_string.clone(),
+							};
+							routes.insert(client_id, active_route.clone());
+							let result = self
+								.dispatch_route(
+									route_match,
+									crate::wui::route_handler::RouteFormData::default(),
+									Some(client_id),
+									session.clone(),
+									active_route.clone(),
+								)
+								.await;
+							match result {
+								crate::wui::route_handler::RouteResult::View(mut view) => {
+									let loading = view.is_loading();
+									self.spawn_route_load(
+										client_id,
+										page_load,
+										&mut view,
+										active_route.clone(),
+									);
 									client_sessions.insert(
 										client_id,
 										self.client_session_for_route(
-											target_route,
+											active_route.clone(),
 											view.item.clone(),
 										),
 									);
-									self.render_route_view(
-										client_id,
-										*view,
+									if let Some(title) = &view.title {
+										if hydrated_title.as_deref() != Some(title.as_str()) {
+											handle.set_title(client_id, title).await;
+										}
+									}
+									let mut rendered = view.item.clone();
+									resolve_custom_component_entries(
+										&mut rendered,
 										&custom_component_entries,
-										false,
-									)
-									.await;
-								}
-								continue;
-							};
-							if let Some(target_match) = self.match_route(
-								&target.path,
-								crate::wui::route_handler::HttpMethod::Get,
-							) {
-								let target = RouteContext {
-									path: target.path.clone(),
-									params: target_match.params.0.clone(),
-									query: target.query.clone(),
-								};
-								if let crate::wui::route_handler::RouteResult::View(view) = self
-									.dispatch_route(
-										target_match,
-										crate::wui::route_handler::RouteFormData::default(),
-										Some(client_id),
-										session,
-										target.clone(),
-									)
-									.await
-								{
-									routes.insert(client_id, target.clone());
-									selected_pages.insert(client_id, None);
-									client_sessions.insert(
-										client_id,
-										self.client_session_for_route(target, view.item.clone()),
 									);
-									self.render_route_view(
-										client_id,
-										*view,
-										&custom_component_entries,
-										false,
-									)
-									.await;
+									if let Some(root) = initial_root.clone() {
+										handle.hydrate_root(client_id, root).await;
+										// The server-rendered document stays up until the
+										// data lands instead of flashing the placeholder.
+										if !loading {
+											handle.render(client_id, rendered).await;
+										}
+									} else {
+										handle.render(client_id, rendered).await;
+									}
+									self.sync_custom_components(client_id, &view.item).await;
+									for page in self.pages.iter_mut() {
+										page.unmount(client_id);
+									}
+									for component in self.components.iter_mut() {
+										component.unmount(client_id);
+									}
+									selected_pages.insert(client_id, None);
+									return;
 								}
-							}
-						}
-						crate::wui::route_handler::RouteResult::NotFound => {}
-					}
-				}
-				ClientEvent::PathChanged(change) => {
-					let session = handle.session_for_client(client_id).await;
-					let mut initial_root = change.initial_root.clone();
-					let mut hydrated_title: Option<String> = None;
-					#[cfg(feature = "hyper")]
-					if initial_root.is_none() {
-						if let Some(hydration_id) = &change.ssr_hydration_id {
-							let mut roots = self.ssr_hydration_roots.write().await;
-							if let Some(root) = roots.remove(hydration_id) {
-								if root.path == change.path {
-									hydrated_title = root.title;
-									initial_root = Some(root.item);
+								crate::wui::route_handler::RouteResult::Redirect(redirect) => {
+									if !redirect.0.is_empty() {
+										handle.push_state(client_id, &redirect.0).await;
+									}
+									return;
+								}
+								crate::wui::route_handler::RouteResult::NotFound
+								| crate::wui::route_handler::RouteResult::BadRequest(_)
+								| crate::wui::route_handler::RouteResult::Error(_) => {
+									// Fall through to legacy pages below
 								}
 							}
 						}
-					}
 
-					// ── #[route] handler dispatch (GET pages) ──────────────
-					// Check new-style free-fn routes before the legacy
-					// `add_page` registry. If a matching GET handler is
-					// found, run it and render/diff directly — no
-					// WuiController mount, no process() lifecycle.
-					if let Some(route_match) =
-						self.match_route(&change.path, crate::wui::route_handler::HttpMethod::Get)
-					{
-						let active_route = RouteContext {
-							path: change.path.clone(),
-							params: route_match.params.0.clone(),
-							query: change.query.clone(),
+						let selected_page =
+							best_route_index(&self.pages, &change.path, |page| &page.pattern);
+						let selected_page_changed =
+							selected_pages.get(&client_id).copied().flatten() != selected_page;
+						selected_pages.insert(client_id, selected_page);
+						let active_route = if let Some(index) = selected_page {
+							page_route_context(
+								&self.pages[index].pattern,
+								&change.path,
+								&change.query,
+								&change.query_string,
+							)
+							.unwrap_or_else(|| {
+								component_route_context(
+									&change.path,
+									&change.query,
+									&change.query_string,
+								)
+							})
+						} else {
+							component_route_context(
+								&change.path,
+								&change.query,
+								&change.query_string,
+							)
 						};
 						routes.insert(client_id, active_route.clone());
-						let result = self
-							.dispatch_route(
-								route_match,
-								crate::wui::route_handler::RouteFormData::default(),
+
+						let mut rendered_custom_sync: Option<Item> = None;
+						for (index, page) in self.pages.iter_mut().enumerate() {
+							if Some(index) != selected_page {
+								page.unmount(client_id);
+								continue;
+							}
+
+							page.unmount(client_id);
+							match (page.factory)(
+								active_route.clone(),
 								Some(client_id),
 								session.clone(),
-								active_route.clone(),
 							)
-							.await;
-						match result {
-							crate::wui::route_handler::RouteResult::View(view) => {
-								client_sessions.insert(
-									client_id,
-									self.client_session_for_route(
-										active_route.clone(),
-										view.item.clone(),
-									),
-								);
-								if let Some(title) = &view.title {
-									if hydrated_title.as_deref() != Some(title.as_str()) {
-										handle.set_title(client_id, title).await;
+							.await
+							{
+								PageMount::Ready(mut controller) => {
+									controller
+										.set_runtime_context(Some(client_id), session.clone());
+									controller.set_route_context(Some(active_route.clone()));
+									let item = crate::i18n::with_client_locale(client_id, || {
+										render_controller(
+											&*controller,
+											&active_route,
+											&self.error_pages,
+										)
+									});
+									let title = controller
+										.title()
+										.or_else(|| controller.route_title(&active_route.path));
+									if let Some(title) = title {
+										if hydrated_title.as_deref() != Some(title.as_str()) {
+											handle.set_title(client_id, &title).await;
+										}
 									}
+									let mut rendered = item.clone();
+									resolve_custom_component_entries(
+										&mut rendered,
+										&custom_component_entries,
+									);
+									if selected_page_changed {
+										if let Some(root) = initial_root.clone() {
+											handle.hydrate_root(client_id, root).await;
+											handle.render(client_id, rendered).await;
+										} else {
+											handle.replace_root(client_id, rendered).await;
+										}
+									} else {
+										handle.render(client_id, rendered).await;
+									}
+									rendered_custom_sync = Some(item);
+									page.controllers.insert(client_id, controller);
+									page.mount_process(client_id, handle.event_tx.clone());
 								}
-								let mut rendered = view.item.clone();
-								resolve_custom_component_entries(
-									&mut rendered,
-									&custom_component_entries,
-								);
-								if let Some(root) = initial_root.clone() {
-									handle.hydrate_root(client_id, root).await;
-									handle.render(client_id, rendered).await;
-								} else {
-									handle.render(client_id, rendered).await;
-								}
-								self.sync_custom_components(client_id, &view.item).await;
-								for page in self.pages.iter_mut() {
+								PageMount::Redirect(url) => {
 									page.unmount(client_id);
+									handle.push_state(client_id, &url).await;
 								}
-								for component in self.components.iter_mut() {
-									component.unmount(client_id);
-								}
-								selected_pages.insert(client_id, None);
-								continue;
-							}
-							crate::wui::route_handler::RouteResult::Redirect(redirect) => {
-								if !redirect.0.is_empty() {
-									handle.push_state(client_id, &redirect.0).await;
-								}
-								continue;
-							}
-							crate::wui::route_handler::RouteResult::NotFound => {
-								// Fall through to legacy pages below
 							}
 						}
-					}
-
-					let selected_page =
-						best_route_index(&self.pages, &change.path, |page| &page.pattern);
-					let selected_page_changed =
-						selected_pages.get(&client_id).copied().flatten() != selected_page;
-					selected_pages.insert(client_id, selected_page);
-					let active_route = if let Some(index) = selected_page {
-						page_route_context(&self.pages[index].pattern, &change.path, &change.query)
-							.unwrap_or_else(|| component_route_context(&change.path, &change.query))
-					} else {
-						component_route_context(&change.path, &change.query)
-					};
-					routes.insert(client_id, active_route.clone());
+						if let Some(item) = rendered_custom_sync {
+							self.sync_custom_components(client_id, &item).await;
+						} else if selected_page.is_some() {
+							self.unmount_custom_components_for_client(client_id).await;
+						}
 
-					let mut rendered_custom_sync: Option<Item> = None;
-					for (index, page) in self.pages.iter_mut().enumerate() {
-						if Some(index) != selected_page {
-							page.unmount(client_id);
-							continue;
+						if selected_page.is_some() {
+							for component in self.components.iter_mut() {
+								component.unmount(client_id);
+							}
+							return;
 						}
 
-						page.unmount(client_id);
-						match (page.factory)(active_route.clone(), Some(client_id), session.clone())
-							.await
-						{
-							PageMount::Ready(mut controller) => {
+						let selected_component = best_component_route_index(
+							&self.components,
+							&change.path,
+							|component| component.route_path.as_str(),
+						);
+
+						let mut hydrated_initial_root = false;
+						let mut rendered_custom_sync: Option<Item> = None;
+						for (index, component) in self.components.iter_mut().enumerate() {
+							if Some(index) != selected_component {
+								component.unmount(client_id);
+								continue;
+							}
+
+							if let Some(controller) = component.controllers.get_mut(&client_id) {
 								controller.set_runtime_context(Some(client_id), session.clone());
 								controller.set_route_context(Some(active_route.clone()));
-								let item = controller.render_with_route(&active_route);
+								let item = crate::i18n::with_client_locale(client_id, || {
+									render_controller(
+										&**controller,
+										&active_route,
+										&self.error_pages,
+									)
+								});
 								let title = controller
 									.title()
 									.or_else(|| controller.route_title(&active_route.path));
@@ -2801,318 +3586,397 @@ where
 									&mut rendered,
 									&custom_component_entries,
 								);
-								if selected_page_changed {
+								handle.render(client_id, rendered).await;
+								rendered_custom_sync = Some(item);
+							} else {
+								let mut controller = (component.factory)().await;
+								controller.set_runtime_context(Some(client_id), session.clone());
+								controller.set_route_context(Some(active_route.clone()));
+								let item = crate::i18n::with_client_locale(client_id, || {
+									render_controller(
+										&*controller,
+										&active_route,
+										&self.error_pages,
+									)
+								});
+								let title = controller
+									.title()
+									.or_else(|| controller.route_title(&active_route.path));
+								if let Some(title) = title {
+									if hydrated_title.as_deref() != Some(title.as_str()) {
+										handle.set_title(client_id, &title).await;
+									}
+								}
+								let mut rendered = item.clone();
+								resolve_custom_component_entries(
+									&mut rendered,
+									&custom_component_entries,
+								);
+								if !hydrated_initial_root {
 									if let Some(root) = initial_root.clone() {
 										handle.hydrate_root(client_id, root).await;
-										handle.render(client_id, rendered).await;
-									} else {
-										handle.replace_root(client_id, rendered).await;
+										hydrated_initial_root = true;
 									}
-								} else {
-									handle.render(client_id, rendered).await;
 								}
+								handle.render(client_id, rendered).await;
 								rendered_custom_sync = Some(item);
-								page.controllers.insert(client_id, controller);
-								page.mount_process(client_id, handle.event_tx.clone());
-							}
-							PageMount::Redirect(url) => {
-								page.unmount(client_id);
-								handle.push_state(client_id, &url).await;
+								component.controllers.insert(client_id, controller);
+								component.mount_process(client_id, handle.event_tx.clone());
 							}
 						}
-					}
-					if let Some(item) = rendered_custom_sync {
-						self.sync_custom_components(client_id, &item).await;
-					} else if selected_page.is_some() {
-						self.unmount_custom_components_for_client(client_id).await;
-					}
-
-					if selected_page.is_some() {
-						for component in self.components.iter_mut() {
-							component.unmount(client_id);
-						}
-						continue;
-					}
-
-					let selected_component =
-						best_component_route_index(&self.components, &change.path, |component| {
-							component.route_path.as_str()
-						});
-
-					let mut hydrated_initial_root = false;
-					let mut rendered_custom_sync: Option<Item> = None;
-					for (index, component) in self.components.iter_mut().enumerate() {
-						if Some(index) != selected_component {
-							component.unmount(client_id);
-							continue;
-						}
-
-						if let Some(controller) = component.controllers.get_mut(&client_id) {
-							controller.set_runtime_context(Some(client_id), session.clone());
-							controller.set_route_context(Some(active_route.clone()));
-							let item = controller.render_with_route(&active_route);
-							let title = controller
-								.title()
-								.or_else(|| controller.route_title(&active_route.path));
-							if let Some(title) = title {
-								if hydrated_title.as_deref() != Some(title.as_str()) {
-									handle.set_title(client_id, &title).await;
-								}
-							}
-							let mut rendered = item.clone();
-							resolve_custom_component_entries(
-								&mut rendered,
-								&custom_component_entries,
-							);
-							handle.render(client_id, rendered).await;
-							rendered_custom_sync = Some(item);
+						if let Some(item) = rendered_custom_sync {
+							self.sync_custom_components(client_id, &item).await;
 						} else {
-							let mut controller = (component.factory)().await;
-							controller.set_runtime_context(Some(client_id), session.clone());
-							controller.set_route_context(Some(active_route.clone()));
-							let item = controller.render_with_route(&active_route);
-							let title = controller
-								.title()
-								.or_else(|| controller.route_title(&active_route.path));
-							if let Some(title) = title {
-								if hydrated_title.as_deref() != Some(title.as_str()) {
-									handle.set_title(client_id, &title).await;
-								}
-							}
-							let mut rendered = item.clone();
-							resolve_custom_component_entries(
-								&mut rendered,
-								&custom_component_entries,
-							);
-							if !hydrated_initial_root {
-								if let Some(root) = initial_root.clone() {
+							self.unmount_custom_components_for_client(client_id).await;
+						}
+						if selected_component.is_none() {
+							let not_found = self
+								.error_pages
+								.read()
+								.unwrap()
+								.configured_not_found_view(&active_route);
+							if let Some(view) = not_found {
+								if let Some(root) = initial_root {
 									handle.hydrate_root(client_id, root).await;
-									hydrated_initial_root = true;
 								}
+								self.render_route_view(
+									client_id,
+									view,
+									&custom_component_entries,
+									false,
+								)
+								.await;
 							}
-							handle.render(client_id, rendered).await;
-							rendered_custom_sync = Some(item);
-							component.controllers.insert(client_id, controller);
-							component.mount_process(client_id, handle.event_tx.clone());
 						}
 					}
-					if let Some(item) = rendered_custom_sync {
-						self.sync_custom_components(client_id, &item).await;
-					} else {
-						self.unmount_custom_components_for_client(client_id).await;
-					}
-				}
-				ClientEvent::Refresh => {
-					let session = handle.session_for_client(client_id).await;
-					let route = routes
-						.get(&client_id)
-						.cloned()
-						.unwrap_or_else(|| component_route_context("/", &HashMap::new()));
-					let mut rendered_custom_sync: Option<Item> = None;
-
-					if let Some(page_index) = selected_pages.get(&client_id).copied().flatten() {
-						if let Some(controller) =
-							self.pages[page_index].controllers.get_mut(&client_id)
+					ClientEvent::TemplatesReloaded { modules } => {
+						let route = routes
+							.get(&client_id)
+							.cloned()
+							.unwrap_or_else(|| component_route_context("/", &HashMap::new(), ""));
+						// Same view selection as `Refresh` below.
+						let template = if let Some(page_index) =
+							selected_pages.get(&client_id).copied().flatten()
 						{
-							controller.set_runtime_context(Some(client_id), session.clone());
-							controller.set_route_context(Some(route.clone()));
-							let item = controller.render_with_route(&route);
-							let title = controller
-								.title()
-								.or_else(|| controller.route_title(&route.path));
-							if let Some(title) = title {
-								handle.set_title(client_id, &title).await;
-							}
-							let mut rendered = item.clone();
-							resolve_custom_component_entries(
-								&mut rendered,
-								&custom_component_entries,
-							);
-							handle.render(client_id, rendered).await;
-							rendered_custom_sync = Some(item);
-						}
-					} else if let Some(component_index) =
-						best_component_route_index(&self.components, &route.path, |component| {
-							component.route_path.as_str()
-						}) {
-						if let Some(controller) = self.components[component_index]
-							.controllers
-							.get_mut(&client_id)
+							self.pages[page_index]
+								.controllers
+								.get(&client_id)
+								.and_then(|controller| controller.wui_template())
+						} else if let Some(component_index) =
+							best_component_route_index(&self.components, &route.path, |component| {
+								component.route_path.as_str()
+							}) {
+							self.components[component_index]
+								.controllers
+								.get(&client_id)
+								.and_then(|controller| controller.wui_template())
+						} else {
+							None
+						};
+						let template = template.or_else(|| {
+							let route_match = self.match_route(
+								&route.path,
+								crate::wui::route_handler::HttpMethod::Get,
+							)?;
+							self.routes.read().unwrap()[route_match.index]
+								.handler
+								.wui_template()
+						});
+						if template
+							.is_some_and(|template| modules.iter().any(|m| m == template.module()))
 						{
-							controller.set_runtime_context(Some(client_id), session.clone());
-							controller.set_route_context(Some(route.clone()));
-							let item = controller.render_with_route(&route);
-							let title = controller
-								.title()
-								.or_else(|| controller.route_title(&route.path));
-							if let Some(title) = title {
-								handle.set_title(client_id, &title).await;
-							}
-							let mut rendered = item.clone();
-							resolve_custom_component_entries(
-								&mut rendered,
-								&custom_component_entries,
-							);
-							handle.render(client_id, rendered).await;
-							rendered_custom_sync = Some(item);
+							handle.refresh(client_id);
 						}
 					}
+					ClientEvent::Refresh => {
+						let session = handle.session_for_client(client_id).await;
+						let route = routes
+							.get(&client_id)
+							.cloned()
+							.unwrap_or_else(|| component_route_context("/", &HashMap::new(), ""));
+						let mut rendered_custom_sync: Option<Item> = None;
 
-					// ── #[route] re-render on refresh ─────────────────
-					// If no legacy page/component handled the refresh, check
-					// whether the client's current route matches a
-					// #[route] GET handler and re-render it.
-					if rendered_custom_sync.is_none() {
-						if let Some(route_match) = self
-							.match_route(&route.path, crate::wui::route_handler::HttpMethod::Get)
+						if let Some(page_index) = selected_pages.get(&client_id).copied().flatten()
 						{
-							let active_route = RouteContext {
-								path: route.path.clone(),
-								params: route_match.params.0.clone(),
-								query: route.query.clone(),
-							};
-							let result = self
-								.dispatch_route(
-									route_match,
-									crate::wui::route_handler::RouteFormData::default(),
-									Some(client_id),
-									session.clone(),
-									active_route,
-								)
-								.await;
-							if let crate::wui::route_handler::RouteResult::View(view) = result {
-								if let Some(title) = &view.title {
-									handle.set_title(client_id, title).await;
+							if let Some(controller) =
+								self.pages[page_index].controllers.get_mut(&client_id)
+							{
+								controller.set_runtime_context(Some(client_id), session.clone());
+								controller.set_route_context(Some(route.clone()));
+								let item = crate::i18n::with_client_locale(client_id, || {
+									render_controller(&**controller, &route, &self.error_pages)
+								});
+								let title = controller
+									.title()
+									.or_else(|| controller.route_title(&route.path));
+								if let Some(title) = title {
+									handle.set_title(client_id, &title).await;
 								}
-								let mut rendered = view.item.clone();
+								let mut rendered = item.clone();
 								resolve_custom_component_entries(
 									&mut rendered,
 									&custom_component_entries,
 								);
 								handle.render(client_id, rendered).await;
-								self.sync_custom_components(client_id, &view.item).await;
+								rendered_custom_sync = Some(item);
 							}
-						}
-					}
-
-					if let Some(item) = rendered_custom_sync {
-						self.sync_custom_components(client_id, &item).await;
-					}
-				}
-				ClientEvent::Input(_) => {}
-				_ => {
-					let session = handle.session_for_client(client_id).await;
-					if let ClientEvent::OnCustom(custom) = &message.event {
-						if self.handle_custom_component_event(client_id, custom).await {
-							continue;
-						}
-					}
-					let mut handled = false;
-					let mut custom_sync_updates: Vec<(usize, Item)> = Vec::new();
-					for component in self.components.iter_mut() {
-						if let Some(controller) = component.controllers.get_mut(&client_id) {
-							let route = routes
-								.get(&client_id)
-								.cloned()
-								.unwrap_or_else(|| component_route_context("/", &HashMap::new()));
-							controller.set_runtime_context(Some(client_id), session.clone());
-							controller.set_route_context(Some(route));
-							handled = controller.handle(&message.event).await;
-						}
-						if handled {
-							let mut updates: Vec<(usize, Item, Option<String>)> = Vec::new();
-							for (mounted_client_id, mounted_controller) in
-								component.controllers.iter_mut()
+						} else if let Some(component_index) =
+							best_component_route_index(&self.components, &route.path, |component| {
+								component.route_path.as_str()
+							}) {
+							if let Some(controller) = self.components[component_index]
+								.controllers
+								.get_mut(&client_id)
 							{
-								let mounted_session =
-									handle.session_for_client(*mounted_client_id).await;
-								let route =
-									routes.get(mounted_client_id).cloned().unwrap_or_else(|| {
-										component_route_context("/", &HashMap::new())
-									});
-								mounted_controller
-									.set_runtime_context(Some(*mounted_client_id), mounted_session);
-								mounted_controller.set_route_context(Some(route.clone()));
-								let item = mounted_controller.render_with_route(&route);
-								let title = mounted_controller
+								controller.set_runtime_context(Some(client_id), session.clone());
+								controller.set_route_context(Some(route.clone()));
+								let item = crate::i18n::with_client_locale(client_id, || {
+									render_controller(&**controller, &route, &self.error_pages)
+								});
+								let title = controller
 									.title()
-									.or_else(|| mounted_controller.route_title(&route.path));
-								updates.push((*mounted_client_id, item, title));
-							}
-
-							for (mounted_client_id, item, title) in updates {
+									.or_else(|| controller.route_title(&route.path));
 								if let Some(title) = title {
-									handle.set_title(mounted_client_id, &title).await;
+									handle.set_title(client_id, &title).await;
 								}
 								let mut rendered = item.clone();
 								resolve_custom_component_entries(
 									&mut rendered,
 									&custom_component_entries,
 								);
-								handle.render(mounted_client_id, rendered).await;
-								custom_sync_updates.push((mounted_client_id, item));
+								handle.render(client_id, rendered).await;
+								rendered_custom_sync = Some(item);
 							}
-							break;
 						}
-					}
-					if handled {
-						for (mounted_client_id, item) in custom_sync_updates {
-							self.sync_custom_components(mounted_client_id, &item).await;
+
+						// ── #[route] re-render on refresh ─────────────────
+						// If no legacy page/component handled the refresh, check
+						// whether the client's current route matches a
+						// #[route] GET handler and re-render it.
+						if rendered_custom_sync.is_none() {
+							if let Some(route_match) = self.match_route(
+								&route.path,
+								crate::wui::route_handler::HttpMethod::Get,
+							) {
+								let active_route = RouteContext {
+									path: route.path.clone(),
+									params: route_match.params.0.clone(),
+									query: route.query.clone(),
+									query_string: route.query_string.clone(),
+								};
+								let result = self
+									.dispatch_route(
+										route_match,
+										crate::wui::route_handler::RouteFormData::default(),
+										Some(client_id),
+										session.clone(),
+										active_route.clone(),
+									)
+									.await;
+								if let crate::wui::route_handler::RouteResult::View(mut view) =
+									result
+								{
+									let generation = route_loads.begin(client_id, None);
+									self.spawn_route_load(
+										client_id,
+										generation,
+										&mut view,
+										active_route,
+									);
+									if let Some(title) = &view.title {
+										handle.set_title(client_id, title).await;
+									}
+									let mut rendered = view.item.clone();
+									resolve_custom_component_entries(
+										&mut rendered,
+										&custom_component_entries,
+									);
+									handle.render(client_id, rendered).await;
+									self.sync_custom_components(client_id, &view.item).await;
+								}
+							}
+						}
+
+						if let Some(item) = rendered_custom_sync {
+							self.sync_custom_components(client_id, &item).await;
 						}
-						continue;
 					}
-					let mut custom_sync_updates: Vec<(usize, Item)> = Vec::new();
-					for page in self.pages.iter_mut() {
-						if let Some(controller) = page.controllers.get_mut(&client_id) {
-							let route = routes
-								.get(&client_id)
-								.cloned()
-								.unwrap_or_else(|| component_route_context("/", &HashMap::new()));
-							controller.set_runtime_context(Some(client_id), session.clone());
-							controller.set_route_context(Some(route));
-							handled = controller.handle(&message.event).await;
+					ClientEvent::Input(_) => {}
+					_ => {
+						let session = handle.session_for_client(client_id).await;
+						if let ClientEvent::OnCustom(custom) = &message.event {
+							if self.handle_custom_component_event(client_id, custom).await {
+								return;
+							}
 						}
-						if handled {
-							let mut updates: Vec<(usize, Item, Option<String>)> = Vec::new();
-							for (mounted_client_id, mounted_controller) in
-								page.controllers.iter_mut()
-							{
-								let mounted_session =
-									handle.session_for_client(*mounted_client_id).await;
-								let route =
-									routes.get(mounted_client_id).cloned().unwrap_or_else(|| {
-										component_route_context("/", &HashMap::new())
-									});
-								mounted_controller
-									.set_runtime_context(Some(*mounted_client_id), mounted_session);
-								mounted_controller.set_route_context(Some(route.clone()));
-								let item = mounted_controller.render_with_route(&route);
-								let title = mounted_controller
-									.title()
-									.or_else(|| mounted_controller.route_title(&route.path));
-								updates.push((*mounted_client_id, item, title));
+						let event_span = tracing::info_span!(
+							"controller_event",
+							client_id,
+							session = session.as_deref(),
+							route = routes.get(&client_id).map(|route| route.path.as_str()),
+							kind = crate::ui_client::event_kind_name(&message.event),
+							action = tracing::field::Empty,
+						);
+						// The controller, and the re-render of every client showing it, run
+						// inside the event span.
+						async {
+							let mut handled = false;
+							let mut custom_sync_updates: Vec<(usize, Item)> = Vec::new();
+							for component in self.components.iter_mut() {
+								if let Some(controller) = component.controllers.get_mut(&client_id)
+								{
+									let route =
+										routes.get(&client_id).cloned().unwrap_or_else(|| {
+											component_route_context("/", &HashMap::new(), "")
+										});
+									controller
+										.set_runtime_context(Some(client_id), session.clone());
+									controller.set_route_context(Some(route.clone()));
+									handled = dispatch_controller_event(
+										&handle,
+										client_id,
+										&route,
+										&self.error_pages,
+										controller.handle(&message.event),
+									)
+									.await;
+								}
+								if handled {
+									let mut updates: Vec<(usize, Item, Option<String>)> =
+										Vec::new();
+									for (mounted_client_id, mounted_controller) in
+										component.controllers.iter_mut()
+									{
+										let mounted_session =
+											handle.session_for_client(*mounted_client_id).await;
+										let route = routes
+											.get(mounted_client_id)
+											.cloned()
+											.unwrap_or_else(|| {
+												component_route_context("/", &HashMap::new(), "")
+											});
+										mounted_controller.set_runtime_context(
+											Some(*mounted_client_id),
+											mounted_session,
+										);
+										mounted_controller.set_route_context(Some(route.clone()));
+										let item = crate::i18n::with_client_locale(
+											*mounted_client_id,
+											|| {
+												render_controller(
+													&**mounted_controller,
+													&route,
+													&self.error_pages,
+												)
+											},
+										);
+										let title = mounted_controller.title().or_else(|| {
+											mounted_controller.route_title(&route.path)
+										});
+										updates.push((*mounted_client_id, item, title));
+									}
+
+									for (mounted_client_id, item, title) in updates {
+										if let Some(title) = title {
+											handle.set_title(mounted_client_id, &title).await;
+										}
+										let mut rendered = item.clone();
+										resolve_custom_component_entries(
+											&mut rendered,
+											&custom_component_entries,
+										);
+										handle.render(mounted_client_id, rendered).await;
+										custom_sync_updates.push((mounted_client_id, item));
+									}
+									break;
+								}
+							}
+							if handled {
+								for (mounted_client_id, item) in custom_sync_updates {
+									self.sync_custom_components(mounted_client_id, &item).await;
+								}
+								return;
 							}
+							let mut custom_sync_updates: Vec<(usize, Item)> = Vec::new();
+							for page in self.pages.iter_mut() {
+								if let Some(controller) = page.controllers.get_mut(&client_id) {
+									let route =
+										routes.get(&client_id).cloned().unwrap_or_else(|| {
+											component_route_context("/", &HashMap::new(), "")
+										});
+									controller
+										.set_runtime_context(Some(client_id), session.clone());
+									controller.set_route_context(Some(route.clone()));
+									handled = dispatch_controller_event(
+										&handle,
+										client_id,
+										&route,
+										&self.error_pages,
+										controller.handle(&message.event),
+									)
+									.await;
+								}
+								if handled {
+									let mut updates: Vec<(usize, Item, Option<String>)> =
+										Vec::new();
+									for (mounted_client_id, mounted_controller) in
+										page.controllers.iter_mut()
+									{
+										let mounted_session =
+											handle.session_for_client(*mounted_client_id).await;
+										let route = routes
+											.get(mounted_client_id)
+											.cloned()
+											.unwrap_or_else(|| {
+												component_route_context("/", &HashMap::new(), "")
+											});
+										mounted_controller.set_runtime_context(
+											Some(*mounted_client_id),
+											mounted_session,
+										);
+										mounted_controller.set_route_context(Some(route.clone()));
+										let item = crate::i18n::with_client_locale(
+											*mounted_client_id,
+											|| {
+												render_controller(
+													&**mounted_controller,
+													&route,
+													&self.error_pages,
+												)
+											},
+										);
+										let title = mounted_controller.title().or_else(|| {
+											mounted_controller.route_title(&route.path)
+										});
+										updates.push((*mounted_client_id, item, title));
+									}
 
-							for (mounted_client_id, item, title) in updates {
-								if let Some(title) = title {
-									handle.set_title(mounted_client_id, &title).await;
+									for (mounted_client_id, item, title) in updates {
+										if let Some(title) = title {
+											handle.set_title(mounted_client_id, &title).await;
+										}
+										let mut rendered = item.clone();
+										resolve_custom_component_entries(
+											&mut rendered,
+											&custom_component_entries,
+										);
+										handle.render(mounted_client_id, rendered).await;
+										custom_sync_updates.push((mounted_client_id, item));
+									}
+									break;
 								}
-								let mut rendered = item.clone();
-								resolve_custom_component_entries(
-									&mut rendered,
-									&custom_component_entries,
-								);
-								handle.render(mounted_client_id, rendered).await;
-								custom_sync_updates.push((mounted_client_id, item));
 							}
-							break;
+							for (mounted_client_id, item) in custom_sync_updates {
+								self.sync_custom_components(mounted_client_id, &item).await;
+							}
 						}
-					}
-					for (mounted_client_id, item) in custom_sync_updates {
-						self.sync_custom_components(mounted_client_id, &item).await;
+						.instrument(event_span)
+						.await;
 					}
 				}
 			}
+			.instrument(span)
+			.await;
 		}
 	}
 }
@@ -3170,6 +4034,83 @@ mod tests {
 		assert!(body.contains("invalid credentials"));
 	}
 
+	#[cfg(all(feature = "hyper", feature = "metrics"))]
+	#[tokio::test]
+	async fn every_instance_serves_the_process_wide_metrics() {
+		use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+		async fn scrape(addr: SocketAddr) -> String {
+			let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+			stream
+				.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+				.await
+				.unwrap();
+			let mut response = String::new();
+			stream.read_to_string(&mut response).await.unwrap();
+			response
+		}
+
+		let first = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let second = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let (first_addr, second_addr) = (first.local_addr().unwrap(), second.local_addr().unwrap());
+		let _first = Wgui::new_with_listener(first);
+		let _second = Wgui::new_with_listener(second);
+
+		crate::metrics::record_event("SharedRegistryProbe");
+
+		for addr in [first_addr, second_addr] {
+			assert!(scrape(addr)
+				.await
+				.contains("wgui_client_events_total{kind=\"SharedRegistryProbe\"} 1"));
+		}
+	}
+
+	#[cfg(debug_assertions)]
+	#[test]
+	fn devtools_are_enabled_per_instance() {
+		let with_devtools = Wgui::new_without_server();
+		let without = Wgui::new_without_server();
+
+		with_devtools.enable_devtools();
+
+		assert!(with_devtools.handle().devtools_enabled());
+		assert!(!without.handle().devtools_enabled());
+	}
+
+	#[cfg(feature = "hyper")]
+	#[tokio::test]
+	async fn ssr_bind_errors_are_returned() {
+		let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+		let addr = taken.local_addr().unwrap();
+
+		let result = Wgui::try_new_with_ssr(addr, std::sync::Arc::new(Item::default));
+
+		assert!(result.is_err());
+	}
+
+	#[cfg(all(feature = "hyper", unix))]
+	#[tokio::test]
+	async fn serves_http_on_a_unix_listener() {
+		use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+		let path = std::env::temp_dir().join(format!("wgui-{}.sock", std::process::id()));
+		let _ = std::fs::remove_file(&path);
+		let listener = tokio::net::UnixListener::bind(&path).unwrap();
+		let _wgui = Wgui::new_with_listener(listener);
+
+		let mut stream = tokio::net::UnixStream::connect(&path).await.unwrap();
+		stream
+			.write_all(b"GET /index.css HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+			.await
+			.unwrap();
+		let mut response = String::new();
+		stream.read_to_string(&mut response).await.unwrap();
+
+		assert!(response.starts_with("HTTP/1.1 200"));
+		assert!(response.contains("content-type: text/css"));
+		std::fs::remove_file(path).unwrap();
+	}
+
 	#[test]
 	fn custom_component_asset_comes_from_type_name() {
 		struct RobotSceneComponent;
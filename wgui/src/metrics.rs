@@ -0,0 +1,262 @@
+//! Prometheus metrics for the WGUI runtime.
+//!
+//! Enabled with the `metrics` feature. The websocket worker, diff, HTTP server
+//! and SQLite tables record into a process-wide registry, and the server
+//! exposes it in the Prometheus text format at the path configured with
+//! `Wgui::set_metrics_path` (`/metrics` by default).
+//!
+//! The registry is deliberately not tied to a `Wgui`: SQLite tables and diffs
+//! have no instance to report to. A process running several `Wgui` servers
+//! serves the combined numbers from each of them.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub const DEFAULT_METRICS_PATH: &str = "/metrics";
+
+const BUCKETS: [f64; 11] = [
+	0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry::new());
+
+struct Histogram {
+	buckets: [u64; BUCKETS.len()],
+	sum: f64,
+	count: u64,
+}
+
+impl Histogram {
+	const fn new() -> Self {
+		Self {
+			buckets: [0; BUCKETS.len()],
+			sum: 0.0,
+			count: 0,
+		}
+	}
+
+	fn observe(&mut self, elapsed: Duration) {
+		let seconds = elapsed.as_secs_f64();
+		for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS) {
+			if seconds <= bound {
+				*bucket += 1;
+			}
+		}
+		self.sum += seconds;
+		self.count += 1;
+	}
+
+	fn write(&self, out: &mut String, name: &str, labels: &str) {
+		let separator = if labels.is_empty() { "" } else { "," };
+		for (count, bound) in self.buckets.iter().zip(BUCKETS) {
+			let _ = writeln!(
+				out,
+				"{name}_bucket{{{labels}{separator}le=\"{bound}\"}} {count}"
+			);
+		}
+		let _ = writeln!(
+			out,
+			"{name}_bucket{{{labels}{separator}le=\"+Inf\"}} {}",
+			self.count
+		);
+		let labels = if labels.is_empty() {
+			String::new()
+		} else {
+			format!("{{{labels}}}")
+		};
+		let _ = writeln!(out, "{name}_sum{labels} {}", self.sum);
+		let _ = writeln!(out, "{name}_count{labels} {}", self.count);
+	}
+}
+
+struct Registry {
+	connected_clients: i64,
+	events: BTreeMap<&'static str, u64>,
+	ws_bytes_received: u64,
+	ws_bytes_sent: u64,
+	render: Histogram,
+	diff: Histogram,
+	post_routes: BTreeMap<String, Histogram>,
+	sqlite_queries: BTreeMap<(String, &'static str), Histogram>,
+}
+
+impl Registry {
+	const fn new() -> Self {
+		Self {
+			connected_clients: 0,
+			events: BTreeMap::new(),
+			ws_bytes_received: 0,
+			ws_bytes_sent: 0,
+			render: Histogram::new(),
+			diff: Histogram::new(),
+			post_routes: BTreeMap::new(),
+			sqlite_queries: BTreeMap::new(),
+		}
+	}
+}
+
+fn with_registry(update: impl FnOnce(&mut Registry)) {
+	let mut registry = REGISTRY.lock().unwrap_or_else(|err| err.into_inner());
+	update(&mut registry);
+}
+
+fn escape_label(value: &str) -> String {
+	value
+		.replace('\\', "\\\\")
+		.replace('"', "\\\"")
+		.replace('\n', "\\n")
+}
+
+pub(crate) fn client_connected() {
+	with_registry(|registry| registry.connected_clients += 1);
+}
+
+pub(crate) fn client_disconnected() {
+	with_registry(|registry| registry.connected_clients -= 1);
+}
+
+pub(crate) fn record_event(kind: &'static str) {
+	with_registry(|registry| *registry.events.entry(kind).or_default() += 1);
+}
+
+pub(crate) fn record_ws_bytes_received(bytes: usize) {
+	with_registry(|registry| registry.ws_bytes_received += bytes as u64);
+}
+
+pub(crate) fn record_ws_bytes_sent(bytes: usize) {
+	with_registry(|registry| registry.ws_bytes_sent += bytes as u64);
+}
+
+pub(crate) fn observe_render(elapsed: Duration) {
+	with_registry(|registry| registry.render.observe(elapsed));
+}
+
+pub(crate) fn observe_diff(elapsed: Duration) {
+	with_registry(|registry| registry.diff.observe(elapsed));
+}
+
+/// Record a POST route's handler latency, whether the form arrived over HTTP
+/// or the websocket.
+pub(crate) fn observe_post_route(route: &str, elapsed: Duration) {
+	with_registry(|registry| {
+		registry
+			.post_routes
+			.entry(route.to_string())
+			.or_insert_with(Histogram::new)
+			.observe(elapsed)
+	});
+}
+
+#[cfg(feature = "sqlite")]
+pub(crate) fn observe_sqlite_query(table: &str, operation: &'static str, elapsed: Duration) {
+	with_registry(|registry| {
+		registry
+			.sqlite_queries
+			.entry((table.to_string(), operation))
+			.or_insert_with(Histogram::new)
+			.observe(elapsed)
+	});
+}
+
+/// Render every metric recorded anywhere in the process in the Prometheus
+/// text exposition format.
+pub fn gather() -> String {
+	let registry = REGISTRY.lock().unwrap_or_else(|err| err.into_inner());
+	let mut out = String::new();
+
+	out.push_str("# HELP wgui_connected_clients Currently connected websocket clients.\n");
+	out.push_str("# TYPE wgui_connected_clients gauge\n");
+	let _ = writeln!(out, "wgui_connected_clients {}", registry.connected_clients);
+
+	out.push_str("# HELP wgui_client_events_total Client events received by kind.\n");
+	out.push_str("# TYPE wgui_client_events_total counter\n");
+	for (kind, count) in &registry.events {
+		let _ = writeln!(out, "wgui_client_events_total{{kind=\"{kind}\"}} {count}");
+	}
+
+	out.push_str("# HELP wgui_ws_received_bytes_total Websocket payload bytes received.\n");
+	out.push_str("# TYPE wgui_ws_received_bytes_total counter\n");
+	let _ = writeln!(
+		out,
+		"wgui_ws_received_bytes_total {}",
+		registry.ws_bytes_received
+	);
+	out.push_str("# HELP wgui_ws_sent_bytes_total Websocket payload bytes sent.\n");
+	out.push_str("# TYPE wgui_ws_sent_bytes_total counter\n");
+	let _ = writeln!(out, "wgui_ws_sent_bytes_total {}", registry.ws_bytes_sent);
+
+	out.push_str(
+		"# HELP wgui_render_duration_seconds Time to diff, encode and send a rendered tree.\n",
+	);
+	out.push_str("# TYPE wgui_render_duration_seconds histogram\n");
+	registry
+		.render
+		.write(&mut out, "wgui_render_duration_seconds", "");
+
+	out.push_str("# HELP wgui_diff_duration_seconds Time spent diffing item trees.\n");
+	out.push_str("# TYPE wgui_diff_duration_seconds histogram\n");
+	registry
+		.diff
+		.write(&mut out, "wgui_diff_duration_seconds", "");
+
+	out.push_str("# HELP wgui_post_route_duration_seconds POST route handler latency.\n");
+	out.push_str("# TYPE wgui_post_route_duration_seconds histogram\n");
+	for (route, histogram) in &registry.post_routes {
+		histogram.write(
+			&mut out,
+			"wgui_post_route_duration_seconds",
+			&format!("route=\"{}\"", escape_label(route)),
+		);
+	}
+
+	out.push_str("# HELP wgui_sqlite_query_duration_seconds SQLite table operation latency.\n");
+	out.push_str("# TYPE wgui_sqlite_query_duration_seconds histogram\n");
+	for ((table, operation), histogram) in &registry.sqlite_queries {
+		histogram.write(
+			&mut out,
+			"wgui_sqlite_query_duration_seconds",
+			&format!(
+				"table=\"{}\",operation=\"{operation}\"",
+				escape_label(table)
+			),
+		);
+	}
+
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn histogram_buckets_are_cumulative() {
+		let mut histogram = Histogram::new();
+		histogram.observe(Duration::from_millis(20));
+		histogram.observe(Duration::from_secs(20));
+
+		let mut out = String::new();
+		histogram.write(&mut out, "test_seconds", "route=\"/a\"");
+
+		assert!(out.contains("test_seconds_bucket{route=\"/a\",le=\"0.01\"} 0\n"));
+		assert!(out.contains("test_seconds_bucket{route=\"/a\",le=\"0.025\"} 1\n"));
+		assert!(out.contains("test_seconds_bucket{route=\"/a\",le=\"10\"} 1\n"));
+		assert!(out.contains("test_seconds_bucket{route=\"/a\",le=\"+Inf\"} 2\n"));
+		assert!(out.contains("test_seconds_count{route=\"/a\"} 2\n"));
+	}
+
+	#[test]
+	fn gather_reports_recorded_events() {
+		record_event("OnClick");
+		record_ws_bytes_received(12);
+
+		let out = gather();
+
+		assert!(out.contains("# TYPE wgui_client_events_total counter\n"));
+		assert!(out.contains("wgui_client_events_total{kind=\"OnClick\"}"));
+		assert!(out.contains("wgui_ws_received_bytes_total"));
+		assert!(out.contains("# TYPE wgui_render_duration_seconds histogram\n"));
+	}
+}
@@ -18,7 +18,10 @@ use std::pin::Pin;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::TcpListener;
+#[cfg(unix)]
+use tokio::net::UnixListener;
 use tokio::sync::mpsc;
 
 use crate::ssr;
@@ -26,13 +29,15 @@ use crate::types::{ClientMessage, Clients};
 use crate::ws::TungsteniteWs;
 use crate::wui::routing::{best_route_index, RoutePattern};
 use crate::wui::runtime::RouteContext;
-use crate::{Sessions, SsrHydrationRoot, SsrHydrationRoots, SsrRenderer, SsrResponse, WguiHandle};
+use crate::{
+	Sessions, SsrHydrationRoot, SsrHydrationRoots, SsrRenderer, SsrResponse, WguiHandle,
+	STATIC_ASSET_VERSION_PARAM,
+};
 
 const INDEX_HTML_BYTES: &[u8] = include_bytes!("../../dist/index.html");
 const INDEX_JS_BYTES: &[u8] = include_bytes!("../../dist/index.js");
 const CSS_JS_BYTES: &[u8] = include_bytes!("../../dist/index.css");
 const MAX_SSR_HYDRATION_ROOTS: usize = 128;
-const STATIC_ASSET_VERSION_PARAM: &str = "wgui-v";
 const IMMUTABLE_STATIC_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
 const DEFAULT_STATIC_CACHE_CONTROL: &str = "public, max-age=86400";
 const UNVERSIONED_STATIC_CACHE_CONTROL: &str = "no-store";
@@ -50,6 +55,8 @@ pub(crate) type SharedAppCss = Arc<RwLock<Option<String>>>;
 pub(crate) type SharedHttpHandler = Arc<RwLock<Option<HttpHandler>>>;
 pub(crate) type SharedHttpRoutes = Arc<RwLock<Vec<HttpRoute>>>;
 pub(crate) type SharedStaticMounts = Arc<RwLock<Vec<StaticMount>>>;
+#[cfg(feature = "metrics")]
+pub(crate) type SharedMetricsPath = Arc<RwLock<String>>;
 
 #[derive(Clone)]
 pub(crate) struct HttpRoute {
@@ -144,6 +151,8 @@ pub struct HttpRequest {
 	pub method: String,
 	pub path: String,
 	pub query: HashMap<String, String>,
+	/// Raw query string without the leading `?`.
+	pub query_string: String,
 	pub headers: HashMap<String, String>,
 	pub body: Vec<u8>,
 }
@@ -291,6 +300,35 @@ fn normalize_mount_route(route: String) -> String {
 	}
 }
 
+/// The files and directories behind `mounts`, for the hot reloader.
+#[cfg(feature = "hot-reload")]
+pub(crate) fn static_mount_paths(mounts: &SharedStaticMounts) -> Vec<PathBuf> {
+	mounts
+		.read()
+		.unwrap()
+		.iter()
+		.map(|mount| match mount {
+			StaticMount::File { file, .. } => file.clone(),
+			StaticMount::Dir { dir, .. } => dir.clone(),
+		})
+		.collect()
+}
+
+/// Fingerprint the mounted files among `changed` again, so their old
+/// versioned URLs are no longer served as immutable.
+#[cfg(feature = "hot-reload")]
+pub(crate) fn refresh_static_versions(mounts: &SharedStaticMounts, changed: &[PathBuf]) {
+	for mount in mounts.write().unwrap().iter_mut() {
+		let StaticMount::File { file, version, .. } = mount else {
+			continue;
+		};
+		let canonical = std::fs::canonicalize(&*file).unwrap_or_else(|_| file.clone());
+		if changed.contains(&canonical) {
+			*version = static_file_version(file).ok();
+		}
+	}
+}
+
 fn static_file_version(path: &Path) -> std::io::Result<String> {
 	let bytes = std::fs::read(path)?;
 	let mut hasher = DefaultHasher::new();
@@ -411,18 +449,51 @@ async fn static_mount_response(
 }
 
 fn index_html_response(app_css: &SharedAppCss) -> Vec<u8> {
-	if app_css.read().unwrap().is_none() {
+	let stylesheet = crate::wui::styles::stylesheet();
+	if app_css.read().unwrap().is_none() && stylesheet.is_none() {
 		return INDEX_HTML_BYTES.to_vec();
 	}
 
+	let mut links = "<link rel=\"stylesheet\" href=\"/index.css\"></link>".to_string();
+	if app_css.read().unwrap().is_some() {
+		links.push_str("<link rel=\"stylesheet\" href=\"/app.css\"></link>");
+	}
+	if let Some(stylesheet) = stylesheet {
+		links.push_str(&format!(
+			"<link rel=\"stylesheet\" href=\"{}\"></link>",
+			stylesheet.url()
+		));
+	}
 	let html = String::from_utf8_lossy(INDEX_HTML_BYTES);
 	html.replace(
 		"<link rel=\"stylesheet\" href=\"/index.css\"></link>",
-		"<link rel=\"stylesheet\" href=\"/index.css\"></link><link rel=\"stylesheet\" href=\"/app.css\"></link>",
+		&links,
 	)
 	.into_bytes()
 }
 
+/// The `<Style>` stylesheet; cached for good when the request names its
+/// current version.
+fn stylesheet_response(requested_version: Option<&str>) -> Response<HttpBody> {
+	let Some(stylesheet) = crate::wui::styles::stylesheet() else {
+		return Response::builder()
+			.status(404)
+			.header("cache-control", UNVERSIONED_STATIC_CACHE_CONTROL)
+			.body(full_body("no WUI styles"))
+			.unwrap();
+	};
+	let cache_control = if requested_version == Some(stylesheet.version.as_str()) {
+		IMMUTABLE_STATIC_CACHE_CONTROL
+	} else {
+		UNVERSIONED_STATIC_CACHE_CONTROL
+	};
+	Response::builder()
+		.header("content-type", "text/css")
+		.header("cache-control", cache_control)
+		.body(full_body(stylesheet.css))
+		.unwrap()
+}
+
 struct Ctx {
 	event_tx: mpsc::UnboundedSender<ClientMessage>,
 	clients: Clients,
@@ -433,6 +504,8 @@ struct Ctx {
 	app_css: SharedAppCss,
 	static_mounts: SharedStaticMounts,
 	ssr_hydration_roots: SsrHydrationRoots,
+	#[cfg(feature = "metrics")]
+	metrics_path: SharedMetricsPath,
 }
 
 fn next_ssr_hydration_id() -> String {
@@ -520,6 +593,12 @@ fn session_from_request(req: &Request<hyper::body::Incoming>) -> Option<String>
 	session_from_query(req).or_else(|| cookie_value(req, "sid"))
 }
 
+fn accept_language<B>(req: &Request<B>) -> Option<&str> {
+	req.headers()
+		.get(hyper::header::ACCEPT_LANGUAGE)
+		.and_then(|value| value.to_str().ok())
+}
+
 fn new_session_id() -> String {
 	format!("sid-{}", next_ssr_hydration_id())
 }
@@ -579,12 +658,14 @@ async fn http_request(
 	let method = req.method().as_str().to_string();
 	let path = req.uri().path().to_string();
 	let query = query_map(req);
+	let query_string = req.uri().query().unwrap_or_default().to_string();
 	let headers = header_map(req);
 	let body = req.body_mut().collect().await?.to_bytes().to_vec();
 	Ok(HttpRequest {
 		method,
 		path,
 		query,
+		query_string,
 		headers,
 		body,
 	})
@@ -603,13 +684,14 @@ async fn handle_req(
 		let clients = ctx.clients.clone();
 		let sessions = ctx.sessions.clone();
 		let session = session_from_request(&req);
+		let locale = crate::i18n::negotiate(accept_language(&req));
 		tokio::spawn(async move {
 			match websocket.await {
 				Ok(ws) => {
 					log::info!("websocket connected");
 					let ws = TungsteniteWs::new(ws);
 					let handle = WguiHandle::new(event_tx, clients, sessions);
-					handle.handle_ws_with_session(ws, session).await;
+					handle.handle_ws_with_locale(ws, session, locale).await;
 				}
 				Err(err) => {
 					log::error!("websocket error: {:?}", err);
@@ -619,6 +701,15 @@ async fn handle_req(
 		return Ok(response.map(|body| body.map_err(|never| match never {}).boxed_unsync()));
 	}
 
+	#[cfg(feature = "metrics")]
+	if req.method() == hyper::Method::GET && *ctx.metrics_path.read().unwrap() == req.uri().path() {
+		return Ok(Response::builder()
+			.header("content-type", "text/plain; version=0.0.4")
+			.header("cache-control", "no-store")
+			.body(full_body(crate::metrics::gather()))
+			.unwrap());
+	}
+
 	let session = session_from_request(&req);
 	let route = matching_http_route(&ctx.http_routes, req.method().as_str(), req.uri().path());
 	let has_http_handler = ctx.http_handler.read().unwrap().is_some();
@@ -642,7 +733,13 @@ async fn handle_req(
 				headers: request.headers.clone(),
 				session,
 			};
+			#[cfg(feature = "metrics")]
+			let started = std::time::Instant::now();
 			let mut response = http_response((route.handler)(request, http_ctx).await);
+			#[cfg(feature = "metrics")]
+			if route.method == "POST" {
+				crate::metrics::observe_post_route(route.pattern.raw(), started.elapsed());
+			}
 			if let Some(session) = new_session {
 				response.headers_mut().insert(
 					hyper::header::SET_COOKIE,
@@ -685,6 +782,9 @@ async fn handle_req(
 			.header("cache-control", "no-store")
 			.body(full_body(CSS_JS_BYTES))
 			.unwrap()),
+		crate::wui::styles::STYLESHEET_ROUTE => {
+			Ok(stylesheet_response(requested_static_version.as_deref()))
+		}
 		"/app.css" => {
 			let css = ctx.app_css.read().unwrap().clone();
 			match css {
@@ -749,9 +849,15 @@ async fn handle_req(
 					path: req.uri().path().to_string(),
 					params: std::collections::HashMap::new(),
 					query: query_map(&req),
+					query_string: req.uri().query().unwrap_or_default().to_string(),
 				};
-				match (renderer)(route, session) {
-					Some(SsrResponse::Render { item, title }) => {
+				let locale = crate::i18n::negotiate(accept_language(&req));
+				match crate::i18n::with_locale(locale, || (renderer)(route, session)) {
+					Some(SsrResponse::Render {
+						item,
+						title,
+						status,
+					}) => {
 						let hydration_id = next_ssr_hydration_id();
 						store_ssr_hydration_root(
 							&ctx.ssr_hydration_roots,
@@ -768,6 +874,7 @@ async fn handle_req(
 							title.as_deref(),
 						);
 						let mut response = Response::builder()
+							.status(status)
 							.header("content-type", "text/html")
 							.header("cache-control", "no-store")
 							.body(full_body(html))
@@ -816,8 +923,61 @@ async fn handle_req(
 	}
 }
 
+/// A listener the WGUI HTTP server accepts connections from.
+///
+/// Build one from a bound `tokio::net::TcpListener` (for example a socket
+/// inherited through systemd socket activation) or, on Unix, from a
+/// `tokio::net::UnixListener` placed behind a reverse proxy.
+pub enum ServerListener {
+	Tcp(TcpListener),
+	#[cfg(unix)]
+	Unix(UnixListener),
+}
+
+impl ServerListener {
+	/// Bind a TCP listener without panicking on address conflicts.
+	///
+	/// Must be called from within a Tokio runtime.
+	pub fn bind(addr: SocketAddr) -> std::io::Result<Self> {
+		let listener = std::net::TcpListener::bind(addr)?;
+		listener.set_nonblocking(true)?;
+		Ok(Self::Tcp(TcpListener::from_std(listener)?))
+	}
+
+	fn describe(&self) -> String {
+		match self {
+			Self::Tcp(listener) => match listener.local_addr() {
+				Ok(addr) => format!("http://localhost:{}", addr.port()),
+				Err(_) => "tcp listener".to_string(),
+			},
+			#[cfg(unix)]
+			Self::Unix(listener) => match listener
+				.local_addr()
+				.ok()
+				.and_then(|addr| addr.as_pathname().map(Path::to_path_buf))
+			{
+				Some(path) => format!("unix:{}", path.display()),
+				None => "unix socket".to_string(),
+			},
+		}
+	}
+}
+
+impl From<TcpListener> for ServerListener {
+	fn from(listener: TcpListener) -> Self {
+		Self::Tcp(listener)
+	}
+}
+
+#[cfg(unix)]
+impl From<UnixListener> for ServerListener {
+	fn from(listener: UnixListener) -> Self {
+		Self::Unix(listener)
+	}
+}
+
 pub struct Server {
-	listener: TcpListener,
+	listener: ServerListener,
 	event_tx: mpsc::UnboundedSender<ClientMessage>,
 	clients: Clients,
 	sessions: Sessions,
@@ -827,10 +987,12 @@ pub struct Server {
 	app_css: SharedAppCss,
 	static_mounts: SharedStaticMounts,
 	ssr_hydration_roots: SsrHydrationRoots,
+	#[cfg(feature = "metrics")]
+	metrics_path: SharedMetricsPath,
 }
 
 pub(crate) struct ServerConfig {
-	pub(crate) addr: SocketAddr,
+	pub(crate) listener: ServerListener,
 	pub(crate) event_tx: mpsc::UnboundedSender<ClientMessage>,
 	pub(crate) clients: Clients,
 	pub(crate) sessions: Sessions,
@@ -840,12 +1002,14 @@ pub(crate) struct ServerConfig {
 	pub(crate) app_css: SharedAppCss,
 	pub(crate) static_mounts: SharedStaticMounts,
 	pub(crate) ssr_hydration_roots: SsrHydrationRoots,
+	#[cfg(feature = "metrics")]
+	pub(crate) metrics_path: SharedMetricsPath,
 }
 
 impl Server {
-	pub async fn new(config: ServerConfig) -> Self {
+	pub fn new(config: ServerConfig) -> Self {
 		let ServerConfig {
-			addr,
+			listener,
 			event_tx,
 			clients,
 			sessions,
@@ -855,9 +1019,10 @@ impl Server {
 			app_css,
 			static_mounts,
 			ssr_hydration_roots,
+			#[cfg(feature = "metrics")]
+			metrics_path,
 		} = config;
-		let listener = TcpListener::bind(addr).await.unwrap();
-		log::info!("listening on http://localhost:{}", addr.port());
+		log::info!("listening on {}", listener.describe());
 
 		Self {
 			listener,
@@ -870,58 +1035,82 @@ impl Server {
 			app_css,
 			static_mounts,
 			ssr_hydration_roots,
+			#[cfg(feature = "metrics")]
+			metrics_path,
 		}
 	}
 
 	pub async fn run(self) {
 		loop {
-			tokio::select! {
-				res = self.listener.accept() => {
-					match res {
-						Ok((socket, addr)) => {
-							log::info!("accepted connection from {}", addr);
-							let io = TokioIo::new(socket);
-							let event_tx = self.event_tx.clone();
-							let clients = self.clients.clone();
-								let sessions = self.sessions.clone();
-								let ssr = self.ssr.clone();
-								let http_handler = self.http_handler.clone();
-								let http_routes = self.http_routes.clone();
-								let app_css = self.app_css.clone();
-								let static_mounts = self.static_mounts.clone();
-								let ssr_hydration_roots = self.ssr_hydration_roots.clone();
-								tokio::spawn(async move {
-									let service = service_fn(move |req| {
-										handle_req(req, Ctx {
-											event_tx: event_tx.clone(),
-											clients: clients.clone(),
-											sessions: sessions.clone(),
-											ssr: ssr.clone(),
-											http_handler: http_handler.clone(),
-											http_routes: http_routes.clone(),
-											app_css: app_css.clone(),
-											static_mounts: static_mounts.clone(),
-											ssr_hydration_roots: ssr_hydration_roots.clone(),
-										})
-									});
-
-								if let Err(err) = http1::Builder::new()
-									.serve_connection(io, service)
-									.with_upgrades()
-									.await {
-
-									log::error!("server error: {:?}", err);
-								}
-							});
-						},
-						Err(err) => {
-							log::error!("accept error: {:?}", err);
-						}
+			match &self.listener {
+				ServerListener::Tcp(listener) => match listener.accept().await {
+					Ok((socket, addr)) => {
+						log::info!("accepted connection from {}", addr);
+						self.serve(socket);
 					}
-				}
+					Err(err) => {
+						log::error!("accept error: {:?}", err);
+					}
+				},
+				#[cfg(unix)]
+				ServerListener::Unix(listener) => match listener.accept().await {
+					Ok((socket, _)) => {
+						log::info!("accepted unix socket connection");
+						self.serve(socket);
+					}
+					Err(err) => {
+						log::error!("accept error: {:?}", err);
+					}
+				},
 			}
 		}
 	}
+
+	fn serve<IO>(&self, socket: IO)
+	where
+		IO: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+	{
+		let io = TokioIo::new(socket);
+		let event_tx = self.event_tx.clone();
+		let clients = self.clients.clone();
+		let sessions = self.sessions.clone();
+		let ssr = self.ssr.clone();
+		let http_handler = self.http_handler.clone();
+		let http_routes = self.http_routes.clone();
+		let app_css = self.app_css.clone();
+		let static_mounts = self.static_mounts.clone();
+		let ssr_hydration_roots = self.ssr_hydration_roots.clone();
+		#[cfg(feature = "metrics")]
+		let metrics_path = self.metrics_path.clone();
+		tokio::spawn(async move {
+			let service = service_fn(move |req| {
+				handle_req(
+					req,
+					Ctx {
+						event_tx: event_tx.clone(),
+						clients: clients.clone(),
+						sessions: sessions.clone(),
+						ssr: ssr.clone(),
+						http_handler: http_handler.clone(),
+						http_routes: http_routes.clone(),
+						app_css: app_css.clone(),
+						static_mounts: static_mounts.clone(),
+						ssr_hydration_roots: ssr_hydration_roots.clone(),
+						#[cfg(feature = "metrics")]
+						metrics_path: metrics_path.clone(),
+					},
+				)
+			});
+
+			if let Err(err) = http1::Builder::new()
+				.serve_connection(io, service)
+				.with_upgrades()
+				.await
+			{
+				log::error!("server error: {:?}", err);
+			}
+		});
+	}
 }
 
 #[cfg(test)]
@@ -966,6 +1155,35 @@ mod tests {
 		std::fs::remove_file(path).unwrap();
 	}
 
+	#[cfg(feature = "hot-reload")]
+	#[test]
+	fn changed_static_files_are_fingerprinted_again() {
+		let path = temporary_static_file(b"body { color: red }");
+		let (mount, asset) = StaticMount::file("/app.css".to_string(), path.clone());
+		let mounts: SharedStaticMounts = Arc::new(RwLock::new(vec![mount]));
+		assert_eq!(static_mount_paths(&mounts), vec![path.clone()]);
+
+		std::fs::write(&path, b"body { color: blue }").unwrap();
+		refresh_static_versions(&mounts, &[std::fs::canonicalize(&path).unwrap()]);
+		let StaticMount::File { version, .. } = &mounts.read().unwrap()[0] else {
+			unreachable!();
+		};
+		assert!(!asset.url().ends_with(version.as_deref().unwrap()));
+
+		std::fs::remove_file(path).unwrap();
+	}
+
+	#[tokio::test]
+	async fn binding_an_address_in_use_returns_an_error() {
+		let first = ServerListener::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+		let ServerListener::Tcp(listener) = &first else {
+			panic!("expected tcp listener");
+		};
+		let addr = listener.local_addr().unwrap();
+
+		assert!(ServerListener::bind(addr).is_err());
+	}
+
 	#[test]
 	fn unreadable_static_asset_keeps_unversioned_route() {
 		let path = std::env::temp_dir().join("wgui-static-asset-does-not-exist.js");
@@ -1001,4 +1219,15 @@ mod tests {
 
 		std::fs::remove_file(path).unwrap();
 	}
+
+	#[test]
+	fn stylesheet_is_served_with_registered_styles() {
+		crate::wui::styles::register("server_test", "wui-server-test", ".server-test { x: y }\n");
+		let response = stylesheet_response(Some("old"));
+		assert_eq!(response.status(), 200);
+		assert_eq!(cache_control(&response), UNVERSIONED_STATIC_CACHE_CONTROL);
+		let stylesheet = crate::wui::styles::stylesheet().unwrap();
+		assert!(stylesheet.css.contains(".server-test { x: y }"));
+		assert!(stylesheet.url().starts_with("/wui.css?wgui-v="));
+	}
 }
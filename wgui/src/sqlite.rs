@@ -543,18 +543,36 @@ impl<T> std::fmt::Debug for SqliteTable<T> {
 	}
 }
 
+impl<T> SqliteTable<T> {
+	fn instrumented<R>(
+		&self,
+		operation: &'static str,
+		run: impl FnOnce() -> Result<R>,
+	) -> Result<R> {
+		let _span = tracing::debug_span!("sqlite", table = %self.table_name, operation).entered();
+		#[cfg(feature = "metrics")]
+		let started = std::time::Instant::now();
+		let result = run();
+		#[cfg(feature = "metrics")]
+		crate::metrics::observe_sqlite_query(&self.table_name, operation, started.elapsed());
+		result
+	}
+}
+
 impl<T> SqliteTable<T>
 where
 	T: WdbModel + Clone + Serialize + DeserializeOwned,
 {
 	pub fn row_count_sync(&self) -> Result<u64> {
-		let sql = format!("SELECT COUNT(*) FROM \"{}\"", self.table_name);
-		let conn = self.conn.lock().unwrap();
-		let count: i64 = conn
-			.query_row(&sql, [], |row| row.get(0))
-			.with_context(|| format!("failed counting rows in {}", self.table_name))?;
-		let count = u64::try_from(count).context("row count overflowed u64")?;
-		Ok(count)
+		self.instrumented("row_count", || {
+			let sql = format!("SELECT COUNT(*) FROM \"{}\"", self.table_name);
+			let conn = self.conn.lock().unwrap();
+			let count: i64 = conn
+				.query_row(&sql, [], |row| row.get(0))
+				.with_context(|| format!("failed counting rows in {}", self.table_name))?;
+			let count = u64::try_from(count).context("row count overflowed u64")?;
+			Ok(count)
+		})
 	}
 
 	pub async fn row_count(&self) -> Result<u64> {
@@ -562,26 +580,28 @@ where
 	}
 
 	pub fn snapshot_sync(&self) -> Result<Vec<T>> {
-		let schema = T::schema();
-		let cols = model_columns(&schema)?;
-		let has_model_id = has_model_id(&schema);
-		let select_cols = column_select_list(&cols);
-		let sql = format!(
-			"SELECT id{} FROM \"{}\" ORDER BY id",
-			select_cols, self.table_name
-		);
-		let conn = self.conn.lock().unwrap();
-		let mut stmt = conn
-			.prepare(&sql)
-			.with_context(|| format!("failed to prepare snapshot query for {}", self.table_name))?;
-		let mut rows = stmt
-			.query([])
-			.with_context(|| format!("failed to query snapshot for {}", self.table_name))?;
-		let mut out = Vec::new();
-		while let Some(row) = rows.next().context("failed to iterate sqlite rows")? {
-			out.push(row_to_model::<T>(row, &schema, &cols, has_model_id)?);
-		}
-		Ok(out)
+		self.instrumented("snapshot", || {
+			let schema = T::schema();
+			let cols = model_columns(&schema)?;
+			let has_model_id = has_model_id(&schema);
+			let select_cols = column_select_list(&cols);
+			let sql = format!(
+				"SELECT id{} FROM \"{}\" ORDER BY id",
+				select_cols, self.table_name
+			);
+			let conn = self.conn.lock().unwrap();
+			let mut stmt = conn.prepare(&sql).with_context(|| {
+				format!("failed to prepare snapshot query for {}", self.table_name)
+			})?;
+			let mut rows = stmt
+				.query([])
+				.with_context(|| format!("failed to query snapshot for {}", self.table_name))?;
+			let mut out = Vec::new();
+			while let Some(row) = rows.next().context("failed to iterate sqlite rows")? {
+				out.push(row_to_model::<T>(row, &schema, &cols, has_model_id)?);
+			}
+			Ok(out)
+		})
 	}
 
 	pub async fn snapshot(&self) -> Result<Vec<T>> {
@@ -589,19 +609,21 @@ where
 	}
 
 	pub fn replace_sync(&self, rows: Vec<T>) -> Result<()> {
-		let delete_sql = format!("DELETE FROM \"{}\"", self.table_name);
-		let mut conn = self.conn.lock().unwrap();
-		let tx = conn
-			.transaction()
-			.context("failed to start sqlite transaction")?;
-		tx.execute(&delete_sql, [])
-			.with_context(|| format!("failed to clear table {}", self.table_name))?;
-		for row in rows {
-			self.insert_with_connection(&tx, row)?;
-		}
-		tx.commit()
-			.with_context(|| format!("failed to commit replacing table {}", self.table_name))?;
-		Ok(())
+		self.instrumented("replace", || {
+			let delete_sql = format!("DELETE FROM \"{}\"", self.table_name);
+			let mut conn = self.conn.lock().unwrap();
+			let tx = conn
+				.transaction()
+				.context("failed to start sqlite transaction")?;
+			tx.execute(&delete_sql, [])
+				.with_context(|| format!("failed to clear table {}", self.table_name))?;
+			for row in rows {
+				self.insert_with_connection(&tx, row)?;
+			}
+			tx.commit()
+				.with_context(|| format!("failed to commit replacing table {}", self.table_name))?;
+			Ok(())
+		})
 	}
 
 	pub async fn replace(&self, rows: Vec<T>) -> Result<()> {
@@ -609,8 +631,10 @@ where
 	}
 
 	pub fn insert_sync(&self, row: T) -> Result<()> {
-		let conn = self.conn.lock().unwrap();
-		self.insert_with_connection(&conn, row)
+		self.instrumented("insert", || {
+			let conn = self.conn.lock().unwrap();
+			self.insert_with_connection(&conn, row)
+		})
 	}
 
 	fn insert_with_connection(&self, conn: &Connection, row: T) -> Result<()> {
@@ -656,16 +680,18 @@ where
 	T: WdbModel + HasId + Clone + Serialize + DeserializeOwned,
 {
 	pub fn next_id_sync(&self) -> Result<u32> {
-		let sql = format!(
-			"SELECT COALESCE(MAX(id), 0) + 1 FROM \"{}\"",
-			self.table_name
-		);
-		let conn = self.conn.lock().unwrap();
-		let id: i64 = conn
-			.query_row(&sql, [], |row| row.get(0))
-			.with_context(|| format!("failed to read next id for {}", self.table_name))?;
-		let id = u32::try_from(id).context("sqlite id overflowed u32")?;
-		Ok(id)
+		self.instrumented("next_id", || {
+			let sql = format!(
+				"SELECT COALESCE(MAX(id), 0) + 1 FROM \"{}\"",
+				self.table_name
+			);
+			let conn = self.conn.lock().unwrap();
+			let id: i64 = conn
+				.query_row(&sql, [], |row| row.get(0))
+				.with_context(|| format!("failed to read next id for {}", self.table_name))?;
+			let id = u32::try_from(id).context("sqlite id overflowed u32")?;
+			Ok(id)
+		})
 	}
 
 	pub async fn next_id(&self) -> Result<u32> {
@@ -673,78 +699,84 @@ where
 	}
 
 	pub async fn find(&self, id: u32) -> Result<Option<T>> {
-		let schema = T::schema();
-		let cols = model_columns(&schema)?;
-		let has_model_id = has_model_id(&schema);
-		let select_cols = column_select_list(&cols);
-		let sql = format!(
-			"SELECT id{} FROM \"{}\" WHERE id = ?1",
-			select_cols, self.table_name
-		);
-		let conn = self.conn.lock().unwrap();
-		let mut stmt = conn
-			.prepare(&sql)
-			.with_context(|| format!("failed to prepare find query for {}", self.table_name))?;
-		let mut rows = stmt
-			.query(params![id])
-			.with_context(|| format!("failed to run find query on {}", self.table_name))?;
-		let Some(row) = rows.next().context("failed to iterate sqlite rows")? else {
-			return Ok(None);
-		};
-		Ok(Some(row_to_model::<T>(row, &schema, &cols, has_model_id)?))
+		self.instrumented("find", || {
+			let schema = T::schema();
+			let cols = model_columns(&schema)?;
+			let has_model_id = has_model_id(&schema);
+			let select_cols = column_select_list(&cols);
+			let sql = format!(
+				"SELECT id{} FROM \"{}\" WHERE id = ?1",
+				select_cols, self.table_name
+			);
+			let conn = self.conn.lock().unwrap();
+			let mut stmt = conn
+				.prepare(&sql)
+				.with_context(|| format!("failed to prepare find query for {}", self.table_name))?;
+			let mut rows = stmt
+				.query(params![id])
+				.with_context(|| format!("failed to run find query on {}", self.table_name))?;
+			let Some(row) = rows.next().context("failed to iterate sqlite rows")? else {
+				return Ok(None);
+			};
+			Ok(Some(row_to_model::<T>(row, &schema, &cols, has_model_id)?))
+		})
 	}
 
 	pub async fn delete(&self, id: u32) -> Result<bool> {
-		let sql = format!("DELETE FROM \"{}\" WHERE id = ?1", self.table_name);
-		let conn = self.conn.lock().unwrap();
-		let affected = conn
-			.execute(&sql, params![id])
-			.with_context(|| format!("failed deleting row from {}", self.table_name))?;
-		Ok(affected > 0)
+		self.instrumented("delete", || {
+			let sql = format!("DELETE FROM \"{}\" WHERE id = ?1", self.table_name);
+			let conn = self.conn.lock().unwrap();
+			let affected = conn
+				.execute(&sql, params![id])
+				.with_context(|| format!("failed deleting row from {}", self.table_name))?;
+			Ok(affected > 0)
+		})
 	}
 
 	pub async fn save(&self, mut row: T) -> Result<T> {
-		let schema = T::schema();
-		let cols = model_columns(&schema)?;
-		let mut object = to_object(&row)?;
-		if row.id() == 0 {
-			let sql = format!("INSERT INTO \"{}\" DEFAULT VALUES", self.table_name);
-			let conn = self.conn.lock().unwrap();
-			conn.execute(&sql, []).with_context(|| {
-				format!("failed to insert placeholder row into {}", self.table_name)
-			})?;
-			let inserted = conn.last_insert_rowid();
-			let inserted = u32::try_from(inserted).context("sqlite id overflowed u32")?;
-			row.set_id(inserted);
-			object.insert(
-				"id".to_string(),
-				JsonValue::Number(serde_json::Number::from(inserted)),
-			);
-		}
+		self.instrumented("save", || {
+			let schema = T::schema();
+			let cols = model_columns(&schema)?;
+			let mut object = to_object(&row)?;
+			if row.id() == 0 {
+				let sql = format!("INSERT INTO \"{}\" DEFAULT VALUES", self.table_name);
+				let conn = self.conn.lock().unwrap();
+				conn.execute(&sql, []).with_context(|| {
+					format!("failed to insert placeholder row into {}", self.table_name)
+				})?;
+				let inserted = conn.last_insert_rowid();
+				let inserted = u32::try_from(inserted).context("sqlite id overflowed u32")?;
+				row.set_id(inserted);
+				object.insert(
+					"id".to_string(),
+					JsonValue::Number(serde_json::Number::from(inserted)),
+				);
+			}
 
-		if cols.is_empty() {
-			return Ok(row);
-		}
+			if cols.is_empty() {
+				return Ok(row);
+			}
 
-		let set_clause = cols
-			.iter()
-			.enumerate()
-			.map(|(idx, (name, _))| format!("\"{}\" = ?{}", name, idx + 2))
-			.collect::<Vec<_>>()
-			.join(", ");
-		let sql = format!(
-			"UPDATE \"{}\" SET {} WHERE id = ?1",
-			self.table_name, set_clause
-		);
-		let mut values = Vec::with_capacity(cols.len() + 1);
-		values.push(SqlValue::Integer(i64::from(row.id())));
-		for (name, rust_type) in &cols {
-			values.push(json_to_sql_value(object.get(name), rust_type));
-		}
-		let conn = self.conn.lock().unwrap();
-		conn.execute(&sql, params_from_iter(values))
-			.with_context(|| format!("failed to update row in {}", self.table_name))?;
-		Ok(row)
+			let set_clause = cols
+				.iter()
+				.enumerate()
+				.map(|(idx, (name, _))| format!("\"{}\" = ?{}", name, idx + 2))
+				.collect::<Vec<_>>()
+				.join(", ");
+			let sql = format!(
+				"UPDATE \"{}\" SET {} WHERE id = ?1",
+				self.table_name, set_clause
+			);
+			let mut values = Vec::with_capacity(cols.len() + 1);
+			values.push(SqlValue::Integer(i64::from(row.id())));
+			for (name, rust_type) in &cols {
+				values.push(json_to_sql_value(object.get(name), rust_type));
+			}
+			let conn = self.conn.lock().unwrap();
+			conn.execute(&sql, params_from_iter(values))
+				.with_context(|| format!("failed to update row in {}", self.table_name))?;
+			Ok(row)
+		})
 	}
 }
 
@@ -1209,10 +1241,8 @@ fn normalize_migration_name(raw: &str) -> Result<String> {
 	for ch in raw.chars() {
 		if ch.is_ascii_alphanumeric() {
 			out.push(ch.to_ascii_lowercase());
-		} else if ch == '-' || ch == '_' || ch == ' ' {
-			if !out.ends_with('_') {
-				out.push('_');
-			}
+		} else if (ch == '-' || ch == '_' || ch == ' ') && !out.ends_with('_') {
+			out.push('_');
 		}
 	}
 	let out = out.trim_matches('_').to_string();
@@ -1439,66 +1469,6 @@ fn default_json_value(ty: &str) -> JsonValue {
 	}
 }
 
-fn backfill_from_legacy_json(
-	conn: &Connection,
-	table: &str,
-	_schema: &WdbModelSchema,
-	columns: &[(String, String)],
-) -> Result<()> {
-	let select_sql = format!("SELECT id, json FROM \"{}\" WHERE json IS NOT NULL", table);
-	let mut select = conn
-		.prepare(&select_sql)
-		.with_context(|| format!("failed to prepare legacy json scan for {}", table))?;
-	let mut rows = select
-		.query([])
-		.with_context(|| format!("failed to query legacy json rows for {}", table))?;
-
-	if columns.is_empty() {
-		return Ok(());
-	}
-
-	let set_clause = columns
-		.iter()
-		.enumerate()
-		.map(|(idx, (name, _))| format!("\"{}\" = COALESCE(\"{}\", ?{})", name, name, idx + 1))
-		.collect::<Vec<_>>()
-		.join(", ");
-	let update_sql = format!(
-		"UPDATE \"{}\" SET {} WHERE id = ?{}",
-		table,
-		set_clause,
-		columns.len() + 1
-	);
-	let mut update = conn
-		.prepare(&update_sql)
-		.with_context(|| format!("failed to prepare legacy update for {}", table))?;
-
-	while let Some(row) = rows.next().context("failed iterating legacy rows")? {
-		let id: i64 = row.get(0).context("failed reading legacy row id")?;
-		let raw: String = row.get(1).context("failed reading legacy json payload")?;
-		let value: JsonValue = serde_json::from_str(&raw)
-			.with_context(|| format!("failed parsing legacy json row in {}", table))?;
-		let JsonValue::Object(object) = value else {
-			continue;
-		};
-
-		let mut values = Vec::with_capacity(columns.len() + 1);
-		for (name, rust_type) in columns {
-			let val = object
-				.get(name)
-				.cloned()
-				.unwrap_or_else(|| default_json_value(rust_type));
-			values.push(json_to_sql_value(Some(&val), rust_type));
-		}
-		values.push(SqlValue::Integer(id));
-		update
-			.execute(params_from_iter(values))
-			.with_context(|| format!("failed backfilling legacy row {} in {}", id, table))?;
-	}
-
-	Ok(())
-}
-
 fn default_db_path<S>() -> PathBuf {
 	let raw = std::any::type_name::<S>();
 	let mut out = String::with_capacity(raw.len());
@@ -41,6 +41,11 @@ pub fn render_document_with_app_css_hydration_title(
 	if app_css {
 		out.push_str("<link rel=\"stylesheet\" href=\"/app.css\"></link>");
 	}
+	if let Some(stylesheet) = crate::wui::styles::stylesheet() {
+		out.push_str("<link rel=\"stylesheet\" href=\"");
+		out.push_str(&escape_attr(&stylesheet.url()));
+		out.push_str("\"></link>");
+	}
 	out.push_str("<script defer src=\"/index.js\"></script>");
 	out.push_str("</head>");
 	out.push_str(
@@ -127,6 +132,7 @@ pub fn render_item(item: &Item) -> String {
 			body,
 		} => render_connection_status(item, *connected, flex, *spacing, *wrap, body),
 		ItemPayload::Custom { name, .. } => render_custom(item, name),
+		ItemPayload::Shortcut => render_shortcut(),
 		ItemPayload::None => String::new(),
 	}
 }
@@ -425,6 +431,12 @@ fn render_folder_picker(item: &Item) -> String {
 	render_void_element("input", &[], style, &attrs)
 }
 
+fn render_shortcut() -> String {
+	// Keeps the child indexes the client hydrates against in step.
+	let attrs = vec![("hidden".to_string(), "true".to_string())];
+	render_element("span", &[], StyleBuilder::new(), &attrs, "")
+}
+
 fn render_floating_layout(item: &Item, x: u32, y: u32, width: u32, height: u32) -> String {
 	let mut style = StyleBuilder::new();
 	style.push("position", "absolute");
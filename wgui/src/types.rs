@@ -37,14 +37,59 @@ pub struct OnRepeat {
 pub struct OnKeyDown {
 	pub id: Option<String>,
 	pub keycode: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub inx: Option<u32>,
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub ctrl: bool,
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub alt: bool,
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub shift: bool,
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub meta: bool,
 }
 
 #[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
 pub struct OnKeyUp {
 	pub id: Option<String>,
 	pub keycode: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub inx: Option<u32>,
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub ctrl: bool,
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub alt: bool,
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub shift: bool,
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub meta: bool,
+}
+
+macro_rules! impl_key_event {
+	($event:ty) => {
+		impl $event {
+			pub fn modifiers(&self) -> crate::wui::keys::Modifiers {
+				crate::wui::keys::Modifiers {
+					ctrl: self.ctrl,
+					alt: self.alt,
+					shift: self.shift,
+					meta: self.meta,
+				}
+			}
+
+			/// Whether this event is the key combination `combo`, such as
+			/// `Ctrl+S`. Invalid combinations never match.
+			pub fn matches(&self, combo: &str) -> bool {
+				crate::wui::keys::KeyCombo::parse(combo)
+					.is_ok_and(|combo| combo.matches(&self.keycode, self.modifiers()))
+			}
+		}
+	};
 }
 
+impl_key_event!(OnKeyDown);
+impl_key_event!(OnKeyUp);
+
 #[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
 pub struct OnTextChanged {
 	pub id: u32,
@@ -56,6 +101,9 @@ pub struct OnTextChanged {
 pub struct PathChanged {
 	pub path: String,
 	pub query: HashMap<String, String>,
+	/// Raw query string without the leading `?`, keeping repeated keys.
+	#[serde(default, rename = "queryString")]
+	pub query_string: String,
 	#[serde(default, rename = "ssrHydrationId")]
 	pub ssr_hydration_id: Option<String>,
 	#[serde(default, rename = "initialRoot")]
@@ -76,6 +124,10 @@ pub struct FormSubmit {
 	pub submission_id: u64,
 	#[serde(default)]
 	pub query: HashMap<String, String>,
+	/// The page's raw query string without the leading `?`, keeping
+	/// repeated keys.
+	#[serde(default, rename = "queryString")]
+	pub query_string: String,
 	#[serde(default)]
 	pub fields: HashMap<String, String>,
 }
@@ -121,6 +173,50 @@ pub struct CustomData {
 	pub payload: serde_json::Value,
 }
 
+/// A template parse error shown in the browser while hot reloading.
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateDiagnostic {
+	pub module: String,
+	pub file: String,
+	pub line: usize,
+	pub column: usize,
+	pub message: String,
+}
+
+/// A client event as seen by the devtools overlay.
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevtoolsEvent {
+	/// Milliseconds since the websocket connected.
+	pub at_ms: u64,
+	pub kind: String,
+	pub bytes: usize,
+	/// WUI actions the event decoded to, e.g. `Save` or `Select`.
+	#[serde(default)]
+	pub actions: Vec<String>,
+}
+
+/// A batch of client actions sent in one websocket frame.
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevtoolsBatch {
+	pub at_ms: u64,
+	pub kinds: Vec<String>,
+	pub bytes: usize,
+	/// Time spent diffing and encoding the batch, for renders.
+	pub elapsed_ms: Option<f64>,
+}
+
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Devtools {
+	pub events: Vec<DevtoolsEvent>,
+	pub batches: Vec<DevtoolsBatch>,
+	/// The current item tree, only present when it changed.
+	pub tree: Option<Item>,
+}
+
 #[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WebRtcJoin {
@@ -163,12 +259,26 @@ pub struct WebPushSubscriptionChanged {
 #[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum ClientEvent {
-	Disconnected { id: usize },
-	Connected { id: usize },
+	Disconnected {
+		id: usize,
+	},
+	Connected {
+		id: usize,
+	},
 	Refresh,
+	/// Sent by the hot reloader after the named templates were re-parsed.
+	TemplatesReloaded {
+		modules: Vec<String>,
+	},
+	/// Sent by the task driving a loading route view once its data resolved.
+	/// Never accepted from clients.
+	#[serde(skip)]
+	RouteLoaded(Box<RouteLoaded>),
 	PathChanged(Box<PathChanged>),
 	FormSubmit(FormSubmit),
-	RenderPartial { topic: String },
+	RenderPartial {
+		topic: String,
+	},
 	Input(InputQuery),
 	OnClick(OnClick),
 	OnPress(OnPress),
@@ -187,10 +297,44 @@ pub enum ClientEvent {
 	WebPushSubscriptionChanged(WebPushSubscriptionChanged),
 }
 
+/// The view a loading route resolved to, for the client it was dispatched
+/// for.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RouteLoaded {
+	/// Matches the client's latest dispatch of the same page or partial while
+	/// the result is still wanted.
+	pub generation: u64,
+	/// The partial this was loaded for, or `None` for the whole page.
+	pub partial_addr: Option<String>,
+	pub outcome: LoadedRoute,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum LoadedRoute {
+	View {
+		title: Option<String>,
+		item: Box<Item>,
+	},
+	Redirect(String),
+}
+
 #[derive(Debug, Clone)]
 pub struct ClientMessage {
 	pub client_id: usize,
 	pub event: ClientEvent,
+	/// The span the event was sent from, such as the websocket worker's
+	/// `ws_message`; the event loop handles the event inside it.
+	pub span: tracing::Span,
+}
+
+impl ClientMessage {
+	pub fn new(client_id: usize, event: ClientEvent) -> Self {
+		Self {
+			client_id,
+			event,
+			span: tracing::Span::current(),
+		}
+	}
 }
 
 pub type ItemPath = Vec<usize>;
@@ -342,6 +486,16 @@ pub enum ClientAction {
 		service_worker_path: String,
 	},
 	CustomData(CustomData),
+	Devtools(Box<Devtools>),
+	TemplateDiagnostics {
+		diagnostics: Vec<TemplateDiagnostic>,
+	},
+	/// Sent by the hot reloader when a mounted static file changed: re-fetch
+	/// the page's stylesheets, or reload the page if more than CSS changed.
+	ReloadAssets {
+		#[serde(rename = "stylesheetsOnly")]
+		stylesheets_only: bool,
+	},
 }
 
 pub enum ServerEvent {
@@ -363,9 +517,28 @@ pub enum Command {
 	PushState(String),
 	Navigate(String),
 	Actions(Vec<ClientAction>),
+	#[cfg(debug_assertions)]
+	DecodedActions(Vec<String>),
+}
+
+/// A [`Command`] with the span it was sent from, so the worker's
+/// `ws_command` span nests under the event that caused it.
+#[derive(Debug)]
+pub struct CommandMessage {
+	pub command: Command,
+	pub span: tracing::Span,
+}
+
+impl From<Command> for CommandMessage {
+	fn from(command: Command) -> Self {
+		Self {
+			command,
+			span: tracing::Span::current(),
+		}
+	}
 }
 
-pub type Clients = Arc<RwLock<HashMap<usize, mpsc::UnboundedSender<Command>>>>;
+pub type Clients = Arc<RwLock<HashMap<usize, mpsc::UnboundedSender<CommandMessage>>>>;
 
 #[cfg(test)]
 mod tests {
@@ -433,6 +606,7 @@ mod tests {
 		let event = ClientEvent::PathChanged(Box::new(PathChanged {
 			path: "/".to_string(),
 			query: HashMap::new(),
+			query_string: String::new(),
 			ssr_hydration_id: None,
 			initial_root: Some(Item::default()),
 		}));
@@ -4,17 +4,20 @@ use super::{
 };
 use crate::{
 	diff::diff,
-	types::{ClientAction, Clients, Command, Replace},
+	types::{ClientAction, Clients, Command, CommandMessage, Replace},
 	ws::{WsMessage, WsStream},
 };
 use futures_util::{SinkExt, StreamExt};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
-fn event_kind_name(event: &ClientEvent) -> &'static str {
+pub(crate) fn event_kind_name(event: &ClientEvent) -> &'static str {
 	match event {
 		ClientEvent::Disconnected { .. } => "Disconnected",
 		ClientEvent::Connected { .. } => "Connected",
 		ClientEvent::Refresh => "Refresh",
+		ClientEvent::TemplatesReloaded { .. } => "TemplatesReloaded",
+		ClientEvent::RouteLoaded(_) => "RouteLoaded",
 		ClientEvent::PathChanged(_) => "PathChanged",
 		ClientEvent::FormSubmit(_) => "FormSubmit",
 		ClientEvent::RenderPartial { .. } => "RenderPartial",
@@ -44,9 +47,11 @@ where
 	id: usize,
 	ws: S,
 	event_tx: mpsc::UnboundedSender<ClientMessage>,
-	cmd_recv: mpsc::UnboundedReceiver<Command>,
+	cmd_recv: mpsc::UnboundedReceiver<CommandMessage>,
 	clients: Clients,
 	last_root: Option<Item>,
+	#[cfg(debug_assertions)]
+	devtools: Option<crate::devtools::Recorder>,
 }
 
 impl<S> UiWsWorker<S>
@@ -58,15 +63,15 @@ where
 		ws: S,
 		event_tx: mpsc::UnboundedSender<ClientMessage>,
 		clients: Clients,
+		#[cfg_attr(not(debug_assertions), allow(unused_variables))] devtools: bool,
 	) -> Self {
 		log::info!("[{}] connection started", id);
 		let (cmd_sender, cmd_recv) = mpsc::unbounded_channel();
 		clients.write().await.insert(id, cmd_sender);
+		#[cfg(feature = "metrics")]
+		crate::metrics::client_connected();
 		event_tx
-			.send(ClientMessage {
-				client_id: id,
-				event: ClientEvent::Connected { id },
-			})
+			.send(ClientMessage::new(id, ClientEvent::Connected { id }))
 			.unwrap();
 		Self {
 			id,
@@ -75,29 +80,48 @@ where
 			event_tx,
 			last_root: None,
 			clients,
+			#[cfg(debug_assertions)]
+			devtools: devtools.then(crate::devtools::Recorder::new),
 		}
 	}
 
+	#[tracing::instrument(name = "ws_message", skip_all, fields(client_id = self.id))]
 	pub async fn handle_websocket(&mut self, msg: WsMessage) -> anyhow::Result<()> {
 		match msg {
 			WsMessage::Text(msg) => {
 				log::info!("received text frame ({} bytes)", msg.len());
+				#[cfg(feature = "metrics")]
+				crate::metrics::record_ws_bytes_received(msg.len());
 
 				let msgs: Vec<ClientEvent> = serde_json::from_str(&msg)?;
-				let kinds: Vec<&str> = msgs.iter().map(event_kind_name).collect();
+				let kinds: Vec<&'static str> = msgs.iter().map(event_kind_name).collect();
 				log::info!("received {} event(s): {:?}", msgs.len(), kinds);
+				#[cfg(feature = "metrics")]
+				for kind in &kinds {
+					crate::metrics::record_event(kind);
+				}
+
+				#[cfg(debug_assertions)]
+				if let Some(devtools) = &mut self.devtools {
+					for msg in &msgs {
+						let bytes = serde_json::to_string(msg).map_or(0, |json| json.len());
+						devtools.record_event(event_kind_name(msg), bytes);
+					}
+				}
 
 				for msg in msgs {
+					tracing::debug!(kind = event_kind_name(&msg), "client event");
 					self.event_tx
-						.send(ClientMessage {
-							client_id: self.id,
-							event: msg,
-						})
+						.send(ClientMessage::new(self.id, msg))
 						.unwrap();
 				}
+				#[cfg(debug_assertions)]
+				self.send_devtools().await?;
 			}
 			WsMessage::Binary(msg) => {
-				println!("Received binary message: {:02X?}", msg);
+				#[cfg(feature = "metrics")]
+				crate::metrics::record_ws_bytes_received(msg.len());
+				tracing::debug!(bytes = msg.len(), "received binary message");
 				self.ws
 					.send(WsMessage::Binary(b"Thank you, come again.".to_vec()))
 					.await?;
@@ -109,17 +133,66 @@ where
 				log::info!("Received pong message: {:02X?}", msg);
 			}
 			WsMessage::Close => {
-				println!("Received close message");
+				tracing::debug!("received close message");
 			}
 		};
 
 		Ok(())
 	}
 
-	async fn handle_command(&mut self, cmd: Command) -> anyhow::Result<()> {
+	async fn send_text(&mut self, text: String) -> anyhow::Result<()> {
+		#[cfg(feature = "metrics")]
+		crate::metrics::record_ws_bytes_sent(text.len());
+		self.ws.send(WsMessage::Text(text)).await
+	}
+
+	async fn send_actions(
+		&mut self,
+		actions: &[ClientAction],
+		elapsed: Option<Duration>,
+	) -> anyhow::Result<()> {
+		let str = serde_json::to_string(actions).unwrap();
+		#[cfg(debug_assertions)]
+		if let Some(devtools) = &mut self.devtools {
+			devtools.record_batch(actions, str.len(), elapsed);
+		}
+		#[cfg(not(debug_assertions))]
+		let _ = elapsed;
+		self.send_text(str).await?;
+		#[cfg(debug_assertions)]
+		self.send_devtools().await?;
+		Ok(())
+	}
+
+	#[cfg(debug_assertions)]
+	async fn send_devtools(&mut self) -> anyhow::Result<()> {
+		let Some(devtools) = &mut self.devtools else {
+			return Ok(());
+		};
+		let str = serde_json::to_string(&[devtools.snapshot()]).unwrap();
+		self.send_text(str).await
+	}
+
+	fn set_last_root(&mut self, root: Item) {
+		#[cfg(debug_assertions)]
+		if let Some(devtools) = &mut self.devtools {
+			devtools.record_tree(&root);
+		}
+		self.last_root = Some(root);
+	}
+
+	#[tracing::instrument(
+		name = "ws_command",
+		parent = &message.span,
+		skip_all,
+		fields(client_id = self.id)
+	)]
+	async fn handle_command(&mut self, message: CommandMessage) -> anyhow::Result<()> {
+		let cmd = message.command;
 		log::debug!("handling command: {:?}", cmd);
 		match cmd {
 			Command::Render(root) => {
+				let started = Instant::now();
 				let changes = match &self.last_root {
 					Some(last_root) => diff(last_root, &root),
 					None => vec![ClientAction::Replace(Replace {
@@ -130,44 +203,49 @@ where
 				if changes.is_empty() {
 					return Ok(());
 				}
-				self.last_root = Some(root);
+				self.set_last_root(root);
 				log::debug!("sending changes: {:?}", changes);
-				let str = serde_json::to_string(&changes).unwrap();
-				self.ws.send(WsMessage::Text(str)).await?;
+				self.send_actions(&changes, Some(started.elapsed())).await?;
+				#[cfg(feature = "metrics")]
+				crate::metrics::observe_render(started.elapsed());
 			}
 			Command::ReplaceRoot(root) => {
-				self.last_root = Some(root.clone());
+				self.set_last_root(root.clone());
 				let changes = vec![ClientAction::Replace(Replace {
 					path: vec![],
 					item: root,
 				})];
-				let str = serde_json::to_string(&changes).unwrap();
-				self.ws.send(WsMessage::Text(str)).await?;
+				self.send_actions(&changes, None).await?;
 			}
 			Command::HydrateRoot(root) => {
-				self.last_root = Some(root);
+				self.set_last_root(root);
+				#[cfg(debug_assertions)]
+				self.send_devtools().await?;
 			}
 			Command::SetTitle(title) => {
 				let changes = vec![ClientAction::SetTitle { title }];
-				let str = serde_json::to_string(&changes).unwrap();
-				self.ws.send(WsMessage::Text(str)).await?;
+				self.send_actions(&changes, None).await?;
 			}
 			Command::PushState(url) => {
 				let changes = vec![ClientAction::PushState(crate::types::PushState { url })];
-				let str = serde_json::to_string(&changes).unwrap();
-				self.ws.send(WsMessage::Text(str)).await?;
+				self.send_actions(&changes, None).await?;
 			}
 			Command::Navigate(url) => {
 				let changes = vec![ClientAction::Navigate(crate::types::Navigate { url })];
-				let str = serde_json::to_string(&changes).unwrap();
-				self.ws.send(WsMessage::Text(str)).await?;
+				self.send_actions(&changes, None).await?;
 			}
 			Command::Actions(actions) => {
 				if actions.is_empty() {
 					return Ok(());
 				}
-				let str = serde_json::to_string(&actions).unwrap();
-				self.ws.send(WsMessage::Text(str)).await?;
+				self.send_actions(&actions, None).await?;
+			}
+			#[cfg(debug_assertions)]
+			Command::DecodedActions(names) => {
+				if let Some(devtools) = &mut self.devtools {
+					devtools.record_decoded_actions(names);
+				}
+				self.send_devtools().await?;
 			}
 		};
 
@@ -223,11 +301,13 @@ where
 
 		log::info!("[{}] connection closed", self.id);
 		self.clients.write().await.remove(&self.id);
+		#[cfg(feature = "metrics")]
+		crate::metrics::client_disconnected();
 		self.event_tx
-			.send(ClientMessage {
-				client_id: self.id,
-				event: ClientEvent::Disconnected { id: self.id },
-			})
+			.send(ClientMessage::new(
+				self.id,
+				ClientEvent::Disconnected { id: self.id },
+			))
 			.unwrap();
 	}
 }
@@ -0,0 +1,168 @@
+//! What clients see when a route handler or controller fails, or when no
+//! route matches: the app's `error.wui` / `not_found.wui` templates when set
+//! with [`crate::Wgui::set_error_page`] and [`crate::Wgui::set_not_found_page`],
+//! or a plain built-in view otherwise.
+
+use crate::gui::{self, Item};
+use crate::wui::route_handler::View;
+use crate::wui::runtime::{RouteContext, Template, WuiController, WuiValue};
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, RwLock};
+
+pub(crate) type SharedErrorPages = Arc<RwLock<ErrorPages>>;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ErrorPages {
+	pub error: Option<Template>,
+	pub not_found: Option<Template>,
+}
+
+impl ErrorPages {
+	/// The view for a handler that failed with `message`, with status 500.
+	///
+	/// The message is logged, and only shown to the client in debug builds.
+	pub fn error_view(&self, message: &str, route: &RouteContext) -> View {
+		log::error!("{}: {message}", route.path);
+		let message = if cfg!(debug_assertions) { message } else { "" };
+		let item = match &self.error {
+			Some(template) => render_page(template, 500, message, route),
+			None => {
+				let mut body = vec![gui::text("Something went wrong").font_weight("bold")];
+				if !message.is_empty() {
+					body.push(gui::text(message).break_words(true));
+				}
+				gui::vstack(body).spacing(8).padding(16)
+			}
+		};
+		View::page("Error", item).with_status(500)
+	}
+
+	/// The view for a request the route couldn't decode, such as a bad
+	/// [`crate::Query`], with status 400. The message describes the client's
+	/// mistake, so it's shown in release builds too.
+	pub fn bad_request_view(&self, message: &str, route: &RouteContext) -> View {
+		let item = match &self.error {
+			Some(template) => render_page(template, 400, message, route),
+			None => gui::vstack(vec![
+				gui::text("Bad request").font_weight("bold"),
+				gui::text(message).break_words(true),
+			])
+			.spacing(8)
+			.padding(16),
+		};
+		View::page("Bad request", item).with_status(400)
+	}
+
+	/// The view for a path no route, page or component claims, with status
+	/// 404.
+	pub fn not_found_view(&self, route: &RouteContext) -> View {
+		let item = match &self.not_found {
+			Some(template) => render_page(template, 404, "", route),
+			None => gui::vstack(vec![gui::text("Page not found").font_weight("bold")]).padding(16),
+		};
+		View::page("Not found", item).with_status(404)
+	}
+
+	/// [`ErrorPages::not_found_view`] if the app set a not-found page. Apps
+	/// without one keep serving the client shell for unmatched paths, since
+	/// they may render those paths themselves.
+	pub fn configured_not_found_view(&self, route: &RouteContext) -> Option<View> {
+		self.not_found.as_ref()?;
+		Some(self.not_found_view(route))
+	}
+}
+
+/// Error page templates get `state.status`, `state.message` (empty in
+/// release builds) and `state.path`.
+fn render_page(template: &Template, status: u16, message: &str, route: &RouteContext) -> Item {
+	let state = WuiValue::object(vec![
+		("status".to_string(), WuiValue::Number(status as f64)),
+		("message".to_string(), WuiValue::String(message.to_string())),
+		("path".to_string(), WuiValue::String(route.path.clone())),
+	]);
+	template.render_with_route(&state, route)
+}
+
+/// Renders `controller` at `route`, returning the panic message if the
+/// render panics.
+pub(crate) fn catch_render(
+	controller: &dyn WuiController,
+	route: &RouteContext,
+) -> Result<Item, String> {
+	catch_unwind(AssertUnwindSafe(|| controller.render_with_route(route)))
+		.map_err(|payload| panic_message(payload.as_ref()))
+}
+
+/// The message a panic was raised with, for `panic!("...")` and
+/// `panic!("{}", ..)` payloads.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+	if let Some(message) = payload.downcast_ref::<&str>() {
+		format!("panicked: {message}")
+	} else if let Some(message) = payload.downcast_ref::<String>() {
+		format!("panicked: {message}")
+	} else {
+		"panicked".to_string()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct Panicking;
+
+	#[crate::wui::runtime::async_trait]
+	impl WuiController for Panicking {
+		fn render(&self) -> Item {
+			panic!("no todos table");
+		}
+
+		async fn handle(&mut self, _event: &crate::types::ClientEvent) -> bool {
+			false
+		}
+	}
+
+	fn route(path: &str) -> RouteContext {
+		RouteContext {
+			path: path.to_string(),
+			..RouteContext::default()
+		}
+	}
+
+	#[test]
+	fn catch_render_returns_the_panic_message() {
+		let error = catch_render(&Panicking, &route("/todos")).unwrap_err();
+		assert_eq!(error, "panicked: no todos table");
+	}
+
+	#[test]
+	fn error_and_not_found_pages_render_templates_with_status() {
+		let pages = ErrorPages {
+			error: Some(
+				Template::parse(
+					r#"<Text value={`${state.status} at ${state.path}: ${state.message}`} />"#,
+					"error",
+				)
+				.unwrap(),
+			),
+			not_found: None,
+		};
+		let view = pages.error_view("boom", &route("/todos"));
+		assert_eq!(view.status, 500);
+		let json = serde_json::to_string(&view.item).unwrap();
+		assert!(json.contains("500 at /todos: boom"), "{json}");
+
+		let view = pages.bad_request_view("invalid query", &route("/todos"));
+		assert_eq!(view.status, 400);
+		let json = serde_json::to_string(&view.item).unwrap();
+		assert!(json.contains("400 at /todos: invalid query"), "{json}");
+
+		assert!(pages.configured_not_found_view(&route("/nope")).is_none());
+		let view = pages.not_found_view(&route("/nope"));
+		assert_eq!(view.status, 404);
+		assert!(serde_json::to_string(&view.item)
+			.unwrap()
+			.contains("Page not found"));
+	}
+}
@@ -0,0 +1,426 @@
+//! Hot reloading of runtime WUI templates and mounted static assets.
+//!
+//! Templates loaded through a [`TemplateSlot`] (which `#[wgui_controller]`
+//! and `#[route(.., view)]` generate for runtime templates) are recorded
+//! together with the files they import. When one of those files changes,
+//! [`reload_changed`] re-parses only the templates whose import graph contains
+//! it and swaps the new version into the slot. `Wgui::enable_hot_reload`
+//! drives this from a filesystem watcher and re-renders affected clients; it
+//! also watches the files behind `mount_static_file` and `mount_static_dir`,
+//! refreshing stylesheets or reloading the page when one of those changes.
+//!
+//! All of this needs the `hot-reload` feature. Without it a slot loads its
+//! template once and keeps it. With it, replaced templates are leaked so the
+//! `&'static Template` handed out by a slot stays valid; reloading is a
+//! development tool, so that is bounded by the number of edits made while the
+//! server runs.
+
+use std::path::{Path, PathBuf};
+#[cfg(feature = "hot-reload")]
+use std::{
+	collections::BTreeMap,
+	fs,
+	sync::{Mutex, RwLock},
+};
+
+#[cfg(feature = "hot-reload")]
+use crate::types::TemplateDiagnostic;
+#[cfg(feature = "hot-reload")]
+use crate::wui::diagnostic::Diagnostic;
+use crate::wui::runtime::{load_template, Template, TemplateLoadError};
+
+#[cfg(feature = "hot-reload")]
+static REGISTRY: Mutex<Vec<Registration>> = Mutex::new(Vec::new());
+
+/// Holds the current version of one runtime template.
+///
+/// Each reload leaks the template it replaces, so every `&'static Template`
+/// handed out stays valid. Only the `hot-reload` feature, a development
+/// tool, builds this version; without it a slot never reloads or leaks.
+#[cfg(feature = "hot-reload")]
+pub struct TemplateSlot {
+	current: RwLock<Option<&'static Template>>,
+}
+
+/// Holds one runtime template, loaded on first use.
+#[cfg(not(feature = "hot-reload"))]
+pub struct TemplateSlot {
+	current: std::sync::OnceLock<Template>,
+}
+
+impl Default for TemplateSlot {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(not(feature = "hot-reload"))]
+impl TemplateSlot {
+	pub const fn new() -> Self {
+		Self {
+			current: std::sync::OnceLock::new(),
+		}
+	}
+
+	/// The template, if it has been loaded.
+	pub fn get(&'static self) -> Option<&'static Template> {
+		self.current.get()
+	}
+
+	/// Return the template, loading it from `path` on first use.
+	pub fn get_or_load(
+		&'static self,
+		path: &Path,
+		module_name: &str,
+	) -> Result<&'static Template, TemplateLoadError> {
+		if let Some(template) = self.current.get() {
+			return Ok(template);
+		}
+		let template = load_template(path, module_name)?;
+		Ok(self.current.get_or_init(|| template))
+	}
+}
+
+#[cfg(feature = "hot-reload")]
+impl TemplateSlot {
+	pub const fn new() -> Self {
+		Self {
+			current: RwLock::new(None),
+		}
+	}
+
+	/// The current template, if one has been loaded.
+	pub fn get(&'static self) -> Option<&'static Template> {
+		*self.current.read().unwrap_or_else(|err| err.into_inner())
+	}
+
+	/// Return the current template, loading it from `path` on first use.
+	pub fn get_or_load(
+		&'static self,
+		path: &Path,
+		module_name: &str,
+	) -> Result<&'static Template, TemplateLoadError> {
+		if let Some(template) = self.get() {
+			return Ok(template);
+		}
+		let mut current = self.current.write().unwrap_or_else(|err| err.into_inner());
+		if let Some(template) = *current {
+			return Ok(template);
+		}
+		let template: &'static Template = Box::leak(Box::new(load_template(path, module_name)?));
+		*current = Some(template);
+		drop(current);
+		REGISTRY
+			.lock()
+			.unwrap_or_else(|err| err.into_inner())
+			.push(Registration {
+				slot: self,
+				path: path.to_path_buf(),
+				module: module_name.to_string(),
+				files: template_files(path, template),
+				diagnostics: Vec::new(),
+			});
+		Ok(template)
+	}
+
+	fn replace(&self, template: Template) {
+		let template: &'static Template = Box::leak(Box::new(template));
+		*self.current.write().unwrap_or_else(|err| err.into_inner()) = Some(template);
+	}
+}
+
+#[cfg(feature = "hot-reload")]
+struct Registration {
+	slot: &'static TemplateSlot,
+	path: PathBuf,
+	module: String,
+	files: Vec<PathBuf>,
+	diagnostics: Vec<TemplateDiagnostic>,
+}
+
+#[cfg(feature = "hot-reload")]
+/// Outcome of [`reload_changed`].
+#[derive(Debug, Default)]
+pub struct ReloadReport {
+	/// Modules that were re-parsed and swapped in.
+	pub reloaded: Vec<String>,
+	/// Diagnostics of every template that currently fails to parse, including
+	/// ones that failed on an earlier reload. Empty once all templates parse.
+	pub diagnostics: Vec<TemplateDiagnostic>,
+}
+
+#[cfg(feature = "hot-reload")]
+fn canonical(path: &Path) -> PathBuf {
+	fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(feature = "hot-reload")]
+fn template_files(path: &Path, template: &Template) -> Vec<PathBuf> {
+	let mut files = vec![canonical(path)];
+	files.extend(template.source_files().iter().map(|file| canonical(file)));
+	files
+}
+
+pub(crate) fn line_column(source: &str, offset: usize) -> (usize, usize) {
+	let before = &source[..offset.min(source.len())];
+	let line = before.matches('\n').count() + 1;
+	let column = before
+		.rfind('\n')
+		.map_or(before.len(), |newline| before.len() - newline - 1)
+		+ 1;
+	(line, column)
+}
+
+#[cfg(feature = "hot-reload")]
+fn to_template_diagnostics(
+	module: &str,
+	path: &Path,
+	diagnostics: &[Diagnostic],
+) -> Vec<TemplateDiagnostic> {
+	let source = fs::read_to_string(path).unwrap_or_default();
+	diagnostics
+		.iter()
+		.map(|diag| {
+			let (line, column) = line_column(&source, diag.span.start);
+			TemplateDiagnostic {
+				module: module.to_string(),
+				file: path.display().to_string(),
+				line,
+				column,
+				message: diag.message.clone(),
+			}
+		})
+		.collect()
+}
+
+#[cfg(feature = "hot-reload")]
+/// Re-parse every registered template whose import graph contains one of
+/// `changed`, swapping successful results into their slots.
+pub fn reload_changed(changed: &[PathBuf]) -> ReloadReport {
+	let changed = changed
+		.iter()
+		.map(|path| canonical(path))
+		.collect::<Vec<_>>();
+	let mut registry = REGISTRY.lock().unwrap_or_else(|err| err.into_inner());
+	let mut report = ReloadReport::default();
+	for registration in registry.iter_mut() {
+		if !registration.files.iter().any(|file| changed.contains(file)) {
+			continue;
+		}
+		match load_template(&registration.path, &registration.module) {
+			Ok(template) => {
+				registration.files = template_files(&registration.path, &template);
+				registration.diagnostics.clear();
+				registration.slot.replace(template);
+				log::info!("reloaded WUI template {}", registration.module);
+				report.reloaded.push(registration.module.clone());
+			}
+			Err(TemplateLoadError::Diagnostics(diagnostics)) => {
+				log::warn!(
+					"failed to reload WUI template {}: {:?}",
+					registration.module,
+					diagnostics
+				);
+				registration.diagnostics =
+					to_template_diagnostics(&registration.module, &registration.path, &diagnostics);
+			}
+			Err(TemplateLoadError::Io(err)) => {
+				log::warn!(
+					"failed to read WUI template {}: {err}",
+					registration.path.display()
+				);
+			}
+		}
+	}
+	let failing = registry
+		.iter()
+		.map(|registration| (registration.module.as_str(), &registration.diagnostics))
+		.collect::<BTreeMap<_, _>>();
+	report.diagnostics = failing.into_values().flatten().cloned().collect();
+	report
+}
+
+/// A file the watcher saw change.
+#[cfg(feature = "hot-reload")]
+enum Change {
+	Template(PathBuf),
+	Asset(PathBuf),
+}
+
+/// Poll `path` every 250ms on a background thread and call `on_change`
+/// each time its modification time moves forward. Needs no `hot-reload`
+/// feature, but only reports the change; nothing is reloaded.
+pub(crate) fn poll_file(
+	path: PathBuf,
+	on_change: impl Fn() + Send + 'static,
+) -> std::thread::JoinHandle<()> {
+	let mtime = move || {
+		std::fs::metadata(&path)
+			.and_then(|meta| meta.modified())
+			.unwrap_or(std::time::SystemTime::UNIX_EPOCH)
+	};
+	let mut last_mtime = mtime();
+	std::thread::spawn(move || loop {
+		std::thread::sleep(std::time::Duration::from_millis(250));
+		let current = mtime();
+		if current > last_mtime {
+			last_mtime = current;
+			on_change();
+		}
+	})
+}
+
+/// Watch `dir` for template changes and each of `assets` (mounted static
+/// files and directories) for asset changes. `assets_changed` runs with the
+/// changed asset files before clients are told to reload them.
+#[cfg(feature = "hot-reload")]
+pub(crate) fn watch(
+	dir: &Path,
+	assets: Vec<PathBuf>,
+	assets_changed: impl Fn(&[PathBuf]) + Send + 'static,
+	handle: crate::WguiHandle,
+) -> std::io::Result<()> {
+	use notify::{RecursiveMode, Watcher};
+	use std::time::Duration;
+	use tokio::sync::mpsc;
+
+	let assets = assets
+		.iter()
+		.filter(|path| path.exists())
+		.map(|path| canonical(path))
+		.collect::<Vec<_>>();
+	let watched_assets = assets.clone();
+	let (tx, mut rx) = mpsc::unbounded_channel::<Change>();
+	let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+		let Ok(event) = event else {
+			return;
+		};
+		if event.kind.is_access() {
+			return;
+		}
+		for path in event.paths {
+			if watched_assets.iter().any(|asset| path.starts_with(asset)) {
+				let _ = tx.send(Change::Asset(path));
+			} else if path.extension().is_some_and(|ext| ext == "wui") {
+				let _ = tx.send(Change::Template(path));
+			}
+		}
+	})
+	.map_err(std::io::Error::other)?;
+	watcher
+		.watch(dir, RecursiveMode::Recursive)
+		.map_err(std::io::Error::other)?;
+	log::info!("watching {} for WUI template changes", dir.display());
+	for asset in &assets {
+		// Editors often replace a file rather than write it, so watch the
+		// directory a mounted file is in.
+		let (target, mode) = match asset.parent() {
+			Some(parent) if asset.is_file() => (parent, RecursiveMode::NonRecursive),
+			_ => (asset.as_path(), RecursiveMode::Recursive),
+		};
+		if let Err(err) = watcher.watch(target, mode) {
+			log::warn!("unable to watch {}: {err}", asset.display());
+		}
+	}
+
+	tokio::spawn(async move {
+		// Keep the watcher alive for as long as the task runs.
+		let _watcher = watcher;
+		while let Some(change) = rx.recv().await {
+			// Editors often write a file in several steps; settle first.
+			tokio::time::sleep(Duration::from_millis(50)).await;
+			let mut templates = Vec::new();
+			let mut changed_assets = Vec::new();
+			for change in std::iter::once(change).chain(std::iter::from_fn(|| rx.try_recv().ok())) {
+				match change {
+					Change::Template(path) => templates.push(path),
+					Change::Asset(path) if !changed_assets.contains(&path) => {
+						changed_assets.push(path)
+					}
+					Change::Asset(_) => {}
+				}
+			}
+			if !templates.is_empty() {
+				let report = tokio::task::spawn_blocking(move || reload_changed(&templates))
+					.await
+					.unwrap_or_default();
+				handle.templates_reloaded(report).await;
+			}
+			if !changed_assets.is_empty() {
+				assets_changed(&changed_assets);
+				handle.assets_changed(&changed_assets).await;
+			}
+		}
+	});
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn line_column_is_one_based() {
+		let source = "<Text>\n  <Bad\n";
+		assert_eq!(line_column(source, 0), (1, 1));
+		assert_eq!(line_column(source, 9), (2, 3));
+		assert_eq!(line_column(source, 500), (3, 1));
+	}
+
+	#[allow(deprecated)]
+	#[tokio::test]
+	async fn deprecated_template_watcher_reports_changes() {
+		let path = std::env::temp_dir().join(format!("wgui-watch-{}.wui", std::process::id()));
+		std::fs::write(&path, "<Text value=\"one\" />").unwrap();
+		let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+		crate::wui::runtime::spawn_template_watcher(path.clone(), tx);
+		std::fs::File::options()
+			.write(true)
+			.open(&path)
+			.and_then(|file| {
+				file.set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(5))
+			})
+			.unwrap();
+
+		tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+			.await
+			.expect("the change is reported")
+			.unwrap();
+		let _ = std::fs::remove_file(&path);
+	}
+
+	#[cfg(feature = "hot-reload")]
+	#[test]
+	fn reload_swaps_only_templates_importing_the_changed_file() {
+		static PAGE: TemplateSlot = TemplateSlot::new();
+		static OTHER: TemplateSlot = TemplateSlot::new();
+
+		let dir = std::env::temp_dir().join(format!("wgui-hot-reload-{}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		let card = dir.join("card.wui");
+		let page = dir.join("page.wui");
+		let other = dir.join("other.wui");
+		fs::write(&card, "<Text value=\"one\" />").unwrap();
+		fs::write(&page, "<VStack><Import src=\"card.wui\" /></VStack>").unwrap();
+		fs::write(&other, "<Text value=\"other\" />").unwrap();
+
+		let first = PAGE.get_or_load(&page, "page").unwrap();
+		let other_template = OTHER.get_or_load(&other, "other").unwrap();
+
+		fs::write(&card, "<Text value=\"two\" />").unwrap();
+		let report = reload_changed(std::slice::from_ref(&card));
+		assert_eq!(report.reloaded, vec!["page"]);
+		assert!(report.diagnostics.is_empty());
+		assert!(!std::ptr::eq(first, PAGE.get().unwrap()));
+		assert!(std::ptr::eq(other_template, OTHER.get().unwrap()));
+
+		fs::write(&page, "<VStack>").unwrap();
+		let report = reload_changed(std::slice::from_ref(&page));
+		assert!(report.reloaded.is_empty());
+		assert_eq!(report.diagnostics.len(), 1);
+		assert_eq!(report.diagnostics[0].module, "page");
+
+		let _ = fs::remove_dir_all(&dir);
+	}
+}
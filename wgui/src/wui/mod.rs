@@ -1,3 +1,11 @@
-pub use wui_core::{ast, compiler, diagnostic, expr, imports, parser, routing};
+pub use wui_core::{
+	ast, builtins, compiler, cst, diagnostic, expr, format, imports, keys, parser, routing, schema,
+	style,
+};
+pub(crate) mod error_pages;
+pub mod hot_reload;
+pub mod query;
 pub mod route_handler;
 pub mod runtime;
+pub mod styles;
+pub mod testing;
@@ -0,0 +1,308 @@
+//! Typed query strings: the [`Query`] argument of `#[route]` handlers, and
+//! [`encode_query`] to write one back into a URL.
+
+use crate::wui::runtime::RouteContext;
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::Serialize;
+use std::ops::Deref;
+
+/// Query-string parameters decoded into `T`, as a `#[route]` handler
+/// argument.
+///
+/// A key repeated in the URL fills a `Vec` field, and any other field takes
+/// the last value. Missing keys fall back to `#[serde(default)]`; missing or
+/// empty keys are `None` for `Option` fields. A query that doesn't decode
+/// renders the 400 error page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query<T>(pub T);
+
+impl<T: DeserializeOwned> Query<T> {
+	/// Decode the query of `route`.
+	pub fn from_route(route: &RouteContext) -> Result<Self, QueryError> {
+		Self::from_pairs(route.query_pairs())
+	}
+
+	pub fn from_pairs(
+		pairs: impl IntoIterator<Item = (String, String)>,
+	) -> Result<Self, QueryError> {
+		let mut grouped: Vec<(String, Values)> = Vec::new();
+		for (key, value) in pairs {
+			match grouped.iter_mut().find(|(name, _)| *name == key) {
+				Some((_, values)) => values.0.push(value),
+				None => grouped.push((key, Values(vec![value]))),
+			}
+		}
+		T::deserialize(de::value::MapDeserializer::new(grouped.into_iter()))
+			.map(Query)
+			.map_err(|error: de::value::Error| QueryError(error.to_string()))
+	}
+}
+
+impl<T> Deref for Query<T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		&self.0
+	}
+}
+
+/// Error returned when a query string can't be decoded into a [`Query`] or
+/// a value can't be encoded as one.
+#[derive(Debug, Clone)]
+pub struct QueryError(pub String);
+
+impl std::fmt::Display for QueryError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "invalid query: {}", self.0)
+	}
+}
+
+impl std::error::Error for QueryError {}
+
+/// Encode `value` as a query string, without the leading `?`, so that
+/// [`Query`] decodes it back. Lists become repeated keys and `None` fields
+/// are left out. Nested structs are not supported.
+pub fn encode_query<T: Serialize>(value: &T) -> Result<String, QueryError> {
+	let value = serde_json::to_value(value).map_err(|error| QueryError(error.to_string()))?;
+	let serde_json::Value::Object(fields) = value else {
+		return Err(QueryError("a query must be a struct or map".to_string()));
+	};
+	let mut query = form_urlencoded::Serializer::new(String::new());
+	for (key, value) in &fields {
+		let values = match value {
+			serde_json::Value::Array(items) => items.iter().collect(),
+			value => vec![value],
+		};
+		for value in values {
+			match value {
+				serde_json::Value::Null => {}
+				serde_json::Value::String(text) => {
+					query.append_pair(key, text);
+				}
+				serde_json::Value::Bool(_) | serde_json::Value::Number(_) => {
+					query.append_pair(key, &value.to_string());
+				}
+				serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+					return Err(QueryError(format!("`{key}` is nested")));
+				}
+			}
+		}
+	}
+	Ok(query.finish())
+}
+
+/// `path` with `query` encoded onto it, or `path` alone if the query is
+/// empty or can't be encoded.
+pub(crate) fn url_with_query<T: Serialize>(path: &str, query: &T) -> String {
+	match encode_query(query) {
+		Ok(query) if !query.is_empty() => format!("{path}?{query}"),
+		Ok(_) => path.to_string(),
+		Err(err) => {
+			log::warn!("{path}: {err}");
+			path.to_string()
+		}
+	}
+}
+
+/// Every value of one query key, in URL order.
+struct Values(Vec<String>);
+
+impl Values {
+	fn last(&self) -> &str {
+		self.0.last().map(String::as_str).unwrap_or("")
+	}
+
+	fn parse<'de, T: std::str::FromStr, V: Visitor<'de>>(
+		&self,
+		visitor: &V,
+	) -> Result<T, de::value::Error> {
+		self.last()
+			.parse()
+			.map_err(|_| de::Error::invalid_value(de::Unexpected::Str(self.last()), visitor))
+	}
+}
+
+impl<'de> IntoDeserializer<'de, de::value::Error> for Values {
+	type Deserializer = Self;
+
+	fn into_deserializer(self) -> Self {
+		self
+	}
+}
+
+macro_rules! deserialize_parsed {
+	($($method:ident => $visit:ident,)*) => {
+		$(
+			fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+				let value = self.parse(&visitor)?;
+				visitor.$visit(value)
+			}
+		)*
+	};
+}
+
+impl<'de> de::Deserializer<'de> for Values {
+	type Error = de::value::Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		if self.0.len() == 1 {
+			self.deserialize_string(visitor)
+		} else {
+			self.deserialize_seq(visitor)
+		}
+	}
+
+	fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		// Checkboxes submit `on`.
+		match self.last() {
+			"true" | "on" | "1" => visitor.visit_bool(true),
+			"false" | "off" | "0" => visitor.visit_bool(false),
+			other => Err(de::Error::invalid_value(
+				de::Unexpected::Str(other),
+				&visitor,
+			)),
+		}
+	}
+
+	deserialize_parsed! {
+		deserialize_i8 => visit_i8,
+		deserialize_i16 => visit_i16,
+		deserialize_i32 => visit_i32,
+		deserialize_i64 => visit_i64,
+		deserialize_u8 => visit_u8,
+		deserialize_u16 => visit_u16,
+		deserialize_u32 => visit_u32,
+		deserialize_u64 => visit_u64,
+		deserialize_f32 => visit_f32,
+		deserialize_f64 => visit_f64,
+		deserialize_char => visit_char,
+	}
+
+	fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_string(visitor)
+	}
+
+	fn deserialize_string<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_string(self.0.pop().unwrap_or_default())
+	}
+
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		if self.last().is_empty() {
+			visitor.visit_none()
+		} else {
+			visitor.visit_some(self)
+		}
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_seq(de::value::SeqDeserializer::new(
+			self.0.into_iter().map(|value| Values(vec![value])),
+		))
+	}
+
+	fn deserialize_tuple<V: Visitor<'de>>(
+		self,
+		_len: usize,
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		self.deserialize_seq(visitor)
+	}
+
+	fn deserialize_enum<V: Visitor<'de>>(
+		mut self,
+		name: &'static str,
+		variants: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		let value: String = self.0.pop().unwrap_or_default();
+		value
+			.into_deserializer()
+			.deserialize_enum(name, variants, visitor)
+	}
+
+	serde::forward_to_deserialize_any! {
+		i128 u128 bytes byte_buf unit unit_struct tuple_struct map struct identifier ignored_any
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+	#[serde(rename_all = "lowercase")]
+	enum Sort {
+		Newest,
+		Oldest,
+	}
+
+	#[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+	struct Filters {
+		#[serde(default)]
+		tag: Vec<String>,
+		#[serde(default = "first_page")]
+		page: u32,
+		search: Option<String>,
+		sort: Option<Sort>,
+		#[serde(default)]
+		done: bool,
+	}
+
+	fn first_page() -> u32 {
+		1
+	}
+
+	fn pairs(query: &str) -> Vec<(String, String)> {
+		form_urlencoded::parse(query.as_bytes())
+			.into_owned()
+			.collect()
+	}
+
+	#[test]
+	fn decodes_repeated_keys_defaults_and_options() {
+		let Query(filters) =
+			Query::<Filters>::from_pairs(pairs("tag=a&tag=b+c&search=&sort=oldest&done=on"))
+				.unwrap();
+		assert_eq!(
+			filters,
+			Filters {
+				tag: vec!["a".to_string(), "b c".to_string()],
+				page: 1,
+				search: None,
+				sort: Some(Sort::Oldest),
+				done: true,
+			}
+		);
+	}
+
+	#[test]
+	fn reports_values_that_do_not_decode() {
+		let error = Query::<Filters>::from_pairs(pairs("page=two")).unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"invalid query: invalid value: string \"two\", expected u32"
+		);
+	}
+
+	#[test]
+	fn encoded_queries_round_trip() {
+		let filters = Filters {
+			tag: vec!["a&b".to_string(), "c".to_string()],
+			page: 3,
+			search: None,
+			sort: Some(Sort::Newest),
+			done: false,
+		};
+		let query = encode_query(&filters).unwrap();
+		assert_eq!(query, "done=false&page=3&sort=newest&tag=a%26b&tag=c");
+		assert_eq!(Query::from_pairs(pairs(&query)).unwrap(), Query(filters));
+	}
+}
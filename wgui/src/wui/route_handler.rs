@@ -1,10 +1,14 @@
 use crate::gui::Item;
+use crate::wui::error_pages::panic_message;
+use crate::wui::routing::{RoutePattern, RouteTable};
 use crate::wui::runtime::{Ctx, RouteContext, Template, WuiValue};
+use futures_util::FutureExt;
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 use std::future::Future;
+use std::panic::AssertUnwindSafe;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
 
 /// Path params extracted from a route's `:segment` placeholders.
 ///
@@ -236,6 +240,13 @@ pub enum RouteResult {
 	Redirect(Redirect),
 	/// No matching route / guarded access denied. Triggers the fallback page.
 	NotFound,
+	/// The request couldn't be decoded, e.g. a [`crate::Query`] argument
+	/// whose query string doesn't match its type. Renders the app's error
+	/// page with status 400.
+	BadRequest(String),
+	/// The handler returned `Err` or panicked. Renders the app's error page
+	/// with status 500; see [`crate::Wgui::set_error_page`].
+	Error(String),
 }
 
 /// A rendered view: an [`Item`] tree plus optional page title.
@@ -253,6 +264,27 @@ pub struct View {
 	/// [`crate::view!`] and resolved by the route dispatcher before a response
 	/// reaches the client or SSR renderer.
 	wui_model: Option<WuiValue>,
+	/// The data a [`View::loading`] view is waiting on; `item` is its
+	/// placeholder until then.
+	pending: Option<PendingView>,
+}
+
+/// A future a loading [`View`] resolves to. Shared so `View` stays `Clone`;
+/// whichever copy takes it first drives it, and the other copies keep their
+/// placeholder.
+#[derive(Clone)]
+struct PendingView(Arc<Mutex<Option<RouteFuture>>>);
+
+impl PendingView {
+	fn new(future: RouteFuture) -> Self {
+		Self(Arc::new(Mutex::new(Some(future))))
+	}
+}
+
+impl std::fmt::Debug for PendingView {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("PendingView")
+	}
 }
 
 impl View {
@@ -264,6 +296,7 @@ impl View {
 			status: 200,
 			partial_addr: None,
 			wui_model: None,
+			pending: None,
 		}
 	}
 
@@ -275,6 +308,7 @@ impl View {
 			status: 200,
 			partial_addr: None,
 			wui_model: None,
+			pending: None,
 		}
 	}
 
@@ -289,6 +323,7 @@ impl View {
 			status: 200,
 			partial_addr: None,
 			wui_model: None,
+			pending: None,
 		}
 	}
 
@@ -302,7 +337,48 @@ impl View {
 			status: 200,
 			partial_addr: None,
 			wui_model: Some(model),
+			pending: None,
+		}
+	}
+
+	/// Render `placeholder` now, and whatever `data` resolves to once it is
+	/// ready.
+	///
+	/// Websocket clients get the placeholder immediately; the resolved view is
+	/// diffed in when `data` completes, without holding up other clients. A
+	/// newer navigation or render of the same partial discards the result. SSR
+	/// waits for `data` or serves the placeholder depending on the route's
+	/// `ssr` option, and plain form responses always wait.
+	///
+	/// `data` runs detached from the handler call, so copy what it needs out
+	/// of `ctx` before building it.
+	pub fn loading<F, R>(placeholder: Item, data: F) -> Self
+	where
+		F: Future<Output = R> + Send + 'static,
+		R: Into<RouteResult> + 'static,
+	{
+		let mut view = Self::untitled(placeholder);
+		view.pending = Some(PendingView::new(Box::pin(data.map(Into::into))));
+		view
+	}
+
+	/// Whether this view is a placeholder still waiting on its data. A clone
+	/// stays loading when another copy of the view takes the data, since its
+	/// own placeholder is never replaced.
+	pub fn is_loading(&self) -> bool {
+		self.pending.is_some()
+	}
+
+	/// Take the future this view is waiting on, leaving only the placeholder.
+	/// `None`, with a warning, when a clone already took it.
+	pub(crate) fn take_pending(&mut self) -> Option<RouteFuture> {
+		let data = self.pending.take()?.0.lock().unwrap().take();
+		if data.is_none() {
+			log::warn!(
+				"another clone of this loading view already took its data; keeping the placeholder"
+			);
 		}
+		data
 	}
 
 	/// Set the HTTP status used when this view is returned to a regular form
@@ -321,6 +397,49 @@ impl View {
 		);
 		self.item = template.render_with_route(&model, route);
 	}
+
+	/// Renders a `view!` model with the route's template, now and in every
+	/// view a loading view resolves to later.
+	fn finish(&mut self, template: Option<&'static Template>, route: &Option<RouteContext>) {
+		if let Some(route) = route {
+			self.render_wui(template, route);
+		}
+		let Some(data) = self.take_pending() else {
+			return;
+		};
+		let route = route.clone();
+		self.pending = Some(PendingView::new(catch_route_panic(async move {
+			let mut result = data.await;
+			if let RouteResult::View(view) = &mut result {
+				view.finish(template, &route);
+			}
+			result
+		})));
+	}
+}
+
+/// Waits out loading views, for transports that can only send one response.
+#[cfg(feature = "hyper")]
+pub(crate) async fn resolve_loading(mut result: RouteResult) -> RouteResult {
+	loop {
+		match result {
+			RouteResult::View(mut view) => match view.take_pending() {
+				Some(data) => result = data.await,
+				None => return RouteResult::View(view),
+			},
+			result => return result,
+		}
+	}
+}
+
+/// A panicking handler fails its own request instead of the caller.
+fn catch_route_panic<F>(future: F) -> RouteFuture
+where
+	F: Future<Output = RouteResult> + Send + 'static,
+{
+	Box::pin(AssertUnwindSafe(future).catch_unwind().map(|result| {
+		result.unwrap_or_else(|payload| RouteResult::Error(panic_message(payload.as_ref())))
+	}))
 }
 
 impl From<Item> for View {
@@ -341,6 +460,17 @@ impl From<View> for RouteResult {
 	}
 }
 
+/// `Result` handler returns render their `Ok` value, or the error page for
+/// `Err`.
+impl<T: Into<RouteResult>, E: std::fmt::Display> From<Result<T, E>> for RouteResult {
+	fn from(result: Result<T, E>) -> Self {
+		match result {
+			Ok(value) => value.into(),
+			Err(error) => RouteResult::Error(error.to_string()),
+		}
+	}
+}
+
 /// A redirect response. Wire-shape: client navigates to `url`, server
 /// matches a `#[route]`, renders, diffs, patches.
 #[derive(Debug, Clone)]
@@ -350,6 +480,12 @@ impl Redirect {
 	pub fn to(url: impl Into<String>) -> Self {
 		Self(url.into())
 	}
+
+	/// Redirect to `path` with `query` as its query string, for a
+	/// [`crate::Query`] argument of the target route to read back.
+	pub fn with_query<Q: serde::Serialize>(path: &str, query: &Q) -> Self {
+		Self(crate::wui::query::url_with_query(path, query))
+	}
 }
 
 impl From<Redirect> for RouteResult {
@@ -402,11 +538,26 @@ pub trait RouteHandler: Send + Sync + 'static + Copy {
 	}
 
 	/// Template used to render a [`crate::view!`] result. The `#[route]`
-	/// macro supplies this for routes declared with its `view` option.
+	/// macro supplies this for routes declared with its `view` option, except
+	/// in `mode = "codegen"`, whose render is compiled in and so not hot
+	/// reloaded.
 	fn wui_template(&self) -> Option<&'static Template> {
 		None
 	}
 
+	/// What SSR does with a [`View::loading`] result. The `#[route]` macro
+	/// sets this from its `ssr = "wait" | "fallback"` option.
+	fn ssr_loading(&self) -> SsrLoading {
+		SsrLoading::Wait
+	}
+
+	/// Name the WUI `url()` built-in finds this route by. The `#[route]`
+	/// macro uses the handler function's module path and name, e.g.
+	/// `app::todos::show`.
+	fn name(&self) -> Option<&str> {
+		None
+	}
+
 	/// Dispatch the handler. Params have already been extracted from the
 	/// URL and placed in `params`. `ctx` is an `Arc<Ctx<T>>` clone from the
 	/// framework's context registry — handlers can cheaply clone it into
@@ -424,12 +575,75 @@ pub trait RouteHandler: Send + Sync + 'static + Copy {
 	) -> RouteFuture;
 }
 
+/// Patterns of the named routes registered with any [`crate::Wgui`], for the
+/// WUI `url()` built-in, keyed by module path. Shared by every instance in the
+/// process: the templates that call `url()` render without knowing their
+/// server.
+static ROUTE_URLS: LazyLock<RwLock<RouteTable>> = LazyLock::new(Default::default);
+
+pub(crate) fn register_route_url(name: &str, path: &str) {
+	ROUTE_URLS
+		.write()
+		.unwrap()
+		.insert(name, RoutePattern::parse(path));
+}
+
+/// `pattern` with `params` in its parameter segments, percent-encoded, or an
+/// error if `params` doesn't have one value per parameter.
+pub fn build_url(pattern: &str, params: &[&dyn std::fmt::Display]) -> Result<String, String> {
+	let params = params.iter().map(ToString::to_string).collect::<Vec<_>>();
+	RoutePattern::parse(pattern).url(&params)
+}
+
+/// Backs the `url` functions the `#[route]` macro generates, which take one
+/// value per parameter by construction.
+#[doc(hidden)]
+pub fn fill_url(pattern: &str, params: &[&dyn std::fmt::Display]) -> String {
+	let params = params.iter().map(ToString::to_string).collect::<Vec<_>>();
+	RoutePattern::parse(pattern).fill(&params)
+}
+
+/// The path of the registered route `name` with `params` filled in, as the
+/// WUI `url("name", ...)` built-in renders it. `name` is the handler's
+/// function name, qualified by as much of its module path as it takes to be
+/// unique. A route that isn't registered, is ambiguous or takes other
+/// parameters renders `""`.
+pub fn route_url(name: &str, params: &[WuiValue]) -> String {
+	try_route_url(name, params).unwrap_or_else(|message| {
+		log::warn!("{message}");
+		String::new()
+	})
+}
+
+pub(crate) fn try_route_url(name: &str, params: &[WuiValue]) -> Result<String, String> {
+	let routes = ROUTE_URLS.read().unwrap();
+	let pattern = routes.get(name)?;
+	let params = params
+		.iter()
+		.map(crate::wui::runtime::value_as_string)
+		.collect::<Vec<_>>();
+	pattern
+		.url(&params)
+		.map_err(|message| format!("route {name:?}: {message}"))
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HttpMethod {
 	Get,
 	Post,
 }
 
+/// Whether the server-rendered document of a route that returns a
+/// [`View::loading`] view waits for its data or carries the placeholder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SsrLoading {
+	/// Render the resolved view, so the first response is complete.
+	#[default]
+	Wait,
+	/// Render the placeholder; the client loads the data once it connects.
+	Fallback,
+}
+
 impl HttpMethod {
 	pub fn as_str(self) -> &'static str {
 		match self {
@@ -456,6 +670,15 @@ pub trait DynRouteHandler: Send + Sync + 'static {
 		HttpMethod::Get
 	}
 	fn state_type_id(&self) -> std::any::TypeId;
+	fn wui_template(&self) -> Option<&'static Template> {
+		None
+	}
+	fn ssr_loading(&self) -> SsrLoading {
+		SsrLoading::Wait
+	}
+	fn name(&self) -> Option<&str> {
+		None
+	}
 	/// Object-safe dispatch. Implementors downcast `ctx_any` to their
 	/// concrete `Ctx<T>`, set runtime context, then forward to the typed
 	/// handler.
@@ -486,6 +709,18 @@ where
 		std::any::TypeId::of::<H::State>()
 	}
 
+	fn wui_template(&self) -> Option<&'static Template> {
+		RouteHandler::wui_template(self)
+	}
+
+	fn ssr_loading(&self) -> SsrLoading {
+		RouteHandler::ssr_loading(self)
+	}
+
+	fn name(&self) -> Option<&str> {
+		RouteHandler::name(self)
+	}
+
 	fn call_dyn(
 		&self,
 		ctx_any: std::sync::Arc<dyn std::any::Any + Send + Sync>,
@@ -494,7 +729,7 @@ where
 		runtime: RuntimeContext,
 	) -> RouteFuture {
 		let this = *self;
-		Box::pin(async move {
+		let call = async move {
 			let ctx = ctx_any
 				.downcast::<Ctx<H::State, H::Db>>()
 				.expect("route context type mismatch: Ctx<T, DB> registered with Wgui does not match the #[route] handler");
@@ -503,12 +738,11 @@ where
 			ctx.set_current_route(runtime.route.clone());
 			let mut result = RouteHandler::call(this, ctx, params, form).await;
 			if let RouteResult::View(view) = &mut result {
-				if let Some(route) = runtime.route.as_ref() {
-					view.render_wui(RouteHandler::wui_template(&this), route);
-				}
+				view.finish(RouteHandler::wui_template(&this), &runtime.route);
 			}
 			result
-		})
+		};
+		catch_route_panic(call)
 	}
 }
 
@@ -602,6 +836,53 @@ mod tests {
 		assert!(v.title.is_none());
 	}
 
+	#[test]
+	fn only_one_clone_of_a_loading_view_takes_its_data() {
+		let mut view = View::loading(Item::default(), async {
+			View::page("Done", crate::gui::text("loaded"))
+		});
+		let mut copy = view.clone();
+
+		assert!(view.take_pending().is_some());
+		assert!(!view.is_loading());
+		assert!(copy.is_loading());
+		assert!(copy.take_pending().is_none());
+		assert!(!copy.is_loading());
+	}
+
+	#[cfg(feature = "hyper")]
+	#[tokio::test]
+	async fn resolve_loading_waits_through_nested_loading_views() {
+		let inner = View::loading(crate::gui::text("still loading"), async {
+			View::page("Done", crate::gui::text("loaded"))
+		});
+		let outer = View::loading(crate::gui::text("loading"), async move { inner });
+		assert!(outer.is_loading());
+		let RouteResult::View(view) = resolve_loading(RouteResult::View(Box::new(outer))).await
+		else {
+			panic!("expected a view");
+		};
+		assert!(!view.is_loading());
+		assert_eq!(view.title.as_deref(), Some("Done"));
+	}
+
+	#[cfg(feature = "hyper")]
+	#[tokio::test]
+	async fn finished_loading_view_turns_panicking_data_into_an_error() {
+		let mut view = View::loading(Item::default(), async {
+			if true {
+				panic!("fetch failed");
+			}
+			View::untitled(Item::default())
+		});
+		view.finish(None, &None);
+		let result = resolve_loading(RouteResult::View(Box::new(view))).await;
+		assert!(
+			matches!(&result, RouteResult::Error(message) if message == "panicked: fetch failed"),
+			"{result:?}"
+		);
+	}
+
 	#[test]
 	fn redirect_to_string() {
 		let r = Redirect::to("/todos");
@@ -627,6 +908,14 @@ mod tests {
 		assert!(matches!(r, RouteResult::Redirect(_)));
 	}
 
+	#[test]
+	fn route_result_from_err_is_error() {
+		let r: RouteResult = Err::<Item, _>("no such todo").into();
+		assert!(matches!(r, RouteResult::Error(message) if message == "no such todo"));
+		let r: RouteResult = Ok::<_, String>(Redirect::to("/x")).into();
+		assert!(matches!(r, RouteResult::Redirect(_)));
+	}
+
 	#[test]
 	fn route_result_from_unit_is_redirect_to_empty() {
 		let r: RouteResult = ().into();
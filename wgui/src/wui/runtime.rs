@@ -1,25 +1,78 @@
 use crate::gui::{self, Item};
 use crate::wui::ast::{BinaryOp, Expr, Literal, UnaryOp};
+use crate::wui::builtins::{self, WuiContains, WuiLen, WuiSlice};
 use crate::wui::compiler::ir::{ActionDef, ActionPayload, EventKind, IrNode, IrProp, IrWidget};
-use crate::wui::diagnostic::Diagnostic;
+use crate::wui::compiler::registry::handler_key;
+use crate::wui::compiler::typecheck;
+use crate::wui::diagnostic::{Diagnostic, Span};
+use crate::wui::error_pages::panic_message;
 use crate::wui::imports;
 use crate::wui::routing::route_params;
+use crate::wui::schema::{FieldType, StateSchema};
 
 pub use anyhow;
 pub use async_trait::async_trait;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fs;
 use std::future::Future;
 use std::io;
+use std::panic::{self, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::{Duration, SystemTime};
 use tokio::sync::mpsc;
 
 #[derive(Debug, Clone)]
 pub struct Template {
 	doc: crate::wui::compiler::ir::IrDocument,
+	module: String,
+	source_files: Vec<PathBuf>,
+	/// The file the template was loaded from, when it was.
+	path: Option<PathBuf>,
+	source: String,
+	component_files: HashMap<String, PathBuf>,
+	sources: HashMap<PathBuf, String>,
+}
+
+/// A template mistake found while rendering, such as `For` over something
+/// that is not a list or arithmetic on a string. The render goes on with
+/// `Null` or a placeholder item in its place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderWarning {
+	pub module: String,
+	/// The imported component the mistake is in, if it is not in the
+	/// template itself.
+	pub component: Option<String>,
+	pub span: Span,
+	/// The file `span` points into, when known.
+	pub file: Option<PathBuf>,
+	/// 1-based line and column of `span.start`.
+	pub line: usize,
+	pub column: usize,
+	pub message: String,
+	/// Raised inside an `<ErrorBoundary>` that rendered its fallback instead.
+	pub contained: bool,
+}
+
+impl std::fmt::Display for RenderWarning {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match &self.file {
+			Some(file) => write!(f, "{}", file.display())?,
+			None => write!(f, "{}.wui", self.module)?,
+		}
+		write!(f, ":{}:{}: {}", self.line, self.column, self.message)
+	}
+}
+
+static STRICT: AtomicBool = AtomicBool::new(false);
+
+/// Render templates that produce [`RenderWarning`]s as an error view listing
+/// them, instead of the degraded tree. Warnings inside an `<ErrorBoundary>`
+/// only replace that boundary with its fallback. Off by default; without it,
+/// debug builds log the warnings.
+pub fn set_strict_rendering(strict: bool) {
+	STRICT.store(strict, Ordering::Relaxed);
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -27,6 +80,29 @@ pub struct RouteContext {
 	pub path: String,
 	pub params: HashMap<String, String>,
 	pub query: HashMap<String, String>,
+	/// Raw query string without the leading `?`, which keeps repeated keys
+	/// that `query` collapses. Empty when the URL had no query or it wasn't
+	/// known.
+	pub query_string: String,
+}
+
+impl RouteContext {
+	/// Every query key/value pair in URL order, falling back to `query` when
+	/// the raw query string isn't known.
+	pub fn query_pairs(&self) -> Vec<(String, String)> {
+		if self.query_string.is_empty() {
+			let mut pairs: Vec<_> = self
+				.query
+				.iter()
+				.map(|(key, value)| (key.clone(), value.clone()))
+				.collect();
+			pairs.sort();
+			return pairs;
+		}
+		form_urlencoded::parse(self.query_string.as_bytes())
+			.into_owned()
+			.collect()
+	}
 }
 
 pub enum MountResult<C> {
@@ -60,6 +136,11 @@ pub trait WuiController {
 	fn route_title(&self, _path: &str) -> Option<String> {
 		None
 	}
+	/// Template this controller renders; used to find clients to re-render
+	/// after a hot reload.
+	fn wui_template(&self) -> Option<&'static Template> {
+		None
+	}
 	fn set_runtime_context(&mut self, _client_id: Option<usize>, _session: Option<String>) {}
 	fn set_route_context(&mut self, _route: Option<RouteContext>) {}
 	#[cfg(feature = "hyper")]
@@ -112,10 +193,10 @@ impl ControllerProcessCtx {
 	}
 
 	pub fn refresh(&self) {
-		let _ = self.event_tx.send(crate::types::ClientMessage {
-			client_id: self.client_id,
-			event: crate::types::ClientEvent::Refresh,
-		});
+		let _ = self.event_tx.send(crate::types::ClientMessage::new(
+			self.client_id,
+			crate::types::ClientEvent::Refresh,
+		));
 	}
 }
 
@@ -210,6 +291,18 @@ where
 			.send(RuntimeCommand::PushState { client_id, url });
 	}
 
+	/// Replace the current client's query string with `query` and re-run
+	/// its route, so filters and pagination live in the URL. The typed
+	/// counterpart of [`crate::Query`]: that argument reads back what this
+	/// writes. From a `POST` action, return [`crate::Redirect::with_query`]
+	/// instead, since the current route there is the action's.
+	pub fn set_query<Q: serde::Serialize>(&self, query: &Q) {
+		let Some(route) = self.route() else {
+			return;
+		};
+		self.push_state(crate::wui::query::url_with_query(&route.path, query));
+	}
+
 	pub fn navigate(&self, url: impl Into<String>) {
 		let url = url.into();
 		if let Some(client_id) = *self.current_client.lock().unwrap() {
@@ -347,11 +440,33 @@ pub enum RuntimeAction {
 		name: String,
 		value: String,
 	},
+	KeyDown {
+		name: String,
+		arg: Option<u32>,
+		key: String,
+	},
+	KeyUp {
+		name: String,
+		arg: Option<u32>,
+		key: String,
+	},
 	Custom {
 		name: String,
 		event: String,
 		payload: serde_json::Value,
 	},
+	/// A `bind:*` prop changed; apply it with [`apply_binding`].
+	Bind {
+		path: Vec<String>,
+		write: BindingWrite,
+	},
+}
+
+#[derive(Debug, Clone)]
+pub enum BindingWrite {
+	Set(WuiValue),
+	/// Checkbox clicks carry no value, so the bound bool is flipped.
+	Toggle,
 }
 
 #[derive(Debug, Clone)]
@@ -395,12 +510,144 @@ pub enum WuiValue {
 	Object(HashMap<String, WuiValue>),
 }
 
+/// State a template renders. Templates walk paths such as `state.rows` with
+/// the visitor methods, so only the values they read are ever built.
 pub trait WuiValueProvider {
 	fn wui_value(&self) -> WuiValue;
+
+	/// Calls `visit` with the field `name` when this is an object that has it.
+	fn visit_field(&self, name: &str, visit: &mut dyn FnMut(&dyn WuiValueProvider));
+
+	/// Calls `visit` with each item and its index when this is a list.
+	fn visit_items(&self, visit: &mut dyn FnMut(usize, &dyn WuiValueProvider));
 }
 
 pub trait WuiValueConvert {
 	fn to_wui_value(&self) -> WuiValue;
+
+	/// Lends the field `name` to `visit`; derived models borrow the field,
+	/// the default builds the whole value first.
+	fn visit_wui_field(&self, name: &str, visit: &mut dyn FnMut(&dyn WuiValueProvider)) {
+		if let WuiValue::Object(map) = self.to_wui_value() {
+			if let Some(field) = map.get(name) {
+				visit(field);
+			}
+		}
+	}
+
+	/// Lends each item to `visit`; the default builds the whole value first.
+	fn visit_wui_items(&self, visit: &mut dyn FnMut(usize, &dyn WuiValueProvider)) {
+		if let WuiValue::List(items) = self.to_wui_value() {
+			for (index, item) in items.iter().enumerate() {
+				visit(index, item);
+			}
+		}
+	}
+}
+
+/// Write access to state for `bind:*` props, derived by `WuiModel` and
+/// `WguiModel` alongside [`WuiValueConvert`]. Derived models skip fields
+/// whose type doesn't implement it, so those fields are read-only to
+/// bindings; an empty `impl WuiValueSetter for T {}` says so explicitly.
+pub trait WuiValueSetter {
+	/// Set the value at `path` below `self`. Returns `false` when the path
+	/// does not exist or `value` does not fit the field.
+	fn set_wui_value(&mut self, _path: &[String], _value: WuiValue) -> bool {
+		false
+	}
+
+	/// Build a value from scratch, used to fill a `None` option. Only plain
+	/// values can; models keep the default.
+	fn from_wui_value(_value: WuiValue) -> Option<Self>
+	where
+		Self: Sized,
+	{
+		None
+	}
+}
+
+/// A derived model's field, for the setter to pick [`SetWuiField`] when the
+/// field's type implements [`WuiValueSetter`] and [`SkipWuiField`]
+/// otherwise.
+#[doc(hidden)]
+pub struct SetterProbe<'a, T>(pub &'a mut T);
+
+#[doc(hidden)]
+pub trait SetWuiField {
+	fn set_wui_field(self, path: &[String], value: WuiValue) -> bool;
+}
+
+impl<T: WuiValueSetter> SetWuiField for SetterProbe<'_, T> {
+	fn set_wui_field(self, path: &[String], value: WuiValue) -> bool {
+		self.0.set_wui_value(path, value)
+	}
+}
+
+#[doc(hidden)]
+pub trait SkipWuiField {
+	fn set_wui_field(self, path: &[String], value: WuiValue) -> bool;
+}
+
+impl<T> SkipWuiField for &SetterProbe<'_, T> {
+	fn set_wui_field(self, _path: &[String], _value: WuiValue) -> bool {
+		false
+	}
+}
+
+/// What a controller without a `&mut self` method returning `&mut` state
+/// does with a [`RuntimeAction::Bind`] write: nothing, with a warning.
+#[doc(hidden)]
+pub fn unbound_binding(controller: &str, path: &[String]) -> bool {
+	log::warn!(
+		"{controller}: ignored bind:* write to `state.{}`; add a `&mut self` method returning `&mut` state",
+		path.join(".")
+	);
+	false
+}
+
+/// Apply a [`RuntimeAction::Bind`] write to `state`.
+pub fn apply_binding<T: WuiValueSetter + WuiValueConvert + ?Sized>(
+	state: &mut T,
+	path: &[String],
+	write: BindingWrite,
+) -> bool {
+	let value = match write {
+		BindingWrite::Set(value) => value,
+		BindingWrite::Toggle => {
+			let mut current = false;
+			let mut read = |value: &dyn WuiValueProvider| {
+				current = value_as_bool(&value.wui_value());
+			};
+			match path.split_first() {
+				Some((first, rest)) => state.visit_wui_field(first, &mut |field| {
+					visit_binding_path(field, rest, &mut read)
+				}),
+				None => read(&state.to_wui_value()),
+			}
+			WuiValue::Bool(!current)
+		}
+	};
+	state.set_wui_value(path, value)
+}
+
+/// Like [`visit_path`], but list items are addressed by index too.
+fn visit_binding_path(
+	value: &dyn WuiValueProvider,
+	path: &[String],
+	visit: &mut dyn FnMut(&dyn WuiValueProvider),
+) {
+	let Some((first, rest)) = path.split_first() else {
+		visit(value);
+		return;
+	};
+	value.visit_field(first, &mut |field| visit_binding_path(field, rest, visit));
+	if let Ok(index) = first.parse::<usize>() {
+		value.visit_items(&mut |at, item| {
+			if at == index {
+				visit_binding_path(item, rest, visit);
+			}
+		});
+	}
 }
 
 #[derive(Debug, Clone)]
@@ -433,12 +680,36 @@ impl<T: WuiValueConvert + ?Sized> WuiValueProvider for T {
 	fn wui_value(&self) -> WuiValue {
 		self.to_wui_value()
 	}
+
+	fn visit_field(&self, name: &str, visit: &mut dyn FnMut(&dyn WuiValueProvider)) {
+		self.visit_wui_field(name, visit);
+	}
+
+	fn visit_items(&self, visit: &mut dyn FnMut(usize, &dyn WuiValueProvider)) {
+		self.visit_wui_items(visit);
+	}
 }
 
 impl WuiValueConvert for WuiValue {
 	fn to_wui_value(&self) -> WuiValue {
 		self.clone()
 	}
+
+	fn visit_wui_field(&self, name: &str, visit: &mut dyn FnMut(&dyn WuiValueProvider)) {
+		if let WuiValue::Object(map) = self {
+			if let Some(field) = map.get(name) {
+				visit(field);
+			}
+		}
+	}
+
+	fn visit_wui_items(&self, visit: &mut dyn FnMut(usize, &dyn WuiValueProvider)) {
+		if let WuiValue::List(items) = self {
+			for (index, item) in items.iter().enumerate() {
+				visit(index, item);
+			}
+		}
+	}
 }
 
 impl WuiValueConvert for String {
@@ -493,6 +764,14 @@ impl<T: WuiValueConvert> WuiValueConvert for Vec<T> {
 	fn to_wui_value(&self) -> WuiValue {
 		WuiValue::List(self.iter().map(|item| item.to_wui_value()).collect())
 	}
+
+	fn visit_wui_field(&self, _name: &str, _visit: &mut dyn FnMut(&dyn WuiValueProvider)) {}
+
+	fn visit_wui_items(&self, visit: &mut dyn FnMut(usize, &dyn WuiValueProvider)) {
+		for (index, item) in self.iter().enumerate() {
+			visit(index, item);
+		}
+	}
 }
 
 impl<T: WuiValueConvert> WuiValueConvert for Option<T> {
@@ -502,6 +781,166 @@ impl<T: WuiValueConvert> WuiValueConvert for Option<T> {
 			None => WuiValue::Null,
 		}
 	}
+
+	fn visit_wui_field(&self, name: &str, visit: &mut dyn FnMut(&dyn WuiValueProvider)) {
+		if let Some(value) = self {
+			value.visit_wui_field(name, visit);
+		}
+	}
+
+	fn visit_wui_items(&self, visit: &mut dyn FnMut(usize, &dyn WuiValueProvider)) {
+		if let Some(value) = self {
+			value.visit_wui_items(visit);
+		}
+	}
+}
+
+fn set_plain<T: WuiValueSetter>(target: &mut T, path: &[String], value: WuiValue) -> bool {
+	if !path.is_empty() {
+		return false;
+	}
+	match T::from_wui_value(value) {
+		Some(value) => {
+			*target = value;
+			true
+		}
+		None => false,
+	}
+}
+
+impl WuiValueSetter for WuiValue {
+	fn set_wui_value(&mut self, path: &[String], value: WuiValue) -> bool {
+		let Some((first, rest)) = path.split_first() else {
+			*self = value;
+			return true;
+		};
+		match self {
+			WuiValue::Object(map) => map
+				.get_mut(first)
+				.is_some_and(|field| field.set_wui_value(rest, value)),
+			WuiValue::List(items) => first
+				.parse::<usize>()
+				.ok()
+				.and_then(|index| items.get_mut(index))
+				.is_some_and(|item| item.set_wui_value(rest, value)),
+			_ => false,
+		}
+	}
+
+	fn from_wui_value(value: WuiValue) -> Option<Self> {
+		Some(value)
+	}
+}
+
+impl WuiValueSetter for String {
+	fn set_wui_value(&mut self, path: &[String], value: WuiValue) -> bool {
+		set_plain(self, path, value)
+	}
+
+	fn from_wui_value(value: WuiValue) -> Option<Self> {
+		match value {
+			WuiValue::String(value) => Some(value),
+			WuiValue::Number(_) | WuiValue::Bool(_) => Some(value_as_string(&value)),
+			_ => None,
+		}
+	}
+}
+
+impl WuiValueSetter for &str {
+	fn set_wui_value(&mut self, _path: &[String], _value: WuiValue) -> bool {
+		false
+	}
+}
+
+impl WuiValueSetter for bool {
+	fn set_wui_value(&mut self, path: &[String], value: WuiValue) -> bool {
+		set_plain(self, path, value)
+	}
+
+	fn from_wui_value(value: WuiValue) -> Option<Self> {
+		match value {
+			WuiValue::Bool(value) => Some(value),
+			WuiValue::String(value) => value.parse().ok(),
+			_ => None,
+		}
+	}
+}
+
+macro_rules! impl_number_setter {
+	($($ty:ty),*) => {$(
+		impl WuiValueSetter for $ty {
+			fn set_wui_value(&mut self, path: &[String], value: WuiValue) -> bool {
+				set_plain(self, path, value)
+			}
+
+			fn from_wui_value(value: WuiValue) -> Option<Self> {
+				match value {
+					WuiValue::Number(value) => {
+						let converted = value as $ty;
+						(converted as f64 == value).then_some(converted)
+					}
+					WuiValue::String(value) => value.trim().parse().ok(),
+					_ => None,
+				}
+			}
+		}
+	)*};
+}
+
+impl_number_setter!(u32, i32, usize, f64);
+
+impl WuiValueSetter for f32 {
+	fn set_wui_value(&mut self, path: &[String], value: WuiValue) -> bool {
+		set_plain(self, path, value)
+	}
+
+	fn from_wui_value(value: WuiValue) -> Option<Self> {
+		match value {
+			WuiValue::Number(value) => Some(value as f32),
+			WuiValue::String(value) => value.trim().parse().ok(),
+			_ => None,
+		}
+	}
+}
+
+impl<T: WuiValueSetter> WuiValueSetter for Vec<T> {
+	fn set_wui_value(&mut self, path: &[String], value: WuiValue) -> bool {
+		let Some((first, rest)) = path.split_first() else {
+			return false;
+		};
+		first
+			.parse::<usize>()
+			.ok()
+			.and_then(|index| self.get_mut(index))
+			.is_some_and(|item| item.set_wui_value(rest, value))
+	}
+}
+
+impl<T: WuiValueSetter> WuiValueSetter for Option<T> {
+	fn set_wui_value(&mut self, path: &[String], value: WuiValue) -> bool {
+		if path.is_empty() && matches!(value, WuiValue::Null) {
+			*self = None;
+			return true;
+		}
+		match self {
+			Some(inner) => inner.set_wui_value(path, value),
+			None if path.is_empty() => match T::from_wui_value(value) {
+				Some(inner) => {
+					*self = Some(inner);
+					true
+				}
+				None => false,
+			},
+			None => false,
+		}
+	}
+
+	fn from_wui_value(value: WuiValue) -> Option<Self> {
+		match value {
+			WuiValue::Null => Some(None),
+			value => T::from_wui_value(value).map(Some),
+		}
+	}
 }
 
 #[derive(Debug)]
@@ -521,7 +960,7 @@ impl Template {
 		base_dir: Option<&Path>,
 	) -> Result<Self, Vec<Diagnostic>> {
 		let resolved = imports::resolve(source, module_name, base_dir)?;
-		Self::from_resolved(resolved, module_name)
+		Self::from_resolved(resolved, source, module_name)
 	}
 
 	pub fn parse_with_loader<F>(
@@ -534,7 +973,7 @@ impl Template {
 		F: FnMut(&Path) -> io::Result<String>,
 	{
 		let resolved = imports::resolve_with_loader(source, module_name, base_dir, loader)?;
-		Self::from_resolved(resolved, module_name)
+		Self::from_resolved(resolved, source, module_name)
 	}
 
 	pub fn parse_with_sources(
@@ -563,6 +1002,7 @@ impl Template {
 
 	fn from_resolved(
 		resolved: crate::wui::imports::ImportResult,
+		source: &str,
 		module_name: &str,
 	) -> Result<Self, Vec<Diagnostic>> {
 		let mut diags = Vec::new();
@@ -578,7 +1018,33 @@ impl Template {
 		if !diags.is_empty() {
 			return Err(diags);
 		}
-		Ok(Self { doc: lowered })
+		for style in &lowered.styles {
+			crate::wui::styles::register(&style.module, &style.scope, &style.css);
+		}
+		Ok(Self {
+			doc: lowered,
+			module: module_name.to_string(),
+			source_files: resolved.source_files,
+			path: None,
+			source: source.to_string(),
+			component_files: resolved.component_files,
+			sources: resolved.sources,
+		})
+	}
+
+	pub fn module(&self) -> &str {
+		&self.module
+	}
+
+	/// Files pulled in through `<Import>`, not including the root template.
+	pub fn source_files(&self) -> &[PathBuf] {
+		&self.source_files
+	}
+
+	/// Check the template's paths against `state`, typically
+	/// `FieldType::Model` of a type registered in `schema`.
+	pub fn check_state(&self, schema: &StateSchema, state: &FieldType) -> Vec<Diagnostic> {
+		typecheck::check(&self.doc, schema, state)
 	}
 
 	pub fn render<T: WuiValueProvider>(&self, state: &T) -> Item {
@@ -590,15 +1056,78 @@ impl Template {
 			path: path.to_string(),
 			params: HashMap::new(),
 			query: HashMap::new(),
+			query_string: String::new(),
 		};
 		self.render_with_route(state, &route)
 	}
 
+	/// Renders `state` at `route`. Mistakes found on the way are logged in
+	/// debug builds, or fail the render with an error view in strict mode;
+	/// see [`set_strict_rendering`].
 	pub fn render_with_route<T: WuiValueProvider>(&self, state: &T, route: &RouteContext) -> Item {
-		let mut ctx = EvalContext::new(state.wui_value(), route, &self.doc.components);
+		let (item, warnings) = self.render_with_warnings(state, route);
+		if warnings.is_empty() {
+			return item;
+		}
+		if STRICT.load(Ordering::Relaxed) && warnings.iter().any(|warning| !warning.contained) {
+			return warnings_view(&warnings);
+		}
+		#[cfg(debug_assertions)]
+		for warning in &warnings {
+			log::warn!("{warning}");
+		}
+		item
+	}
+
+	/// Renders like [`Template::render_with_route`], returning the mistakes
+	/// found on the way instead of reporting them.
+	pub fn render_with_warnings<T: WuiValueProvider>(
+		&self,
+		state: &T,
+		route: &RouteContext,
+	) -> (Item, Vec<RenderWarning>) {
+		let _span = tracing::debug_span!("wui_render", module = %self.module, route = %route.path)
+			.entered();
+		let route_vars = [
+			("path", WuiValue::String(route.path.clone())),
+			("params", string_map_to_wui_value(&route.params)),
+			("query", string_map_to_wui_value(&route.query)),
+		];
+		let warnings = RefCell::new(Vec::new());
+		let mut ctx = EvalContext::new(&self.doc.components, &warnings);
+		ctx.vars.push(("state", Var::Shared(state)));
+		ctx.vars
+			.extend(route_vars.map(|(name, value)| (name, Var::Owned(value))));
 		let mut children = Vec::new();
-		render_nodes(&self.doc.nodes, &mut children, &mut ctx);
-		single_or_wrapped(children)
+		render_nodes(&self.doc.nodes, &mut children, &ctx);
+		let warnings = warnings
+			.into_inner()
+			.into_iter()
+			.map(|warning| self.locate(warning))
+			.collect();
+		(single_or_wrapped(children), warnings)
+	}
+
+	fn locate(&self, warning: PendingWarning) -> RenderWarning {
+		let (file, source) = match &warning.component {
+			Some(component) => {
+				let file = self.component_files.get(component);
+				let source = file.and_then(|file| self.sources.get(file));
+				(file.cloned(), source.map_or("", String::as_str))
+			}
+			None => (self.path.clone(), self.source.as_str()),
+		};
+		let (line, column) = crate::wui::hot_reload::line_column(source, warning.span.start);
+		RenderWarning {
+			module: self.module.clone(),
+			component: warning.component,
+			span: warning.span,
+			file,
+			line,
+			column,
+			message: warning.message,
+			contained: warning.contained,
+		}
 	}
 
 	pub fn title_for_path(&self, path: &str) -> Option<String> {
@@ -613,9 +1142,21 @@ impl Template {
 		None
 	}
 
+	pub(crate) fn actions(&self) -> &[ActionDef] {
+		&self.doc.actions
+	}
+
 	pub fn decode(&self, event: &crate::types::ClientEvent) -> Option<RuntimeAction> {
 		for action in &self.doc.actions {
 			if let Some(decoded) = decode_action(action, event) {
+				let decoded = match &action.binding {
+					Some(path) => binding_action(path, decoded),
+					None => decoded,
+				};
+				// Recorded on the dispatching `controller_event` span, if any.
+				tracing::Span::current().record("action", action.name.as_str());
+				#[cfg(debug_assertions)]
+				crate::devtools::record_decoded_action(&action.name);
 				return Some(decoded);
 			}
 		}
@@ -625,31 +1166,34 @@ impl Template {
 
 pub fn load_template(path: &Path, module_name: &str) -> Result<Template, TemplateLoadError> {
 	let source = fs::read_to_string(path).map_err(TemplateLoadError::Io)?;
-	Template::parse_with_dir(&source, module_name, path.parent())
-		.map_err(TemplateLoadError::Diagnostics)
+	let mut template = Template::parse_with_dir(&source, module_name, path.parent())
+		.map_err(TemplateLoadError::Diagnostics)?;
+	template.path = Some(path.to_path_buf());
+	Ok(template)
 }
 
+/// Send on `tx` whenever the file at `path` changes.
+#[deprecated(
+	note = "use `Wgui::enable_hot_reload`, which also reloads the changed templates and re-renders clients"
+)]
 pub fn spawn_template_watcher(
 	path: PathBuf,
 	tx: mpsc::UnboundedSender<()>,
-) -> thread::JoinHandle<()> {
-	thread::spawn(move || {
-		let mut last_mtime = file_mtime(&path);
-		loop {
-			thread::sleep(Duration::from_millis(250));
-			let mtime = file_mtime(&path);
-			if mtime > last_mtime {
-				last_mtime = mtime;
-				let _ = tx.send(());
-			}
-		}
+) -> std::thread::JoinHandle<()> {
+	crate::wui::hot_reload::poll_file(path, move || {
+		let _ = tx.send(());
 	})
 }
 
-fn file_mtime(path: &Path) -> SystemTime {
-	fs::metadata(path)
-		.and_then(|meta| meta.modified())
-		.unwrap_or(SystemTime::UNIX_EPOCH)
+/// What strict mode renders in place of a template that produced warnings.
+fn warnings_view(warnings: &[RenderWarning]) -> Item {
+	let mut body = vec![gui::text("Template error").color("#b00020")];
+	body.extend(
+		warnings
+			.iter()
+			.map(|warning| gui::text(&warning.to_string()).break_words(true)),
+	);
+	gui::vstack(body).spacing(8).padding(16)
 }
 
 fn normalize_embedded_path(path: &Path) -> PathBuf {
@@ -674,41 +1218,135 @@ impl WuiValue {
 		}
 		WuiValue::Object(map)
 	}
+
+	/// The JSON `CustomComponent` props are sent as; non-finite numbers
+	/// become `null`.
+	pub fn to_json(&self) -> serde_json::Value {
+		match self {
+			WuiValue::String(value) => serde_json::Value::String(value.clone()),
+			WuiValue::Number(value) => serde_json::Number::from_f64(*value)
+				.map(serde_json::Value::Number)
+				.unwrap_or(serde_json::Value::Null),
+			WuiValue::Bool(value) => serde_json::Value::Bool(*value),
+			WuiValue::Null => serde_json::Value::Null,
+			WuiValue::List(values) => {
+				serde_json::Value::Array(values.iter().map(WuiValue::to_json).collect())
+			}
+			WuiValue::Object(values) => serde_json::Value::Object(
+				values
+					.iter()
+					.map(|(key, value)| (key.clone(), value.to_json()))
+					.collect(),
+			),
+		}
+	}
+
+	/// The value of `json`, as template fixtures give state.
+	pub fn from_json(json: &serde_json::Value) -> Self {
+		match json {
+			serde_json::Value::String(value) => WuiValue::String(value.clone()),
+			serde_json::Value::Number(value) => WuiValue::Number(value.as_f64().unwrap_or(0.0)),
+			serde_json::Value::Bool(value) => WuiValue::Bool(*value),
+			serde_json::Value::Null => WuiValue::Null,
+			serde_json::Value::Array(values) => {
+				WuiValue::List(values.iter().map(WuiValue::from_json).collect())
+			}
+			serde_json::Value::Object(values) => WuiValue::Object(
+				values
+					.iter()
+					.map(|(key, value)| (key.clone(), WuiValue::from_json(value)))
+					.collect(),
+			),
+		}
+	}
+}
+
+/// One scope of template variables. Nested scopes point at their parent
+/// instead of copying it, and state is borrowed from the caller.
+struct EvalContext<'a> {
+	parent: Option<&'a EvalContext<'a>>,
+	vars: Vec<(&'a str, Var<'a>)>,
+	components: &'a HashMap<String, crate::wui::compiler::ir::IrComponent>,
+	/// What the component being rendered was called with, for `<Children>`
+	/// and `<Slot>`.
+	content: &'a [IrNode],
+	/// The component whose nodes are being rendered, `None` in the template
+	/// itself, and the one `content` came from.
+	component: Option<&'a str>,
+	content_component: Option<&'a str>,
+	warnings: &'a RefCell<Vec<PendingWarning>>,
 }
 
-struct EvalContext {
-	vars: HashMap<String, WuiValue>,
-	components: HashMap<String, crate::wui::compiler::ir::IrComponent>,
-	children: Vec<IrNode>,
+/// A [`RenderWarning`] before its span is turned into a line.
+struct PendingWarning {
+	component: Option<String>,
+	span: Span,
+	message: String,
+	contained: bool,
 }
 
-impl EvalContext {
+enum Var<'a> {
+	Owned(WuiValue),
+	Shared(&'a dyn WuiValueProvider),
+}
+
+impl<'a> EvalContext<'a> {
 	fn new(
-		state: WuiValue,
-		route: &RouteContext,
-		components: &HashMap<String, crate::wui::compiler::ir::IrComponent>,
+		components: &'a HashMap<String, crate::wui::compiler::ir::IrComponent>,
+		warnings: &'a RefCell<Vec<PendingWarning>>,
 	) -> Self {
-		let mut vars = HashMap::new();
-		vars.insert("state".to_string(), state);
-		vars.insert("path".to_string(), WuiValue::String(route.path.clone()));
-		vars.insert("params".to_string(), string_map_to_wui_value(&route.params));
-		vars.insert("query".to_string(), string_map_to_wui_value(&route.query));
 		Self {
-			vars,
-			components: components.clone(),
-			children: Vec::new(),
+			parent: None,
+			vars: Vec::new(),
+			components,
+			content: &[],
+			component: None,
+			content_component: None,
+			warnings,
+		}
+	}
+
+	fn with_var(&'a self, name: &'a str, value: Var<'a>) -> Self {
+		Self {
+			parent: Some(self),
+			vars: vec![(name, value)],
+			..*self
 		}
 	}
 
-	fn with_var(&self, name: &str, value: WuiValue) -> Self {
-		let mut vars = self.vars.clone();
-		vars.insert(name.to_string(), value);
+	/// This scope, rendering the content the component was called with.
+	fn for_content(&'a self) -> Self {
 		Self {
-			vars,
-			components: self.components.clone(),
-			children: self.children.clone(),
+			parent: Some(self),
+			vars: Vec::new(),
+			component: self.content_component,
+			..*self
+		}
+	}
+
+	fn warn(&self, span: Span, message: impl Into<String>) {
+		self.warnings.borrow_mut().push(PendingWarning {
+			component: self.component.map(str::to_string),
+			span,
+			message: message.into(),
+			contained: false,
+		});
+	}
+
+	fn var(&self, name: &str) -> Option<&dyn WuiValueProvider> {
+		let found = self.vars.iter().rev().find(|(var, _)| *var == name);
+		match found {
+			Some((_, Var::Owned(value))) => Some(value),
+			Some((_, Var::Shared(value))) => Some(*value),
+			None => self.parent.and_then(|parent| parent.var(name)),
 		}
 	}
+
+	fn path(&self) -> String {
+		self.var("path")
+			.map(|path| value_as_string(&path.wui_value()))
+			.unwrap_or_default()
+	}
 }
 
 fn string_map_to_wui_value(map: &HashMap<String, String>) -> WuiValue {
@@ -814,6 +1452,43 @@ fn decode_action(action: &ActionDef, event: &crate::types::ClientEvent) -> Optio
 			}
 			_ => None,
 		},
+		EventKind::KeyDown | EventKind::KeyUp => {
+			// Only the declared combinations fire, whatever the client sends.
+			let declared = |matches: &dyn Fn(&str) -> bool| {
+				action.key_combos.is_empty()
+					|| action
+						.key_combos
+						.iter()
+						.any(|combo| combo.as_deref().is_none_or(matches))
+			};
+			let (id, keycode, inx) = match (&action.kind, event) {
+				(EventKind::KeyDown, crate::types::ClientEvent::OnKeyDown(ev))
+					if declared(&|combo| ev.matches(combo)) =>
+				{
+					(&ev.id, &ev.keycode, ev.inx)
+				}
+				(EventKind::KeyUp, crate::types::ClientEvent::OnKeyUp(ev))
+					if declared(&|combo| ev.matches(combo)) =>
+				{
+					(&ev.id, &ev.keycode, ev.inx)
+				}
+				_ => return None,
+			};
+			if id.as_deref().and_then(|id| id.parse::<u32>().ok()) != Some(action.id) {
+				return None;
+			}
+			let arg = match action.payload {
+				ActionPayload::String => None,
+				ActionPayload::U32String => Some(inx?),
+				_ => return None,
+			};
+			let name = action.name.clone();
+			let key = keycode.clone();
+			Some(match action.kind {
+				EventKind::KeyDown => RuntimeAction::KeyDown { name, arg, key },
+				_ => RuntimeAction::KeyUp { name, arg, key },
+			})
+		}
 		EventKind::Select => match event {
 			crate::types::ClientEvent::OnSelect(ev) if ev.id == action.id => {
 				Some(RuntimeAction::Select {
@@ -838,26 +1513,75 @@ fn decode_action(action: &ActionDef, event: &crate::types::ClientEvent) -> Optio
 	}
 }
 
-fn render_nodes(nodes: &[IrNode], out: &mut Vec<Item>, ctx: &mut EvalContext) {
+fn binding_action(path: &[String], decoded: RuntimeAction) -> RuntimeAction {
+	let write = match decoded {
+		RuntimeAction::TextChanged { value, .. } | RuntimeAction::Select { value, .. } => {
+			BindingWrite::Set(WuiValue::String(value))
+		}
+		RuntimeAction::SliderChange { value, .. } => {
+			BindingWrite::Set(WuiValue::Number(value as f64))
+		}
+		_ => BindingWrite::Toggle,
+	};
+	RuntimeAction::Bind {
+		path: path.to_vec(),
+		write,
+	}
+}
+
+fn render_nodes(nodes: &[IrNode], out: &mut Vec<Item>, ctx: &EvalContext) {
 	for node in nodes {
 		match node {
 			IrNode::Widget(widget) => out.push(render_widget(widget, ctx)),
 			IrNode::Children => {
-				let children = ctx.children.clone();
-				render_nodes(&children, out, ctx);
+				let content = ctx.for_content();
+				for child in ctx.content {
+					if !matches!(child, IrNode::Slot(_)) {
+						render_nodes(std::slice::from_ref(child), out, &content);
+					}
+				}
+			}
+			IrNode::Slot(slot) => {
+				let fill = ctx.content.iter().find_map(|child| match child {
+					IrNode::Slot(fill) if fill.name == slot.name => Some(&fill.body),
+					_ => None,
+				});
+				match fill {
+					Some(fill) => render_nodes(fill, out, &ctx.for_content()),
+					None => render_nodes(&slot.body, out, ctx),
+				}
 			}
 			IrNode::Text(text) => out.push(gui::text(text)),
 			IrNode::For(node) => {
-				let list_value = eval_expr(&node.each, ctx);
-				let WuiValue::List(items) = list_value else {
-					continue;
-				};
-				for (inx, item) in items.into_iter().enumerate() {
-					let mut nested = ctx.with_var(&node.item, item);
-					if let Some(index) = &node.index {
-						nested = nested.with_var(index, WuiValue::Number(inx as f64));
+				let mut render_items = |list: &dyn WuiValueProvider| {
+					let mut visited = false;
+					list.visit_items(&mut |inx, item| {
+						visited = true;
+						let nested = ctx.with_var(&node.item, Var::Shared(item));
+						match &node.index {
+							Some(index) => {
+								let number = Var::Owned(WuiValue::Number(inx as f64));
+								render_nodes(&node.body, out, &nested.with_var(index, number));
+							}
+							None => render_nodes(&node.body, out, &nested),
+						}
+					});
+					// Only lists visit items, so only a list can be empty.
+					if !visited {
+						let value = list.wui_value();
+						if !matches!(value, WuiValue::List(_) | WuiValue::Null) {
+							let kind = value_kind(&value);
+							ctx.warn(
+								node.each.span(),
+								format!("`For each` over {kind}, not a list"),
+							);
+						}
 					}
-					render_nodes(&node.body, out, &mut nested);
+				};
+				// Lists from state are walked in place rather than copied.
+				match &node.each {
+					Expr::Path(parts, _) => visit_path(parts, ctx, &mut render_items),
+					each => render_items(&eval_expr(each, ctx)),
 				}
 			}
 			IrNode::If(node) => {
@@ -868,42 +1592,59 @@ fn render_nodes(nodes: &[IrNode], out: &mut Vec<Item>, ctx: &mut EvalContext) {
 					render_nodes(&node.else_body, out, ctx);
 				}
 			}
+			IrNode::Let(node) => {
+				let value = eval_expr(&node.value, ctx);
+				let nested = ctx.with_var(&node.name, Var::Owned(value));
+				render_nodes(&node.body, out, &nested);
+			}
+			IrNode::Match(node) => {
+				let value = value_as_string(&eval_expr(&node.value, ctx));
+				let body = node
+					.cases
+					.iter()
+					.find(|case| case.is == value)
+					.map_or(&node.else_body, |case| &case.body);
+				render_nodes(body, out, ctx);
+			}
 			IrNode::Scope(node) => {
 				render_nodes(&node.body, out, ctx);
 			}
+			IrNode::ErrorBoundary(node) => {
+				let before = ctx.warnings.borrow().len();
+				let mut body = Vec::new();
+				let rendered = panic::catch_unwind(AssertUnwindSafe(|| {
+					render_nodes(&node.body, &mut body, ctx)
+				}));
+				let failed = match rendered {
+					Ok(()) => {
+						STRICT.load(Ordering::Relaxed) && ctx.warnings.borrow().len() > before
+					}
+					Err(payload) => {
+						ctx.warn(node.span, panic_message(payload.as_ref()));
+						true
+					}
+				};
+				if failed {
+					for warning in &mut ctx.warnings.borrow_mut()[before..] {
+						warning.contained = true;
+					}
+					render_nodes(&node.fallback, out, ctx);
+				} else {
+					out.extend(body);
+				}
+			}
 			IrNode::Route(node) => {
-				let path = ctx
-					.vars
-					.get("path")
-					.map(value_as_string)
-					.unwrap_or_default();
-				if let Some(params) = route_params(&node.path, &path) {
-					let params = WuiValue::object(
-						params
-							.into_iter()
-							.map(|(k, v)| (k, WuiValue::String(v)))
-							.collect(),
-					);
-					let mut nested = ctx.with_var("params", params);
-					render_nodes(&node.body, out, &mut nested);
+				if let Some(params) = route_params(&node.path, &ctx.path()) {
+					let nested = ctx.with_var("params", Var::Owned(params_value(params)));
+					render_nodes(&node.body, out, &nested);
 				}
 			}
 			IrNode::Switch(node) => {
-				let path = ctx
-					.vars
-					.get("path")
-					.map(value_as_string)
-					.unwrap_or_default();
+				let path = ctx.path();
 				for case in &node.cases {
 					if let Some(params) = route_params(&case.path, &path) {
-						let params = WuiValue::object(
-							params
-								.into_iter()
-								.map(|(k, v)| (k, WuiValue::String(v)))
-								.collect(),
-						);
-						let mut nested = ctx.with_var("params", params);
-						render_nodes(&case.body, out, &mut nested);
+						let nested = ctx.with_var("params", Var::Owned(params_value(params)));
+						render_nodes(&case.body, out, &nested);
 						break;
 					}
 				}
@@ -912,7 +1653,16 @@ fn render_nodes(nodes: &[IrNode], out: &mut Vec<Item>, ctx: &mut EvalContext) {
 	}
 }
 
-fn render_widget(widget: &IrWidget, ctx: &mut EvalContext) -> Item {
+fn params_value(params: HashMap<String, String>) -> WuiValue {
+	WuiValue::Object(
+		params
+			.into_iter()
+			.map(|(k, v)| (k, WuiValue::String(v)))
+			.collect(),
+	)
+}
+
+fn render_widget(widget: &IrWidget, ctx: &EvalContext) -> Item {
 	if ctx.components.contains_key(&widget.tag) {
 		let mut item = single_or_wrapped(render_component(widget, ctx));
 		for prop in &widget.props {
@@ -953,8 +1703,12 @@ fn render_widget(widget: &IrWidget, ctx: &mut EvalContext) -> Item {
 			gui::audio(&room)
 		}
 		"FolderPicker" => gui::folder_picker(),
+		"Shortcut" => gui::shortcut(&textual_value(widget, ctx, "keyCombo")),
 		"Modal" => render_modal(widget, ctx),
-		_ => gui::text("unsupported"),
+		tag => {
+			ctx.warn(widget.span, format!("`{tag}` cannot be rendered"));
+			gui::text("unsupported")
+		}
 	};
 
 	for prop in &widget.props {
@@ -967,7 +1721,7 @@ fn render_widget(widget: &IrWidget, ctx: &mut EvalContext) -> Item {
 	base
 }
 
-fn render_custom(widget: &IrWidget, ctx: &mut EvalContext) -> Item {
+fn render_custom(widget: &IrWidget, ctx: &EvalContext) -> Item {
 	let src = textual_value(widget, ctx, "src");
 	let (name, entry) = if src.is_empty() {
 		(
@@ -981,9 +1735,7 @@ fn render_custom(widget: &IrWidget, ctx: &mut EvalContext) -> Item {
 		.props
 		.iter()
 		.find_map(|prop| match prop {
-			IrProp::Value { name, expr } if name == "props" => {
-				Some(wui_value_to_json(&eval_expr(expr, ctx)))
-			}
+			IrProp::Value { name, expr } if name == "props" => Some(eval_expr(expr, ctx).to_json()),
 			IrProp::Literal { name, value } if name == "props" => {
 				Some(serde_json::Value::String(value.clone()))
 			}
@@ -996,42 +1748,35 @@ fn render_custom(widget: &IrWidget, ctx: &mut EvalContext) -> Item {
 	gui::custom_component(name, entry, props)
 }
 
-fn wui_value_to_json(value: &WuiValue) -> serde_json::Value {
-	match value {
-		WuiValue::String(value) => serde_json::Value::String(value.clone()),
-		WuiValue::Number(value) => serde_json::Number::from_f64(*value)
-			.map(serde_json::Value::Number)
-			.unwrap_or(serde_json::Value::Null),
-		WuiValue::Bool(value) => serde_json::Value::Bool(*value),
-		WuiValue::Null => serde_json::Value::Null,
-		WuiValue::List(values) => {
-			serde_json::Value::Array(values.iter().map(wui_value_to_json).collect())
-		}
-		WuiValue::Object(values) => serde_json::Value::Object(
-			values
-				.iter()
-				.map(|(key, value)| (key.clone(), wui_value_to_json(value)))
-				.collect(),
-		),
-	}
-}
-
-fn render_component(widget: &IrWidget, ctx: &mut EvalContext) -> Vec<Item> {
-	let Some(component) = ctx.components.get(&widget.tag).cloned() else {
+fn render_component(widget: &IrWidget, ctx: &EvalContext) -> Vec<Item> {
+	let Some(component) = ctx.components.get(&widget.tag) else {
+		ctx.warn(widget.span, format!("unknown component `{}`", widget.tag));
 		return vec![gui::text("unsupported")];
 	};
-	let mut vars = ctx.vars.clone();
+	// Declared props never fall through to a caller variable of the same name.
+	let mut vars = Vec::new();
+	for prop in &component.props {
+		let value = match &prop.default {
+			Some(default) => eval_expr(default, ctx),
+			None => WuiValue::Null,
+		};
+		vars.push((prop.name.as_str(), Var::Owned(value)));
+	}
 	for prop in &widget.props {
 		let (name, value) = prop_value(prop, ctx);
-		vars.insert(name, value);
+		vars.push((name, Var::Owned(value)));
 	}
-	let mut nested = EvalContext {
+	let nested = EvalContext {
+		parent: Some(ctx),
 		vars,
-		components: ctx.components.clone(),
-		children: widget.children.clone(),
+		components: ctx.components,
+		content: &widget.children,
+		component: Some(&widget.tag),
+		content_component: ctx.component,
+		warnings: ctx.warnings,
 	};
 	let mut items = Vec::new();
-	render_nodes(&component.body, &mut items, &mut nested);
+	render_nodes(&component.body, &mut items, &nested);
 	items
 }
 
@@ -1043,18 +1788,18 @@ fn single_or_wrapped(mut items: Vec<Item>) -> Item {
 	}
 }
 
-fn prop_value(prop: &IrProp, ctx: &mut EvalContext) -> (String, WuiValue) {
+fn prop_value<'a>(prop: &'a IrProp, ctx: &EvalContext) -> (&'a str, WuiValue) {
 	match prop {
-		IrProp::Literal { name, value } => (name.clone(), WuiValue::String(value.clone())),
-		IrProp::Number { name, value } => (name.clone(), WuiValue::Number(*value)),
-		IrProp::Bool { name, value } => (name.clone(), WuiValue::Bool(*value)),
-		IrProp::Value { name, expr } => (name.clone(), eval_expr(expr, ctx)),
-		IrProp::Bind { name, expr } => (name.clone(), eval_expr(expr, ctx)),
-		IrProp::Event { name, .. } => (name.clone(), WuiValue::Null),
+		IrProp::Literal { name, value } => (name, WuiValue::String(value.clone())),
+		IrProp::Number { name, value } => (name, WuiValue::Number(*value)),
+		IrProp::Bool { name, value } => (name, WuiValue::Bool(*value)),
+		IrProp::Value { name, expr } => (name, eval_expr(expr, ctx)),
+		IrProp::Bind { name, expr } => (name, eval_expr(expr, ctx)),
+		IrProp::Event { name, .. } => (name, WuiValue::Null),
 	}
 }
 
-fn render_container<F>(builder: F, children: &[IrNode], ctx: &mut EvalContext) -> Item
+fn render_container<F>(builder: F, children: &[IrNode], ctx: &EvalContext) -> Item
 where
 	F: Fn(Vec<Item>) -> Item,
 {
@@ -1063,13 +1808,13 @@ where
 	builder(items)
 }
 
-fn render_modal(widget: &IrWidget, ctx: &mut EvalContext) -> Item {
+fn render_modal(widget: &IrWidget, ctx: &EvalContext) -> Item {
 	let mut items = Vec::new();
 	render_nodes(&widget.children, &mut items, ctx);
 	gui::modal(items)
 }
 
-fn text_value(widget: &IrWidget, ctx: &mut EvalContext) -> String {
+fn text_value(widget: &IrWidget, ctx: &EvalContext) -> String {
 	for prop in &widget.props {
 		match prop {
 			IrProp::Literal { name, value } if name == "value" => return value.clone(),
@@ -1082,7 +1827,7 @@ fn text_value(widget: &IrWidget, ctx: &mut EvalContext) -> String {
 	String::new()
 }
 
-fn textual_value(widget: &IrWidget, ctx: &mut EvalContext, prop_name: &str) -> String {
+fn textual_value(widget: &IrWidget, ctx: &EvalContext, prop_name: &str) -> String {
 	for prop in &widget.props {
 		match prop {
 			IrProp::Literal { name, value } if name == prop_name => return value.clone(),
@@ -1095,7 +1840,7 @@ fn textual_value(widget: &IrWidget, ctx: &mut EvalContext, prop_name: &str) -> S
 	String::new()
 }
 
-fn image_values(widget: &IrWidget, ctx: &mut EvalContext) -> (String, String) {
+fn image_values(widget: &IrWidget, ctx: &EvalContext) -> (String, String) {
 	let mut src = String::new();
 	let mut alt = String::new();
 	for prop in &widget.props {
@@ -1114,7 +1859,7 @@ fn image_values(widget: &IrWidget, ctx: &mut EvalContext) -> (String, String) {
 	(src, alt)
 }
 
-fn media_room_value(widget: &IrWidget, ctx: &mut EvalContext) -> String {
+fn media_room_value(widget: &IrWidget, ctx: &EvalContext) -> String {
 	for prop in &widget.props {
 		match prop {
 			IrProp::Literal { name, value } if name == "room" => return value.clone(),
@@ -1142,7 +1887,7 @@ fn select_option_from_value(value: WuiValue) -> Option<gui::SelectOption> {
 	))
 }
 
-fn select_options(widget: &IrWidget, ctx: &mut EvalContext) -> Vec<gui::SelectOption> {
+fn select_options(widget: &IrWidget, ctx: &EvalContext) -> Vec<gui::SelectOption> {
 	for prop in &widget.props {
 		match prop {
 			IrProp::Value { name, expr } if name == "options" => {
@@ -1160,7 +1905,7 @@ fn select_options(widget: &IrWidget, ctx: &mut EvalContext) -> Vec<gui::SelectOp
 	Vec::new()
 }
 
-fn render_select(widget: &IrWidget, ctx: &mut EvalContext) -> Item {
+fn render_select(widget: &IrWidget, ctx: &EvalContext) -> Item {
 	let mut item = gui::select(select_options(widget, ctx));
 	for prop in &widget.props {
 		match prop {
@@ -1193,31 +1938,43 @@ fn should_apply_prop(tag: &str, prop: &IrProp) -> bool {
 			"Image" => name != "src" && name != "alt",
 			"Video" | "Audio" => name != "room",
 			"Select" => name != "value" && name != "bind:value" && name != "options",
+			"Shortcut" => name != "keyCombo",
 			_ => true,
 		},
 	}
 }
 
-fn apply_prop(item: Item, prop: &IrProp, ctx: &mut EvalContext) -> Item {
+fn apply_prop(item: Item, prop: &IrProp, ctx: &EvalContext) -> Item {
 	match prop {
 		IrProp::Event { name, action, arg } => {
 			let id = action_id(action);
-			let mut item = if matches!(&item.payload, gui::ItemPayload::Button { .. }) {
-				apply_button_event(item, name, id)
-			} else if matches!(&item.payload, gui::ItemPayload::Layout(_)) {
-				apply_layout_event(item, name, id)
-			} else if matches!(&item.payload, gui::ItemPayload::Custom { .. }) {
-				item.custom_event(name, id)
-			} else {
-				item.id(id)
+			let key = handler_key(name);
+			// Events older clients know about keep their legacy fields.
+			let mut item = match name.as_str() {
+				"onClick" => item.on_click(id),
+				"onPress" => item.on_press(id),
+				"onRelease" => item.on_release(id),
+				"onRepeat" => item.on_repeat(id),
+				"onScrollNearBottom" => item.on_scroll_near_bottom(id),
+				name if !name.starts_with("on") => item.custom_event(name, id),
+				_ => item,
 			};
-			if let Some(expr) = arg {
-				let value = eval_expr(expr, ctx);
-				if let Some(inx) = value_as_u32(&value) {
-					item = item.inx(inx);
-				}
+			// Change events travel under the item's own id.
+			if key.is_none() {
+				item = item.id(id);
+			}
+			let inx = arg
+				.as_ref()
+				.and_then(|expr| value_as_u32(&eval_expr(expr, ctx)));
+			// Key handlers only exist in `handlers`; the rest keep the
+			// item-level `inx` older clients read.
+			if let Some(inx) = inx.filter(|_| name != "onKeyDown" && name != "onKeyUp") {
+				item = item.inx(inx);
+			}
+			match key {
+				Some(key) => item.on(key, id, inx),
+				None => item,
 			}
-			item
 		}
 		IrProp::Literal { name, value } => apply_string_prop(item, name, value),
 		IrProp::Number { name, value } => apply_number_prop(item, name, *value),
@@ -1233,32 +1990,6 @@ fn apply_prop(item: Item, prop: &IrProp, ctx: &mut EvalContext) -> Item {
 	}
 }
 
-fn apply_button_event(mut item: Item, name: &str, id: u32) -> Item {
-	let gui::ItemPayload::Button { events, .. } = &mut item.payload else {
-		return item;
-	};
-	let events = events.get_or_insert_with(gui::ButtonEvents::default);
-	match name {
-		"onClick" => events.click = Some(id),
-		"onPress" => events.press = Some(id),
-		"onRelease" => events.release = Some(id),
-		"onRepeat" => events.repeat = Some(id),
-		_ => {}
-	}
-	item
-}
-
-fn apply_layout_event(mut item: Item, name: &str, id: u32) -> Item {
-	let gui::ItemPayload::Layout(layout) = &mut item.payload else {
-		return item;
-	};
-	let events = layout.events.get_or_insert_with(gui::LayoutEvents::default);
-	if name == "onScrollNearBottom" {
-		events.scroll_near_bottom = Some(id);
-	}
-	item
-}
-
 fn apply_value_prop(item: Item, name: &str, value: WuiValue) -> Item {
 	if is_string_prop(name) {
 		return apply_string_prop(item, name, &value_as_string(&value));
@@ -1292,6 +2023,7 @@ fn apply_string_prop(item: Item, name: &str, value: &str) -> Item {
 		"objectFit" => item.object_fit(value),
 		"href" => item.image_href(value),
 		"room" => item.room(value),
+		"keyCombo" => item.key_combo(value),
 		_ => item,
 	}
 }
@@ -1322,21 +2054,11 @@ fn apply_number_prop(item: Item, name: &str, value: f64) -> Item {
 		"maxHeight" => item.max_height(value as u32),
 		"grow" => item.grow(value as u32),
 		"arg" => item.form_arg(value as u32),
-		"repeatInterval" => apply_button_repeat_interval(item, value as u32),
+		"repeatInterval" => item.repeat_interval(value as u32),
 		_ => item,
 	}
 }
 
-fn apply_button_repeat_interval(mut item: Item, interval: u32) -> Item {
-	let gui::ItemPayload::Button { events, .. } = &mut item.payload else {
-		return item;
-	};
-	events
-		.get_or_insert_with(gui::ButtonEvents::default)
-		.repeat_interval = Some(interval);
-	item
-}
-
 fn apply_bool_prop(item: Item, name: &str, value: bool) -> Item {
 	match name {
 		"checked" | "bind:checked" => item.checked(value),
@@ -1350,6 +2072,7 @@ fn apply_bool_prop(item: Item, name: &str, value: bool) -> Item {
 		"autoplay" => item.autoplay(value),
 		"muted" => item.muted(value),
 		"controls" => item.controls(value),
+		"preventDefault" => item.prevent_default(value),
 		_ => item,
 	}
 }
@@ -1368,6 +2091,7 @@ fn is_string_prop(name: &str) -> bool {
 			| "color" | "border"
 			| "objectFit"
 			| "href" | "room"
+			| "keyCombo"
 	)
 }
 
@@ -1380,18 +2104,45 @@ fn eval_expr(expr: &Expr, ctx: &EvalContext) -> WuiValue {
 			Literal::Null => WuiValue::Null,
 		},
 		Expr::Path(parts, _) => resolve_path(parts, ctx),
-		Expr::Call { name, args, .. } => eval_call(name, args, ctx),
-		Expr::Unary { op, expr, .. } => match op {
+		Expr::Call { name, args, span } => eval_call(name, args, *span, ctx),
+		Expr::Unary { op, expr, span } => match op {
 			UnaryOp::Not => WuiValue::Bool(!value_as_bool(&eval_expr(expr, ctx))),
-			UnaryOp::Neg => WuiValue::Number(-value_as_number(&eval_expr(expr, ctx))),
+			UnaryOp::Neg => {
+				let value = eval_expr(expr, ctx);
+				check_numeric("-", &value, *span, ctx);
+				WuiValue::Number(-value_as_number(&value))
+			}
 		},
 		Expr::Binary {
-			left, op, right, ..
+			left,
+			op,
+			right,
+			span,
 		} => {
 			let left = eval_expr(left, ctx);
 			let right = eval_expr(right, ctx);
+			let symbol = match op {
+				BinaryOp::Sub => Some("-"),
+				BinaryOp::Mul => Some("*"),
+				BinaryOp::Div => Some("/"),
+				BinaryOp::Mod => Some("%"),
+				BinaryOp::Lt => Some("<"),
+				BinaryOp::Lte => Some("<="),
+				BinaryOp::Gt => Some(">"),
+				BinaryOp::Gte => Some(">="),
+				_ => None,
+			};
+			if let Some(symbol) = symbol {
+				check_numeric(symbol, &left, *span, ctx);
+				check_numeric(symbol, &right, *span, ctx);
+			}
 			match op {
-				BinaryOp::Add => WuiValue::Number(value_as_number(&left) + value_as_number(&right)),
+				BinaryOp::Add => match (&left, &right) {
+					(WuiValue::String(_), _) | (_, WuiValue::String(_)) => {
+						WuiValue::String(value_as_string(&left) + &value_as_string(&right))
+					}
+					_ => WuiValue::Number(value_as_number(&left) + value_as_number(&right)),
+				},
 				BinaryOp::Sub => WuiValue::Number(value_as_number(&left) - value_as_number(&right)),
 				BinaryOp::Mul => WuiValue::Number(value_as_number(&left) * value_as_number(&right)),
 				BinaryOp::Div => WuiValue::Number(value_as_number(&left) / value_as_number(&right)),
@@ -1429,10 +2180,123 @@ fn eval_expr(expr: &Expr, ctx: &EvalContext) -> WuiValue {
 	}
 }
 
-fn eval_call(name: &str, args: &[Expr], ctx: &EvalContext) -> WuiValue {
+/// Warns when `value`, an operand of `op`, is something other than a
+/// number; `Null` stands for a missing optional value and is left alone.
+fn check_numeric(op: &str, value: &WuiValue, span: Span, ctx: &EvalContext) {
+	if !matches!(value, WuiValue::Number(_) | WuiValue::Null) {
+		let kind = value_kind(value);
+		ctx.warn(span, format!("`{op}` on {kind}, not a number"));
+	}
+}
+
+fn value_kind(value: &WuiValue) -> &'static str {
+	match value {
+		WuiValue::String(_) => "a string",
+		WuiValue::Number(_) => "a number",
+		WuiValue::Bool(_) => "a bool",
+		WuiValue::Null => "null",
+		WuiValue::List(_) => "a list",
+		WuiValue::Object(_) => "an object",
+	}
+}
+
+fn eval_call(name: &str, args: &[Expr], span: Span, ctx: &EvalContext) -> WuiValue {
+	if name == "path_matches" {
+		return eval_path_matches(args, ctx);
+	}
+	let values = args
+		.iter()
+		.map(|arg| eval_expr(arg, ctx))
+		.collect::<Vec<_>>();
+	let arg = |index: usize| values.get(index).unwrap_or(&WuiValue::Null);
+	let number_arg = |index: usize| values.get(index).map(value_as_number);
 	match name {
-		"path_matches" => eval_path_matches(args, ctx),
-		_ => WuiValue::Null,
+		"len" => WuiValue::Number(match arg(0) {
+			WuiValue::String(text) => text.wui_len(),
+			WuiValue::List(items) => items.len(),
+			WuiValue::Object(map) => map.len(),
+			_ => 0,
+		} as f64),
+		"upper" => WuiValue::String(value_as_string(arg(0)).to_uppercase()),
+		"lower" => WuiValue::String(value_as_string(arg(0)).to_lowercase()),
+		"trim" => WuiValue::String(value_as_string(arg(0)).trim().to_string()),
+		"contains" => WuiValue::Bool(match arg(0) {
+			WuiValue::List(items) => items.iter().any(|item| values_equal(item, arg(1))),
+			haystack => value_as_string(haystack).wui_contains(&value_as_string(arg(1))),
+		}),
+		"join" => {
+			let items = match arg(0) {
+				WuiValue::List(items) => items.iter().map(value_as_string).collect(),
+				_ => Vec::new(),
+			};
+			WuiValue::String(builtins::join(&items, &value_as_string(arg(1))))
+		}
+		"slice" => {
+			let start = value_as_number(arg(1));
+			let end = number_arg(2);
+			match arg(0) {
+				WuiValue::List(items) => WuiValue::List(items.wui_slice(start, end)),
+				value => WuiValue::String(value_as_string(value).wui_slice(start, end)),
+			}
+		}
+		"format_number" => WuiValue::String(crate::i18n::format_number(
+			value_as_number(arg(0)),
+			number_arg(1),
+		)),
+		"format_date" => {
+			let pattern = value_as_string(arg(1));
+			WuiValue::String(match arg(0) {
+				WuiValue::Number(seconds) => crate::i18n::format_date(seconds, &pattern),
+				WuiValue::String(date) => crate::i18n::format_date(date, &pattern),
+				_ => String::new(),
+			})
+		}
+		"pluralize" => {
+			let plural = values.get(2).map(value_as_string);
+			WuiValue::String(builtins::pluralize(
+				value_as_number(arg(0)),
+				&value_as_string(arg(1)),
+				plural.as_deref(),
+			))
+		}
+		"min" | "max" => {
+			let numbers = values.iter().map(value_as_number).collect::<Vec<_>>();
+			WuiValue::Number(if name == "min" {
+				builtins::min(&numbers)
+			} else {
+				builtins::max(&numbers)
+			})
+		}
+		"round" => WuiValue::Number(builtins::round(value_as_number(arg(0)), number_arg(1))),
+		"t" => {
+			let names = values
+				.iter()
+				.skip(1)
+				.step_by(2)
+				.map(value_as_string)
+				.collect::<Vec<_>>();
+			let args = names
+				.iter()
+				.map(String::as_str)
+				.zip(values.iter().skip(2).step_by(2).cloned())
+				.collect::<Vec<_>>();
+			WuiValue::String(crate::i18n::t(&value_as_string(arg(0)), &args))
+		}
+		"url" => WuiValue::String(
+			crate::wui::route_handler::try_route_url(
+				&value_as_string(arg(0)),
+				values.get(1..).unwrap_or_default(),
+			)
+			.unwrap_or_else(|message| {
+				ctx.warn(span, message);
+				String::new()
+			}),
+		),
+		builtins::CONCAT => WuiValue::String(values.iter().map(value_as_string).collect()),
+		_ => {
+			ctx.warn(span, format!("unknown function `{name}`"));
+			WuiValue::Null
+		}
 	}
 }
 
@@ -1443,28 +2307,36 @@ fn eval_path_matches(args: &[Expr], ctx: &EvalContext) -> WuiValue {
 	else {
 		return WuiValue::Bool(false);
 	};
-	let path = ctx
-		.vars
-		.get("path")
-		.map(value_as_string)
-		.unwrap_or_default();
-	WuiValue::Bool(route_params(&pattern, &path).is_some())
+	WuiValue::Bool(route_params(&pattern, &ctx.path()).is_some())
 }
 
 fn resolve_path(parts: &[String], ctx: &EvalContext) -> WuiValue {
+	let mut value = WuiValue::Null;
+	visit_path(parts, ctx, &mut |found| value = found.wui_value());
+	value
+}
+
+/// Calls `visit` with the value at `parts`, borrowed from where it lives.
+fn visit_path(parts: &[String], ctx: &EvalContext, visit: &mut dyn FnMut(&dyn WuiValueProvider)) {
 	let Some((first, rest)) = parts.split_first() else {
-		return WuiValue::Null;
-	};
-	let Some(mut current) = ctx.vars.get(first).cloned() else {
-		return WuiValue::Null;
+		return;
 	};
-	for part in rest {
-		current = match current {
-			WuiValue::Object(mut map) => map.remove(part).unwrap_or(WuiValue::Null),
-			_ => WuiValue::Null,
-		};
+	if let Some(root) = ctx.var(first) {
+		visit_fields(root, rest, visit);
+	}
+}
+
+fn visit_fields(
+	value: &dyn WuiValueProvider,
+	path: &[String],
+	visit: &mut dyn FnMut(&dyn WuiValueProvider),
+) {
+	match path.split_first() {
+		Some((first, rest)) => {
+			value.visit_field(first, &mut |field| visit_fields(field, rest, visit));
+		}
+		None => visit(value),
 	}
-	current
 }
 
 fn value_as_bool(value: &WuiValue) -> bool {
@@ -1488,7 +2360,7 @@ fn value_as_u32(value: &WuiValue) -> Option<u32> {
 	}
 }
 
-fn value_as_string(value: &WuiValue) -> String {
+pub(crate) fn value_as_string(value: &WuiValue) -> String {
 	match value {
 		WuiValue::String(s) => s.clone(),
 		WuiValue::Number(n) => n.to_string(),
@@ -1524,6 +2396,7 @@ fn action_id(name: &str) -> u32 {
 mod tests {
 	use super::*;
 	use crate::gui::{Item, ItemPayload};
+	use crate::wui::schema::ModelSchema;
 
 	fn text_values(item: &Item, out: &mut Vec<String>) {
 		match &item.payload {
@@ -1549,6 +2422,7 @@ mod tests {
 			path: "/posts/123".to_string(),
 			params: HashMap::from([("post_id".to_string(), "123".to_string())]),
 			query: HashMap::from([("tab".to_string(), "comments".to_string())]),
+			query_string: "tab=comments".to_string(),
 		}));
 
 		assert_eq!(ctx.param("post_id"), Some("123".to_string()));
@@ -1579,6 +2453,7 @@ mod tests {
 			path: "/posts/123".to_string(),
 			params: HashMap::from([("post_id".to_string(), "123".to_string())]),
 			query: HashMap::from([("tab".to_string(), "comments".to_string())]),
+			query_string: "tab=comments".to_string(),
 		};
 		let rendered = template.render_with_route(&state, &route);
 		let mut values = Vec::new();
@@ -1595,6 +2470,294 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn template_expressions_call_builtins() {
+		let template = Template::parse(
+			r#"
+			<Text value={upper(trim(state.name))} />
+			<Text value={`${len(state.items)} ${pluralize(len(state.items), "item")}`} />
+			<Text value={join(slice(state.items, -2), ", ")} />
+			<Text value={format_number(state.total, 2)} />
+			<Text value={format_date(state.created, "D MMM YYYY")} />
+			<Text value={"Total: " + round(max(state.total, 10), 0)} />
+			<Text value={contains(state.items, "b") ? "has b" : "no b"} />
+			"#,
+			"test",
+		)
+		.expect("parse template");
+		let state = WuiValue::object(vec![
+			("name".to_string(), WuiValue::String("  ada ".to_string())),
+			(
+				"items".to_string(),
+				WuiValue::List(
+					["a", "b", "c"]
+						.into_iter()
+						.map(|item| WuiValue::String(item.to_string()))
+						.collect(),
+				),
+			),
+			("total".to_string(), WuiValue::Number(1234.5)),
+			(
+				"created".to_string(),
+				WuiValue::String("2024-03-05".to_string()),
+			),
+		]);
+		let mut values = Vec::new();
+		text_values(&template.render(&state), &mut values);
+
+		assert_eq!(
+			values,
+			vec![
+				"ADA",
+				"3 items",
+				"b, c",
+				"1,234.50",
+				"5 Mar 2024",
+				"Total: 1235",
+				"has b",
+			]
+		);
+	}
+
+	#[test]
+	fn template_renders_let_else_if_and_match() {
+		let template = Template::parse(
+			r#"
+			<Let name="total" value={state.price * state.qty}>
+				<Text value={"Total: " + total} />
+			</Let>
+			<For each={state.counts} itemAs="count">
+				<If test={count == 0}><Text value="none" /></If>
+				<ElseIf test={count == 1}><Text value="one" /></ElseIf>
+				<Else><Text value="many" /></Else>
+			</For>
+			<For each={state.statuses} itemAs="status">
+				<Match value={status}>
+					<Case is="open"><Text value="Open" /></Case>
+					<Case is="done"><Text value="Done" /></Case>
+					<Else><Text value="Unknown" /></Else>
+				</Match>
+			</For>
+			"#,
+			"test",
+		)
+		.expect("parse template");
+		let state = WuiValue::object(vec![
+			("price".to_string(), WuiValue::Number(3.0)),
+			("qty".to_string(), WuiValue::Number(4.0)),
+			(
+				"counts".to_string(),
+				WuiValue::List(vec![
+					WuiValue::Number(0.0),
+					WuiValue::Number(1.0),
+					WuiValue::Number(5.0),
+				]),
+			),
+			(
+				"statuses".to_string(),
+				WuiValue::List(
+					["done", "open", "lost"]
+						.into_iter()
+						.map(|status| WuiValue::String(status.to_string()))
+						.collect(),
+				),
+			),
+		]);
+		let mut values = Vec::new();
+		text_values(&template.render(&state), &mut values);
+
+		assert_eq!(
+			values,
+			vec![
+				"Total: 12",
+				"none",
+				"one",
+				"many",
+				"Done",
+				"Open",
+				"Unknown"
+			]
+		);
+	}
+
+	#[test]
+	fn template_scopes_and_registers_styles() {
+		let template = Template::parse(
+			r#"
+			<Style>.runtime-styled { color: teal; }</Style>
+			<VStack class={state.tone}>
+				<Text value="a" />
+			</VStack>
+			"#,
+			"runtime_styles",
+		)
+		.expect("parse template");
+		let scope = &template.doc.styles[0].scope;
+		let state = WuiValue::object(vec![(
+			"tone".to_string(),
+			WuiValue::String("dark".to_string()),
+		)]);
+		let item = template.render(&state);
+		assert_eq!(item.class_name, format!("dark {scope}"));
+		let ItemPayload::Layout(layout) = &item.payload else {
+			panic!("expected layout");
+		};
+		assert_eq!(layout.body[0].class_name, *scope);
+
+		let stylesheet = crate::wui::styles::stylesheet().expect("registered styles");
+		assert!(stylesheet
+			.css
+			.contains(&format!(".runtime-styled.{scope} {{ color: teal; }}")));
+		assert!(crate::ssr::render_document(&item).contains(&stylesheet.url()));
+	}
+
+	#[test]
+	fn template_builds_urls_of_registered_routes() {
+		crate::wui::route_handler::register_route_url("runtime_todo", "/runtime/todos/:id");
+		let template = Template::parse(
+			"<VStack>\n\t<Text value={url(\"runtime_todo\", state.id)} />\n\t<Text value={url(\"runtime_missing\")} />\n</VStack>",
+			"pages/links",
+		)
+		.expect("parse template");
+		let state = WuiValue::object(vec![(
+			"id".to_string(),
+			WuiValue::String("a b".to_string()),
+		)]);
+
+		let (item, warnings) = template.render_with_warnings(&state, &RouteContext::default());
+
+		let mut texts = Vec::new();
+		text_values(&item, &mut texts);
+		assert_eq!(texts, ["/runtime/todos/a%20b", ""]);
+		let reported = warnings.iter().map(ToString::to_string).collect::<Vec<_>>();
+		assert_eq!(
+			reported,
+			["pages/links.wui:3:15: unknown route \"runtime_missing\""]
+		);
+	}
+
+	#[test]
+	fn template_translates_in_the_current_locale() {
+		crate::i18n::add_catalog(
+			crate::i18n::Catalog::parse(
+				"fi",
+				"runtime-items = { $count ->\n    [one] Yksi tilaus\n   *[other] { $count } tilausta\n}",
+			)
+			.unwrap(),
+		);
+		let template = Template::parse(
+			r#"
+			<VStack>
+				<Text value={t("runtime-items", "count", state.count)} />
+				<Text value={format_number(state.total, 2)} />
+				<Text value={t("runtime-untranslated")} />
+			</VStack>
+			"#,
+			"runtime_i18n",
+		)
+		.expect("parse template");
+		let state = WuiValue::object(vec![
+			("count".to_string(), WuiValue::Number(1200.0)),
+			("total".to_string(), WuiValue::Number(1234.5)),
+		]);
+
+		let mut texts = Vec::new();
+		text_values(
+			&crate::i18n::with_locale("fi", || template.render(&state)),
+			&mut texts,
+		);
+		assert_eq!(
+			texts,
+			[
+				"1\u{a0}200 tilausta",
+				"1\u{a0}234,50",
+				"runtime-untranslated"
+			]
+		);
+
+		let mut texts = Vec::new();
+		text_values(&template.render(&state), &mut texts);
+		assert_eq!(texts[1], "1,234.50");
+	}
+
+	/// State that refuses to be built as a whole, so rendering it proves
+	/// paths are walked through the visitor.
+	struct LazyRows(Vec<LazyRow>);
+
+	struct LazyRow(String);
+
+	impl WuiValueConvert for LazyRows {
+		fn to_wui_value(&self) -> WuiValue {
+			panic!("rows were materialized");
+		}
+
+		fn visit_wui_field(&self, name: &str, visit: &mut dyn FnMut(&dyn WuiValueProvider)) {
+			if name == "rows" {
+				visit(&self.0);
+			}
+		}
+	}
+
+	impl WuiValueConvert for LazyRow {
+		fn to_wui_value(&self) -> WuiValue {
+			panic!("a row was materialized");
+		}
+
+		fn visit_wui_field(&self, name: &str, visit: &mut dyn FnMut(&dyn WuiValueProvider)) {
+			if name == "label" {
+				visit(&self.0);
+			}
+		}
+	}
+
+	#[test]
+	fn template_walks_state_without_materializing_it() {
+		let template = Template::parse(
+			r#"
+			<For each={state.rows} itemAs="row" indexAs="i">
+				<Text value={i + ": " + row.label} />
+			</For>
+			"#,
+			"test",
+		)
+		.expect("parse template");
+		let state = LazyRows(vec![LazyRow("a".to_string()), LazyRow("b".to_string())]);
+		let mut values = Vec::new();
+		text_values(&template.render(&state), &mut values);
+		assert_eq!(values, vec!["0: a", "1: b"]);
+	}
+
+	#[test]
+	fn template_checks_paths_against_state_schema() {
+		let template = Template::parse(
+			r#"
+			<For each={state.rows} itemAs="row">
+				<Text value={row.label} />
+				<Text value={row.lable} />
+			</For>
+			"#,
+			"test",
+		)
+		.expect("parse template");
+		let mut schema = StateSchema::new();
+		schema.insert(ModelSchema::new(
+			"Table",
+			[(
+				"rows",
+				FieldType::List(Box::new(FieldType::Model("Row".to_string()))),
+			)],
+		));
+		schema.insert(ModelSchema::new("Row", [("label", FieldType::String)]));
+
+		let diags = template.check_state(&schema, &FieldType::Model("Table".to_string()));
+
+		assert_eq!(diags.len(), 1);
+		assert_eq!(
+			diags[0].message,
+			"unknown field `lable` on `row`; known fields: label"
+		);
+	}
+
 	#[test]
 	fn single_root_template_renders_directly() {
 		let template =
@@ -1606,6 +2769,7 @@ mod tests {
 				path: "/".to_string(),
 				params: HashMap::new(),
 				query: HashMap::new(),
+				query_string: String::new(),
 			},
 		);
 
@@ -1804,6 +2968,135 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn elements_carry_one_handler_per_event() {
+		let template = Template::parse(
+			r#"<VStack onClick="Open" onPress="Grab" onKeyDown="Navigate" arg=7 arg:onKeyDown=2>
+				<Text value="row" />
+			</VStack>"#,
+			"test",
+		)
+		.expect("parse template");
+		let rendered = template.render(&WuiValue::Null);
+
+		let handler = |event: &str| rendered.handlers.get(event).cloned();
+		assert_eq!(
+			handler("click"),
+			Some(gui::EventHandler {
+				id: action_id("Open"),
+				inx: Some(7),
+			})
+		);
+		assert_eq!(
+			handler("press"),
+			Some(gui::EventHandler {
+				id: action_id("Grab"),
+				inx: Some(7),
+			})
+		);
+		assert_eq!(
+			handler("keyDown"),
+			Some(gui::EventHandler {
+				id: action_id("Navigate"),
+				inx: Some(2),
+			})
+		);
+		assert_eq!(rendered.id, action_id("Open"));
+
+		let press = crate::types::ClientEvent::OnPress(crate::types::OnPress {
+			id: action_id("Grab"),
+			inx: Some(7),
+		});
+		assert!(matches!(
+			template.decode(&press),
+			Some(RuntimeAction::Click { name, arg: Some(7) }) if name == "Grab"
+		));
+		let key = crate::types::ClientEvent::OnKeyDown(crate::types::OnKeyDown {
+			id: Some(action_id("Navigate").to_string()),
+			keycode: "ArrowDown".to_string(),
+			inx: Some(2),
+			ctrl: false,
+			alt: false,
+			shift: false,
+			meta: false,
+		});
+		assert!(matches!(
+			template.decode(&key),
+			Some(RuntimeAction::KeyDown { name, arg: Some(2), key })
+				if name == "Navigate" && key == "ArrowDown"
+		));
+	}
+
+	#[test]
+	fn key_handlers_and_shortcuts_decode_to_actions() {
+		let template = Template::parse(
+			r#"<VStack>
+				<Shortcut key="ctrl+s" onKeyDown="Save" preventDefault=true />
+				<TextInput onKeyUp="Submit" key="Enter" />
+			</VStack>"#,
+			"test",
+		)
+		.expect("parse template");
+		let rendered = template.render(&WuiValue::Null);
+		let gui::ItemPayload::Layout(layout) = &rendered.payload else {
+			panic!("expected layout");
+		};
+		let shortcut = &layout.body[0];
+		assert_eq!(shortcut.payload, gui::ItemPayload::Shortcut);
+		assert_eq!(shortcut.key_combo, "ctrl+s");
+		assert!(shortcut.prevent_default);
+		assert_eq!(shortcut.handlers["keyDown"].id, action_id("Save"));
+		let input = &layout.body[1];
+		assert_eq!(input.key_combo, "Enter");
+		assert_eq!(input.handlers["keyUp"].id, action_id("Submit"));
+
+		let save = crate::types::OnKeyDown {
+			id: Some(action_id("Save").to_string()),
+			keycode: "KeyS".to_string(),
+			inx: None,
+			ctrl: true,
+			alt: false,
+			shift: false,
+			meta: false,
+		};
+		assert!(save.matches("Ctrl+S"));
+		assert!(!save.matches("Ctrl+Shift+S"));
+		let plain_s = crate::types::OnKeyDown {
+			ctrl: false,
+			..save.clone()
+		};
+		assert!(template
+			.decode(&crate::types::ClientEvent::OnKeyDown(plain_s))
+			.is_none());
+		assert!(matches!(
+			template.decode(&crate::types::ClientEvent::OnKeyDown(save)),
+			Some(RuntimeAction::KeyDown { name, arg: None, key }) if name == "Save" && key == "KeyS"
+		));
+		let submit = crate::types::ClientEvent::OnKeyUp(crate::types::OnKeyUp {
+			id: Some(action_id("Submit").to_string()),
+			keycode: "Enter".to_string(),
+			inx: None,
+			ctrl: false,
+			alt: false,
+			shift: false,
+			meta: false,
+		});
+		assert!(matches!(
+			template.decode(&submit),
+			Some(RuntimeAction::KeyUp { name, arg: None, .. }) if name == "Submit"
+		));
+		let escape = crate::types::ClientEvent::OnKeyUp(crate::types::OnKeyUp {
+			id: Some(action_id("Submit").to_string()),
+			keycode: "Escape".to_string(),
+			inx: None,
+			ctrl: false,
+			alt: false,
+			shift: false,
+			meta: false,
+		});
+		assert!(template.decode(&escape).is_none());
+	}
+
 	#[test]
 	fn imported_component_forwards_custom_event_bindings_to_custom_root() {
 		let template = Template::parse_with_sources(
@@ -1879,6 +3172,42 @@ mod tests {
 		assert_eq!(values, vec!["Peers".to_string(), "Body".to_string()]);
 	}
 
+	#[test]
+	fn imported_component_fills_named_slots_and_prop_defaults() {
+		let template = Template::parse_with_sources(
+			r#"
+			<Import name="Card" from="card" />
+			<Card title="Inbox">
+				<Slot name="footer"><Text value="3 unread" /></Slot>
+				<Text value="Body" />
+			</Card>
+			"#,
+			"test",
+			Some(Path::new("/embedded/pages")),
+			&[(
+				"/embedded/pages/card.wui",
+				r#"
+				<Props>
+					<Prop name="title" type="string" required />
+					<Prop name="subtitle" type="string" default="none" />
+				</Props>
+				<VStack>
+					<Slot name="header"><Text value={title} /></Slot>
+					<Text value={subtitle} />
+					<Children />
+					<Slot name="footer" />
+				</VStack>
+				"#,
+			)],
+		)
+		.expect("parse template");
+		let rendered = template.render(&WuiValue::Null);
+		let mut values = Vec::new();
+		text_values(&rendered, &mut values);
+
+		assert_eq!(values, vec!["Inbox", "none", "Body", "3 unread"]);
+	}
+
 	#[test]
 	fn embedded_sources_resolve_imports_without_filesystem_templates() {
 		let root = "/embedded/pages/home.wui";
@@ -1968,4 +3297,126 @@ mod tests {
 			}
 		);
 	}
+
+	#[test]
+	fn render_reports_mistakes_with_their_lines() {
+		let template = Template::parse(
+			"<VStack>\n\t<For each={state.title} itemAs=\"row\"><Text value={row} /></For>\n\t<Text value={\"n = \" + (state.title * 2)} />\n\t<For each={state.missing} itemAs=\"row\"><Text value={row} /></For>\n</VStack>",
+			"pages/home",
+		)
+		.expect("parse template");
+		let state = WuiValue::object(vec![(
+			"title".to_string(),
+			WuiValue::String("Inbox".to_string()),
+		)]);
+
+		let (_, warnings) = template.render_with_warnings(&state, &RouteContext::default());
+
+		let reported = warnings.iter().map(ToString::to_string).collect::<Vec<_>>();
+		assert_eq!(
+			reported,
+			vec![
+				"pages/home.wui:2:13: `For each` over a string, not a list",
+				"pages/home.wui:3:25: `*` on a string, not a number",
+			]
+		);
+		assert_eq!(warnings[0].module, "pages/home");
+		assert_eq!(warnings[0].component, None);
+	}
+
+	#[test]
+	fn component_warnings_point_into_the_component_file() {
+		let template = Template::parse_with_sources(
+			r#"<Import name="Card" from="card" />
+<Card title="Inbox">
+	<Text value={"" + -state.name} />
+</Card>"#,
+			"test",
+			Some(Path::new("/embedded/pages")),
+			&[(
+				"/embedded/pages/card.wui",
+				"<VStack>\n\t<Text value={\"\" + (title - 1)} />\n\t<Children />\n</VStack>",
+			)],
+		)
+		.expect("parse template");
+		let state = WuiValue::object(vec![(
+			"name".to_string(),
+			WuiValue::String("Ann".to_string()),
+		)]);
+
+		let (_, warnings) = template.render_with_warnings(&state, &RouteContext::default());
+
+		assert_eq!(warnings.len(), 2, "{warnings:?}");
+		assert_eq!(warnings[0].component.as_deref(), Some("Card"));
+		assert_eq!(
+			warnings[0].file.as_deref(),
+			Some(Path::new("/embedded/pages/card.wui"))
+		);
+		assert_eq!(
+			(warnings[0].line, warnings[0].message.as_str()),
+			(2, "`-` on a string, not a number")
+		);
+		assert_eq!(warnings[1].component, None);
+		assert_eq!(warnings[1].line, 3);
+	}
+
+	#[test]
+	fn error_boundary_renders_its_fallback_when_the_body_panics() {
+		struct Broken;
+
+		impl WuiValueProvider for Broken {
+			fn wui_value(&self) -> WuiValue {
+				panic!("feed unavailable");
+			}
+
+			fn visit_field(&self, _name: &str, _visit: &mut dyn FnMut(&dyn WuiValueProvider)) {}
+
+			fn visit_items(&self, _visit: &mut dyn FnMut(usize, &dyn WuiValueProvider)) {}
+		}
+
+		let template = Template::parse(
+			r#"<VStack>
+	<Text value="Inbox" />
+	<ErrorBoundary>
+		<Text value={state} />
+		<Fallback><Text value="Feed is down" /></Fallback>
+	</ErrorBoundary>
+</VStack>"#,
+			"home",
+		)
+		.expect("parse template");
+
+		let (item, warnings) = template.render_with_warnings(&Broken, &RouteContext::default());
+
+		let mut values = Vec::new();
+		text_values(&item, &mut values);
+		assert_eq!(values, vec!["Inbox", "Feed is down"]);
+		assert_eq!(warnings.len(), 1, "{warnings:?}");
+		assert_eq!(warnings[0].message, "panicked: feed unavailable");
+		assert_eq!(warnings[0].line, 3);
+		assert!(warnings[0].contained);
+	}
+
+	#[test]
+	fn strict_mode_view_lists_the_warnings() {
+		let warning = RenderWarning {
+			module: "home".to_string(),
+			component: None,
+			span: Span::new(0, 1),
+			file: None,
+			line: 4,
+			column: 2,
+			message: "unknown function `shout`".to_string(),
+			contained: false,
+		};
+
+		let view = warnings_view(&[warning]);
+
+		let mut values = Vec::new();
+		text_values(&view, &mut values);
+		assert_eq!(
+			values,
+			vec!["Template error", "home.wui:4:2: unknown function `shout`"]
+		);
+	}
 }
@@ -0,0 +1,104 @@
+//! The stylesheet built from the `<Style>` blocks of every template parsed
+//! or compiled so far, served at [`STYLESHEET_ROUTE`].
+
+use std::collections::BTreeMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::RwLock;
+
+pub const STYLESHEET_ROUTE: &str = "/wui.css";
+
+static STYLES: RwLock<Styles> = RwLock::new(Styles {
+	modules: BTreeMap::new(),
+	stylesheet: None,
+});
+
+struct Styles {
+	/// Scope and CSS of each template or component module.
+	modules: BTreeMap<String, (String, String)>,
+	/// The concatenation of `modules`, built on first request.
+	stylesheet: Option<Stylesheet>,
+}
+
+/// Sets the scoped CSS of a template or component module, replacing what it
+/// registered before, e.g. ahead of a hot reload.
+pub fn register(module: &str, scope: &str, css: &str) {
+	let unchanged = |styles: &Styles| {
+		styles
+			.modules
+			.get(module)
+			.is_some_and(|(current_scope, current_css)| {
+				current_scope == scope && current_css == css
+			})
+	};
+	if unchanged(&STYLES.read().unwrap()) {
+		return;
+	}
+	let mut styles = STYLES.write().unwrap();
+	if unchanged(&styles) {
+		return;
+	}
+	styles
+		.modules
+		.insert(module.to_string(), (scope.to_string(), css.to_string()));
+	styles.stylesheet = None;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stylesheet {
+	pub css: String,
+	/// Hash of `css`; it changes whenever a template's styles do.
+	pub version: String,
+}
+
+impl Stylesheet {
+	/// The fingerprinted URL documents link to.
+	pub fn url(&self) -> String {
+		format!(
+			"{STYLESHEET_ROUTE}?{}={}",
+			crate::STATIC_ASSET_VERSION_PARAM,
+			self.version
+		)
+	}
+}
+
+/// All registered styles, or `None` while no template has any.
+pub fn stylesheet() -> Option<Stylesheet> {
+	if let Some(stylesheet) = &STYLES.read().unwrap().stylesheet {
+		return Some(stylesheet.clone());
+	}
+	let mut styles = STYLES.write().unwrap();
+	if styles.modules.is_empty() {
+		return None;
+	}
+	let css = styles
+		.modules
+		.values()
+		.map(|(_, css)| css.as_str())
+		.collect::<String>();
+	let mut hasher = DefaultHasher::new();
+	css.hash(&mut hasher);
+	let stylesheet = Stylesheet {
+		css,
+		version: format!("{:016x}", hasher.finish()),
+	};
+	styles.stylesheet = Some(stylesheet.clone());
+	Some(stylesheet)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn registering_a_module_again_replaces_its_styles() {
+		register("styles_test", "wui-old", ".a.wui-old { color: red; }\n");
+		let first = stylesheet().unwrap();
+		assert_eq!(stylesheet().unwrap(), first);
+
+		register("styles_test", "wui-new", ".a.wui-new { color: blue; }\n");
+		let second = stylesheet().unwrap();
+		assert!(!second.css.contains("wui-old"), "{}", second.css);
+		assert!(second.css.contains(".a.wui-new { color: blue; }"));
+		assert_ne!(first.version, second.version);
+	}
+}
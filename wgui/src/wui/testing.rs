@@ -0,0 +1,401 @@
+//! Unit tests for templates. A template `home.wui` is tested with the
+//! fixtures in `home.fixtures.json`: each fixture renders the template with
+//! its state and route, and replays events through [`Template::decode`]. The
+//! results are compared against `home.snap`. See "Testing templates" in
+//! `docs/wui.md` for the fixture format.
+
+use crate::gui::Item;
+use crate::wui::compiler::ir::{ActionDef, EventKind};
+use crate::wui::runtime::{
+	load_template, RenderWarning, RouteContext, RuntimeAction, Template, TemplateLoadError,
+	WuiValue,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Fixtures {
+	pub snapshot: SnapshotFormat,
+	pub fixtures: Vec<Fixture>,
+}
+
+/// How rendered trees are written to snapshots.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotFormat {
+	/// One line per item, children indented below it.
+	#[default]
+	Tree,
+	/// The server-rendered HTML.
+	Html,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Fixture {
+	pub name: String,
+	/// Available as `state` in the template.
+	pub state: serde_json::Value,
+	pub path: String,
+	pub params: HashMap<String, String>,
+	pub query: HashMap<String, String>,
+	pub events: Vec<SimulatedEvent>,
+}
+
+/// A client event on the element that sends `action`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SimulatedEvent {
+	pub action: String,
+	pub arg: Option<u32>,
+	/// Text for inputs, selects and keys, a number for sliders, and the
+	/// payload of custom events.
+	pub value: serde_json::Value,
+}
+
+/// The result of [`test_template`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+	Matched,
+	/// The snapshot was written because updating was requested.
+	Written,
+	/// There is no snapshot yet and updating was not requested.
+	Missing {
+		actual: String,
+	},
+	Changed {
+		expected: String,
+		actual: String,
+	},
+}
+
+/// Whether `WGUI_UPDATE_SNAPSHOTS=1` asks for snapshots to be written.
+pub fn update_requested() -> bool {
+	std::env::var("WGUI_UPDATE_SNAPSHOTS").is_ok_and(|value| value == "1")
+}
+
+/// `home.fixtures.json` for `home.wui`.
+pub fn fixtures_path(template: &Path) -> PathBuf {
+	template.with_extension("fixtures.json")
+}
+
+/// `home.snap` for `home.wui`.
+pub fn snapshot_path(template: &Path) -> PathBuf {
+	template.with_extension("snap")
+}
+
+pub fn read_fixtures(path: &Path) -> Result<Fixtures, String> {
+	let source = std::fs::read_to_string(path)
+		.map_err(|e| format!("failed reading {}: {e}", path.display()))?;
+	serde_json::from_str(&source).map_err(|e| format!("invalid fixtures {}: {e}", path.display()))
+}
+
+/// Renders `fixture`, with the mistakes the template made on the way.
+pub fn render_fixture(template: &Template, fixture: &Fixture) -> (Item, Vec<RenderWarning>) {
+	let route = RouteContext {
+		path: fixture.path.clone(),
+		params: fixture.params.clone(),
+		query: fixture.query.clone(),
+		query_string: String::new(),
+	};
+	template.render_with_warnings(&WuiValue::from_json(&fixture.state), &route)
+}
+
+/// Decodes `event` the way the controller would receive it, or fails when
+/// the template has no action by that name.
+pub fn simulate(
+	template: &Template,
+	event: &SimulatedEvent,
+) -> Result<Option<RuntimeAction>, String> {
+	let action = template
+		.actions()
+		.iter()
+		.find(|action| action.name == event.action)
+		.ok_or_else(|| format!("no action `{}` in {}", event.action, template.module()))?;
+	let event = serde_json::from_value(client_event(action, event))
+		.map_err(|e| format!("cannot send {}: {e}", action.name))?;
+	Ok(template.decode(&event))
+}
+
+fn client_event(action: &ActionDef, event: &SimulatedEvent) -> serde_json::Value {
+	let text = || match &event.value {
+		serde_json::Value::String(value) => serde_json::Value::String(value.clone()),
+		serde_json::Value::Null => serde_json::Value::String(String::new()),
+		value => serde_json::Value::String(value.to_string()),
+	};
+	let (kind, mut json) = match &action.kind {
+		EventKind::Click => ("onClick", serde_json::json!({})),
+		EventKind::Press => ("onPress", serde_json::json!({})),
+		EventKind::Release => ("onRelease", serde_json::json!({})),
+		EventKind::Repeat => ("onRepeat", serde_json::json!({})),
+		EventKind::ScrollNearBottom => ("onScrollNearBottom", serde_json::json!({})),
+		EventKind::TextChanged => ("onTextChanged", serde_json::json!({ "value": text() })),
+		EventKind::Select => ("onSelect", serde_json::json!({ "value": text() })),
+		EventKind::SliderChange => (
+			"onSliderChange",
+			serde_json::json!({ "value": event.value.as_i64().unwrap_or(0) }),
+		),
+		EventKind::KeyDown | EventKind::KeyUp => {
+			let kind = if action.kind == EventKind::KeyDown {
+				"onKeyDown"
+			} else {
+				"onKeyUp"
+			};
+			let json = serde_json::json!({ "id": action.id.to_string(), "keycode": text() });
+			(kind, json)
+		}
+		EventKind::Custom(name) => (
+			"onCustom",
+			serde_json::json!({ "name": name, "payload": event.value }),
+		),
+	};
+	json["type"] = serde_json::Value::from(kind);
+	if json.get("id").is_none() {
+		json["id"] = serde_json::Value::from(action.id);
+	}
+	json["inx"] = serde_json::to_value(event.arg).unwrap_or_default();
+	json
+}
+
+/// The snapshot `fixtures` produce: each rendered tree and its render
+/// warnings, followed by the actions its events decode to.
+pub fn snapshot(template: &Template, fixtures: &Fixtures) -> Result<String, String> {
+	let mut out = String::new();
+	for fixture in &fixtures.fixtures {
+		out.push_str(&format!("== {} {:?}\n", fixture.name, fixture.path));
+		let (item, warnings) = render_fixture(template, fixture);
+		match fixtures.snapshot {
+			SnapshotFormat::Tree => out.push_str(&tree(&item)),
+			SnapshotFormat::Html => {
+				out.push_str(&crate::ssr::render_item(&item));
+				out.push('\n');
+			}
+		}
+		// Files are left out so snapshots do not depend on the checkout.
+		for warning in warnings {
+			out.push_str("!! ");
+			if let Some(component) = &warning.component {
+				out.push_str(&format!("{component} "));
+			}
+			out.push_str(&format!(
+				"{}:{}: {}\n",
+				warning.line, warning.column, warning.message
+			));
+		}
+		for event in &fixture.events {
+			out.push_str(&format!("-- {}", event.action));
+			if let Some(arg) = event.arg {
+				out.push_str(&format!(" arg={arg}"));
+			}
+			if !event.value.is_null() {
+				out.push_str(&format!(" value={}", event.value));
+			}
+			match simulate(template, event)? {
+				Some(action) => out.push_str(&format!("\n{action:?}\n")),
+				None => out.push_str("\nno action\n"),
+			}
+		}
+		out.push('\n');
+	}
+	Ok(out)
+}
+
+/// `item` one line per item: its type and the props that differ from the
+/// defaults, with children indented below it.
+pub fn tree(item: &Item) -> String {
+	let mut out = String::new();
+	let json = serde_json::to_value(item).unwrap_or_default();
+	write_tree(&json, 0, &mut out);
+	out
+}
+
+fn write_tree(item: &serde_json::Value, depth: usize, out: &mut String) {
+	let indent = "  ".repeat(depth);
+	let payload = &item["payload"];
+	out.push_str(&indent);
+	out.push_str(payload["type"].as_str().unwrap_or("item"));
+	let mut children = Vec::new();
+	let props = item
+		.as_object()
+		.into_iter()
+		.chain(payload.as_object())
+		.flatten()
+		.filter(|(key, _)| *key != "payload" && *key != "type");
+	for (key, value) in props {
+		match value {
+			serde_json::Value::Array(items) if items.iter().all(is_item) && !items.is_empty() => {
+				children.push((key, items.iter().collect::<Vec<_>>()));
+			}
+			value if is_item(value) => children.push((key, vec![value])),
+			value => out.push_str(&format!(" {key}={value}")),
+		}
+	}
+	out.push('\n');
+	for (key, items) in children {
+		let mut depth = depth + 1;
+		if key != "body" {
+			out.push_str(&format!("{indent}  [{key}]\n"));
+			depth += 1;
+		}
+		for item in items {
+			write_tree(item, depth, out);
+		}
+	}
+}
+
+fn is_item(value: &serde_json::Value) -> bool {
+	value
+		.get("payload")
+		.is_some_and(|payload| payload.get("type").is_some())
+}
+
+/// Checks the template at `file` against its snapshot, writing it when
+/// `update` is set or [`update_requested`]; otherwise a missing snapshot is
+/// reported, so CI can't pass by creating one. `None` when it has no fixtures.
+pub fn test_template(file: &Path, module: &str, update: bool) -> Result<Option<Outcome>, String> {
+	let update = update || update_requested();
+	let fixtures_file = fixtures_path(file);
+	if !fixtures_file.is_file() {
+		return Ok(None);
+	}
+	let fixtures = read_fixtures(&fixtures_file)?;
+	let template = load_template(file, module).map_err(|err| match err {
+		TemplateLoadError::Io(err) => format!("failed reading {}: {err}", file.display()),
+		TemplateLoadError::Diagnostics(diags) => diags
+			.iter()
+			.map(|diag| {
+				format!(
+					"{}:{}-{}: {}",
+					file.display(),
+					diag.span.start,
+					diag.span.end,
+					diag.message
+				)
+			})
+			.collect::<Vec<_>>()
+			.join("\n"),
+	})?;
+	let actual = snapshot(&template, &fixtures)?;
+	let snapshot_file = snapshot_path(file);
+	let expected = std::fs::read_to_string(&snapshot_file).ok();
+	match expected {
+		Some(expected) if expected == actual => Ok(Some(Outcome::Matched)),
+		Some(expected) if !update => Ok(Some(Outcome::Changed { expected, actual })),
+		None if !update => Ok(Some(Outcome::Missing { actual })),
+		_ => {
+			std::fs::write(&snapshot_file, actual)
+				.map_err(|e| format!("failed writing {}: {e}", snapshot_file.display()))?;
+			Ok(Some(Outcome::Written))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TEMPLATE: &str = r#"
+<VStack>
+	<Text value={"Hello " + state.name} />
+	<For each={state.rows} itemAs="row">
+		<Button text={row.title} onClick="Remove" arg={row.id} />
+	</For>
+	<TextInput bind:value={state.name} />
+	<Text value={query.filter ?? "all"} />
+	<Text value={"" + (state.name * 2)} />
+</VStack>
+"#;
+
+	fn fixtures(json: &str) -> Fixtures {
+		serde_json::from_str(json).unwrap()
+	}
+
+	#[test]
+	fn snapshots_render_fixtures_and_decoded_events() {
+		let template = Template::parse(TEMPLATE, "home").unwrap();
+		let fixtures = fixtures(
+			r#"{ "fixtures": [{
+				"name": "rows",
+				"path": "/",
+				"query": { "filter": "open" },
+				"state": { "name": "Ann", "rows": [{ "id": 4, "title": "Milk" }] },
+				"events": [
+					{ "action": "Remove", "arg": 4 },
+					{ "action": "Remove" },
+					{ "action": "bind:state.name", "value": "Bo" }
+				]
+			}] }"#,
+		);
+
+		let snapshot = snapshot(&template, &fixtures).unwrap();
+
+		let lines = snapshot.lines().collect::<Vec<_>>();
+		assert_eq!(lines[0], "== rows \"/\"");
+		assert!(lines[1].starts_with("layout"), "{snapshot}");
+		assert_eq!(lines[2], "  text value=\"Hello Ann\"");
+		assert!(lines[3].starts_with("  button "), "{snapshot}");
+		assert!(lines[3].contains("title=\"Milk\""), "{snapshot}");
+		assert!(snapshot.contains("  text value=\"open\"\n"), "{snapshot}");
+		assert!(
+			snapshot.contains("\n!! 9:21: `*` on a string, not a number\n-- Remove"),
+			"{snapshot}"
+		);
+		assert!(snapshot.contains(
+			"-- Remove arg=4\nClick { name: \"Remove\", arg: Some(4) }\n-- Remove\nno action\n"
+		));
+		assert!(
+			snapshot.contains("Bind { path: [\"name\"], write: Set(String(\"Bo\")) }"),
+			"{snapshot}"
+		);
+	}
+
+	#[test]
+	fn simulate_rejects_unknown_actions() {
+		let template = Template::parse(TEMPLATE, "home").unwrap();
+		let event = SimulatedEvent {
+			action: "Missing".to_string(),
+			..SimulatedEvent::default()
+		};
+
+		let err = simulate(&template, &event).unwrap_err();
+
+		assert_eq!(err, "no action `Missing` in home");
+	}
+
+	#[test]
+	fn test_template_writes_only_on_update_then_compares_snapshots() {
+		let dir = std::env::temp_dir().join(format!("wgui-testing-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		let file = dir.join("home.wui");
+		std::fs::write(&file, r#"<Text value={state.title} />"#).unwrap();
+		std::fs::write(
+			fixtures_path(&file),
+			r#"{ "snapshot": "html", "fixtures": [{ "name": "a", "state": { "title": "One" } }] }"#,
+		)
+		.unwrap();
+
+		let missing = test_template(&file, "home", false).unwrap();
+		let written = snapshot_path(&file).exists();
+		let first = test_template(&file, "home", true).unwrap();
+		let second = test_template(&file, "home", false).unwrap();
+		std::fs::write(
+			fixtures_path(&file),
+			r#"{ "snapshot": "html", "fixtures": [{ "name": "a", "state": { "title": "Two" } }] }"#,
+		)
+		.unwrap();
+		let changed = test_template(&file, "home", false).unwrap();
+		let updated = test_template(&file, "home", true).unwrap();
+		let snapshot = std::fs::read_to_string(snapshot_path(&file)).unwrap();
+
+		let _ = std::fs::remove_dir_all(&dir);
+		assert!(matches!(missing, Some(Outcome::Missing { .. })));
+		assert!(!written);
+		assert_eq!(first, Some(Outcome::Written));
+		assert_eq!(second, Some(Outcome::Matched));
+		assert!(matches!(changed, Some(Outcome::Changed { .. })));
+		assert_eq!(updated, Some(Outcome::Written));
+		assert!(snapshot.contains("Two"), "{snapshot}");
+	}
+}
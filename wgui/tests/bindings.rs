@@ -0,0 +1,111 @@
+use wgui::wui::runtime::{apply_binding, BindingWrite, WuiController, WuiValue, WuiValueConvert};
+use wgui::{wgui_controller, ClientEvent, ItemPayload, OnClick, OnTextChanged, WguiModel};
+
+#[derive(Clone, Default, WguiModel)]
+struct Profile {
+	name: String,
+	age: u32,
+	nickname: Option<String>,
+}
+
+/// Only readable from templates: it has no `WuiValueSetter` impl.
+#[derive(Clone, Default)]
+struct Color(u8, u8, u8);
+
+impl WuiValueConvert for Color {
+	fn to_wui_value(&self) -> WuiValue {
+		WuiValue::String(format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2))
+	}
+}
+
+#[derive(Clone, Default, WguiModel)]
+struct FormState {
+	profile: Profile,
+	subscribed: bool,
+	accent: Color,
+}
+
+#[derive(Default)]
+struct FormController {
+	state: FormState,
+}
+
+#[wgui_controller(template = "bindings/form", mode = "runtime")]
+impl FormController {
+	fn state(&self) -> FormState {
+		self.state.clone()
+	}
+
+	fn state_mut(&mut self) -> &mut FormState {
+		&mut self.state
+	}
+}
+
+#[tokio::test]
+async fn bound_inputs_write_back_into_state() {
+	let mut controller = FormController::default();
+	let ItemPayload::Layout(layout) = controller.render().payload else {
+		panic!("expected a layout");
+	};
+	let ids = layout.body.iter().map(|item| item.id).collect::<Vec<_>>();
+	assert!(ids.iter().all(|id| *id != 0), "{ids:?}");
+
+	let events = [
+		ClientEvent::OnTextChanged(OnTextChanged {
+			id: ids[0],
+			inx: None,
+			value: "Ada".to_string(),
+		}),
+		ClientEvent::OnTextChanged(OnTextChanged {
+			id: ids[1],
+			inx: None,
+			value: "36".to_string(),
+		}),
+		ClientEvent::OnClick(OnClick {
+			id: ids[2],
+			inx: None,
+		}),
+	];
+	for event in &events {
+		assert!(controller.handle(event).await, "{event:?}");
+	}
+
+	assert_eq!(controller.state.profile.name, "Ada");
+	assert_eq!(controller.state.profile.age, 36);
+	assert!(controller.state.subscribed);
+}
+
+#[test]
+fn setters_reject_unknown_paths_and_mismatched_values() {
+	let mut state = FormState::default();
+	let path = |parts: &[&str]| {
+		parts
+			.iter()
+			.map(|part| part.to_string())
+			.collect::<Vec<_>>()
+	};
+	let set = |value: WuiValue| BindingWrite::Set(value);
+
+	assert!(!apply_binding(
+		&mut state,
+		&path(&["profile", "email"]),
+		set(WuiValue::String("a@b.c".to_string()))
+	));
+	assert!(!apply_binding(
+		&mut state,
+		&path(&["profile", "age"]),
+		set(WuiValue::String("old".to_string()))
+	));
+	assert!(apply_binding(
+		&mut state,
+		&path(&["profile", "nickname"]),
+		set(WuiValue::String("ada".to_string()))
+	));
+	assert_eq!(state.profile.nickname.as_deref(), Some("ada"));
+	assert!(!apply_binding(
+		&mut state,
+		&path(&["accent"]),
+		set(WuiValue::String("#ffffff".to_string()))
+	));
+	assert_eq!(state.profile.age, 0);
+}
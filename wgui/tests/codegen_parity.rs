@@ -0,0 +1,211 @@
+//! Every template in `wui/parity` rendered through generated Rust and through
+//! the interpreter, on the same state and paths, must give identical trees.
+
+use wgui::{wgui_controller, WguiModel};
+
+#[derive(Clone, Default, WguiModel)]
+struct Row {
+	id: u32,
+	name: String,
+	done: bool,
+	score: f64,
+}
+
+#[derive(Clone, Default, WguiModel)]
+struct Choice {
+	value: String,
+	name: String,
+}
+
+#[derive(Clone, Default, WguiModel)]
+struct Chart {
+	kind: String,
+	points: Vec<f64>,
+	stacked: bool,
+}
+
+#[derive(Clone, Default, WguiModel)]
+struct Fixture {
+	title: String,
+	count: u32,
+	total: f64,
+	ratio: f32,
+	enabled: bool,
+	nickname: Option<String>,
+	missing: Option<String>,
+	tags: Vec<String>,
+	rows: Vec<Row>,
+	choices: Vec<Choice>,
+	status: String,
+	accent: String,
+	room: String,
+	width: u32,
+	date: String,
+	chart: Chart,
+}
+
+fn fixture() -> Fixture {
+	Fixture {
+		title: "groceries".to_string(),
+		count: 3,
+		total: 1234.5,
+		ratio: 0.5,
+		enabled: true,
+		nickname: Some("gro".to_string()),
+		missing: None,
+		tags: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+		rows: vec![
+			Row {
+				id: 1,
+				name: "milk".to_string(),
+				done: true,
+				score: 1.5,
+			},
+			Row {
+				id: 2,
+				name: "bread".to_string(),
+				done: false,
+				score: 2.0,
+			},
+			Row {
+				id: 3,
+				name: "eggs".to_string(),
+				done: false,
+				score: -1.0,
+			},
+		],
+		choices: vec![
+			Choice {
+				value: "open".to_string(),
+				name: "Open".to_string(),
+			},
+			Choice {
+				value: "done".to_string(),
+				name: String::new(),
+			},
+			Choice {
+				value: String::new(),
+				name: "Dropped".to_string(),
+			},
+		],
+		status: "open".to_string(),
+		accent: "#c00".to_string(),
+		room: "standup".to_string(),
+		width: 640,
+		date: "2024-03-05T10:30:00".to_string(),
+		chart: Chart {
+			kind: "line".to_string(),
+			points: vec![1.0, 2.5, 4.0],
+			stacked: false,
+		},
+	}
+}
+
+/// Other states the templates branch on.
+fn variants() -> Vec<Fixture> {
+	let empty = Fixture::default();
+	let flipped = Fixture {
+		enabled: false,
+		nickname: None,
+		missing: Some("here".to_string()),
+		count: 12,
+		status: "done".to_string(),
+		..fixture()
+	};
+	vec![fixture(), empty, flipped]
+}
+
+const PATHS: &[&str] = &["", "/", "/rows/7", "/other/place"];
+
+fn json(item: &wgui::Item) -> serde_json::Value {
+	serde_json::to_value(item).unwrap()
+}
+
+/// Where two trees first differ, for a readable failure.
+fn first_difference(
+	codegen: &serde_json::Value,
+	runtime: &serde_json::Value,
+	at: String,
+) -> Option<String> {
+	use serde_json::Value;
+	match (codegen, runtime) {
+		(Value::Object(left), Value::Object(right)) => left
+			.keys()
+			.chain(right.keys())
+			.find_map(|key| {
+				let (left, right) = (left.get(key)?, right.get(key));
+				first_difference(left, right.unwrap_or(&Value::Null), format!("{at}.{key}"))
+			})
+			.or_else(|| {
+				let missing = right.keys().find(|key| !left.contains_key(*key))?;
+				Some(format!("{at}.{missing}: missing in codegen"))
+			}),
+		(Value::Array(left), Value::Array(right)) if left.len() == right.len() => left
+			.iter()
+			.zip(right)
+			.enumerate()
+			.find_map(|(index, (left, right))| {
+				first_difference(left, right, format!("{at}[{index}]"))
+			}),
+		_ if codegen == runtime => None,
+		_ => Some(format!("{at}: codegen {codegen}, runtime {runtime}")),
+	}
+}
+
+macro_rules! parity {
+	($name:ident, $template:tt) => {
+		mod $name {
+			use super::*;
+			use wgui::wui::runtime::WuiController;
+
+			pub struct Codegen(pub Fixture);
+
+			#[wgui_controller(template = $template, mode = "codegen")]
+			impl Codegen {
+				fn state(&self) -> Fixture {
+					self.0.clone()
+				}
+
+				fn state_mut(&mut self) -> &mut Fixture {
+					&mut self.0
+				}
+			}
+
+			pub struct Runtime(pub Fixture);
+
+			#[wgui_controller(template = $template, mode = "runtime")]
+			impl Runtime {
+				fn state(&self) -> Fixture {
+					self.0.clone()
+				}
+
+				fn state_mut(&mut self) -> &mut Fixture {
+					&mut self.0
+				}
+			}
+
+			#[test]
+			fn renders_like_the_interpreter() {
+				for state in variants() {
+					let codegen = Codegen(state.clone());
+					let runtime = Runtime(state);
+					for path in PATHS {
+						let codegen = json(&codegen.render_with_path(path));
+						let runtime = json(&runtime.render_with_path(path));
+						if let Some(difference) =
+							first_difference(&codegen, &runtime, String::new())
+						{
+							panic!("{} at {path:?} differs at {difference}", $template);
+						}
+					}
+				}
+			}
+		}
+	};
+}
+
+parity!(layout, "parity/layout");
+parity!(inputs, "parity/inputs");
+parity!(expressions, "parity/expressions");
+parity!(components, "parity/components");
+parity!(custom, "parity/custom");
@@ -0,0 +1,241 @@
+//! `mode = "codegen"` renders through Rust generated from the template and
+//! must match the interpreter on the same state.
+
+use std::sync::Arc;
+
+use wgui::wui::route_handler::{PathParams, RouteFormData, RouteResult, RuntimeContext};
+use wgui::wui::runtime::{Ctx, RouteContext, WuiController};
+use wgui::{route, view, wgui_controller, ClientEvent, OnClick, View, WguiModel};
+
+#[derive(Clone, Default, WguiModel)]
+struct Todo {
+	id: u32,
+	name: String,
+	done: bool,
+}
+
+#[derive(Clone, Default, WguiModel)]
+struct BoardState {
+	title: String,
+	items: Vec<Todo>,
+	price: f64,
+	qty: f64,
+	status: String,
+}
+
+fn board() -> BoardState {
+	BoardState {
+		title: "groceries".to_string(),
+		items: vec![
+			Todo {
+				id: 1,
+				name: "milk".to_string(),
+				done: true,
+			},
+			Todo {
+				id: 2,
+				name: "bread".to_string(),
+				done: false,
+			},
+		],
+		price: 1234.5,
+		qty: 2.0,
+		status: "open".to_string(),
+	}
+}
+
+#[derive(Default)]
+struct CodegenBoard {
+	state: BoardState,
+	finished: Vec<u32>,
+}
+
+#[wgui_controller(template = "codegen/board", mode = "codegen")]
+impl CodegenBoard {
+	fn state(&self) -> BoardState {
+		self.state.clone()
+	}
+
+	fn finish(&mut self, id: u32) {
+		self.finished.push(id);
+	}
+}
+
+#[derive(Default)]
+struct RuntimeBoard {
+	state: BoardState,
+}
+
+#[wgui_controller(template = "codegen/board", mode = "runtime")]
+impl RuntimeBoard {
+	fn state(&self) -> BoardState {
+		self.state.clone()
+	}
+
+	fn finish(&mut self, _id: u32) {}
+}
+
+#[route("/board", view, template = "codegen/board", mode = "codegen")]
+fn board_view(_ctx: &Ctx<()>) -> View {
+	wgui::view!(board())
+}
+
+#[route("/board-runtime", view, template = "codegen/board")]
+fn board_runtime_view(_ctx: &Ctx<()>) -> View {
+	view!(board())
+}
+
+mod other {
+	macro_rules! view {
+		() => {
+			"not a wgui view"
+		};
+	}
+	pub(crate) use view;
+}
+
+#[route("/items/:id", view, template = "codegen/route", mode = "codegen")]
+fn item_view(_ctx: &Ctx<()>, id: u32) -> View {
+	let title = format!("{} {id}", other::view!());
+	view!(BoardState {
+		title,
+		..BoardState::default()
+	})
+}
+
+#[route("/items-runtime/:id", view, template = "codegen/route")]
+fn item_runtime_view(_ctx: &Ctx<()>, id: u32) -> View {
+	view!(BoardState {
+		title: format!("not a wgui view {id}"),
+		..BoardState::default()
+	})
+}
+
+#[route("/items-later/:id", view, template = "codegen/route", mode = "codegen")]
+fn item_later_view(_ctx: &Ctx<()>, id: u32) -> View {
+	View::loading(wgui::gui::text("loading"), async move {
+		let title = id.to_string();
+		view!(BoardState {
+			title,
+			..BoardState::default()
+		})
+	})
+}
+
+fn json(item: &wgui::Item) -> serde_json::Value {
+	serde_json::to_value(item).unwrap()
+}
+
+async fn render_route(handler: impl wgui::DynRouteHandler, path: &str) -> wgui::Item {
+	render_route_with(
+		handler,
+		RouteContext {
+			path: path.to_string(),
+			..RouteContext::default()
+		},
+	)
+	.await
+}
+
+async fn render_route_with(handler: impl wgui::DynRouteHandler, route: RouteContext) -> wgui::Item {
+	let mut params = PathParams::default();
+	params.0 = route.params.clone();
+	let result = handler
+		.call_dyn(
+			Arc::new(Ctx::new(())),
+			params,
+			RouteFormData::default(),
+			RuntimeContext {
+				client_id: None,
+				session: None,
+				route: Some(route),
+			},
+		)
+		.await;
+	let RouteResult::View(view) = result else {
+		panic!("route should render a view");
+	};
+	view.item
+}
+
+#[test]
+fn codegen_controller_renders_like_the_interpreter() {
+	let codegen = CodegenBoard {
+		state: board(),
+		..CodegenBoard::default()
+	};
+	let runtime = RuntimeBoard { state: board() };
+
+	let rendered = json(&codegen.render());
+	assert_eq!(rendered, json(&runtime.render()));
+	let text = rendered.to_string();
+	for expected in ["GROCERIES", "2 items", "bread", "Total: 2,469.00", "Open"] {
+		assert!(text.contains(expected), "{expected} missing from {text}");
+	}
+}
+
+#[tokio::test]
+async fn codegen_controller_decodes_events_from_its_render() {
+	let mut controller = CodegenBoard {
+		state: board(),
+		..CodegenBoard::default()
+	};
+	let rendered = json(&controller.render()).to_string();
+	let finish_id = rendered
+		.split("\"id\":")
+		.filter_map(|part| part.split(|ch: char| !ch.is_ascii_digit()).next())
+		.filter_map(|id| id.parse::<u32>().ok())
+		.find(|id| {
+			let event = ClientEvent::OnClick(OnClick {
+				id: *id,
+				inx: Some(2),
+			});
+			controller.wui_template().unwrap().decode(&event).is_some()
+		})
+		.expect("the Finish button has an action id");
+
+	let event = ClientEvent::OnClick(OnClick {
+		id: finish_id,
+		inx: Some(2),
+	});
+	assert!(controller.handle(&event).await);
+	assert_eq!(controller.finished, vec![2]);
+}
+
+#[tokio::test]
+async fn codegen_route_renders_like_the_interpreter() {
+	let codegen = render_route(board_view_route, "/board").await;
+	let runtime = render_route(board_runtime_view_route, "/board-runtime").await;
+
+	assert_eq!(json(&codegen), json(&runtime));
+	assert!(wgui::DynRouteHandler::wui_template(&board_view_route).is_none());
+}
+
+#[tokio::test]
+async fn codegen_route_binds_path_params_and_query() {
+	let route = |path: &str| RouteContext {
+		path: path.to_string(),
+		params: [("id".to_string(), "7".to_string())].into(),
+		query: [("tab".to_string(), "notes".to_string())].into(),
+		..RouteContext::default()
+	};
+	let codegen = json(&render_route_with(item_view_route, route("/items/7")).await);
+	let runtime = json(&render_route_with(item_runtime_view_route, route("/items/7")).await);
+
+	assert_eq!(codegen, runtime);
+	let text = codegen.to_string();
+	for expected in ["not a wgui view 7", "path /items/7", "item 7", "tab notes"] {
+		assert!(text.contains(expected), "{expected} missing from {text}");
+	}
+}
+
+#[tokio::test]
+async fn codegen_views_in_loading_futures_own_the_route() {
+	let route = RouteContext {
+		path: "/items-later/3".to_string(),
+		params: [("id".to_string(), "3".to_string())].into(),
+		..RouteContext::default()
+	};
+	let placeholder = json(&render_route_with(item_later_view_route, route).await);
+	assert!(placeholder.to_string().contains("loading"), "{placeholder}");
+}
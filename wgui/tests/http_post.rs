@@ -46,6 +46,7 @@ fn request(path: &str, content_type: &str, body: impl Into<Vec<u8>>) -> HttpRequ
 		method: "POST".to_string(),
 		path: path.to_string(),
 		query: HashMap::new(),
+		query_string: String::new(),
 		headers,
 		body: body.into(),
 	}
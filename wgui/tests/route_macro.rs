@@ -15,7 +15,7 @@
 use std::sync::Arc;
 use wgui::wui::runtime::Ctx;
 use wgui::{
-	partial, route, view, HttpMethod, PathParams, Redirect, RouteFormData, RouteHandler,
+	partial, route, view, HttpMethod, PathParams, Query, Redirect, RouteFormData, RouteHandler,
 	RouteResult, Router, RuntimeContext, View, Wgui,
 };
 
@@ -114,6 +114,49 @@ fn macro_form_error(_ctx: &Ctx<TestAppState>) -> View {
 	.with_status(422)
 }
 
+// `Err` renders the error page.
+#[route("/todos/:id/edit")]
+fn page_edit(_ctx: &Ctx<TestAppState>, id: u32) -> Result<View, String> {
+	Err(format!("no todo {id}"))
+}
+
+#[route("/broken")]
+async fn page_broken(_ctx: &Ctx<TestAppState>) -> View {
+	panic!("database is gone");
+}
+
+#[route("/reports", ssr = "fallback")]
+fn page_reports(_ctx: &Ctx<TestAppState>) -> View {
+	View::loading(wgui::gui::text("Loading reports"), async {
+		View::page("Reports", wgui::gui::text("3 reports"))
+	})
+}
+
+#[derive(serde::Deserialize)]
+struct TodoFilters {
+	#[serde(default)]
+	tag: Vec<String>,
+	#[serde(default = "first_page")]
+	page: u32,
+}
+
+fn first_page() -> u32 {
+	1
+}
+
+// `Query<T>` arguments are decoded from the query string, on any method.
+#[route("/todos")]
+fn page_todos(_ctx: &Ctx<TestAppState>, filters: Query<TodoFilters>) -> View {
+	View::page(
+		"Todos",
+		wgui::gui::text(&format!(
+			"page {} of {}",
+			filters.page,
+			filters.tag.join("+")
+		)),
+	)
+}
+
 #[test]
 fn routes_register_and_stored_by_path() {
 	// `add_route` doesn't require a context — it just stores the marker.
@@ -136,6 +179,82 @@ fn routes_register_and_stored_by_path() {
 	// Successful registration (no panic, traits resolve) is the contract.
 }
 
+#[test]
+fn routes_build_typed_urls() {
+	assert_eq!(page_show_route::url(7), "/todos/7");
+	assert_eq!(
+		page_user_route::url("Ada Lovelace".to_string()),
+		"/users/Ada%20Lovelace"
+	);
+	assert_eq!(page_index_route::url(), "/");
+	assert_eq!(todo_status_partial::url(3), "/todos/3/status");
+}
+
+#[test]
+fn registered_routes_resolve_by_name() {
+	let mut wgui = Wgui::new_without_server();
+	wgui.add_route(page_user_route);
+	assert_eq!(
+		wgui::wui::route_handler::route_url(
+			"page_user",
+			&[wgui::wui::runtime::WuiValue::String("a/b".to_string())]
+		),
+		"/users/a%2Fb"
+	);
+	assert_eq!(wgui::wui::route_handler::route_url("page_user", &[]), "");
+	assert_eq!(
+		wgui::wui::route_handler::route_url(
+			"route_macro::page_user",
+			&[wgui::wui::runtime::WuiValue::String("b".to_string())]
+		),
+		"/users/b"
+	);
+}
+
+mod admin {
+	use super::*;
+
+	#[route("/admin")]
+	pub fn index(_ctx: &Ctx<TestAppState>) -> View {
+		View::page("Admin", wgui::gui::text("admin"))
+	}
+}
+
+mod shop {
+	use super::*;
+
+	#[route("/shop")]
+	pub fn index(_ctx: &Ctx<TestAppState>) -> View {
+		View::page("Shop", wgui::gui::text("shop"))
+	}
+}
+
+#[test]
+fn every_instance_resolves_the_process_wide_route_urls() {
+	let mut admin_server = Wgui::new_without_server();
+	let mut shop_server = Wgui::new_without_server();
+	admin_server.add_route(admin::index_route);
+	admin_server.add_route(admin::index_route);
+	shop_server.add_route(shop::index_route);
+
+	let url = wgui::wui::route_handler::route_url;
+	assert_eq!(url("admin::index", &[]), "/admin");
+	assert_eq!(url("shop::index", &[]), "/shop");
+	assert_eq!(url("index", &[]), "");
+}
+
+#[test]
+fn build_url_reports_param_count_mismatches() {
+	assert_eq!(
+		wgui::wui::route_handler::build_url("/todos/:id", &[&7]).unwrap(),
+		"/todos/7"
+	);
+	assert_eq!(
+		wgui::wui::route_handler::build_url("/todos/:id", &[]).unwrap_err(),
+		"`/todos/:id` takes 1 path parameter, got 0"
+	);
+}
+
 #[test]
 fn routers_compose_route_groups() {
 	let todo_routes = Router::new()
@@ -194,6 +313,7 @@ async fn post_view_route_renders_the_template_and_retains_its_status() {
 				path: "/macro-form-error".to_string(),
 				params: Default::default(),
 				query: Default::default(),
+				query_string: String::new(),
 			}),
 		},
 	)
@@ -223,6 +343,7 @@ async fn view_macro_renders_anonymous_model_with_registered_template() {
 				path: "/macro-view".to_string(),
 				params: Default::default(),
 				query: Default::default(),
+				query_string: String::new(),
 			}),
 		},
 	)
@@ -235,6 +356,73 @@ async fn view_macro_renders_anonymous_model_with_registered_template() {
 	assert!(rendered.contains("true"));
 }
 
+#[tokio::test]
+async fn failing_and_panicking_handlers_return_errors() {
+	let ctx = Arc::new(Ctx::new(TestAppState::default()));
+	let mut params = PathParams::default();
+	params.0.insert("id".to_string(), "7".to_string());
+	let result = wgui::DynRouteHandler::call_dyn(
+		&page_edit_route,
+		ctx.clone(),
+		params,
+		RouteFormData::default(),
+		RuntimeContext {
+			client_id: None,
+			session: None,
+			route: None,
+		},
+	)
+	.await;
+	assert!(matches!(result, RouteResult::Error(message) if message == "no todo 7"));
+
+	let result = wgui::DynRouteHandler::call_dyn(
+		&page_broken_route,
+		ctx,
+		PathParams::default(),
+		RouteFormData::default(),
+		RuntimeContext {
+			client_id: None,
+			session: None,
+			route: None,
+		},
+	)
+	.await;
+	assert!(
+		matches!(&result, RouteResult::Error(message) if message == "panicked: database is gone"),
+		"{result:?}"
+	);
+}
+
+#[tokio::test]
+async fn loading_route_returns_placeholder_and_ssr_option() {
+	assert_eq!(
+		RouteHandler::ssr_loading(&page_reports_route),
+		wgui::SsrLoading::Fallback
+	);
+	assert_eq!(
+		RouteHandler::ssr_loading(&page_index_route),
+		wgui::SsrLoading::Wait
+	);
+	let ctx = Arc::new(Ctx::new(TestAppState::default()));
+	let result = wgui::DynRouteHandler::call_dyn(
+		&page_reports_route,
+		ctx,
+		PathParams::default(),
+		RouteFormData::default(),
+		RuntimeContext {
+			client_id: None,
+			session: None,
+			route: None,
+		},
+	)
+	.await;
+	let RouteResult::View(view) = result else {
+		panic!("expected a view");
+	};
+	assert!(view.is_loading());
+	assert_eq!(view.item, wgui::gui::text("Loading reports"));
+}
+
 #[tokio::test]
 async fn post_route_decodes_typed_form_argument() {
 	let ctx = Arc::new(Ctx::new(TestAppState::default()));
@@ -265,3 +453,38 @@ fn pascal_case_marker_names_are_generated() {
 	let _ = macro_view_route;
 	let _ = macro_form_error_route;
 }
+
+async fn call_todos(query_string: &str) -> RouteResult {
+	wgui::DynRouteHandler::call_dyn(
+		&page_todos_route,
+		Arc::new(Ctx::new(TestAppState::default())),
+		PathParams::default(),
+		RouteFormData::default(),
+		RuntimeContext {
+			client_id: None,
+			session: None,
+			route: Some(wgui::wui::runtime::RouteContext {
+				path: "/todos".to_string(),
+				query_string: query_string.to_string(),
+				..Default::default()
+			}),
+		},
+	)
+	.await
+}
+
+#[tokio::test]
+async fn query_arguments_decode_repeated_keys_and_defaults() {
+	let RouteResult::View(view) = call_todos("tag=home&tag=work").await else {
+		panic!("query route should render a view");
+	};
+	assert!(serde_json::to_string(&view.item)
+		.unwrap()
+		.contains("page 1 of home+work"));
+
+	let result = call_todos("page=two").await;
+	assert!(
+		matches!(&result, RouteResult::BadRequest(message) if message.contains("\"two\"")),
+		"{result:?}"
+	);
+}
@@ -0,0 +1,90 @@
+use wgui::wui::runtime::{Template, WuiController};
+use wgui::wui::schema::{FieldType, StateSchema};
+use wgui::{wgui_controller, WguiModel};
+
+mod todos {
+	#[derive(Clone, Default, wgui::WguiModel)]
+	pub struct Item {
+		pub title: String,
+		pub done: bool,
+	}
+}
+
+mod shop {
+	#[derive(Clone, Default, wgui::WguiModel)]
+	pub struct Item {
+		pub title: String,
+		pub price: f64,
+	}
+}
+
+#[derive(Clone, Default, WguiModel)]
+struct Tree {
+	label: String,
+	children: Vec<Tree>,
+}
+
+#[derive(Clone, Default, WguiModel)]
+struct Page {
+	todo: todos::Item,
+	product: Option<shop::Item>,
+	tree: Tree,
+}
+
+fn check(source: &str) -> Vec<String> {
+	let template = Template::parse(source, "test").expect("parse template");
+	let (schema, state) = StateSchema::of::<Page>();
+	template
+		.check_state(&schema, &state)
+		.into_iter()
+		.map(|diag| diag.message)
+		.collect()
+}
+
+#[test]
+fn derived_schema_keeps_same_named_models_apart() {
+	let (schema, state) = StateSchema::of::<Page>();
+	assert_eq!(state, FieldType::Model("state_schema::Page".to_string()));
+	assert_eq!(
+		schema.model("state_schema::todos::Item").unwrap().fields,
+		vec![
+			("title".to_string(), FieldType::String),
+			("done".to_string(), FieldType::Bool),
+		]
+	);
+
+	assert!(check(
+		r#"
+		<VStack>
+			<Text value={state.todo.title} />
+			<Text value={state.product.price} />
+			<For each={state.tree.children} itemAs="child">
+				<Text value={child.children} />
+			</For>
+		</VStack>
+		"#
+	)
+	.is_empty());
+	assert_eq!(
+		check(r#"<Text value={state.todo.price} />"#),
+		vec!["unknown field `price` on `state.todo`; known fields: title, done"]
+	);
+}
+
+#[derive(Default)]
+struct MismatchedController {
+	state: Page,
+}
+
+#[wgui_controller(template = "schema/mismatched", mode = "runtime")]
+impl MismatchedController {
+	fn state(&self) -> Page {
+		self.state.clone()
+	}
+}
+
+#[test]
+#[should_panic(expected = "unknown field `price` on `state.todo`")]
+fn debug_builds_check_runtime_templates_against_the_derived_schema() {
+	MismatchedController::default().render();
+}
@@ -0,0 +1,171 @@
+//! Drives `Wgui::run` through a websocket client, the way the browser does.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_util::{Sink, Stream};
+use tokio::sync::mpsc;
+use wgui::ws::WsMessage;
+use wgui::wui::runtime::Ctx;
+use wgui::{route, Query, View, Wgui};
+
+/// One end of an in-memory websocket: the test sends client frames and
+/// reads what the server's worker writes back.
+struct TestWs {
+	incoming: mpsc::UnboundedReceiver<WsMessage>,
+	outgoing: mpsc::UnboundedSender<WsMessage>,
+}
+
+impl Stream for TestWs {
+	type Item = anyhow::Result<WsMessage>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.incoming.poll_recv(cx).map(|msg| msg.map(Ok))
+	}
+}
+
+impl Sink<WsMessage> for TestWs {
+	type Error = anyhow::Error;
+
+	fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<anyhow::Result<()>> {
+		Poll::Ready(Ok(()))
+	}
+
+	fn start_send(self: Pin<&mut Self>, item: WsMessage) -> anyhow::Result<()> {
+		self.outgoing.send(item)?;
+		Ok(())
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<anyhow::Result<()>> {
+		Poll::Ready(Ok(()))
+	}
+
+	fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<anyhow::Result<()>> {
+		Poll::Ready(Ok(()))
+	}
+}
+
+struct Client {
+	send: mpsc::UnboundedSender<WsMessage>,
+	received: mpsc::UnboundedReceiver<WsMessage>,
+}
+
+impl Client {
+	async fn connect(wgui: &Wgui) -> Self {
+		let (send, incoming) = mpsc::unbounded_channel();
+		let (outgoing, received) = mpsc::unbounded_channel();
+		wgui.handle().handle_ws(TestWs { incoming, outgoing }).await;
+		Self { send, received }
+	}
+
+	fn send(&self, events: serde_json::Value) {
+		self.send.send(WsMessage::Text(events.to_string())).unwrap();
+	}
+
+	/// Runs `wgui`'s event loop until a text frame containing `needle` is
+	/// sent.
+	async fn expect_text(&mut self, wgui: &mut Wgui, needle: &str) {
+		let wait = async {
+			while let Some(msg) = self.received.recv().await {
+				if let WsMessage::Text(text) = msg {
+					if text.contains(needle) {
+						return;
+					}
+				}
+			}
+			panic!("websocket closed before `{needle}` was sent");
+		};
+		tokio::select! {
+			_ = wgui.run() => panic!("the event loop stopped"),
+			_ = wait => {}
+			_ = tokio::time::sleep(Duration::from_secs(5)) => {
+				panic!("no frame containing `{needle}`")
+			}
+		}
+	}
+}
+
+#[derive(Default)]
+struct AppState;
+
+#[derive(serde::Deserialize)]
+struct SearchQuery {
+	#[serde(default)]
+	tag: Vec<String>,
+}
+
+#[route("/search", method = "POST")]
+fn search(_ctx: &Ctx<AppState>, query: Query<SearchQuery>) -> View {
+	View::page(
+		"Search",
+		wgui::gui::text(&format!("tags {}", query.tag.join("+"))),
+	)
+}
+
+fn app() -> Wgui {
+	let mut wgui = Wgui::new_without_server();
+	wgui.set_ctx_state(AppState);
+	wgui.add_route(search_route);
+	wgui
+}
+
+#[tokio::test]
+async fn form_submit_keeps_repeated_query_keys() {
+	let mut wgui = app();
+	let mut client = Client::connect(&wgui).await;
+
+	client.send(serde_json::json!([{
+		"type": "formSubmit",
+		"path": "/search",
+		"submissionId": 1,
+		"query": { "tag": "b" },
+		"queryString": "tag=a&tag=b",
+		"fields": {},
+	}]));
+
+	client.expect_text(&mut wgui, "tags a+b").await;
+}
+
+#[tokio::test]
+async fn form_submit_falls_back_to_the_page_query_string() {
+	let mut wgui = app();
+	let mut client = Client::connect(&wgui).await;
+
+	client.send(serde_json::json!([
+		{
+			"type": "pathChanged",
+			"path": "/",
+			"query": { "tag": "b" },
+			"queryString": "tag=a&tag=b",
+		},
+		{
+			"type": "formSubmit",
+			"path": "/search",
+			"submissionId": 1,
+			"query": { "tag": "b" },
+			"fields": {},
+		},
+	]));
+
+	client.expect_text(&mut wgui, "tags a+b").await;
+}
+
+#[cfg(feature = "metrics")]
+#[tokio::test]
+async fn form_submit_records_post_route_latency() {
+	let mut wgui = app();
+	let mut client = Client::connect(&wgui).await;
+
+	client.send(serde_json::json!([{
+		"type": "formSubmit",
+		"path": "/search",
+		"submissionId": 1,
+		"query": {},
+		"fields": {},
+	}]));
+	client.expect_text(&mut wgui, "tags ").await;
+
+	assert!(wgui::metrics::gather()
+		.contains("wgui_post_route_duration_seconds_count{route=\"/search\"}"));
+}
@@ -96,6 +96,34 @@ pub enum BinaryOp {
 }
 
 impl Expr {
+	/// Calls `visit` on this expression, then on each subexpression.
+	pub(crate) fn visit(&self, visit: &mut dyn FnMut(&Expr)) {
+		visit(self);
+		match self {
+			Expr::Literal(_, _) | Expr::Path(_, _) => {}
+			Expr::Call { args, .. } => {
+				for arg in args {
+					arg.visit(visit);
+				}
+			}
+			Expr::Unary { expr, .. } => expr.visit(visit),
+			Expr::Binary { left, right, .. } | Expr::Coalesce { left, right, .. } => {
+				left.visit(visit);
+				right.visit(visit);
+			}
+			Expr::Ternary {
+				cond,
+				then_expr,
+				else_expr,
+				..
+			} => {
+				cond.visit(visit);
+				then_expr.visit(visit);
+				else_expr.visit(visit);
+			}
+		}
+	}
+
 	pub fn span(&self) -> Span {
 		match self {
 			Expr::Literal(_, span)
@@ -108,3 +136,24 @@ impl Expr {
 		}
 	}
 }
+
+impl Node {
+	/// Calls `visit` on every expression in this node and its children,
+	/// including each subexpression.
+	pub(crate) fn visit_exprs(&self, visit: &mut dyn FnMut(&Expr)) {
+		match self {
+			Node::Element(el) => {
+				for attr in &el.attrs {
+					if let AttrValue::Expr(expr) = &attr.value {
+						expr.visit(visit);
+					}
+				}
+				for child in &el.children {
+					child.visit_exprs(visit);
+				}
+			}
+			Node::Text(_, _) => {}
+			Node::Expr(expr) => expr.visit(visit),
+		}
+	}
+}
@@ -0,0 +1,923 @@
+//! Built-in functions callable from WUI expressions, e.g. `{upper(state.name)}`.
+//!
+//! [`BUILTINS`] is the single list of names and arities: the validator uses it
+//! to report unknown calls, `compiler::codegen` to emit Rust for them and the
+//! runtime evaluator in `wgui::wui::runtime` to dispatch. The helpers below
+//! hold the behaviour that is more than a one-line std call, so runtime and
+//! compiled templates format numbers, dates and slices identically.
+
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReturnType {
+	String,
+	Number,
+	Bool,
+	List,
+	/// The kind of the first argument: a string for a string, a list for a
+	/// list.
+	FirstArg,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Builtin {
+	pub name: &'static str,
+	pub min_args: usize,
+	/// `None` for variadic functions.
+	pub max_args: Option<usize>,
+	pub returns: ReturnType,
+	pub signature: &'static str,
+	pub doc: &'static str,
+}
+
+impl Builtin {
+	pub fn accepts(&self, count: usize) -> bool {
+		count >= self.min_args && self.max_args.is_none_or(|max| count <= max)
+	}
+
+	pub fn arity(&self) -> String {
+		match self.max_args {
+			Some(max) if max == self.min_args => format!("{max}"),
+			Some(max) => format!("{} to {max}", self.min_args),
+			None => format!("at least {}", self.min_args),
+		}
+	}
+}
+
+/// Function name template strings (`` `Hi ${name}` ``) are parsed into.
+pub const CONCAT: &str = "concat";
+
+pub const BUILTINS: &[Builtin] = &[
+	Builtin {
+		name: "len",
+		min_args: 1,
+		max_args: Some(1),
+		returns: ReturnType::Number,
+		signature: "len(value)",
+		doc: "Number of characters in a string or items in a list.",
+	},
+	Builtin {
+		name: "upper",
+		min_args: 1,
+		max_args: Some(1),
+		returns: ReturnType::String,
+		signature: "upper(text)",
+		doc: "Upper-cases a string.",
+	},
+	Builtin {
+		name: "lower",
+		min_args: 1,
+		max_args: Some(1),
+		returns: ReturnType::String,
+		signature: "lower(text)",
+		doc: "Lower-cases a string.",
+	},
+	Builtin {
+		name: "trim",
+		min_args: 1,
+		max_args: Some(1),
+		returns: ReturnType::String,
+		signature: "trim(text)",
+		doc: "Removes leading and trailing whitespace.",
+	},
+	Builtin {
+		name: "contains",
+		min_args: 2,
+		max_args: Some(2),
+		returns: ReturnType::Bool,
+		signature: "contains(haystack, needle)",
+		doc: "Whether a string contains a substring or a list contains a value.",
+	},
+	Builtin {
+		name: "join",
+		min_args: 2,
+		max_args: Some(2),
+		returns: ReturnType::String,
+		signature: "join(list, separator)",
+		doc: "Joins list items into a string.",
+	},
+	Builtin {
+		name: "slice",
+		min_args: 2,
+		max_args: Some(3),
+		returns: ReturnType::FirstArg,
+		signature: "slice(value, start, end?)",
+		doc: "Part of a string or list; negative indices count from the end.",
+	},
+	Builtin {
+		name: "format_number",
+		min_args: 1,
+		max_args: Some(2),
+		returns: ReturnType::String,
+		signature: "format_number(number, decimals?)",
+		doc: "Formats a number with thousands separators.",
+	},
+	Builtin {
+		name: "format_date",
+		min_args: 2,
+		max_args: Some(2),
+		returns: ReturnType::String,
+		signature: "format_date(date, pattern)",
+		doc: "Formats a Unix timestamp or ISO date using YYYY, MM, MMM, DD, HH, mm and ss.",
+	},
+	Builtin {
+		name: "pluralize",
+		min_args: 2,
+		max_args: Some(3),
+		returns: ReturnType::String,
+		signature: "pluralize(count, singular, plural?)",
+		doc: "The singular word for a count of 1, otherwise the plural (singular + \"s\" by default).",
+	},
+	Builtin {
+		name: "t",
+		min_args: 1,
+		max_args: None,
+		returns: ReturnType::String,
+		signature: "t(key, name?, value?, ...)",
+		doc: "The message `key` from the current locale's catalog, with arguments given as name/value pairs.",
+	},
+	Builtin {
+		name: "url",
+		min_args: 1,
+		max_args: None,
+		returns: ReturnType::String,
+		signature: "url(route, param?, ...)",
+		doc: "The path of the `#[route]` handler named `route`, with its path parameters filled in order and percent-encoded.",
+	},
+	Builtin {
+		name: "min",
+		min_args: 2,
+		max_args: None,
+		returns: ReturnType::Number,
+		signature: "min(a, b, ...)",
+		doc: "Smallest of the numbers.",
+	},
+	Builtin {
+		name: "max",
+		min_args: 2,
+		max_args: None,
+		returns: ReturnType::Number,
+		signature: "max(a, b, ...)",
+		doc: "Largest of the numbers.",
+	},
+	Builtin {
+		name: "round",
+		min_args: 1,
+		max_args: Some(2),
+		returns: ReturnType::Number,
+		signature: "round(number, decimals?)",
+		doc: "Rounds to the given number of decimals (0 by default).",
+	},
+	Builtin {
+		name: CONCAT,
+		min_args: 0,
+		max_args: None,
+		returns: ReturnType::String,
+		signature: "concat(values...)",
+		doc: "Concatenates values as strings.",
+	},
+	Builtin {
+		name: "path_matches",
+		min_args: 1,
+		max_args: Some(1),
+		returns: ReturnType::Bool,
+		signature: "path_matches(route)",
+		doc: "Whether the current path matches a route pattern.",
+	},
+];
+
+pub fn lookup(name: &str) -> Option<&'static Builtin> {
+	BUILTINS.iter().find(|builtin| builtin.name == name)
+}
+
+/// Character count, so runtime and compiled templates agree on non-ASCII text.
+pub trait WuiLen {
+	fn wui_len(&self) -> usize;
+}
+
+impl WuiLen for str {
+	fn wui_len(&self) -> usize {
+		self.chars().count()
+	}
+}
+
+impl WuiLen for String {
+	fn wui_len(&self) -> usize {
+		self.as_str().wui_len()
+	}
+}
+
+impl<T> WuiLen for [T] {
+	fn wui_len(&self) -> usize {
+		self.len()
+	}
+}
+
+impl<T> WuiLen for Vec<T> {
+	fn wui_len(&self) -> usize {
+		self.len()
+	}
+}
+
+pub trait WuiContains<N: ?Sized> {
+	fn wui_contains(&self, needle: &N) -> bool;
+}
+
+impl<N: AsRef<str> + ?Sized> WuiContains<N> for str {
+	fn wui_contains(&self, needle: &N) -> bool {
+		self.contains(needle.as_ref())
+	}
+}
+
+impl<N: AsRef<str> + ?Sized> WuiContains<N> for String {
+	fn wui_contains(&self, needle: &N) -> bool {
+		self.contains(needle.as_ref())
+	}
+}
+
+impl<T: PartialEq<N>, N: ?Sized> WuiContains<N> for [T] {
+	fn wui_contains(&self, needle: &N) -> bool {
+		self.iter().any(|item| item == needle)
+	}
+}
+
+impl<T: PartialEq<N>, N: ?Sized> WuiContains<N> for Vec<T> {
+	fn wui_contains(&self, needle: &N) -> bool {
+		self.as_slice().wui_contains(needle)
+	}
+}
+
+pub trait WuiSlice {
+	type Output;
+	fn wui_slice(&self, start: f64, end: Option<f64>) -> Self::Output;
+}
+
+impl WuiSlice for str {
+	type Output = String;
+	fn wui_slice(&self, start: f64, end: Option<f64>) -> String {
+		let range = slice_range(self.wui_len(), start, end);
+		self.chars()
+			.skip(range.start)
+			.take(range.end - range.start)
+			.collect()
+	}
+}
+
+impl WuiSlice for String {
+	type Output = String;
+	fn wui_slice(&self, start: f64, end: Option<f64>) -> String {
+		self.as_str().wui_slice(start, end)
+	}
+}
+
+impl<T: Clone> WuiSlice for [T] {
+	type Output = Vec<T>;
+	fn wui_slice(&self, start: f64, end: Option<f64>) -> Vec<T> {
+		self[slice_range(self.len(), start, end)].to_vec()
+	}
+}
+
+impl<T: Clone> WuiSlice for Vec<T> {
+	type Output = Vec<T>;
+	fn wui_slice(&self, start: f64, end: Option<f64>) -> Vec<T> {
+		self.as_slice().wui_slice(start, end)
+	}
+}
+
+/// Clamp `start..end` to `0..len`, counting negative indices from the end.
+pub fn slice_range(len: usize, start: f64, end: Option<f64>) -> Range<usize> {
+	let resolve = |index: f64| -> usize {
+		let index = index.trunc();
+		if index < 0.0 {
+			len.saturating_sub((-index) as usize)
+		} else {
+			(index as usize).min(len)
+		}
+	};
+	let start = resolve(start);
+	let end = end.map_or(len, resolve);
+	start..end.max(start)
+}
+
+/// Text of a value as the runtime shows it: numbers print as `f64`,
+/// `None` as nothing. Strings are borrowed.
+pub trait WuiText {
+	fn wui_text(&self) -> Cow<'_, str>;
+}
+
+impl WuiText for str {
+	fn wui_text(&self) -> Cow<'_, str> {
+		Cow::Borrowed(self)
+	}
+}
+
+impl WuiText for String {
+	fn wui_text(&self) -> Cow<'_, str> {
+		Cow::Borrowed(self)
+	}
+}
+
+impl WuiText for bool {
+	fn wui_text(&self) -> Cow<'_, str> {
+		Cow::Borrowed(if *self { "true" } else { "false" })
+	}
+}
+
+impl<T: WuiText> WuiText for Option<T> {
+	fn wui_text(&self) -> Cow<'_, str> {
+		self.as_ref().map_or(Cow::Borrowed(""), T::wui_text)
+	}
+}
+
+impl<T: WuiText + ?Sized> WuiText for &T {
+	fn wui_text(&self) -> Cow<'_, str> {
+		(**self).wui_text()
+	}
+}
+
+/// Numeric values as the `f64` runtime templates compute with.
+pub trait WuiNumber {
+	fn wui_number(&self) -> f64;
+}
+
+impl<T: WuiNumber + ?Sized> WuiNumber for &T {
+	fn wui_number(&self) -> f64 {
+		(**self).wui_number()
+	}
+}
+
+impl<T: WuiNumber> WuiNumber for Option<T> {
+	fn wui_number(&self) -> f64 {
+		self.as_ref().map_or(0.0, T::wui_number)
+	}
+}
+
+/// Values `??` can fall back from: only a missing value is replaced.
+pub trait WuiMaybe {
+	type Value: ?Sized;
+
+	fn wui_maybe(&self) -> Option<&Self::Value>;
+}
+
+impl<T> WuiMaybe for Option<T> {
+	type Value = T;
+
+	fn wui_maybe(&self) -> Option<&T> {
+		self.as_ref()
+	}
+}
+
+impl WuiMaybe for str {
+	type Value = str;
+
+	fn wui_maybe(&self) -> Option<&str> {
+		Some(self)
+	}
+}
+
+impl<T: WuiMaybe + ?Sized> WuiMaybe for &T {
+	type Value = T::Value;
+
+	fn wui_maybe(&self) -> Option<&T::Value> {
+		(**self).wui_maybe()
+	}
+}
+
+/// Truthiness as runtime templates test it: only `true` is true.
+pub trait WuiBool {
+	fn wui_bool(&self) -> bool;
+}
+
+impl WuiBool for bool {
+	fn wui_bool(&self) -> bool {
+		*self
+	}
+}
+
+impl<T: WuiBool> WuiBool for Option<T> {
+	fn wui_bool(&self) -> bool {
+		self.as_ref().is_some_and(T::wui_bool)
+	}
+}
+
+impl<T: WuiBool + ?Sized> WuiBool for &T {
+	fn wui_bool(&self) -> bool {
+		(**self).wui_bool()
+	}
+}
+
+impl WuiBool for str {
+	fn wui_bool(&self) -> bool {
+		false
+	}
+}
+
+macro_rules! present {
+	($($ty:ty),*) => {
+		$(impl WuiMaybe for $ty {
+			type Value = $ty;
+
+			fn wui_maybe(&self) -> Option<&$ty> {
+				Some(self)
+			}
+		})*
+	};
+}
+
+macro_rules! never_true {
+	($($ty:ty),*) => {
+		$(impl WuiBool for $ty {
+			fn wui_bool(&self) -> bool {
+				false
+			}
+		})*
+	};
+}
+
+never_true!(String, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+present!(String, bool, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+macro_rules! number_text {
+	($($ty:ty),*) => {
+		$(impl WuiText for $ty {
+			fn wui_text(&self) -> Cow<'_, str> {
+				Cow::Owned((*self as f64).to_string())
+			}
+		}
+
+		impl WuiNumber for $ty {
+			fn wui_number(&self) -> f64 {
+				*self as f64
+			}
+		})*
+	};
+}
+
+number_text!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+/// A value as an operand of `+`, the way runtime templates see it.
+pub enum WuiScalar<'a> {
+	Text(Cow<'a, str>),
+	Number(f64),
+	Bool(bool),
+	Null,
+}
+
+/// Values compiled templates can `+` when neither side is known to be a
+/// string or a number.
+pub trait WuiOperand {
+	fn wui_operand(&self) -> WuiScalar<'_>;
+}
+
+impl WuiOperand for str {
+	fn wui_operand(&self) -> WuiScalar<'_> {
+		WuiScalar::Text(Cow::Borrowed(self))
+	}
+}
+
+impl WuiOperand for String {
+	fn wui_operand(&self) -> WuiScalar<'_> {
+		WuiScalar::Text(Cow::Borrowed(self))
+	}
+}
+
+impl WuiOperand for bool {
+	fn wui_operand(&self) -> WuiScalar<'_> {
+		WuiScalar::Bool(*self)
+	}
+}
+
+impl<T: WuiOperand> WuiOperand for Option<T> {
+	fn wui_operand(&self) -> WuiScalar<'_> {
+		self.as_ref().map_or(WuiScalar::Null, T::wui_operand)
+	}
+}
+
+impl<T: WuiOperand + ?Sized> WuiOperand for &T {
+	fn wui_operand(&self) -> WuiScalar<'_> {
+		(**self).wui_operand()
+	}
+}
+
+macro_rules! number_operand {
+	($($ty:ty),*) => {
+		$(impl WuiOperand for $ty {
+			fn wui_operand(&self) -> WuiScalar<'_> {
+				WuiScalar::Number(*self as f64)
+			}
+		})*
+	};
+}
+
+number_operand!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+/// The result of `+`: text when either side is a string, otherwise the sum.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WuiSum {
+	Text(String),
+	Number(f64),
+}
+
+/// `left + right` as runtime templates compute it.
+pub fn add<L: WuiOperand + ?Sized, R: WuiOperand + ?Sized>(left: &L, right: &R) -> WuiSum {
+	fn text(value: WuiScalar<'_>) -> Cow<'_, str> {
+		match value {
+			WuiScalar::Text(text) => text,
+			WuiScalar::Number(number) => Cow::Owned(number.to_string()),
+			WuiScalar::Bool(value) => Cow::Borrowed(if value { "true" } else { "false" }),
+			WuiScalar::Null => Cow::Borrowed(""),
+		}
+	}
+	fn number(value: &WuiScalar<'_>) -> f64 {
+		match value {
+			WuiScalar::Number(number) => *number,
+			_ => 0.0,
+		}
+	}
+	match (left.wui_operand(), right.wui_operand()) {
+		(left @ WuiScalar::Text(_), right) | (left, right @ WuiScalar::Text(_)) => {
+			WuiSum::Text(text(left).into_owned() + &text(right))
+		}
+		(left, right) => WuiSum::Number(number(&left) + number(&right)),
+	}
+}
+
+impl WuiOperand for WuiSum {
+	fn wui_operand(&self) -> WuiScalar<'_> {
+		match self {
+			WuiSum::Text(text) => WuiScalar::Text(Cow::Borrowed(text)),
+			WuiSum::Number(number) => WuiScalar::Number(*number),
+		}
+	}
+}
+
+impl WuiText for WuiSum {
+	fn wui_text(&self) -> Cow<'_, str> {
+		match self {
+			WuiSum::Text(text) => Cow::Borrowed(text),
+			WuiSum::Number(number) => Cow::Owned(number.to_string()),
+		}
+	}
+}
+
+impl WuiNumber for WuiSum {
+	fn wui_number(&self) -> f64 {
+		match self {
+			WuiSum::Text(_) => 0.0,
+			WuiSum::Number(number) => *number,
+		}
+	}
+}
+
+impl WuiBool for WuiSum {
+	fn wui_bool(&self) -> bool {
+		false
+	}
+}
+
+impl WuiMaybe for WuiSum {
+	type Value = WuiSum;
+
+	fn wui_maybe(&self) -> Option<&WuiSum> {
+		Some(self)
+	}
+}
+
+pub fn join<T: Display>(items: &[T], separator: &str) -> String {
+	items
+		.iter()
+		.map(|item| item.to_string())
+		.collect::<Vec<_>>()
+		.join(separator)
+}
+
+pub fn min(values: &[f64]) -> f64 {
+	values.iter().copied().fold(f64::INFINITY, f64::min)
+}
+
+pub fn max(values: &[f64]) -> f64 {
+	values.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+}
+
+/// The most decimals `round` and `format_number` show; more would only
+/// print float noise, and unbounded counts make `format!` panic.
+const MAX_DECIMALS: f64 = 20.0;
+
+pub fn round(value: f64, decimals: Option<f64>) -> f64 {
+	let factor = 10f64.powi(decimals.unwrap_or(0.0).clamp(0.0, MAX_DECIMALS) as i32);
+	(value * factor).round() / factor
+}
+
+/// `1234567.891` → `"1,234,567.89"`. Without `decimals`, up to two decimals
+/// are shown and trailing zeros dropped.
+pub fn format_number(value: f64, decimals: Option<f64>) -> String {
+	format_number_with(value, decimals, ",", ".")
+}
+
+/// [`format_number`] with the separators of a locale.
+pub(crate) fn format_number_with(
+	value: f64,
+	decimals: Option<f64>,
+	group: &str,
+	decimal: &str,
+) -> String {
+	if !value.is_finite() {
+		return value.to_string();
+	}
+	let fixed = match decimals {
+		Some(decimals) => format!(
+			"{:.*}",
+			decimals.clamp(0.0, MAX_DECIMALS) as usize,
+			value.abs()
+		),
+		None => {
+			let fixed = format!("{:.2}", value.abs());
+			fixed
+				.trim_end_matches('0')
+				.trim_end_matches('.')
+				.to_string()
+		}
+	};
+	let (int_part, frac_part) = match fixed.split_once('.') {
+		Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+		None => (fixed.as_str(), None),
+	};
+	let mut out = String::new();
+	if value < 0.0 && fixed.chars().any(|ch| ch.is_ascii_digit() && ch != '0') {
+		out.push('-');
+	}
+	for (index, digit) in int_part.chars().enumerate() {
+		if index > 0 && (int_part.len() - index) % 3 == 0 {
+			out.push_str(group);
+		}
+		out.push(digit);
+	}
+	if let Some(frac_part) = frac_part {
+		out.push_str(decimal);
+		out.push_str(frac_part);
+	}
+	out
+}
+
+pub fn pluralize(count: f64, singular: &str, plural: Option<&str>) -> String {
+	if count == 1.0 {
+		singular.to_string()
+	} else {
+		plural.map_or_else(|| format!("{singular}s"), str::to_string)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateParts {
+	pub year: i64,
+	pub month: u32,
+	pub day: u32,
+	pub hour: u32,
+	pub minute: u32,
+	pub second: u32,
+}
+
+impl DateParts {
+	/// UTC date of a Unix timestamp in seconds.
+	pub fn from_timestamp(seconds: f64) -> Option<Self> {
+		if !seconds.is_finite() {
+			return None;
+		}
+		let seconds = seconds.floor() as i64;
+		let days = seconds.div_euclid(86_400);
+		let time = seconds.rem_euclid(86_400);
+		// Civil-from-days, see http://howardhinnant.github.io/date_algorithms.html
+		let z = days + 719_468;
+		let era = z.div_euclid(146_097);
+		let doe = z.rem_euclid(146_097);
+		let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+		let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+		let mp = (5 * doy + 2) / 153;
+		let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+		let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+		let year = yoe + era * 400 + i64::from(month <= 2);
+		Some(Self {
+			year,
+			month,
+			day,
+			hour: (time / 3_600) as u32,
+			minute: (time % 3_600 / 60) as u32,
+			second: (time % 60) as u32,
+		})
+	}
+
+	/// Parse `YYYY-MM-DD` with an optional `THH:MM[:SS]` (or space separated)
+	/// time; anything after the seconds, such as a zone, is ignored.
+	pub fn parse(value: &str) -> Option<Self> {
+		let value = value.trim();
+		let (date, time) = match value.find(['T', ' ']) {
+			Some(index) => (&value[..index], Some(&value[index + 1..])),
+			None => (value, None),
+		};
+		let mut date_parts = date.splitn(3, '-');
+		let year = date_parts.next()?.parse().ok()?;
+		let month = date_parts.next()?.parse().ok()?;
+		let day = date_parts.next()?.parse().ok()?;
+		if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+			return None;
+		}
+		let (mut hour, mut minute, mut second) = (0, 0, 0);
+		if let Some(time) = time {
+			let digits = |part: &str| -> Option<u32> {
+				part.get(..2).and_then(|digits| digits.parse().ok())
+			};
+			let mut time_parts = time.split(':');
+			hour = time_parts.next().and_then(digits)?;
+			minute = time_parts.next().and_then(digits)?;
+			second = time_parts.next().and_then(digits).unwrap_or(0);
+		}
+		Some(Self {
+			year,
+			month,
+			day,
+			hour,
+			minute,
+			second,
+		})
+	}
+}
+
+/// Values accepted by `format_date`: Unix timestamps in seconds or ISO strings.
+pub trait WuiDate {
+	fn wui_date(&self) -> Option<DateParts>;
+}
+
+impl WuiDate for str {
+	fn wui_date(&self) -> Option<DateParts> {
+		DateParts::parse(self)
+	}
+}
+
+impl WuiDate for String {
+	fn wui_date(&self) -> Option<DateParts> {
+		DateParts::parse(self)
+	}
+}
+
+impl<T: WuiDate + ?Sized> WuiDate for &T {
+	fn wui_date(&self) -> Option<DateParts> {
+		(**self).wui_date()
+	}
+}
+
+macro_rules! timestamp_date {
+	($($ty:ty),*) => {
+		$(impl WuiDate for $ty {
+			fn wui_date(&self) -> Option<DateParts> {
+				DateParts::from_timestamp(*self as f64)
+			}
+		})*
+	};
+}
+
+timestamp_date!(f64, f32, i64, u64, i32, u32, usize);
+
+const MONTHS: [&str; 12] = [
+	"January",
+	"February",
+	"March",
+	"April",
+	"May",
+	"June",
+	"July",
+	"August",
+	"September",
+	"October",
+	"November",
+	"December",
+];
+
+const SHORT_MONTHS: [&str; 12] = [
+	"Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Format a date with `pattern`; an unparseable date formats as `""`.
+pub fn format_date<D: WuiDate + ?Sized>(value: &D, pattern: &str) -> String {
+	format_date_with(value, pattern, &MONTHS, &SHORT_MONTHS)
+}
+
+/// [`format_date`] with the month names of a locale.
+pub(crate) fn format_date_with<D: WuiDate + ?Sized>(
+	value: &D,
+	pattern: &str,
+	months: &[&str; 12],
+	short_months: &[&str; 12],
+) -> String {
+	let Some(date) = value.wui_date() else {
+		return String::new();
+	};
+	let month = (date.month as usize).clamp(1, 12) - 1;
+	let tokens: [(&str, String); 12] = [
+		("YYYY", format!("{:04}", date.year)),
+		("YY", format!("{:02}", date.year.rem_euclid(100))),
+		("MMMM", months[month].to_string()),
+		("MMM", short_months[month].to_string()),
+		("MM", format!("{:02}", date.month)),
+		("M", date.month.to_string()),
+		("DD", format!("{:02}", date.day)),
+		("D", date.day.to_string()),
+		("HH", format!("{:02}", date.hour)),
+		("H", date.hour.to_string()),
+		("mm", format!("{:02}", date.minute)),
+		("ss", format!("{:02}", date.second)),
+	];
+	let mut out = String::new();
+	let mut rest = pattern;
+	'outer: while let Some(ch) = rest.chars().next() {
+		for (token, value) in &tokens {
+			if let Some(after) = rest.strip_prefix(token) {
+				out.push_str(value);
+				rest = after;
+				continue 'outer;
+			}
+		}
+		out.push(ch);
+		rest = &rest[ch.len_utf8()..];
+	}
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn arity_checks() {
+		assert!(lookup("slice").unwrap().accepts(2));
+		assert!(lookup("slice").unwrap().accepts(3));
+		assert!(!lookup("slice").unwrap().accepts(4));
+		assert!(lookup("max").unwrap().accepts(5));
+		assert_eq!(lookup("max").unwrap().arity(), "at least 2");
+		assert_eq!(lookup("round").unwrap().arity(), "1 to 2");
+		assert!(lookup("nope").is_none());
+	}
+
+	#[test]
+	fn formats_numbers() {
+		assert_eq!(format_number(1234567.891, None), "1,234,567.89");
+		assert_eq!(format_number(1000.0, None), "1,000");
+		assert_eq!(format_number(-1234.5, Some(2.0)), "-1,234.50");
+		assert_eq!(format_number(999.0, Some(0.0)), "999");
+		assert_eq!(round(2.345, Some(2.0)), 2.35);
+		assert_eq!(round(2.5, None), 3.0);
+		assert_eq!(format_number(1.5, Some(-3.0)), "2");
+		assert_eq!(format_number(1.5, Some(1e12)).len(), "1.".len() + 20);
+		assert_eq!(round(1.5, Some(f64::MAX)), 1.5);
+	}
+
+	#[test]
+	fn adds_like_the_runtime() {
+		assert_eq!(
+			add("Ada", &" Lovelace".to_string()),
+			WuiSum::Text("Ada Lovelace".to_string())
+		);
+		assert_eq!(add(&2u32, &0.5f64), WuiSum::Number(2.5));
+		assert_eq!(add(&1i32, "x"), WuiSum::Text("1x".to_string()));
+		assert_eq!(add(&None::<String>, &true), WuiSum::Number(0.0));
+		assert_eq!(add(&add("a", "b"), &3u8), WuiSum::Text("ab3".to_string()));
+	}
+
+	#[test]
+	fn slices_strings_and_lists() {
+		assert_eq!("héllo".wui_slice(1.0, Some(3.0)), "él");
+		assert_eq!("hello".wui_slice(-3.0, None), "llo");
+		assert_eq!(vec![1, 2, 3, 4].wui_slice(1.0, Some(-1.0)), vec![2, 3]);
+		assert_eq!(vec![1, 2].wui_slice(5.0, None), Vec::<i32>::new());
+		assert_eq!("héllo".wui_len(), 5);
+		assert!(vec!["a".to_string()].wui_contains(&"a"));
+		assert!("hello".wui_contains("ell"));
+	}
+
+	#[test]
+	fn formats_dates() {
+		assert_eq!(
+			format_date(&0.0, "YYYY-MM-DD HH:mm:ss"),
+			"1970-01-01 00:00:00"
+		);
+		assert_eq!(
+			format_date(&1_700_000_000i64, "D MMM YYYY, HH:mm"),
+			"14 Nov 2023, 22:13"
+		);
+		assert_eq!(
+			format_date("2024-02-29T08:05:00Z", "MMMM D, YY"),
+			"February 29, 24"
+		);
+		assert_eq!(format_date("not a date", "YYYY"), "");
+	}
+
+	#[test]
+	fn pluralizes() {
+		assert_eq!(pluralize(1.0, "item", None), "item");
+		assert_eq!(pluralize(3.0, "item", None), "items");
+		assert_eq!(pluralize(0.0, "person", Some("people")), "people");
+		assert_eq!(join(&[1, 2, 3], ", "), "1, 2, 3");
+	}
+}
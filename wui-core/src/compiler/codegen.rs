@@ -1,9 +1,11 @@
 use crate::ast::{BinaryOp, Expr, Literal, UnaryOp};
+use crate::builtins;
 use crate::compiler::ir::{
-	ActionDef, ActionPayload, EventKind, IrDocument, IrFor, IrIf, IrNode, IrProp, IrScope,
-	IrSwitch, IrWidget,
+	ActionDef, ActionPayload, EventKind, IrComponent, IrDocument, IrErrorBoundary, IrFor, IrIf,
+	IrLet, IrMatch, IrNode, IrProp, IrScope, IrSwitch, IrWidget,
 };
-use std::collections::BTreeSet;
+use crate::compiler::registry::{handler_key, ValueType};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 pub fn generate(doc: &IrDocument) -> String {
 	let mut out = String::new();
@@ -16,8 +18,12 @@ pub fn generate(doc: &IrDocument) -> String {
 		));
 	}
 	out.push('\n');
+	let (bindings, actions): (Vec<_>, Vec<_>) = doc
+		.actions
+		.iter()
+		.partition(|action| action.binding.is_some());
 	out.push_str("pub enum Action {\n");
-	for action in &doc.actions {
+	for action in &actions {
 		out.push_str(&format!(
 			"\t{}{},\n",
 			action_variant(&action.name),
@@ -27,7 +33,7 @@ pub fn generate(doc: &IrDocument) -> String {
 	out.push_str("}\n\n");
 	out.push_str("pub fn decode(event: &wgui::ClientEvent) -> Option<Action> {\n");
 	out.push_str("\tmatch event {\n");
-	for action in &doc.actions {
+	for action in &actions {
 		out.push_str(&decode_arm(action));
 	}
 	out.push_str("\t\t_ => None,\n\t}\n}\n\n");
@@ -37,7 +43,93 @@ pub fn generate(doc: &IrDocument) -> String {
 		.and_then(|page| page.state_type.clone())
 		.unwrap_or_else(|| "State".to_string());
 	let state_type_path = state_type_path(&state_type);
-	let param_names = collect_param_names(&doc.nodes);
+	out.push_str(&emit_params(doc));
+	out.push_str(&format!(
+		"pub fn render(state: &{}) -> Item {{\n",
+		state_type_path
+	));
+	out.push_str("\trender_with_path(state, \"\")\n");
+	out.push_str("}\n\n");
+	out.push_str(&format!(
+		"pub fn render_with_path(state: &{}, path: &str) -> Item {{\n",
+		state_type_path
+	));
+	out.push_str("\tlet __path = path;\n");
+	out.push_str(&emit_styles(doc));
+	out.push_str(&emit_root(doc));
+	out.push_str("}\n\n");
+	out.push_str(&format!(
+		"pub fn apply_binding(state: &mut {}, event: &wgui::ClientEvent) -> bool {{\n",
+		state_type_path
+	));
+	out.push_str("\tmatch event {\n");
+	for action in &bindings {
+		out.push_str(&binding_arm(action));
+	}
+	out.push_str("\t\t_ => false,\n\t}\n}\n\n");
+	out.push_str(&emit_route_helpers());
+	out
+}
+
+/// `doc` rendered as a block expression over `state` and `__path: &str`,
+/// for macros that inline the render into the crate owning the state type.
+/// Components are inlined at each call; imports cannot be circular, so the
+/// expansion is finite.
+pub fn generate_render_block(doc: &IrDocument) -> String {
+	let mut out = String::from("{\n");
+	out.push_str(&emit_params(doc));
+	out.push_str(&emit_route_helpers());
+	out.push_str(&emit_styles(doc));
+	out.push_str(&emit_root(doc));
+	out.push_str("}\n");
+	out
+}
+
+/// Bindings of the route values `doc` reads, for macros that inline the
+/// render into a route handler with a `RouteContext` named `__route` in
+/// scope: `path`, and `params` and `query` as structs of the keys read.
+pub fn generate_route_bindings(doc: &IrDocument) -> String {
+	let mut reads_path = false;
+	let mut fields = BTreeMap::<&str, BTreeSet<String>>::new();
+	visit_node_exprs(&doc.nodes, &mut |expr| {
+		visit_expr_paths(expr, &mut |parts| match parts {
+			[root, ..] if root == "path" => reads_path = true,
+			[root, key, ..] if root == "params" || root == "query" => {
+				let root = if root == "params" { "params" } else { "query" };
+				fields.entry(root).or_default().insert(key.clone());
+			}
+			_ => {}
+		});
+	});
+	let mut out = String::new();
+	if reads_path {
+		out.push_str("let path: &str = __path;\n");
+	}
+	for (root, keys) in &fields {
+		let ty = if *root == "params" {
+			"__WuiRouteParams"
+		} else {
+			"__WuiRouteQuery"
+		};
+		out.push_str(&format!("struct {ty} {{\n"));
+		for key in keys {
+			out.push_str(&format!("\t{}: String,\n", rust_ident(key)));
+		}
+		out.push_str(&format!("}}\nlet {root} = {ty} {{\n"));
+		for key in keys {
+			out.push_str(&format!(
+				"\t{}: __route.{root}.get({key:?}).cloned().unwrap_or_default(),\n",
+				rust_ident(key)
+			));
+		}
+		out.push_str("};\n");
+	}
+	out
+}
+
+fn emit_params(doc: &IrDocument) -> String {
+	let mut out = String::new();
+	let param_names = collect_param_names(doc);
 	out.push_str("#[derive(Clone, Default)]\n");
 	out.push_str("struct __WuiParams {\n");
 	for name in &param_names {
@@ -53,19 +145,11 @@ pub fn generate(doc: &IrDocument) -> String {
 		));
 	}
 	out.push_str("\t\t_ => {}\n\t}\n}\n\n");
-	out.push_str(&format!(
-		"pub fn render(state: &{}) -> Item {{\n",
-		state_type_path
-	));
-	out.push_str("\trender_with_path(state, \"\")\n");
-	out.push_str("}\n\n");
-	out.push_str(&format!(
-		"pub fn render_with_path(state: &{}, path: &str) -> Item {{\n",
-		state_type_path
-	));
-	out.push_str("\tlet __path = path;\n");
-	out.push_str(&emit_nodes(&doc.nodes, 1));
-	out.push_str("}\n\n");
+	out
+}
+
+fn emit_route_helpers() -> String {
+	let mut out = String::new();
 	out.push_str("fn __wui_route_params(route: &str, path: &str) -> Option<__WuiParams> {\n");
 	out.push_str("\tif route == path { return Some(__WuiParams::default()); }\n");
 	out.push_str("\tlet route_parts: Vec<&str> = route.trim_matches('/').split('/').filter(|s| !s.is_empty()).collect();\n");
@@ -106,6 +190,18 @@ pub fn generate(doc: &IrDocument) -> String {
 	out
 }
 
+fn emit_styles(doc: &IrDocument) -> String {
+	doc.styles
+		.iter()
+		.map(|style| {
+			format!(
+				"\twgui::wui::styles::register({:?}, {:?}, {:?});\n",
+				style.module, style.scope, style.css
+			)
+		})
+		.collect()
+}
+
 pub fn generate_controller_stub(doc: &IrDocument, module_name: &str) -> Option<String> {
 	let state_type = doc
 		.pages
@@ -123,7 +219,7 @@ pub fn generate_controller_stub(doc: &IrDocument, module_name: &str) -> Option<S
 		"impl {} {{\n\tpub fn new(state: {}) -> Self {{\n\t\tSelf {{ state }}\n\t}}\n\n",
 		controller_name, state_type_path
 	));
-	for action in &doc.actions {
+	for action in doc.actions.iter().filter(|action| action.binding.is_none()) {
 		let method = action_method_name(&action.name);
 		match action.payload {
 			ActionPayload::None => {
@@ -156,6 +252,12 @@ pub fn generate_controller_stub(doc: &IrDocument, module_name: &str) -> Option<S
 					method
 				));
 			}
+			ActionPayload::U32String => {
+				out.push_str(&format!(
+					"\tpub(crate) fn {}(&mut self, _arg: u32, _value: String) {{\n\t\t// TODO\n\t}}\n\n",
+					method
+				));
+			}
 			ActionPayload::Json => {
 				out.push_str(&format!(
 					"\tpub(crate) fn {}(&mut self, _payload: wgui::serde_json::Value) {{\n\t\t// TODO\n\t}}\n\n",
@@ -175,6 +277,7 @@ fn action_payload(action: &ActionDef) -> String {
 		ActionPayload::String => " { value: String }".to_string(),
 		ActionPayload::I32 => " { value: i32 }".to_string(),
 		ActionPayload::U32I32 => " { arg: u32, value: i32 }".to_string(),
+		ActionPayload::U32String => " { arg: u32, value: String }".to_string(),
 		ActionPayload::Json => " { payload: wgui::serde_json::Value }".to_string(),
 	}
 }
@@ -213,6 +316,19 @@ fn decode_arm(action: &ActionDef) -> String {
 		EventKind::Select => format!(
 			"\t\twgui::ClientEvent::OnSelect(ev) if ev.id == {id} => Some(Action::{variant} {{ value: ev.value.clone() }}),\n"
 		),
+		EventKind::KeyDown | EventKind::KeyUp => match action.payload {
+			ActionPayload::String => format!(
+				"\t\twgui::ClientEvent::{}(ev) if ev.id.as_deref() == Some(\"{id}\"){} => Some(Action::{variant} {{ value: ev.keycode.clone() }}),\n",
+				client_event_variant(&action.kind),
+				key_combo_guard(action)
+			),
+			ActionPayload::U32String => format!(
+				"\t\twgui::ClientEvent::{}(ev) if ev.id.as_deref() == Some(\"{id}\"){} => ev.inx.map(|arg| Action::{variant} {{ arg, value: ev.keycode.clone() }}),\n",
+				client_event_variant(&action.kind),
+				key_combo_guard(action)
+			),
+			_ => String::new(),
+		},
 		EventKind::Custom(event_name) => format!(
 			"\t\twgui::ClientEvent::OnCustom(ev) if ev.id == {id} && ev.name == {:?} => Some(Action::{variant} {{ payload: ev.payload.clone() }}),\n",
 			event_name
@@ -220,6 +336,53 @@ fn decode_arm(action: &ActionDef) -> String {
 	}
 }
 
+/// `&& (ev.matches(..) || ..)` for a key action whose elements all declare a
+/// literal `key`, so other keys sent with its id don't fire it.
+fn key_combo_guard(action: &ActionDef) -> String {
+	let Some(combos) = action
+		.key_combos
+		.iter()
+		.cloned()
+		.collect::<Option<Vec<_>>>()
+	else {
+		return String::new();
+	};
+	if combos.is_empty() {
+		return String::new();
+	}
+	let checks = combos
+		.iter()
+		.map(|combo| format!("ev.matches({combo:?})"))
+		.collect::<Vec<_>>()
+		.join(" || ");
+	format!(" && ({checks})")
+}
+
+fn binding_arm(action: &ActionDef) -> String {
+	let id = action.id;
+	let path = action
+		.binding
+		.iter()
+		.flatten()
+		.map(|segment| format!("{segment:?}.to_string()"))
+		.collect::<Vec<_>>()
+		.join(", ");
+	let (event, write) = match &action.kind {
+		EventKind::Click => ("OnClick", "Toggle".to_string()),
+		EventKind::SliderChange => (
+			"OnSliderChange",
+			"Set(wgui::wui::runtime::WuiValue::Number(ev.value as f64))".to_string(),
+		),
+		kind => (
+			client_event_variant(kind),
+			"Set(wgui::wui::runtime::WuiValue::String(ev.value.clone()))".to_string(),
+		),
+	};
+	format!(
+		"\t\twgui::ClientEvent::{event}(ev) if ev.id == {id} => wgui::wui::runtime::apply_binding(state, &[{path}], wgui::wui::runtime::BindingWrite::{write}),\n"
+	)
+}
+
 fn client_event_variant(kind: &EventKind) -> &'static str {
 	match kind {
 		EventKind::Click => "OnClick",
@@ -230,44 +393,147 @@ fn client_event_variant(kind: &EventKind) -> &'static str {
 		EventKind::SliderChange => "OnSliderChange",
 		EventKind::Select => "OnSelect",
 		EventKind::ScrollNearBottom => "OnScrollNearBottom",
+		EventKind::KeyDown => "OnKeyDown",
+		EventKind::KeyUp => "OnKeyUp",
 		EventKind::Custom(_) => "OnCustom",
 	}
 }
 
-fn emit_nodes(nodes: &[IrNode], indent: usize) -> String {
-	let mut out = String::new();
-	let indent_str = "\t".repeat(indent);
-	out.push_str(&format!("{indent_str}let mut children = Vec::new();\n"));
-	for node in nodes {
-		out.push_str(&emit_node_into(node, indent, "children"));
+/// What emitted nodes can refer to: the document's components, the call an
+/// inlined component is emitted for, and the template names in scope.
+#[derive(Clone, Copy)]
+struct Cx<'a> {
+	components: &'a HashMap<String, IrComponent>,
+	call: Option<&'a Call<'a>>,
+	scope: Option<&'a Scope<'a>>,
+}
+
+/// Template names bound by the Rust emitted so far, innermost first.
+struct Scope<'a> {
+	names: Vec<String>,
+	parent: Option<&'a Scope<'a>>,
+}
+
+/// A component call being inlined. `<Children>` and `<Slot>` in the
+/// component body emit `content` as written at the call site, in `caller`.
+struct Call<'a> {
+	content: &'a [IrNode],
+	caller: Cx<'a>,
+}
+
+impl<'a> Cx<'a> {
+	fn new(doc: &'a IrDocument) -> Self {
+		Self {
+			components: &doc.components,
+			call: None,
+			scope: None,
+		}
 	}
-	out.push_str(&format!("{indent_str}wgui::vstack(children)\n"));
+
+	fn with_scope<'b>(self, scope: &'b Scope<'b>) -> Cx<'b>
+	where
+		'a: 'b,
+	{
+		Cx {
+			scope: Some(scope),
+			..self
+		}
+	}
+
+	fn binds(&self, name: &str) -> bool {
+		let mut scope = self.scope;
+		while let Some(current) = scope {
+			if current.names.iter().any(|bound| bound == name) {
+				return true;
+			}
+			scope = current.parent;
+		}
+		name == "state"
+	}
+
+	fn scope(&self, names: Vec<String>) -> Scope<'a> {
+		Scope {
+			names,
+			parent: self.scope,
+		}
+	}
+}
+
+/// The root nodes, unwrapped when there is a single one like the runtime
+/// does.
+fn emit_root(doc: &IrDocument) -> String {
+	let mut out = String::from("\tlet mut __children = Vec::new();\n");
+	out.push_str(&emit_body(&doc.nodes, 1, "__children", Cx::new(doc)));
+	out.push_str(
+		"\tif __children.len() == 1 { __children.remove(0) } else { wgui::vstack(__children).fill(true) }\n",
+	);
 	out
 }
 
-fn emit_node_into(node: &IrNode, indent: usize, target: &str) -> String {
+fn emit_node_into(node: &IrNode, indent: usize, target: &str, cx: Cx) -> String {
 	let indent_str = "\t".repeat(indent);
 	match node {
 		IrNode::Widget(widget) => {
-			let rendered = emit_widget(widget, indent + 1);
+			let rendered = emit_widget(widget, indent + 1, cx);
 			format!("{indent_str}{target}.push({rendered});\n")
 		}
 		IrNode::Text(text) => format!("{indent_str}{target}.push(wgui::text({:?}));\n", text),
-		IrNode::For(for_node) => emit_for(for_node, indent, target),
-		IrNode::If(if_node) => emit_if(if_node, indent, target),
-		IrNode::Scope(scope) => emit_scope(scope, indent, target),
-		IrNode::Route(route) => emit_route(route, indent, target),
-		IrNode::Switch(node) => emit_switch(node, indent, target),
-		IrNode::Children => String::new(),
+		IrNode::For(for_node) => emit_for(for_node, indent, target, cx),
+		IrNode::If(if_node) => emit_if(if_node, indent, target, cx),
+		IrNode::Let(node) => emit_let(node, indent, target, cx),
+		IrNode::Match(node) => emit_match(node, indent, target, cx),
+		IrNode::Scope(scope) => emit_scope(scope, indent, target, cx),
+		IrNode::Route(route) => emit_route(route, indent, target, cx),
+		IrNode::Switch(node) => emit_switch(node, indent, target, cx),
+		IrNode::ErrorBoundary(node) => emit_error_boundary(node, indent, target, cx),
+		// Call-site content is emitted inside the component's block, where
+		// its props shadow the caller's names as they do in the interpreter.
+		IrNode::Children => cx.call.map_or_else(String::new, |call| {
+			let content_cx = Cx {
+				call: call.caller.call,
+				..cx
+			};
+			call.content
+				.iter()
+				.filter(|node| !matches!(node, IrNode::Slot(_)))
+				.map(|node| emit_node_into(node, indent, target, content_cx))
+				.collect()
+		}),
+		IrNode::Slot(slot) => {
+			let fill = cx.call.and_then(|call| {
+				call.content.iter().find_map(|node| match node {
+					IrNode::Slot(fill) if fill.name == slot.name => Some((&fill.body, call)),
+					_ => None,
+				})
+			});
+			match fill {
+				Some((body, call)) => {
+					let content_cx = Cx {
+						call: call.caller.call,
+						..cx
+					};
+					emit_body(body, indent, target, content_cx)
+				}
+				None => emit_body(&slot.body, indent, target, cx),
+			}
+		}
 	}
 }
 
-fn emit_for(node: &IrFor, indent: usize, target: &str) -> String {
+fn emit_for(node: &IrFor, indent: usize, target: &str, cx: Cx) -> String {
 	let indent_str = "\t".repeat(indent);
 	let mut out = String::new();
 	let list_expr = emit_expr(&node.each);
-	let item = &node.item;
+	let item = rust_ident(&node.item);
+	let scope = cx.scope(
+		[node.item.clone()]
+			.into_iter()
+			.chain(node.index.clone())
+			.collect(),
+	);
+	let cx = cx.with_scope(&scope);
 	if let Some(index) = &node.index {
+		let index = rust_ident(index);
 		out.push_str(&format!(
 			"{indent_str}for ({index}, {item}) in {list_expr}.iter().enumerate() {{\n"
 		));
@@ -276,21 +542,24 @@ fn emit_for(node: &IrFor, indent: usize, target: &str) -> String {
 			"{indent_str}for {item} in {list_expr}.iter() {{\n"
 		));
 	}
-	out.push_str(&emit_body(&node.body, indent + 1, target));
+	out.push_str(&emit_body(&node.body, indent + 1, target, cx));
 	out.push_str(&format!("{indent_str}}}\n"));
 	out
 }
 
-fn emit_if(node: &IrIf, indent: usize, target: &str) -> String {
+fn emit_if(node: &IrIf, indent: usize, target: &str, cx: Cx) -> String {
 	let indent_str = "\t".repeat(indent);
 	let mut out = String::new();
-	let test = emit_expr(&node.test);
+	let test = emit_bool_expr(&node.test);
 	out.push_str(&format!("{indent_str}if {test} {{\n"));
-	out.push_str(&emit_body(&node.then_body, indent + 1, target));
+	out.push_str(&emit_body(&node.then_body, indent + 1, target, cx));
 	out.push_str(&format!("{indent_str}}}"));
-	if !node.else_body.is_empty() {
+	if let [IrNode::If(else_if)] = node.else_body.as_slice() {
+		out.push_str(" else ");
+		out.push_str(emit_if(else_if, indent, target, cx).trim_start());
+	} else if !node.else_body.is_empty() {
 		out.push_str(" else {\n");
-		out.push_str(&emit_body(&node.else_body, indent + 1, target));
+		out.push_str(&emit_body(&node.else_body, indent + 1, target, cx));
 		out.push_str(&format!("{indent_str}}}\n"));
 	} else {
 		out.push('\n');
@@ -298,11 +567,88 @@ fn emit_if(node: &IrIf, indent: usize, target: &str) -> String {
 	out
 }
 
-fn emit_scope(node: &IrScope, indent: usize, target: &str) -> String {
-	emit_body(&node.body, indent, target)
+fn emit_let(node: &IrLet, indent: usize, target: &str, cx: Cx) -> String {
+	let indent_str = "\t".repeat(indent);
+	let name = rust_ident(&node.name);
+	let scope = cx.scope(vec![node.name.clone()]);
+	let cx = cx.with_scope(&scope);
+	let mut out = String::new();
+	out.push_str(&format!("{indent_str}{{\n"));
+	out.push_str(&format!(
+		"{indent_str}\tlet {name} = &({});\n",
+		emit_expr(&node.value)
+	));
+	out.push_str(&format!("{indent_str}\tlet _ = &{name};\n"));
+	out.push_str(&emit_body(&node.body, indent + 1, target, cx));
+	out.push_str(&format!("{indent_str}}}\n"));
+	out
+}
+
+fn emit_match(node: &IrMatch, indent: usize, target: &str, cx: Cx) -> String {
+	if node.cases.is_empty() && node.else_body.is_empty() {
+		return String::new();
+	}
+	let indent_str = "\t".repeat(indent);
+	let mut out = String::new();
+	out.push_str(&format!("{indent_str}{{\n"));
+	out.push_str(&format!(
+		"{indent_str}\tlet __wui_match = {};\n",
+		emit_owned_text(&node.value)
+	));
+	out.push_str(&format!("{indent_str}\t"));
+	for (index, case) in node.cases.iter().enumerate() {
+		if index > 0 {
+			out.push_str(" else ");
+		}
+		out.push_str(&format!("if __wui_match == {:?} {{\n", case.is));
+		out.push_str(&emit_body(&case.body, indent + 2, target, cx));
+		out.push_str(&format!("{indent_str}\t}}"));
+	}
+	if !node.else_body.is_empty() {
+		if node.cases.is_empty() {
+			out.push_str("{\n");
+		} else {
+			out.push_str(" else {\n");
+		}
+		out.push_str(&emit_body(&node.else_body, indent + 2, target, cx));
+		out.push_str(&format!("{indent_str}\t}}"));
+	}
+	out.push('\n');
+	out.push_str(&format!("{indent_str}}}\n"));
+	out
+}
+
+/// The body is built in a closure so a panic in it can be caught; nested
+/// boundaries shadow `__boundary_items` inside their own closure.
+fn emit_error_boundary(node: &IrErrorBoundary, indent: usize, target: &str, cx: Cx) -> String {
+	let indent_str = "\t".repeat(indent);
+	let mut out = String::new();
+	out.push_str(&format!(
+		"{indent_str}match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {{\n"
+	));
+	out.push_str(&format!(
+		"{indent_str}\tlet mut __boundary_items = Vec::new();\n"
+	));
+	out.push_str(&emit_body(&node.body, indent + 1, "__boundary_items", cx));
+	out.push_str(&format!("{indent_str}\t__boundary_items\n"));
+	out.push_str(&format!("{indent_str}}})) {{\n"));
+	out.push_str(&format!(
+		"{indent_str}\tOk(items) => {target}.extend(items),\n"
+	));
+	out.push_str(&format!("{indent_str}\tErr(_) => {{\n"));
+	out.push_str(&emit_body(&node.fallback, indent + 2, target, cx));
+	out.push_str(&format!("{indent_str}\t}}\n"));
+	out.push_str(&format!("{indent_str}}}\n"));
+	out
+}
+
+fn emit_scope(node: &IrScope, indent: usize, target: &str, cx: Cx) -> String {
+	emit_body(&node.body, indent, target, cx)
 }
 
-fn emit_route(node: &crate::compiler::ir::IrRoute, indent: usize, target: &str) -> String {
+fn emit_route(node: &crate::compiler::ir::IrRoute, indent: usize, target: &str, cx: Cx) -> String {
+	let scope = cx.scope(vec!["params".to_string()]);
+	let cx = cx.with_scope(&scope);
 	let indent_str = "\t".repeat(indent);
 	let mut out = String::new();
 	out.push_str(&format!(
@@ -310,12 +656,14 @@ fn emit_route(node: &crate::compiler::ir::IrRoute, indent: usize, target: &str)
 		node.path
 	));
 	out.push_str(&format!("{indent_str}\tlet _ = &params;\n"));
-	out.push_str(&emit_body(&node.body, indent + 1, target));
+	out.push_str(&emit_body(&node.body, indent + 1, target, cx));
 	out.push_str(&format!("{indent_str}}}\n"));
 	out
 }
 
-fn emit_switch(node: &IrSwitch, indent: usize, target: &str) -> String {
+fn emit_switch(node: &IrSwitch, indent: usize, target: &str, cx: Cx) -> String {
+	let scope = cx.scope(vec!["params".to_string()]);
+	let cx = cx.with_scope(&scope);
 	let indent_str = "\t".repeat(indent);
 	let mut out = String::new();
 	let mut first = true;
@@ -326,7 +674,7 @@ fn emit_switch(node: &IrSwitch, indent: usize, target: &str) -> String {
 				case.path
 			));
 			out.push_str(&format!("{indent_str}\tlet _ = &params;\n"));
-			out.push_str(&emit_body(&case.body, indent + 1, target));
+			out.push_str(&emit_body(&case.body, indent + 1, target, cx));
 			out.push_str(&format!("{indent_str}}}"));
 			first = false;
 		} else {
@@ -335,7 +683,7 @@ fn emit_switch(node: &IrSwitch, indent: usize, target: &str) -> String {
 				case.path
 			));
 			out.push_str(&format!("{indent_str}\tlet _ = &params;\n"));
-			out.push_str(&emit_body(&case.body, indent + 1, target));
+			out.push_str(&emit_body(&case.body, indent + 1, target, cx));
 			out.push_str(&format!("{indent_str}}}"));
 		}
 	}
@@ -345,30 +693,28 @@ fn emit_switch(node: &IrSwitch, indent: usize, target: &str) -> String {
 	out
 }
 
-fn collect_param_names(nodes: &[IrNode]) -> Vec<String> {
+fn collect_param_names(doc: &IrDocument) -> Vec<String> {
 	let mut names = BTreeSet::new();
-	collect_param_names_into(nodes, &mut names);
+	collect_param_names_into(&doc.nodes, &mut names);
+	for component in doc.components.values() {
+		collect_param_names_into(&component.body, &mut names);
+	}
 	names.into_iter().collect()
 }
 
 fn collect_param_names_into(nodes: &[IrNode], out: &mut BTreeSet<String>) {
 	for node in nodes {
 		match node {
-			IrNode::Widget(widget) => collect_param_names_into(&widget.children, out),
-			IrNode::For(node) => collect_param_names_into(&node.body, out),
-			IrNode::If(node) => {
-				collect_param_names_into(&node.then_body, out);
-				collect_param_names_into(&node.else_body, out);
-			}
-			IrNode::Scope(node) => collect_param_names_into(&node.body, out),
 			IrNode::Route(node) => collect_names_from_route(&node.path, out),
 			IrNode::Switch(node) => {
 				for case in &node.cases {
 					collect_names_from_route(&case.path, out);
-					collect_param_names_into(&case.body, out);
 				}
 			}
-			IrNode::Children | IrNode::Text(_) => {}
+			_ => {}
+		}
+		for body in node_bodies(node) {
+			collect_param_names_into(body, out);
 		}
 	}
 }
@@ -388,306 +734,610 @@ fn collect_names_from_route(route: &str, out: &mut BTreeSet<String>) {
 	}
 }
 
-fn emit_body(nodes: &[IrNode], indent: usize, target: &str) -> String {
+/// The node lists nested directly in `node`.
+fn node_bodies(node: &IrNode) -> Vec<&[IrNode]> {
+	match node {
+		IrNode::Widget(widget) => vec![&widget.children],
+		IrNode::For(node) => vec![&node.body],
+		IrNode::If(node) => vec![&node.then_body, &node.else_body],
+		IrNode::Let(node) => vec![&node.body],
+		IrNode::Match(node) => node
+			.cases
+			.iter()
+			.map(|case| case.body.as_slice())
+			.chain([node.else_body.as_slice()])
+			.collect(),
+		IrNode::Scope(node) => vec![&node.body],
+		IrNode::Route(node) => vec![&node.body],
+		IrNode::Switch(node) => node.cases.iter().map(|case| case.body.as_slice()).collect(),
+		IrNode::Slot(node) => vec![&node.body],
+		IrNode::ErrorBoundary(node) => vec![&node.body, &node.fallback],
+		IrNode::Children | IrNode::Text(_) => Vec::new(),
+	}
+}
+
+/// Root names of the paths `nodes` read, minus the names they bind
+/// themselves. Bodies of nested components are left to their own calls.
+fn collect_free_names(nodes: &[IrNode], out: &mut BTreeSet<String>) {
+	let mut read = BTreeSet::new();
+	let mut bound = BTreeSet::from(["state".to_string(), "params".to_string()]);
+	collect_names(nodes, &mut read, &mut bound);
+	out.extend(read.difference(&bound).cloned());
+}
+
+fn collect_names(nodes: &[IrNode], read: &mut BTreeSet<String>, bound: &mut BTreeSet<String>) {
+	for node in nodes {
+		match node {
+			IrNode::Widget(widget) => {
+				for prop in &widget.props {
+					match prop {
+						IrProp::Value { expr, .. } | IrProp::Bind { expr, .. } => {
+							collect_expr_names(expr, read);
+						}
+						IrProp::Event {
+							arg: Some(expr), ..
+						} => collect_expr_names(expr, read),
+						_ => {}
+					}
+				}
+			}
+			IrNode::For(node) => {
+				collect_expr_names(&node.each, read);
+				bound.insert(node.item.clone());
+				bound.extend(node.index.clone());
+			}
+			IrNode::If(node) => collect_expr_names(&node.test, read),
+			IrNode::Let(node) => {
+				collect_expr_names(&node.value, read);
+				bound.insert(node.name.clone());
+			}
+			IrNode::Match(node) => collect_expr_names(&node.value, read),
+			_ => {}
+		}
+		for body in node_bodies(node) {
+			collect_names(body, read, bound);
+		}
+	}
+}
+
+/// Call `visit` with every expression `nodes` evaluate, bodies included.
+fn visit_node_exprs(nodes: &[IrNode], visit: &mut dyn FnMut(&Expr)) {
+	for node in nodes {
+		match node {
+			IrNode::Widget(widget) => {
+				for prop in &widget.props {
+					match prop {
+						IrProp::Value { expr, .. } | IrProp::Bind { expr, .. } => visit(expr),
+						IrProp::Event {
+							arg: Some(expr), ..
+						} => visit(expr),
+						_ => {}
+					}
+				}
+			}
+			IrNode::For(node) => visit(&node.each),
+			IrNode::If(node) => visit(&node.test),
+			IrNode::Let(node) => visit(&node.value),
+			IrNode::Match(node) => visit(&node.value),
+			_ => {}
+		}
+		for body in node_bodies(node) {
+			visit_node_exprs(body, visit);
+		}
+	}
+}
+
+/// Call `visit` with the parts of every path `expr` reads.
+fn visit_expr_paths(expr: &Expr, visit: &mut dyn FnMut(&[String])) {
+	match expr {
+		Expr::Literal(..) => {}
+		Expr::Path(parts, _) => visit(parts),
+		Expr::Call { args, .. } => {
+			for arg in args {
+				visit_expr_paths(arg, visit);
+			}
+		}
+		Expr::Unary { expr, .. } => visit_expr_paths(expr, visit),
+		Expr::Binary { left, right, .. } | Expr::Coalesce { left, right, .. } => {
+			visit_expr_paths(left, visit);
+			visit_expr_paths(right, visit);
+		}
+		Expr::Ternary {
+			cond,
+			then_expr,
+			else_expr,
+			..
+		} => {
+			visit_expr_paths(cond, visit);
+			visit_expr_paths(then_expr, visit);
+			visit_expr_paths(else_expr, visit);
+		}
+	}
+}
+
+fn collect_expr_names(expr: &Expr, out: &mut BTreeSet<String>) {
+	match expr {
+		Expr::Literal(..) => {}
+		Expr::Path(parts, _) => out.extend(parts.first().cloned()),
+		Expr::Call { args, .. } => {
+			for arg in args {
+				collect_expr_names(arg, out);
+			}
+		}
+		Expr::Unary { expr, .. } => collect_expr_names(expr, out),
+		Expr::Binary { left, right, .. } | Expr::Coalesce { left, right, .. } => {
+			collect_expr_names(left, out);
+			collect_expr_names(right, out);
+		}
+		Expr::Ternary {
+			cond,
+			then_expr,
+			else_expr,
+			..
+		} => {
+			collect_expr_names(cond, out);
+			collect_expr_names(then_expr, out);
+			collect_expr_names(else_expr, out);
+		}
+	}
+}
+
+fn emit_body(nodes: &[IrNode], indent: usize, target: &str, cx: Cx) -> String {
 	let mut out = String::new();
 	for node in nodes {
-		out.push_str(&emit_node_into(node, indent, target));
+		out.push_str(&emit_node_into(node, indent, target, cx));
 	}
 	out
 }
 
-fn emit_widget(widget: &IrWidget, indent: usize) -> String {
-	let mut base = match widget.tag.as_str() {
-		"VStack" => emit_container("vstack", &widget.children, indent),
-		"HStack" => emit_container("hstack", &widget.children, indent),
-		"Form" => emit_container("form", &widget.children, indent),
-		"Connected" => emit_container("connected", &widget.children, indent),
-		"Disconnected" => emit_container("disconnected", &widget.children, indent),
-		"Text" => emit_text(widget),
-		"Button" => emit_textual("button", widget, "text"),
-		"Link" => emit_link(widget),
+fn emit_widget(widget: &IrWidget, indent: usize, cx: Cx) -> String {
+	if let Some(component) = cx.components.get(&widget.tag) {
+		return emit_component(widget, component, indent, cx);
+	}
+	let mut base = widget_constructor(widget, indent, cx);
+	for prop in &widget.props {
+		if !should_emit_prop(&widget.tag, prop) {
+			continue;
+		}
+		if let IrProp::Event { name, action, arg } = prop {
+			base = apply_event(base, name, action, arg.as_ref());
+		} else if let Some(method) = emit_prop(prop) {
+			base = format!("{base}.{method}");
+		}
+	}
+	base
+}
+
+fn widget_constructor(widget: &IrWidget, indent: usize, cx: Cx) -> String {
+	match widget.tag.as_str() {
+		"VStack" => emit_container("vstack", &widget.children, indent, cx),
+		"HStack" => emit_container("hstack", &widget.children, indent, cx),
+		"Form" => emit_container("form", &widget.children, indent, cx),
+		"Connected" => emit_container("connected", &widget.children, indent, cx),
+		"Disconnected" => emit_container("disconnected", &widget.children, indent, cx),
+		"Text" => format!("wgui::text({})", textual_value(widget, "value")),
+		"Button" => format!("wgui::button({})", textual_value(widget, "text")),
+		"Link" => format!(
+			"wgui::link({}, {})",
+			textual_value(widget, "href"),
+			textual_value(widget, "text")
+		),
 		"TextInput" => "wgui::text_input()".to_string(),
 		"DatePicker" => "wgui::date_picker()".to_string(),
 		"Checkbox" => "wgui::checkbox()".to_string(),
 		"Slider" => "wgui::slider()".to_string(),
 		"Select" => emit_select(widget),
 		"Image" => emit_image(widget),
-		"Video" => emit_media(widget, "video"),
-		"Audio" => emit_media(widget, "audio"),
+		"Video" => format!("wgui::video({})", textual_value(widget, "room")),
+		"Audio" => format!("wgui::audio({})", textual_value(widget, "room")),
 		"FolderPicker" => "wgui::folder_picker()".to_string(),
-		"Modal" => emit_container("modal", &widget.children, indent),
+		"Shortcut" => format!("wgui::shortcut({})", textual_value(widget, "keyCombo")),
+		"Modal" => emit_container("modal", &widget.children, indent, cx),
 		"Custom" | "CustomComponent" => emit_custom(widget),
 		_ => "wgui::text(\"unsupported\")".to_string(),
+	}
+}
+
+/// A component call, inlined: the props are bound over the component body,
+/// which renders as itself when it is a single item.
+fn emit_component(widget: &IrWidget, component: &IrComponent, indent: usize, cx: Cx) -> String {
+	let indent_str = "\t".repeat(indent);
+	let mut out = String::from("{\n");
+	out.push_str(&format!("{indent_str}let mut __items = Vec::new();\n"));
+	out.push_str(&format!("{indent_str}{{\n"));
+	let mut bindings = component_bindings(widget, component);
+	// Names the body reads but nothing binds are null, like unset variables
+	// in the interpreter.
+	let mut free = BTreeSet::new();
+	collect_free_names(&component.body, &mut free);
+	for name in free {
+		let ident = rust_ident(&name);
+		if is_rust_ident(&name)
+			&& !cx.binds(&name)
+			&& !bindings.iter().any(|(bound, _)| *bound == ident)
+		{
+			bindings.push((ident, "None::<&str>".to_string()));
+		}
+	}
+	let scope = cx.scope(
+		bindings
+			.iter()
+			.map(|(name, _)| name.trim_start_matches("r#").to_string())
+			.collect(),
+	);
+	let (names, values): (Vec<_>, Vec<_>) = bindings.into_iter().unzip();
+	if !names.is_empty() {
+		// Bound at once, so every value still sees the caller's names.
+		out.push_str(&format!(
+			"{indent_str}\t#[allow(unused_variables)]\n{indent_str}\tlet ({},) = ({},);\n",
+			names.join(", "),
+			values.join(", ")
+		));
+	}
+	let call = Call {
+		content: &widget.children,
+		caller: cx,
+	};
+	let inner = Cx {
+		components: cx.components,
+		call: Some(&call),
+		scope: Some(&scope),
 	};
+	out.push_str(&emit_body(&component.body, indent + 1, "__items", inner));
+	out.push_str(&format!("{indent_str}}}\n"));
+	let mut item =
+		"(if __items.len() == 1 { __items.remove(0) } else { wgui::vstack(__items).fill(true) })"
+			.to_string();
 	for prop in &widget.props {
-		if !should_emit_prop(&widget.tag, prop) {
+		if let IrProp::Event { name, action, arg } = prop {
+			item = apply_event(item, name, action, arg.as_ref());
+		}
+	}
+	out.push_str(&format!("{indent_str}{item}\n{indent_str}}}"));
+	out
+}
+
+/// The names a component body sees: its declared props, from the call site
+/// or their defaults, then any other props the call passes.
+fn component_bindings(widget: &IrWidget, component: &IrComponent) -> Vec<(String, String)> {
+	let call_site = |name: &str| {
+		widget
+			.props
+			.iter()
+			.rev()
+			.find(|prop| prop_name(prop) == name)
+			.and_then(bound_prop_value)
+	};
+	let mut bindings = Vec::new();
+	for prop in &component.props {
+		let value = call_site(&prop.name).unwrap_or_else(|| match &prop.default {
+			Some(default) => bound_expr(default),
+			None => format!(
+				"None::<{}>",
+				match prop.ty {
+					ValueType::Number => "f64",
+					ValueType::Bool => "bool",
+					_ => "&str",
+				}
+			),
+		});
+		bindings.push((rust_ident(&prop.name), value));
+	}
+	for prop in &widget.props {
+		let name = prop_name(prop);
+		let declared = component.props.iter().any(|declared| declared.name == name);
+		let bound = bindings.iter().any(|(bound, _)| *bound == rust_ident(name));
+		if declared || bound || !is_rust_ident(name) {
 			continue;
 		}
-		base = format!("{}.{}", base, emit_prop(prop));
+		if let Some(value) = call_site(name) {
+			bindings.push((rust_ident(name), value));
+		}
+	}
+	bindings
+}
+
+fn prop_name(prop: &IrProp) -> &str {
+	match prop {
+		IrProp::Literal { name, .. }
+		| IrProp::Number { name, .. }
+		| IrProp::Bool { name, .. }
+		| IrProp::Value { name, .. }
+		| IrProp::Bind { name, .. }
+		| IrProp::Event { name, .. } => name,
+	}
+}
+
+fn bound_prop_value(prop: &IrProp) -> Option<String> {
+	match prop {
+		IrProp::Literal { value, .. } => Some(format!("{value:?}")),
+		IrProp::Number { value, .. } => Some(format!("{value:?}_f64")),
+		IrProp::Bool { value, .. } => Some(value.to_string()),
+		IrProp::Value { expr, .. } | IrProp::Bind { expr, .. } => Some(bound_expr(expr)),
+		IrProp::Event { .. } => None,
+	}
+}
+
+fn bound_expr(expr: &Expr) -> String {
+	match expr {
+		Expr::Literal(Literal::String(value), _) => format!("{value:?}"),
+		Expr::Literal(Literal::Number(value), _) => format!("{value:?}_f64"),
+		Expr::Literal(Literal::Bool(value), _) => value.to_string(),
+		Expr::Literal(Literal::Null, _) => "None::<&str>".to_string(),
+		_ => format!("&({})", emit_expr(expr)),
 	}
-	base
 }
 
 fn emit_select(widget: &IrWidget) -> String {
-	let mut value = "\"\"".to_string();
-	let mut options = "Vec::new()".to_string();
+	let mut value = None;
+	let mut options = "std::iter::empty()".to_string();
 	for prop in &widget.props {
 		match prop {
 			IrProp::Literal {
 				name,
 				value: prop_value,
 			} if name == "value" => {
-				value = format!("{:?}", prop_value);
+				value = Some(format!("{prop_value:?}"));
 			}
 			IrProp::Value { name, expr } if name == "value" => {
-				value = emit_string_expr(expr);
+				value = Some(emit_text_expr(expr));
+			}
+			IrProp::Bind { name, expr } if name == "bind:value" => {
+				value = Some(emit_text_expr(expr));
 			}
 			IrProp::Value { name, expr } if name == "options" => {
-				let expr = emit_expr(expr);
+				// Options without a value are dropped, and a missing name
+				// shows the value, as in the interpreter.
 				options = format!(
-					"{expr}.iter().map(|option| wgui::option(&option.value, &option.name))"
+					"({}).iter().filter_map(|option| {{ \
+					 let value = {BUILTINS_PATH}::WuiText::wui_text(&option.value); \
+					 let name = {BUILTINS_PATH}::WuiText::wui_text(&option.name); \
+					 (!value.is_empty()).then(|| wgui::option(&value, if name.is_empty() {{ &value }} else {{ &name }})) }})",
+					emit_expr(expr)
 				);
 			}
 			_ => {}
 		}
 	}
-	format!("wgui::select({options}).svalue({value})")
+	let mut select = format!("wgui::select({options})");
+	if let Some(value) = value {
+		select = format!("{select}.svalue({value})");
+	}
+	select
 }
 
-fn emit_container(kind: &str, children: &[IrNode], indent: usize) -> String {
+fn emit_container(kind: &str, children: &[IrNode], indent: usize, cx: Cx) -> String {
 	let mut out = String::new();
 	let indent_str = "\t".repeat(indent);
 	out.push_str("{\n");
-	out.push_str(&format!("{indent_str}let mut items = Vec::new();\n"));
-	for node in children {
-		out.push_str(&emit_container_child(node, indent, "items"));
-	}
-	out.push_str(&format!("{indent_str}wgui::{kind}(items)\n"));
+	out.push_str(&format!("{indent_str}let mut __items = Vec::new();\n"));
+	out.push_str(&emit_body(children, indent, "__items", cx));
+	out.push_str(&format!("{indent_str}wgui::{kind}(__items)\n"));
 	out.push_str(&format!("{indent_str}}}"));
 	out
 }
 
-fn emit_container_child(node: &IrNode, indent: usize, target: &str) -> String {
-	emit_node_into(node, indent, target)
-}
-
-fn emit_text(widget: &IrWidget) -> String {
-	for prop in &widget.props {
-		if let IrProp::Literal { name, value } = prop {
-			if name == "value" {
-				return format!("wgui::text({:?})", value);
-			}
-		}
-		if let IrProp::Value { name, expr } = prop {
-			if name == "value" {
-				return format!("wgui::text({})", emit_string_expr(expr));
-			}
-		}
-	}
-	"wgui::text(\"\")".to_string()
-}
-
-fn emit_textual(kind: &str, widget: &IrWidget, prop_name: &str) -> String {
-	for prop in &widget.props {
-		if let IrProp::Literal { name, value } = prop {
-			if name == prop_name {
-				return format!("wgui::{kind}({:?})", value);
-			}
-		}
-		if let IrProp::Value { name, expr } = prop {
-			if name == prop_name {
-				return format!("wgui::{kind}({})", emit_string_expr(expr));
-			}
-		}
-	}
-	format!("wgui::{kind}(\"\")")
-}
-
-fn emit_link(widget: &IrWidget) -> String {
-	let mut href = "\"\"".to_string();
-	let mut text = "\"\"".to_string();
-	for prop in &widget.props {
-		match prop {
-			IrProp::Literal { name, value } if name == "href" => href = format!("{:?}", value),
-			IrProp::Value { name, expr } if name == "href" => href = emit_string_expr(expr),
-			IrProp::Literal { name, value } if name == "text" => text = format!("{:?}", value),
-			IrProp::Value { name, expr } if name == "text" => text = emit_string_expr(expr),
-			_ => {}
-		}
-	}
-	format!("wgui::link({href}, {text})")
+/// The first string or expression `prop_name` of `widget` as a `&str`,
+/// empty without one.
+fn textual_value(widget: &IrWidget, prop_name: &str) -> String {
+	widget
+		.props
+		.iter()
+		.find_map(|prop| match prop {
+			IrProp::Literal { name, value } if name == prop_name => Some(format!("{value:?}")),
+			IrProp::Value { name, expr } if name == prop_name => Some(emit_text_expr(expr)),
+			_ => None,
+		})
+		.unwrap_or_else(|| "\"\"".to_string())
 }
 
 fn emit_image(widget: &IrWidget) -> String {
+	// The last `src` and `alt` win, like in the interpreter.
 	let mut src = "\"\"".to_string();
 	let mut alt = "\"\"".to_string();
 	for prop in &widget.props {
 		match prop {
-			IrProp::Literal { name, value } if name == "src" => src = format!("{:?}", value),
-			IrProp::Value { name, expr } if name == "src" => src = emit_string_expr(expr),
-			IrProp::Literal { name, value } if name == "alt" => alt = format!("{:?}", value),
-			IrProp::Value { name, expr } if name == "alt" => alt = emit_string_expr(expr),
+			IrProp::Literal { name, value } if name == "src" => src = format!("{value:?}"),
+			IrProp::Value { name, expr } if name == "src" => src = emit_text_expr(expr),
+			IrProp::Literal { name, value } if name == "alt" => alt = format!("{value:?}"),
+			IrProp::Value { name, expr } if name == "alt" => alt = emit_text_expr(expr),
 			_ => {}
 		}
 	}
 	format!("wgui::img({src}, {alt})")
 }
 
-fn emit_media(widget: &IrWidget, kind: &str) -> String {
-	let mut room = "\"\"".to_string();
-	for prop in &widget.props {
-		match prop {
-			IrProp::Literal { name, value } if name == "room" => room = format!("{:?}", value),
-			IrProp::Value { name, expr } if name == "room" => room = emit_string_expr(expr),
-			_ => {}
-		}
-	}
-	format!("wgui::{kind}({room})")
+fn emit_custom(widget: &IrWidget) -> String {
+	let props = widget
+		.props
+		.iter()
+		.find_map(|prop| match prop {
+			IrProp::Value { name, expr } if name == "props" => Some(format!(
+				"wgui::wui::runtime::WuiValueConvert::to_wui_value(&({})).to_json()",
+				emit_expr(expr)
+			)),
+			IrProp::Literal { name, value } if name == "props" => Some(format!(
+				"wgui::serde_json::Value::String({value:?}.to_string())"
+			)),
+			IrProp::Number { name, value } if name == "props" => {
+				Some(format!("wgui::serde_json::json!({value:?})"))
+			}
+			IrProp::Bool { name, value } if name == "props" => {
+				Some(format!("wgui::serde_json::json!({value})"))
+			}
+			_ => None,
+		})
+		.unwrap_or_else(|| "wgui::serde_json::json!({})".to_string());
+	// `src` names the component when set, otherwise `name` does.
+	format!(
+		"{{ let __src: &str = {}; let __name: &str = if __src.is_empty() {{ {} }} else {{ __src }}; \
+		 wgui::custom_component(__name, {}, {props}) }}",
+		textual_value(widget, "src"),
+		textual_value(widget, "name"),
+		textual_value(widget, "entry")
+	)
 }
 
-fn emit_custom(widget: &IrWidget) -> String {
-	let mut name = "\"\"".to_string();
-	let mut entry = "\"\"".to_string();
-	let mut props = "wgui::serde_json::json!({})".to_string();
-	for prop in &widget.props {
-		match prop {
-			IrProp::Literal {
-				name: prop_name,
-				value,
-			} if prop_name == "name" => name = format!("{:?}", value),
-			IrProp::Literal {
-				name: prop_name,
-				value,
-			} if prop_name == "src" => name = format!("{:?}", value),
-			IrProp::Value {
-				name: prop_name,
-				expr,
-			} if prop_name == "name" || prop_name == "src" => name = emit_string_expr(expr),
-			IrProp::Literal {
-				name: prop_name,
-				value,
-			} if prop_name == "entry" => entry = format!("{:?}", value),
-			IrProp::Value {
-				name: prop_name,
-				expr,
-			} if prop_name == "entry" => entry = emit_string_expr(expr),
-			IrProp::Literal {
-				name: prop_name,
-				value,
-			} if prop_name == "props" => props = format!("wgui::serde_json::json!({:?})", value),
-			IrProp::Number {
-				name: prop_name,
-				value,
-			} if prop_name == "props" => props = format!("wgui::serde_json::json!({})", value),
-			IrProp::Bool {
-				name: prop_name,
-				value,
-			} if prop_name == "props" => props = format!("wgui::serde_json::json!({})", value),
-			IrProp::Value {
-				name: prop_name,
-				expr,
-			} if prop_name == "props" => props = format!("wgui::serde_json::json!({})", emit_expr(expr)),
-			_ => {}
-		}
-	}
-	format!("wgui::custom_component({name}, {entry}, {props})")
+/// How a prop is applied: the builder method and the type it takes.
+enum Setter {
+	Str(&'static str),
+	/// The method and the integer type its argument is cast to.
+	Number(&'static str, &'static str),
+	Bool(&'static str),
 }
 
-fn emit_prop(prop: &IrProp) -> String {
+/// The setters the interpreter applies props with.
+fn prop_setter(name: &str) -> Option<Setter> {
+	Some(match name {
+		"svalue" | "bind:svalue" => Setter::Str("svalue"),
+		"name" => Setter::Str("name"),
+		"class" => Setter::Str("class"),
+		"partial" => Setter::Str("partial_addr"),
+		"action" => Setter::Str("action"),
+		"method" => Setter::Str("method"),
+		"placeholder" => Setter::Str("placeholder"),
+		"type" => Setter::Str("input_type"),
+		"textAlign" => Setter::Str("text_align"),
+		"fontWeight" => Setter::Str("font_weight"),
+		"whiteSpace" => Setter::Str("white_space"),
+		"cursor" => Setter::Str("cursor"),
+		"overflow" => Setter::Str("overflow"),
+		"backgroundColor" => Setter::Str("background_color"),
+		"color" => Setter::Str("color"),
+		"border" => Setter::Str("border"),
+		"objectFit" => Setter::Str("object_fit"),
+		"href" => Setter::Str("image_href"),
+		"room" => Setter::Str("room"),
+		"keyCombo" => Setter::Str("key_combo"),
+		"id" => Setter::Number("id", "u32"),
+		"ivalue" | "bind:ivalue" => Setter::Number("ivalue", "i32"),
+		"min" => Setter::Number("min", "i32"),
+		"max" => Setter::Number("max", "i32"),
+		"step" => Setter::Number("step", "i32"),
+		"spacing" => Setter::Number("spacing", "u32"),
+		"padding" => Setter::Number("padding", "u16"),
+		"paddingLeft" => Setter::Number("padding_left", "u16"),
+		"paddingRight" => Setter::Number("padding_right", "u16"),
+		"paddingTop" => Setter::Number("padding_top", "u16"),
+		"paddingBottom" => Setter::Number("padding_bottom", "u16"),
+		"margin" => Setter::Number("margin", "u16"),
+		"marginLeft" => Setter::Number("margin_left", "u16"),
+		"marginRight" => Setter::Number("margin_right", "u16"),
+		"marginTop" => Setter::Number("margin_top", "u16"),
+		"marginBottom" => Setter::Number("margin_bottom", "u16"),
+		"width" => Setter::Number("width", "u32"),
+		"height" => Setter::Number("height", "u32"),
+		"minWidth" => Setter::Number("min_width", "u32"),
+		"maxWidth" => Setter::Number("max_width", "u32"),
+		"minHeight" => Setter::Number("min_height", "u32"),
+		"maxHeight" => Setter::Number("max_height", "u32"),
+		"grow" => Setter::Number("grow", "u32"),
+		"arg" => Setter::Number("form_arg", "u32"),
+		"repeatInterval" => Setter::Number("repeat_interval", "u32"),
+		"checked" | "bind:checked" => Setter::Bool("checked"),
+		"breakWords" => Setter::Bool("break_words"),
+		"fill" => Setter::Bool("fill"),
+		"wrap" => Setter::Bool("wrap"),
+		"open" => Setter::Bool("open"),
+		"hresize" => Setter::Bool("hresize"),
+		"vresize" => Setter::Bool("vresize"),
+		"local" => Setter::Bool("local"),
+		"autoplay" => Setter::Bool("autoplay"),
+		"muted" => Setter::Bool("muted"),
+		"controls" => Setter::Bool("controls"),
+		"preventDefault" => Setter::Bool("prevent_default"),
+		_ => return None,
+	})
+}
+
+/// The builder call applying `prop`, if it applies to anything.
+fn emit_prop(prop: &IrProp) -> Option<String> {
 	match prop {
-		IrProp::Literal { name, value } => format!("{}({:?})", prop_method(name), value),
-		IrProp::Number { name, value } => emit_number_prop(name, *value),
-		IrProp::Bool { name, value } => format!("{}({})", prop_method(name), value),
-		IrProp::Value { name, expr } => match name.as_str() {
-			"svalue" => format!("svalue({})", emit_string_expr(expr)),
-			"ivalue" => format!("ivalue({})", emit_expr(expr)),
-			"checked" => format!("checked({})", emit_expr(expr)),
-			_ if is_string_prop(name) => {
-				format!("{}({})", prop_method(name), emit_string_expr(expr))
-			}
-			_ => format!("{}({})", prop_method(name), emit_expr(expr)),
+		IrProp::Literal { name, value } => match prop_setter(name)? {
+			Setter::Str(method) => Some(format!("{method}({value:?})")),
+			_ => None,
 		},
-		IrProp::Bind { name, expr } => match name.as_str() {
-			"bind:svalue" => format!("svalue({})", emit_string_expr(expr)),
-			"bind:ivalue" => format!("ivalue({})", emit_expr(expr)),
-			"bind:checked" => format!("checked({})", emit_expr(expr)),
-			_ => String::new(),
+		IrProp::Number { name, value } => match prop_setter(name)? {
+			Setter::Number(method, ty) => Some(format!("{method}({})", cast_number(*value, ty))),
+			_ => None,
 		},
-		IrProp::Event {
-			name, action, arg, ..
-		} => {
-			let mut base = if let Some(method) = event_method(name) {
-				format!("{}({})", method, action_id(action))
-			} else {
-				format!("custom_event({:?}, {})", name, action_id(action))
-			};
-			if let Some(expr) = arg {
-				base = format!("{base}.inx({})", emit_expr(expr));
-			}
-			base
+		IrProp::Bool { name, value } => match prop_setter(name)? {
+			Setter::Bool(method) => Some(format!("{method}({value})")),
+			_ => None,
+		},
+		IrProp::Value { name, expr } | IrProp::Bind { name, expr } => {
+			Some(match prop_setter(name)? {
+				Setter::Str(method) => format!("{method}({})", emit_text_expr(expr)),
+				Setter::Number(method, ty) => match expr {
+					Expr::Literal(Literal::Number(value), _) => {
+						format!("{method}({})", cast_number(*value, ty))
+					}
+					_ => format!("{method}(({}) as {ty})", emit_number_expr(expr)),
+				},
+				Setter::Bool(method) => format!("{method}({})", emit_bool_expr(expr)),
+			})
 		}
+		IrProp::Event { .. } => None,
 	}
 }
 
-fn emit_number_prop(name: &str, value: f64) -> String {
-	match name {
-		"min" | "max" | "step" | "ivalue" => format!("{}({})", prop_method(name), value as i32),
-		"padding" | "paddingLeft" | "paddingRight" | "paddingTop" | "paddingBottom" | "margin"
-		| "marginLeft" | "marginRight" | "marginTop" | "marginBottom" => {
-			format!("{}({})", prop_method(name), value as u16)
+/// `item` with the event `name` sent as `action`, and `arg` as its index
+/// when it is a number that is not negative.
+fn apply_event(item: String, name: &str, action: &str, arg: Option<&Expr>) -> String {
+	let id = action_id(action);
+	let key = handler_key(name);
+	// Events older clients know about keep their legacy fields.
+	let mut item = match event_method(name) {
+		Some(method) => format!("{item}.{method}({id})"),
+		None if name.starts_with("on") => item,
+		None => format!("{item}.custom_event({name:?}, {id})"),
+	};
+	// Change events travel under the item's own id.
+	if key.is_none() {
+		item = format!("{item}.id({id})");
+	}
+	let handler = |inx: &str| {
+		key.as_ref()
+			.map_or(String::new(), |key| format!(".on({key:?}, {id}, {inx})"))
+	};
+	// Key handlers only exist in `handlers`; the rest keep the item-level
+	// `inx` older clients read.
+	let item_inx = name != "onKeyDown" && name != "onKeyUp";
+	match arg {
+		None => format!("{item}{}", handler("None")),
+		Some(Expr::Literal(Literal::Number(value), _)) if *value >= 0.0 => {
+			let inx = *value as u32;
+			let item = if item_inx {
+				format!("{item}.inx({inx})")
+			} else {
+				item
+			};
+			format!("{item}{}", handler(&format!("Some({inx})")))
+		}
+		Some(Expr::Literal(..)) => format!("{item}{}", handler("None")),
+		Some(arg) => {
+			let mut out = format!(
+				"{{ let __inx = {{ let __n = {}; (__n >= 0.0).then_some(__n as u32) }}; let __item = {item}; ",
+				emit_number_expr(arg)
+			);
+			if item_inx {
+				out.push_str(
+					"let __item = match __inx { Some(inx) => __item.inx(inx), None => __item }; ",
+				);
+			}
+			out.push_str(&format!("__item{} }}", handler("__inx")));
+			out
 		}
-		_ => format!("{}({})", prop_method(name), value as u32),
 	}
 }
 
-fn prop_method(name: &str) -> String {
-	match name {
-		"ivalue" => "ivalue".to_string(),
-		"spacing" => "spacing".to_string(),
-		"padding" => "padding".to_string(),
-		"paddingLeft" => "padding_left".to_string(),
-		"paddingRight" => "padding_right".to_string(),
-		"paddingTop" => "padding_top".to_string(),
-		"paddingBottom" => "padding_bottom".to_string(),
-		"margin" => "margin".to_string(),
-		"marginLeft" => "margin_left".to_string(),
-		"marginRight" => "margin_right".to_string(),
-		"marginTop" => "margin_top".to_string(),
-		"marginBottom" => "margin_bottom".to_string(),
-		"backgroundColor" => "background_color".to_string(),
-		"color" => "color".to_string(),
-		"border" => "border".to_string(),
-		"width" => "width".to_string(),
-		"height" => "height".to_string(),
-		"minWidth" => "min_width".to_string(),
-		"maxWidth" => "max_width".to_string(),
-		"minHeight" => "min_height".to_string(),
-		"maxHeight" => "max_height".to_string(),
-		"grow" => "grow".to_string(),
-		"textAlign" => "text_align".to_string(),
-		"fontWeight" => "font_weight".to_string(),
-		"whiteSpace" => "white_space".to_string(),
-		"cursor" => "cursor".to_string(),
-		"breakWords" => "break_words".to_string(),
-		"fill" => "fill".to_string(),
-		"wrap" => "wrap".to_string(),
-		"overflow" => "overflow".to_string(),
-		"placeholder" => "placeholder".to_string(),
-		"type" => "input_type".to_string(),
-		"objectFit" => "object_fit".to_string(),
-		"href" => "image_href".to_string(),
-		"room" => "room".to_string(),
-		"arg" => "form_arg".to_string(),
-		"partial" => "partial_addr".to_string(),
-		"local" => "local".to_string(),
-		"autoplay" => "autoplay".to_string(),
-		"muted" => "muted".to_string(),
-		"controls" => "controls".to_string(),
-		"min" => "min".to_string(),
-		"max" => "max".to_string(),
-		"step" => "step".to_string(),
-		"repeatInterval" => "repeat_interval".to_string(),
-		_ => name.to_string(),
+/// A number literal as the integer type a setter takes, saturating like
+/// the interpreter's casts.
+fn cast_number(value: f64, ty: &str) -> String {
+	match ty {
+		"i32" => (value as i32).to_string(),
+		"u16" => (value as u16).to_string(),
+		_ => (value as u32).to_string(),
 	}
 }
 
@@ -710,12 +1360,36 @@ fn emit_expr(expr: &Expr) -> String {
 			Literal::Bool(b) => format!("{}", b),
 			Literal::Null => "None".to_string(),
 		},
-		Expr::Path(parts, _) => parts.join("."),
+		Expr::Path(parts, _) => parts
+			.iter()
+			.map(|part| rust_ident(part))
+			.collect::<Vec<_>>()
+			.join("."),
 		Expr::Call { name, args, .. } => emit_call_expr(name, args),
 		Expr::Unary { op, expr, .. } => match op {
-			UnaryOp::Not => format!("!{}", emit_expr(expr)),
-			UnaryOp::Neg => format!("-{}", emit_expr(expr)),
+			UnaryOp::Not => format!("!{}", emit_bool_expr(expr)),
+			UnaryOp::Neg => format!("-{}", emit_number_expr(expr)),
 		},
+		Expr::Binary {
+			left,
+			op: BinaryOp::Add,
+			right,
+			..
+		} if is_string_expr(left) || is_string_expr(right) => {
+			emit_concat(&[left.as_ref(), right.as_ref()])
+		}
+		// Without the operand types, `+` decides between concatenation and
+		// addition at render time, as the interpreter does.
+		Expr::Binary {
+			left,
+			op: BinaryOp::Add,
+			right,
+			..
+		} if !is_number_expr(left) || !is_number_expr(right) => format!(
+			"{BUILTINS_PATH}::add(&({}), &({}))",
+			emit_expr(left),
+			emit_expr(right)
+		),
 		Expr::Binary {
 			left, op, right, ..
 		} => {
@@ -734,30 +1408,129 @@ fn emit_expr(expr: &Expr) -> String {
 				BinaryOp::And => "&&",
 				BinaryOp::Or => "||",
 			};
-			format!("({} {} {})", emit_expr(left), op_str, emit_expr(right))
+			// Arithmetic and ordering are over `f64` and logic is over
+			// truthiness, as in the interpreter.
+			let (left, right) = match op {
+				BinaryOp::And | BinaryOp::Or => (emit_bool_expr(left), emit_bool_expr(right)),
+				BinaryOp::Eq | BinaryOp::Neq => return emit_equality(left, op_str, right),
+				_ => (emit_number_expr(left), emit_number_expr(right)),
+			};
+			format!("({left} {op_str} {right})")
 		}
 		Expr::Ternary {
 			cond,
 			then_expr,
 			else_expr,
 			..
-		} => format!(
-			"if {} {{ {} }} else {{ {} }}",
-			emit_expr(cond),
-			emit_expr(then_expr),
-			emit_expr(else_expr)
-		),
-		Expr::Coalesce { left, right, .. } => {
+		} => {
+			let (then_expr, else_expr) = emit_branches(then_expr, else_expr);
 			format!(
-				"{}.unwrap_or_else(|| {})",
-				emit_expr(left),
-				emit_expr(right)
+				"if {} {{ {then_expr} }} else {{ {else_expr} }}",
+				emit_bool_expr(cond)
 			)
 		}
+		Expr::Coalesce { left, right, .. } => {
+			let maybe = format!(
+				"{BUILTINS_PATH}::WuiMaybe::wui_maybe(&({}))",
+				emit_expr(left)
+			);
+			if is_number_expr(right) {
+				format!(
+					"{maybe}.map_or({}, |__value| {BUILTINS_PATH}::WuiNumber::wui_number(__value))",
+					emit_number_expr(right)
+				)
+			} else if is_bool_expr(right) {
+				format!(
+					"{maybe}.map_or({}, |__value| {BUILTINS_PATH}::WuiBool::wui_bool(__value))",
+					emit_expr(right)
+				)
+			} else {
+				format!(
+					"{maybe}.map_or_else(|| {}, |__value| {BUILTINS_PATH}::WuiText::wui_text(__value).into_owned())",
+					emit_owned_text(right)
+				)
+			}
+		}
+	}
+}
+
+/// The branches of a ternary as one Rust type: both `f64` or `bool` when
+/// both are known to be, otherwise both owned text.
+fn emit_branches(then_expr: &Expr, else_expr: &Expr) -> (String, String) {
+	let both = |test: fn(&Expr) -> bool| test(then_expr) && test(else_expr);
+	let string_literal = |expr: &Expr| matches!(expr, Expr::Literal(Literal::String(_), _));
+	if both(string_literal) || both(is_bool_expr) {
+		(emit_expr(then_expr), emit_expr(else_expr))
+	} else if both(is_number_expr) {
+		(emit_number_expr(then_expr), emit_number_expr(else_expr))
+	} else {
+		(emit_owned_text(then_expr), emit_owned_text(else_expr))
+	}
+}
+
+/// `expr` as a `String`, shown the way the runtime shows it.
+fn emit_owned_text(expr: &Expr) -> String {
+	format!(
+		"{BUILTINS_PATH}::WuiText::wui_text(&({})).into_owned()",
+		emit_expr(expr)
+	)
+}
+
+/// `==` or `!=`, comparing numbers as numbers and strings as text when
+/// either side is known to be one.
+fn emit_equality(left: &Expr, op: &str, right: &Expr) -> String {
+	if is_number_expr(left) || is_number_expr(right) {
+		format!(
+			"({} {op} {})",
+			emit_number_expr(left),
+			emit_number_expr(right)
+		)
+	} else if is_string_expr(left) || is_string_expr(right) {
+		format!(
+			"(*{BUILTINS_PATH}::WuiText::wui_text(&({})) {op} *{BUILTINS_PATH}::WuiText::wui_text(&({})))",
+			emit_expr(left),
+			emit_expr(right)
+		)
+	} else if is_bool_expr(left) || is_bool_expr(right) {
+		format!("({} {op} {})", emit_bool_expr(left), emit_bool_expr(right))
+	} else {
+		format!("({} {op} {})", emit_expr(left), emit_expr(right))
+	}
+}
+
+/// `expr` as an `f64`.
+fn emit_number_expr(expr: &Expr) -> String {
+	match expr {
+		Expr::Literal(Literal::Number(_), _) => format!("({}) as f64", emit_expr(expr)),
+		_ => format!(
+			"{BUILTINS_PATH}::WuiNumber::wui_number(&({}))",
+			emit_expr(expr)
+		),
+	}
+}
+
+/// `expr` as a `bool`; only `true` is truthy.
+fn emit_bool_expr(expr: &Expr) -> String {
+	if is_bool_expr(expr) {
+		emit_expr(expr)
+	} else {
+		format!("{BUILTINS_PATH}::WuiBool::wui_bool(&({}))", emit_expr(expr))
 	}
 }
 
+const BUILTINS_PATH: &str = "wgui::wui::builtins";
+/// Locale-aware built-ins read the current locale from `wgui::i18n`.
+const I18N_PATH: &str = "wgui::i18n";
+
 fn emit_call_expr(name: &str, args: &[Expr]) -> String {
+	let number = |expr: &Expr| emit_number_expr(expr);
+	let text = |expr: &Expr| format!("{BUILTINS_PATH}::WuiText::wui_text(&({}))", emit_expr(expr));
+	let optional_number = |expr: Option<&Expr>| {
+		expr.map_or_else(
+			|| "None".to_string(),
+			|expr| format!("Some({})", number(expr)),
+		)
+	};
 	match (name, args) {
 		("path_matches", [pattern]) => {
 			format!(
@@ -765,35 +1538,213 @@ fn emit_call_expr(name: &str, args: &[Expr]) -> String {
 				emit_string_expr(pattern)
 			)
 		}
+		("len", [value]) => format!("{BUILTINS_PATH}::WuiLen::wui_len(&*({}))", emit_expr(value)),
+		("upper", [value]) => format!("{}.to_uppercase()", text(value)),
+		("lower", [value]) => format!("{}.to_lowercase()", text(value)),
+		("trim", [value]) => format!("{}.trim().to_string()", text(value)),
+		("contains", [haystack, needle]) => format!(
+			"{BUILTINS_PATH}::WuiContains::wui_contains(&*({}), &{})",
+			emit_expr(haystack),
+			emit_expr(needle)
+		),
+		("join", [items, separator]) => format!(
+			"{BUILTINS_PATH}::join(&({}), {})",
+			emit_expr(items),
+			emit_string_expr(separator)
+		),
+		("slice", [value, start, rest @ ..]) if rest.len() <= 1 => format!(
+			"{BUILTINS_PATH}::WuiSlice::wui_slice(&*({}), {}, {})",
+			emit_expr(value),
+			number(start),
+			optional_number(rest.first())
+		),
+		("format_number", [value, rest @ ..]) if rest.len() <= 1 => format!(
+			"{I18N_PATH}::format_number({}, {})",
+			number(value),
+			optional_number(rest.first())
+		),
+		("format_date", [date, pattern]) => format!(
+			"{I18N_PATH}::format_date(&({}), {})",
+			emit_expr(date),
+			emit_string_expr(pattern)
+		),
+		("pluralize", [count, singular, rest @ ..]) if rest.len() <= 1 => format!(
+			"{BUILTINS_PATH}::pluralize({}, {}, {})",
+			number(count),
+			emit_string_expr(singular),
+			rest.first().map_or_else(
+				|| "None".to_string(),
+				|plural| format!("Some({})", emit_string_expr(plural))
+			)
+		),
+		("min" | "max", [_, _, ..]) => format!(
+			"{BUILTINS_PATH}::{name}(&[{}])",
+			args.iter().map(number).collect::<Vec<_>>().join(", ")
+		),
+		("round", [value, rest @ ..]) if rest.len() <= 1 => format!(
+			"{BUILTINS_PATH}::round({}, {})",
+			number(value),
+			optional_number(rest.first())
+		),
+		("t", [key, pairs @ ..]) if pairs.len().is_multiple_of(2) => format!(
+			"{I18N_PATH}::t({}, &[{}])",
+			emit_expr(key),
+			pairs
+				.chunks(2)
+				.map(|pair| format!(
+					"({}, wgui::wui::runtime::WuiValueConvert::to_wui_value(&({})))",
+					emit_expr(&pair[0]),
+					emit_expr(&pair[1])
+				))
+				.collect::<Vec<_>>()
+				.join(", ")
+		),
+		("url", [route, params @ ..]) => format!(
+			"wgui::wui::route_handler::route_url({}, &[{}])",
+			emit_expr(route),
+			params
+				.iter()
+				.map(|param| format!(
+					"wgui::wui::runtime::WuiValueConvert::to_wui_value(&({}))",
+					emit_expr(param)
+				))
+				.collect::<Vec<_>>()
+				.join(", ")
+		),
+		(builtins::CONCAT, _) => emit_concat(&args.iter().collect::<Vec<_>>()),
 		_ => "None".to_string(),
 	}
 }
 
+fn emit_concat(args: &[&Expr]) -> String {
+	format!(
+		"format!(\"{}\"{})",
+		"{}".repeat(args.len()),
+		args.iter()
+			.map(|arg| if is_string_expr(arg) {
+				format!(", {}", emit_expr(arg))
+			} else {
+				format!(
+					", {BUILTINS_PATH}::WuiText::wui_text(&({}))",
+					emit_expr(arg)
+				)
+			})
+			.collect::<String>()
+	)
+}
+
+/// Whether `expr` is known to produce a string, so `+` means concatenation.
+fn is_string_expr(expr: &Expr) -> bool {
+	match expr {
+		Expr::Literal(Literal::String(_), _) => true,
+		Expr::Call { name, args, .. } => {
+			match builtins::lookup(name).map(|builtin| builtin.returns) {
+				Some(builtins::ReturnType::String) => true,
+				Some(builtins::ReturnType::FirstArg) => args.first().is_some_and(is_string_expr),
+				_ => false,
+			}
+		}
+		Expr::Binary {
+			left,
+			op: BinaryOp::Add,
+			right,
+			..
+		} => is_string_expr(left) || is_string_expr(right),
+		_ => false,
+	}
+}
+
 fn emit_string_expr(expr: &Expr) -> String {
 	match expr {
 		Expr::Path(_, _) => format!("&{}", emit_expr(expr)),
+		Expr::Literal(Literal::String(_), _) => emit_expr(expr),
+		_ if is_string_expr(expr) => format!("&({})", emit_expr(expr)),
 		_ => emit_expr(expr),
 	}
 }
 
-fn is_string_prop(name: &str) -> bool {
-	matches!(
-		name,
-		"textAlign"
-			| "fontWeight"
-			| "whiteSpace"
-			| "cursor"
-			| "overflow"
-			| "placeholder"
-			| "action"
-			| "method"
-			| "name" | "class"
-			| "partial"
-			| "type" | "backgroundColor"
-			| "color" | "border"
-			| "objectFit"
-			| "href" | "room"
-	)
+/// A `&str` of any value, shown the way the runtime shows it.
+fn emit_text_expr(expr: &Expr) -> String {
+	match expr {
+		Expr::Literal(Literal::String(_), _) => emit_expr(expr),
+		_ if is_string_expr(expr) => format!("&({})", emit_expr(expr)),
+		_ => format!(
+			"&*{BUILTINS_PATH}::WuiText::wui_text(&({}))",
+			emit_expr(expr)
+		),
+	}
+}
+
+/// Whether `expr` is known to produce a number.
+fn is_number_expr(expr: &Expr) -> bool {
+	match expr {
+		Expr::Literal(Literal::Number(_), _) => true,
+		Expr::Unary {
+			op: UnaryOp::Neg, ..
+		} => true,
+		Expr::Call { name, .. } => builtins::lookup(name)
+			.is_some_and(|builtin| builtin.returns == builtins::ReturnType::Number),
+		Expr::Binary {
+			left,
+			op: BinaryOp::Add,
+			right,
+			..
+		} => is_number_expr(left) && is_number_expr(right),
+		Expr::Binary {
+			op: BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod,
+			..
+		} => true,
+		_ => false,
+	}
+}
+
+/// Whether `expr` is known to produce a `bool`.
+fn is_bool_expr(expr: &Expr) -> bool {
+	match expr {
+		Expr::Literal(Literal::Bool(_), _) => true,
+		Expr::Unary {
+			op: UnaryOp::Not, ..
+		} => true,
+		Expr::Call { name, .. } => builtins::lookup(name)
+			.is_some_and(|builtin| builtin.returns == builtins::ReturnType::Bool),
+		Expr::Binary { op, .. } => {
+			matches!(
+				op,
+				BinaryOp::Eq
+					| BinaryOp::Neq | BinaryOp::Lt
+					| BinaryOp::Lte | BinaryOp::Gt
+					| BinaryOp::Gte | BinaryOp::And
+					| BinaryOp::Or
+			)
+		}
+		_ => false,
+	}
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+	"abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+	"else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+	"loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+	"static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+	"virtual", "where", "while", "yield",
+];
+
+/// `name` as a Rust identifier, raw when it is a keyword.
+fn rust_ident(name: &str) -> String {
+	if RUST_KEYWORDS.contains(&name) {
+		format!("r#{name}")
+	} else {
+		name.to_string()
+	}
+}
+
+fn is_rust_ident(name: &str) -> bool {
+	let mut chars = name.chars();
+	chars
+		.next()
+		.is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+		&& chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+		&& !matches!(name, "_" | "self" | "Self" | "super" | "crate" | "state")
 }
 
 fn should_emit_prop(tag: &str, prop: &IrProp) -> bool {
@@ -809,9 +1760,9 @@ fn should_emit_prop(tag: &str, prop: &IrProp) -> bool {
 			"Link" => name != "href" && name != "text",
 			"Image" => name != "src" && name != "alt",
 			"Video" | "Audio" => name != "room",
-			"Select" => name != "value" && name != "options",
-			"Form" => false,
-			_ => name != "arg",
+			"Select" => name != "value" && name != "bind:value" && name != "options",
+			"Shortcut" => name != "keyCombo",
+			_ => true,
 		},
 	}
 }
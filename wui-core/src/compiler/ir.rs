@@ -1,4 +1,6 @@
 use crate::ast::Expr;
+use crate::compiler::registry::ValueType;
+use crate::diagnostic::Span;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
@@ -7,11 +9,31 @@ pub struct IrDocument {
 	pub components: HashMap<String, IrComponent>,
 	pub actions: Vec<ActionDef>,
 	pub pages: Vec<PageMeta>,
+	/// Scoped CSS from the `<Style>` blocks of the template and its components.
+	pub styles: Vec<IrStyle>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IrStyle {
+	/// The template or component module the styles belong to.
+	pub module: String,
+	/// The class added to every widget the styles apply to.
+	pub scope: String,
+	pub css: String,
 }
 
 #[derive(Debug, Clone)]
 pub struct IrComponent {
 	pub body: Vec<IrNode>,
+	/// Declared in the component's `<Props>` header; empty when untyped.
+	pub props: Vec<IrComponentProp>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IrComponentProp {
+	pub name: String,
+	pub ty: ValueType,
+	pub default: Option<Expr>,
 }
 
 #[derive(Debug, Clone)]
@@ -27,10 +49,16 @@ pub enum IrNode {
 	Widget(IrWidget),
 	For(IrFor),
 	If(IrIf),
+	Let(IrLet),
+	Match(IrMatch),
 	Scope(IrScope),
 	Route(IrRoute),
 	Switch(IrSwitch),
+	ErrorBoundary(IrErrorBoundary),
 	Children,
+	/// A named placeholder in a component body, or the content filling it
+	/// at a call site. A placeholder's body is its fallback.
+	Slot(IrSlot),
 	Text(String),
 }
 
@@ -39,6 +67,13 @@ pub struct IrWidget {
 	pub tag: String,
 	pub props: Vec<IrProp>,
 	pub children: Vec<IrNode>,
+	pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct IrSlot {
+	pub name: String,
+	pub body: Vec<IrNode>,
 }
 
 #[derive(Debug, Clone)]
@@ -57,6 +92,37 @@ pub struct IrIf {
 	pub else_body: Vec<IrNode>,
 }
 
+/// `<Let name value>`: `value` is bound to `name` inside `body`.
+#[derive(Debug, Clone)]
+pub struct IrLet {
+	pub name: String,
+	pub value: Expr,
+	pub body: Vec<IrNode>,
+}
+
+/// `<Match value>`: renders the first case whose `is` equals the value as a
+/// string, or `else_body` when none does.
+#[derive(Debug, Clone)]
+pub struct IrMatch {
+	pub value: Expr,
+	pub cases: Vec<IrCase>,
+	pub else_body: Vec<IrNode>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IrCase {
+	pub is: String,
+	pub body: Vec<IrNode>,
+}
+
+/// Renders `body`, or `fallback` in its place if rendering `body` fails.
+#[derive(Debug, Clone)]
+pub struct IrErrorBoundary {
+	pub body: Vec<IrNode>,
+	pub fallback: Vec<IrNode>,
+	pub span: Span,
+}
+
 #[derive(Debug, Clone)]
 pub struct IrScope {
 	pub name: String,
@@ -113,6 +179,8 @@ pub enum EventKind {
 	SliderChange,
 	Select,
 	ScrollNearBottom,
+	KeyDown,
+	KeyUp,
 	Custom(String),
 }
 
@@ -123,6 +191,7 @@ pub enum ActionPayload {
 	String,
 	I32,
 	U32I32,
+	U32String,
 	Json,
 }
 
@@ -132,4 +201,10 @@ pub struct ActionDef {
 	pub kind: EventKind,
 	pub payload: ActionPayload,
 	pub id: u32,
+	/// Set for the hidden actions behind `bind:*` props: the state path the
+	/// new value is written to, without the leading `state`.
+	pub binding: Option<Vec<String>>,
+	/// The `key` of each element handling this key action, with `None` where
+	/// it is missing or computed, which lets any key through.
+	pub key_combos: Vec<Option<String>>,
 }
@@ -1,9 +1,11 @@
 use crate::ast::{AttrValue, Element, Expr, Node};
 use crate::compiler::ir::{
-	ActionDef, ActionPayload, EventKind, IrComponent, IrDocument, IrFor, IrIf, IrNode, IrProp,
-	IrRoute, IrScope, IrSwitch, IrWidget, PageMeta,
+	ActionDef, ActionPayload, EventKind, IrCase, IrComponent, IrComponentProp, IrDocument,
+	IrErrorBoundary, IrFor, IrIf, IrLet, IrMatch, IrNode, IrProp, IrRoute, IrScope, IrSlot,
+	IrStyle, IrSwitch, IrWidget, PageMeta,
 };
-use crate::compiler::registry::schema_for;
+use crate::compiler::registry::{binding_event, schema_for};
+use crate::compiler::signature::signature;
 use crate::diagnostic::{Diagnostic, Span};
 use std::collections::HashMap;
 
@@ -13,17 +15,68 @@ pub fn lower(
 	diags: &mut Vec<Diagnostic>,
 ) -> IrDocument {
 	let mut ctx = LowerContext::new(module_name);
+	let mut styles = Vec::new();
+	ctx.style_scope = lower_style(module_name, &doc.nodes, &mut styles);
 	let nodes = lower_nodes(&doc.nodes, &mut ctx, diags);
 	let mut components = HashMap::new();
 	for (name, nodes) in &doc.components {
+		ctx.style_scope = lower_style(name, nodes, &mut styles);
 		let body = lower_nodes(nodes, &mut ctx, diags);
-		components.insert(name.clone(), IrComponent { body });
+		let props = signature(nodes)
+			.props
+			.unwrap_or_default()
+			.into_iter()
+			.map(|prop| IrComponentProp {
+				name: prop.name,
+				ty: prop.ty,
+				default: prop.default.as_ref().map(attr_value_expr),
+			})
+			.collect();
+		components.insert(name.clone(), IrComponent { body, props });
 	}
 	IrDocument {
 		nodes,
 		components,
 		actions: ctx.actions,
 		pages: ctx.pages,
+		styles,
+	}
+}
+
+/// Scopes the `<Style>` blocks among `nodes` to `key` and returns the class
+/// for their widgets, if there are any styles.
+fn lower_style(key: &str, nodes: &[Node], styles: &mut Vec<IrStyle>) -> Option<String> {
+	let mut css = String::new();
+	collect_style(nodes, &mut css);
+	if css.trim().is_empty() {
+		return None;
+	}
+	let scope = crate::style::scope_class(key, &css);
+	// Validation has already reported CSS that does not scope.
+	let scoped = crate::style::scope_css(&css, &scope).ok()?;
+	styles.push(IrStyle {
+		module: key.to_string(),
+		scope: scope.clone(),
+		css: scoped,
+	});
+	Some(scope)
+}
+
+fn collect_style(nodes: &[Node], css: &mut String) {
+	for node in nodes {
+		let Node::Element(el) = node else {
+			continue;
+		};
+		if el.name != "Style" {
+			collect_style(&el.children, css);
+			continue;
+		}
+		for child in &el.children {
+			if let Node::Text(text, _) = child {
+				css.push_str(text);
+				css.push('\n');
+			}
+		}
 	}
 }
 
@@ -33,6 +86,9 @@ struct LowerContext {
 	actions: Vec<ActionDef>,
 	action_ids: HashMap<u32, String>,
 	pages: Vec<PageMeta>,
+	/// The class scoping the `<Style>` of the template or component being
+	/// lowered.
+	style_scope: Option<String>,
 }
 
 impl LowerContext {
@@ -43,6 +99,7 @@ impl LowerContext {
 			actions: Vec::new(),
 			action_ids: HashMap::new(),
 			pages: Vec::new(),
+			style_scope: None,
 		}
 	}
 
@@ -59,6 +116,7 @@ impl LowerContext {
 		name: String,
 		kind: EventKind,
 		payload: ActionPayload,
+		binding: Option<Vec<String>>,
 		span: Span,
 		diags: &mut Vec<Diagnostic>,
 	) {
@@ -87,8 +145,17 @@ impl LowerContext {
 			kind,
 			payload,
 			id,
+			binding,
+			key_combos: Vec::new(),
 		});
 	}
+
+	/// Record the `key` an element declares for the key action `name`.
+	fn add_key_combo(&mut self, name: &str, combo: Option<String>) {
+		if let Some(action) = self.actions.iter_mut().find(|a| a.name == name) {
+			action.key_combos.push(combo);
+		}
+	}
 }
 
 fn lower_nodes(nodes: &[Node], ctx: &mut LowerContext, diags: &mut Vec<Diagnostic>) -> Vec<IrNode> {
@@ -97,23 +164,68 @@ fn lower_nodes(nodes: &[Node], ctx: &mut LowerContext, diags: &mut Vec<Diagnosti
 	while i < nodes.len() {
 		match &nodes[i] {
 			Node::Element(el) if el.name == "If" => {
-				let then_body = lower_nodes(&el.children, ctx, diags);
+				let mut branches = vec![(
+					get_expr_attr(el, "test"),
+					lower_nodes(&el.children, ctx, diags),
+				)];
 				let mut else_body = Vec::new();
-				if i + 1 < nodes.len() {
-					if let Node::Element(next) = &nodes[i + 1] {
-						if next.name == "Else" {
-							else_body = lower_nodes(&next.children, ctx, diags);
-							i += 1;
-						}
+				while let Some(Node::Element(next)) = nodes.get(i + 1) {
+					match next.name.as_str() {
+						"ElseIf" => branches.push((
+							get_expr_attr(next, "test"),
+							lower_nodes(&next.children, ctx, diags),
+						)),
+						"Else" => else_body = lower_nodes(&next.children, ctx, diags),
+						_ => break,
+					}
+					i += 1;
+					if next.name == "Else" {
+						break;
+					}
+				}
+				// `ElseIf` chains become nested `If`s in their `else_body`.
+				for (test, then_body) in branches.into_iter().rev() {
+					if let Some(test) = test {
+						else_body = vec![IrNode::If(IrIf {
+							test,
+							then_body,
+							else_body,
+						})];
 					}
 				}
-				if let Some(test) = get_expr_attr(el, "test") {
-					out.push(IrNode::If(IrIf {
-						test,
-						then_body,
-						else_body,
-					}));
+				out.extend(else_body);
+			}
+			Node::Element(el) if el.name == "Let" => {
+				let name = get_string_attr(el, "name").unwrap_or_default();
+				let value = get_expr_like(el, "value")
+					.unwrap_or(Expr::Literal(crate::ast::Literal::Null, el.span));
+				let body = lower_nodes(&el.children, ctx, diags);
+				out.push(IrNode::Let(IrLet { name, value, body }));
+			}
+			Node::Element(el) if el.name == "Match" => {
+				let value = get_expr_like(el, "value")
+					.unwrap_or(Expr::Literal(crate::ast::Literal::Null, el.span));
+				let mut cases = Vec::new();
+				let mut else_body = Vec::new();
+				for child in &el.children {
+					let Node::Element(case_el) = child else {
+						continue;
+					};
+					match case_el.name.as_str() {
+						"Case" => {
+							let is = get_string_attr(case_el, "is").unwrap_or_default();
+							let body = lower_nodes(&case_el.children, ctx, diags);
+							cases.push(IrCase { is, body });
+						}
+						"Else" => else_body = lower_nodes(&case_el.children, ctx, diags),
+						_ => {}
+					}
 				}
+				out.push(IrNode::Match(IrMatch {
+					value,
+					cases,
+					else_body,
+				}));
 			}
 			Node::Element(el) if el.name == "For" => {
 				let each = get_expr_attr(el, "each")
@@ -137,6 +249,23 @@ fn lower_nodes(nodes: &[Node], ctx: &mut LowerContext, diags: &mut Vec<Diagnosti
 				ctx.scope_stack.pop();
 				out.push(IrNode::Scope(IrScope { name, body }));
 			}
+			Node::Element(el) if el.name == "ErrorBoundary" => {
+				let (fallback, body): (Vec<_>, Vec<_>) = el.children.iter().cloned().partition(
+					|child| matches!(child, Node::Element(child_el) if child_el.name == "Fallback"),
+				);
+				let fallback = match fallback.first() {
+					Some(Node::Element(fallback_el)) => {
+						lower_nodes(&fallback_el.children, ctx, diags)
+					}
+					_ => Vec::new(),
+				};
+				let body = lower_nodes(&body, ctx, diags);
+				out.push(IrNode::ErrorBoundary(IrErrorBoundary {
+					body,
+					fallback,
+					span: el.span,
+				}));
+			}
 			Node::Element(el) if el.name == "Page" => {
 				diags.push(Diagnostic::new(
 					"Page is deprecated; use Route instead",
@@ -196,6 +325,12 @@ fn lower_nodes(nodes: &[Node], ctx: &mut LowerContext, diags: &mut Vec<Diagnosti
 			Node::Element(el) if el.name == "Children" => {
 				out.push(IrNode::Children);
 			}
+			Node::Element(el) if el.name == "Props" || el.name == "Style" => {}
+			Node::Element(el) if el.name == "Slot" => {
+				let name = get_string_attr(el, "name").unwrap_or_default();
+				let body = lower_nodes(&el.children, ctx, diags);
+				out.push(IrNode::Slot(IrSlot { name, body }));
+			}
 			Node::Element(el) => {
 				if let Some(widget) = lower_widget(el, ctx, diags) {
 					out.push(IrNode::Widget(widget));
@@ -229,14 +364,11 @@ fn lower_widget(
 ) -> Option<IrWidget> {
 	let mut props = Vec::new();
 	let mut event_props: Vec<(String, EventKind, Option<Expr>, Span)> = Vec::new();
+	let mut bindings: Vec<(Vec<String>, Span)> = Vec::new();
 
 	if let Some(schema) = schema_for(&el.name) {
 		for attr in &el.attrs {
-			if attr.name == "class" {
-				lower_value_prop(&mut props, "class".to_string(), &attr.value);
-				continue;
-			}
-			if let Some(def) = schema.props.iter().find(|p| p.name == attr.name) {
+			if let Some(def) = schema.prop(&attr.name) {
 				match &def.kind {
 					crate::compiler::registry::PropKind::Event(kind) => {
 						let action_name = match &attr.value {
@@ -244,11 +376,15 @@ fn lower_widget(
 							_ => continue,
 						};
 						let scoped = ctx.scoped_action(&action_name);
-						let arg = get_expr_like(el, "arg");
+						let arg = get_expr_like(el, &format!("arg:{}", attr.name))
+							.or_else(|| get_expr_like(el, "arg"));
 						event_props.push((scoped, kind.clone(), arg, attr.span));
 					}
 					crate::compiler::registry::PropKind::Bind(_) => {
 						if let AttrValue::Expr(expr) = &attr.value {
+							if let Expr::Path(parts, _) = expr {
+								bindings.push((parts[1..].to_vec(), attr.span));
+							}
 							let prop_name = normalize_prop_name(&el.name, &attr.name);
 							props.push(IrProp::Bind {
 								name: prop_name,
@@ -259,17 +395,12 @@ fn lower_widget(
 					crate::compiler::registry::PropKind::Value(_) => {
 						if attr.name == "arg"
 							&& el.attrs.iter().any(|candidate| {
-								schema
-									.props
-									.iter()
-									.find(|prop| prop.name == candidate.name)
-									.map(|prop| {
-										matches!(
-											&prop.kind,
-											crate::compiler::registry::PropKind::Event(_)
-										)
-									})
-									.unwrap_or(false)
+								schema.prop(&candidate.name).is_some_and(|prop| {
+									matches!(
+										&prop.kind,
+										crate::compiler::registry::PropKind::Event(_)
+									)
+								})
 							}) {
 							continue;
 						}
@@ -326,10 +457,21 @@ fn lower_widget(
 				}
 			}
 			EventKind::Select => ActionPayload::String,
+			EventKind::KeyDown | EventKind::KeyUp => {
+				if arg.is_some() {
+					ActionPayload::U32String
+				} else {
+					ActionPayload::String
+				}
+			}
 			EventKind::Custom(_) => ActionPayload::Json,
 		};
 		let event_name = kind_name(&kind);
-		ctx.add_action(action.clone(), kind, payload, span, diags);
+		let is_key = matches!(kind, EventKind::KeyDown | EventKind::KeyUp);
+		ctx.add_action(action.clone(), kind, payload, None, span, diags);
+		if is_key {
+			ctx.add_key_combo(&action, get_string_attr(el, "key"));
+		}
 		props.push(IrProp::Event {
 			name: event_name,
 			action,
@@ -337,14 +479,72 @@ fn lower_widget(
 		});
 	}
 
+	// Each binding gets a hidden action on the event that carries the new
+	// value; the runtime writes it back instead of calling a handler.
+	if let Some(kind) = binding_event(&el.name) {
+		for (path, span) in bindings {
+			let action = ctx.scoped_action(&format!("bind:state.{}", path.join(".")));
+			let payload = match &kind {
+				EventKind::Click => ActionPayload::None,
+				EventKind::SliderChange => ActionPayload::I32,
+				_ => ActionPayload::String,
+			};
+			ctx.add_action(
+				action.clone(),
+				kind.clone(),
+				payload,
+				Some(path),
+				span,
+				diags,
+			);
+			props.push(IrProp::Event {
+				name: kind_name(&kind),
+				action,
+				arg: None,
+			});
+		}
+	}
+
+	if let (Some(scope), Some(_)) = (&ctx.style_scope, schema_for(&el.name)) {
+		add_scope_class(&mut props, scope);
+	}
 	let children = lower_nodes(&el.children, ctx, diags);
 	Some(IrWidget {
 		tag: el.name.clone(),
 		props,
 		children,
+		span: el.span,
 	})
 }
 
+fn add_scope_class(props: &mut Vec<IrProp>, scope: &str) {
+	let class = props.iter_mut().find(|prop| {
+		matches!(prop, IrProp::Literal { name, .. } | IrProp::Value { name, .. } if name == "class")
+	});
+	match class {
+		Some(IrProp::Literal { value, .. }) => {
+			value.push(' ');
+			value.push_str(scope);
+		}
+		Some(IrProp::Value { expr, .. }) => {
+			let span = expr.span();
+			*expr = Expr::Binary {
+				left: Box::new(expr.clone()),
+				op: crate::ast::BinaryOp::Add,
+				right: Box::new(Expr::Literal(
+					crate::ast::Literal::String(format!(" {scope}")),
+					span,
+				)),
+				span,
+			};
+		}
+		_ => props.push(IrProp::Literal {
+			name: "class".to_string(),
+			value: scope.to_string(),
+		}),
+	}
+}
+
 fn is_custom_component_tag(name: &str) -> bool {
 	matches!(name, "Custom" | "CustomComponent")
 }
@@ -394,6 +594,8 @@ fn kind_name(kind: &EventKind) -> String {
 		EventKind::SliderChange => "onSliderChange".to_string(),
 		EventKind::Select => "onSelect".to_string(),
 		EventKind::ScrollNearBottom => "onScrollNearBottom".to_string(),
+		EventKind::KeyDown => "onKeyDown".to_string(),
+		EventKind::KeyUp => "onKeyUp".to_string(),
 		EventKind::Custom(name) => name.clone(),
 	}
 }
@@ -420,6 +622,17 @@ fn get_expr_attr(el: &Element, name: &str) -> Option<Expr> {
 	None
 }
 
+fn attr_value_expr(value: &AttrValue) -> Expr {
+	use crate::ast::Literal;
+	match value {
+		AttrValue::String(value, span) => Expr::Literal(Literal::String(value.clone()), *span),
+		AttrValue::Number(value, span) => Expr::Literal(Literal::Number(*value), *span),
+		AttrValue::Bool(value, span) => Expr::Literal(Literal::Bool(*value), *span),
+		AttrValue::Null(span) => Expr::Literal(Literal::Null, *span),
+		AttrValue::Expr(expr) => expr.clone(),
+	}
+}
+
 fn get_expr_like(el: &Element, name: &str) -> Option<Expr> {
 	for attr in &el.attrs {
 		if attr.name == name {
@@ -443,6 +656,7 @@ fn normalize_prop_name(tag: &str, name: &str) -> String {
 		("DatePicker", "bind:value") => "bind:svalue".to_string(),
 		("Slider", "value") => "ivalue".to_string(),
 		("Slider", "bind:value") => "bind:ivalue".to_string(),
+		(_, "key") => "keyCombo".to_string(),
 		_ => name.to_string(),
 	}
 }
@@ -2,11 +2,14 @@ pub mod codegen;
 pub mod ir;
 pub mod lower;
 pub mod registry;
+pub mod signature;
+pub mod typecheck;
 pub mod validate;
 
 use crate::ast::Node;
 use crate::compiler::ir::ActionDef;
 use crate::diagnostic::Diagnostic;
+use crate::schema::{FieldType, StateSchema};
 use std::collections::HashMap;
 use std::io;
 use std::path::Path;
@@ -18,6 +21,12 @@ pub struct GeneratedModule {
 	pub routes: Vec<(String, String)>,
 	pub source_files: Vec<std::path::PathBuf>,
 	pub controller_stub: Option<String>,
+	/// The render as a block expression for macros to inline, see
+	/// [`codegen::generate_render_block`].
+	pub render_block: String,
+	/// What a route handler binds before inlining `render_block`, see
+	/// [`codegen::generate_route_bindings`].
+	pub route_bindings: String,
 }
 
 pub fn compile(source: &str, module_name: &str) -> Result<GeneratedModule, Vec<Diagnostic>> {
@@ -73,6 +82,8 @@ fn compile_nodes_with_components(
 	}
 	let code = codegen::generate(&lowered);
 	let controller_stub = codegen::generate_controller_stub(&lowered, module_name);
+	let render_block = codegen::generate_render_block(&lowered);
+	let route_bindings = codegen::generate_route_bindings(&lowered);
 	let actions = lowered.actions.clone();
 	let routes = lowered
 		.pages
@@ -85,9 +96,49 @@ fn compile_nodes_with_components(
 		routes,
 		source_files,
 		controller_stub,
+		render_block,
+		route_bindings,
 	})
 }
 
+/// Check the paths in `source` against a state type: `state` when given,
+/// otherwise the model named by `<Route state="..">`. Templates with neither
+/// are not checked.
+pub fn check_state(
+	source: &str,
+	module_name: &str,
+	base_dir: Option<&Path>,
+	schema: &StateSchema,
+	state: Option<FieldType>,
+) -> Result<(), Vec<Diagnostic>> {
+	let resolved = crate::imports::resolve(source, module_name, base_dir)?;
+	let mut diags = Vec::new();
+	let Some(validated) = validate::validate(&resolved.nodes, &resolved.components, &mut diags)
+	else {
+		return Err(diags);
+	};
+	let lowered = lower::lower(&validated, module_name, &mut diags);
+	if !diags.is_empty() {
+		return Err(diags);
+	}
+	let state = state.or_else(|| {
+		lowered
+			.pages
+			.iter()
+			.find_map(|page| page.state_type.clone())
+			.map(FieldType::Model)
+	});
+	let Some(state) = state else {
+		return Ok(());
+	};
+	let diags = typecheck::check(&lowered, schema, &state);
+	if diags.is_empty() {
+		Ok(())
+	} else {
+		Err(diags)
+	}
+}
+
 impl GeneratedModule {
 	pub fn source_files(&self) -> Vec<std::path::PathBuf> {
 		self.source_files.clone()
@@ -118,6 +169,39 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn compiles_builtin_calls_and_string_concatenation() {
+		let src = r#"
+<VStack>
+	<Text value={upper(state.name)} />
+	<Text value={`${len(state.items)} ${pluralize(len(state.items), "item")}`} />
+	<Text value={"Total: " + format_number(state.total, 2)} />
+	<Text value={state.first + state.last} />
+	<Text value={slice("n1", 1) + 1} />
+</VStack>
+"#;
+		let generated = compile(src, "stats").expect("compile should succeed");
+
+		assert!(generated
+			.code
+			.contains("wgui::wui::builtins::WuiText::wui_text(&(state.name)).to_uppercase()"));
+		assert!(generated
+			.code
+			.contains("wgui::wui::builtins::WuiLen::wui_len(&*(state.items))"));
+		assert!(generated.code.contains("wgui::wui::builtins::pluralize("));
+		assert!(generated.code.contains(
+			"format!(\"{}{}\", \"Total: \", wgui::i18n::format_number(wgui::wui::builtins::WuiNumber::wui_number(&(state.total)), Some((2) as f64)))"
+		));
+		// Field types are unknown here, so `+` picks concatenation or
+		// addition at render time.
+		assert!(generated
+			.code
+			.contains("wgui::wui::builtins::add(&(state.first), &(state.last))"));
+		assert!(generated
+			.code
+			.contains("format!(\"{}{}\", wgui::wui::builtins::WuiSlice::wui_slice("));
+	}
+
 	#[test]
 	fn compiles_custom_component_event_actions() {
 		let src = r#"
@@ -149,6 +233,305 @@ mod tests {
 		assert!(generated.code.contains(".on_scroll_near_bottom("));
 	}
 
+	#[test]
+	fn compiles_multiple_handlers_with_their_own_args() {
+		let src = r#"
+<For each={state.rows} itemAs="row" indexAs="i">
+	<HStack onClick="Open" onKeyDown="Navigate" arg={row.id} arg:onKeyDown={i}>
+		<Text value={row.title} />
+	</HStack>
+</For>
+"#;
+		let generated = compile(src, "rows").expect("compile should succeed");
+
+		assert!(generated.code.contains("Open { arg: u32 }"));
+		assert!(generated
+			.code
+			.contains("Navigate { arg: u32, value: String }"));
+		let open = action_id_of(&generated, "Open");
+		let navigate = action_id_of(&generated, "Navigate");
+		let index = |expr: &str| {
+			format!(
+				"let __inx = {{ let __n = wgui::wui::builtins::WuiNumber::wui_number(&({expr})); (__n >= 0.0).then_some(__n as u32) }};"
+			)
+		};
+		for expected in [
+			index("row.id"),
+			index("i"),
+			format!(".on_click({open}); let __item = match __inx {{ Some(inx) => __item.inx(inx), None => __item }}; __item.on(\"click\", {open}, __inx) }}"),
+			format!("__item.on(\"keyDown\", {navigate}, __inx) }}"),
+		] {
+			assert!(generated.code.contains(&expected), "{}", generated.code);
+		}
+		assert!(generated.code.contains("wgui::ClientEvent::OnKeyDown(ev)"));
+	}
+
+	#[test]
+	fn rejects_args_for_missing_handlers() {
+		let src = r#"<Button text="Go" onClick="Go" arg:onPress=1 arg:onScroll=2 />"#;
+		let messages = compile(src, "bad")
+			.expect_err("compile should fail")
+			.into_iter()
+			.map(|diag| diag.message)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			messages,
+			vec![
+				"Button has no onPress handler".to_string(),
+				"onScroll is not an event of Button".to_string(),
+			]
+		);
+	}
+
+	#[test]
+	fn compiles_key_handlers_and_page_shortcuts() {
+		let src = r#"
+<VStack>
+	<Shortcut key="Ctrl+S" onKeyDown="Save" preventDefault=true />
+	<Text value="Search" onKeyUp="Close" key="Escape" />
+</VStack>
+"#;
+		let generated = compile(src, "keys").expect("compile should succeed");
+
+		let save = action_id_of(&generated, "Save");
+		let close = action_id_of(&generated, "Close");
+		assert!(
+			generated.code.contains(&format!(
+				"wgui::shortcut(\"Ctrl+S\").prevent_default(true).on(\"keyDown\", {save}, None)"
+			)),
+			"{}",
+			generated.code
+		);
+		assert!(generated.code.contains(&format!(
+			".key_combo(\"Escape\").on(\"keyUp\", {close}, None)"
+		)));
+		assert!(generated.code.contains("Close { value: String }"));
+		assert!(generated.code.contains(&format!(
+			"wgui::ClientEvent::OnKeyDown(ev) if ev.id.as_deref() == Some(\"{save}\") && (ev.matches(\"Ctrl+S\"))"
+		)));
+		assert!(generated.code.contains(&format!(
+			"wgui::ClientEvent::OnKeyUp(ev) if ev.id.as_deref() == Some(\"{close}\") && (ev.matches(\"Escape\"))"
+		)));
+	}
+
+	#[test]
+	fn rejects_invalid_key_declarations() {
+		let src = r#"
+<VStack>
+	<Shortcut key="Ctrl+Shift" onKeyDown="Save" />
+	<Shortcut onKeyDown="Quit" />
+	<Shortcut key="Escape" />
+	<Button text="Go" key="Enter" />
+</VStack>
+"#;
+		let messages = compile(src, "bad")
+			.expect_err("compile should fail")
+			.into_iter()
+			.map(|diag| diag.message)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			messages,
+			vec![
+				"invalid key combination: missing key in Ctrl+Shift".to_string(),
+				"missing required attribute key".to_string(),
+				"Shortcut needs an onKeyDown or onKeyUp handler".to_string(),
+				"key needs an onKeyDown or onKeyUp handler".to_string(),
+			]
+		);
+	}
+
+	#[test]
+	fn compiles_let_else_if_and_match() {
+		let src = r#"
+<VStack>
+	<Let name="total" value={state.price * state.qty}>
+		<Text value={total} />
+	</Let>
+	<If test={state.count == 0}>
+		<Text value="none" />
+	</If>
+	<ElseIf test={state.count == 1}>
+		<Text value="one" />
+	</ElseIf>
+	<Else>
+		<Text value="many" />
+	</Else>
+	<Match value={state.status}>
+		<Case is="open"><Text value="Open" /></Case>
+		<Case is="done"><Text value="Done" /></Case>
+		<Else><Text value="Unknown" /></Else>
+	</Match>
+</VStack>
+"#;
+		let generated = compile(src, "control_flow").expect("compile should succeed");
+
+		assert!(generated.code.contains(
+			"let total = &((wgui::wui::builtins::WuiNumber::wui_number(&(state.price)) * wgui::wui::builtins::WuiNumber::wui_number(&(state.qty))));"
+		));
+		assert!(generated.code.contains(
+			"} else if (wgui::wui::builtins::WuiNumber::wui_number(&(state.count)) == (1) as f64) {"
+		));
+		assert!(generated
+			.code
+			.contains("let __wui_match = wgui::wui::builtins::WuiText::wui_text(&(state.status)).into_owned();"));
+		assert!(generated
+			.code
+			.contains("} else if __wui_match == \"done\" {"));
+	}
+
+	#[test]
+	fn compiles_scoped_styles() {
+		let src = r#"
+<Style>
+	.card > Text { color: gray; }
+</Style>
+<VStack class="card">
+	<Text value="a" class={state.tone} />
+	<Text value="b" />
+</VStack>
+"#;
+		let generated = compile(src, "styled").expect("compile should succeed");
+		let scope = crate::style::scope_class("styled", ".card > Text { color: gray; }\n\n");
+		assert!(generated.code.contains(&format!(
+			"wgui::wui::styles::register(\"styled\", \"{scope}\", \".card > Text.{scope} {{ color: gray; }}\\n\");"
+		)));
+		assert!(generated.code.contains(&format!("\"card {scope}\"")));
+		assert_eq!(generated.code.matches(&format!("\" {scope}\"")).count(), 1);
+
+		let diags = compile("<Style class=\"x\">.a { b: c</Style>", "styled").unwrap_err();
+		let messages = diags.iter().map(|d| d.message.as_str()).collect::<Vec<_>>();
+		assert_eq!(
+			messages,
+			vec![
+				"Style does not take attributes",
+				"invalid CSS in Style: missing }"
+			]
+		);
+	}
+
+	#[test]
+	fn compiles_route_urls() {
+		let src = r#"<Link href={url("todo", state.id)} text="Open" />"#;
+		let generated = compile(src, "links").expect("compile should succeed");
+		assert!(generated.code.contains(
+			"wgui::wui::route_handler::route_url(\"todo\", &[wgui::wui::runtime::WuiValueConvert::to_wui_value(&("
+		));
+
+		let src = r#"<Link href={url(state.route)} text="Open" />"#;
+		let messages = compile(src, "bad")
+			.expect_err("compile should fail")
+			.into_iter()
+			.map(|diag| diag.message)
+			.collect::<Vec<_>>();
+		assert_eq!(messages, vec!["url route must be a string literal"]);
+	}
+
+	#[test]
+	fn compiles_translated_messages() {
+		let src = r#"<Text value={t("cart-items", "count", len(state.items))} />"#;
+		let generated = compile(src, "cart").expect("compile should succeed");
+		assert!(generated.code.contains(
+			"wgui::i18n::t(\"cart-items\", &[(\"count\", wgui::wui::runtime::WuiValueConvert::to_wui_value(&("
+		));
+
+		let src = r#"
+<VStack>
+	<Text value={t(state.key)} />
+	<Text value={t("a", "count")} />
+	<Text value={t("a", state.name, 1)} />
+</VStack>
+"#;
+		let messages = compile(src, "bad")
+			.expect_err("compile should fail")
+			.into_iter()
+			.map(|diag| diag.message)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			messages,
+			vec![
+				"t key must be a string literal",
+				"t arguments must be name/value pairs after the key",
+				"t argument names must be string literals",
+			]
+		);
+	}
+
+	#[test]
+	fn rejects_invalid_control_flow() {
+		let src = r#"
+<VStack>
+	<ElseIf test={true}><Text value="a" /></ElseIf>
+	<Let name="state" value={1}><Text value="b" /></Let>
+	<Let name="self" value={1}><Text value="b" /></Let>
+	<Let name="__item" value={1}><Text value="b" /></Let>
+	<Match value={state.status}>
+		<Else><Text value="c" /></Else>
+		<Case is="open"><Text value="d" /></Case>
+		<Case is="open"><Text value="e" /></Case>
+	</Match>
+</VStack>
+"#;
+		let messages = compile(src, "bad")
+			.expect_err("compile should fail")
+			.into_iter()
+			.map(|diag| diag.message)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			messages,
+			vec![
+				"ElseIf must follow an If or ElseIf".to_string(),
+				"invalid Let name state".to_string(),
+				"invalid Let name self".to_string(),
+				"invalid Let name __item".to_string(),
+				"Else must be the last child of Match".to_string(),
+				"Match has more than one Case is=\"open\"".to_string(),
+			]
+		);
+	}
+
+	#[test]
+	fn compiles_error_boundaries() {
+		let src = r#"
+<ErrorBoundary>
+	<Text value={state.name} />
+	<Fallback><Text value="Could not show the name" /></Fallback>
+</ErrorBoundary>
+"#;
+		let generated = compile(src, "boundary").expect("compile should succeed");
+		assert!(generated
+			.code
+			.contains("match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {"));
+		assert!(generated
+			.code
+			.contains("Ok(items) => __children.extend(items),"));
+		assert!(generated
+			.code
+			.contains("__children.push(wgui::text(\"Could not show the name\")"));
+
+		let src = r#"
+<VStack>
+	<Fallback><Text value="a" /></Fallback>
+	<ErrorBoundary size="2">
+		<Fallback><Text value="b" /></Fallback>
+		<Fallback><Text value="c" /></Fallback>
+	</ErrorBoundary>
+</VStack>
+"#;
+		let messages = compile(src, "bad")
+			.expect_err("compile should fail")
+			.into_iter()
+			.map(|diag| diag.message)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			messages,
+			vec![
+				"Fallback must be inside an ErrorBoundary".to_string(),
+				"ErrorBoundary does not take attributes".to_string(),
+				"ErrorBoundary has more than one Fallback".to_string(),
+			]
+		);
+	}
+
 	#[test]
 	fn compiles_white_space_prop() {
 		let src = r#"<Text value="row" whiteSpace="pre-wrap" />"#;
@@ -198,10 +581,242 @@ mod tests {
 		assert!(generated.code.contains("wgui::connected"));
 	}
 
+	#[test]
+	fn compiles_bindings_to_hidden_setter_actions() {
+		let src = r#"
+<VStack>
+	<TextInput bind:value={state.draft.title} />
+	<Checkbox bind:checked={state.done} />
+	<Button text="Save" onClick="Save" />
+</VStack>
+"#;
+		let generated = compile(src, "editor").expect("compile should succeed");
+		let bindings = generated
+			.actions
+			.iter()
+			.filter_map(|action| action.binding.clone())
+			.collect::<Vec<_>>();
+		assert_eq!(
+			bindings,
+			vec![
+				vec!["draft".to_string(), "title".to_string()],
+				vec!["done".to_string()],
+			]
+		);
+		assert!(generated.code.contains("pub enum Action {\n\tSave,\n}"));
+		assert!(generated
+			.code
+			.contains("pub fn apply_binding(state: &mut crate::State"));
+		assert!(generated.code.contains(
+			"&[\"draft\".to_string(), \"title\".to_string()], wgui::wui::runtime::BindingWrite::Set("
+		));
+		assert!(generated
+			.code
+			.contains("wgui::wui::runtime::BindingWrite::Toggle"));
+		let stub = generated.controller_stub.unwrap();
+		assert!(!stub.contains("bind"), "{stub}");
+	}
+
+	#[test]
+	fn rejects_bindings_that_cannot_write_back() {
+		let src = r#"
+<VStack>
+	<TextInput bind:value={upper(state.name)} />
+	<Checkbox bind:checked={state.done} onClick="Toggle" />
+</VStack>
+"#;
+		let messages = compile(src, "bad")
+			.expect_err("compile should fail")
+			.into_iter()
+			.map(|diag| diag.message)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			messages,
+			vec![
+				"bind:value must be a state path such as {state.name}".to_string(),
+				"bind:checked already handles onClick; remove one of them".to_string(),
+			]
+		);
+	}
+
+	fn action_id_of(generated: &GeneratedModule, name: &str) -> u32 {
+		generated
+			.actions
+			.iter()
+			.find(|action| action.name == name)
+			.map(|action| action.id)
+			.unwrap()
+	}
+
+	#[test]
+	fn checks_component_call_sites_against_props_and_slots() {
+		let card = r#"
+<Props>
+	<Prop name="title" type="string" required />
+	<Prop name="count" type="number" default=0 />
+</Props>
+<VStack>
+	<Slot name="header" />
+	<Text value={title} />
+</VStack>
+"#;
+		let src = r#"
+<Import name="Card" from="card" />
+<Card count="many" colour="red">
+	<Slot name="footer"><Text value="x" /></Slot>
+	<Text value="body" />
+</Card>
+"#;
+		let messages =
+			compile_with_loader(src, "page", Some(Path::new("/t")), |_| Ok(card.to_string()))
+				.expect_err("compile should fail")
+				.into_iter()
+				.map(|diag| diag.message)
+				.collect::<Vec<_>>();
+		assert_eq!(
+			messages,
+			vec![
+				"Card has no slot footer".to_string(),
+				"Card does not render Children".to_string(),
+				"count expects a number".to_string(),
+				"unknown prop colour for Card; expected one of: title, count".to_string(),
+				"Card requires prop title".to_string(),
+			]
+		);
+
+		let ok = r#"
+<Import name="Card" from="card" />
+<Card title="Inbox"><Slot name="header"><Text value="Hi" /></Slot></Card>
+"#;
+		compile_with_loader(ok, "page", Some(Path::new("/t")), |_| Ok(card.to_string()))
+			.expect("compile should succeed");
+	}
+
+	#[test]
+	fn rejects_malformed_prop_declarations() {
+		let src = r#"
+<Props>
+	<Prop name="size" type="int" />
+	<Prop name="label" type="string" required default="x" />
+	<Prop name="dense" type="bool" default="yes" />
+</Props>
+<VStack><Props /></VStack>
+"#;
+		let messages = compile(src, "bad")
+			.expect_err("compile should fail")
+			.into_iter()
+			.map(|diag| diag.message)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			messages,
+			vec![
+				"unknown prop type int; use string, number, bool or any".to_string(),
+				"a required prop cannot have a default".to_string(),
+				"default must be a bool".to_string(),
+				"Props must be at the top level of a component file".to_string(),
+			]
+		);
+	}
+
 	#[test]
 	fn reports_unknown_tag() {
 		let src = "<UnknownTag />";
 		let result = compile(src, "bad");
 		assert!(result.is_err());
 	}
+
+	#[test]
+	fn checks_paths_against_the_state_schema() {
+		let mut schema = StateSchema::new();
+		schema
+			.scan_source(
+				r#"
+				#[derive(WuiModel)]
+				struct TodoState { title: String, items: Vec<TodoItem> }
+				#[derive(WuiModel)]
+				struct TodoItem { name: String, done: bool }
+				"#,
+			)
+			.unwrap();
+		let src = r#"
+<Route path="/todo" state="TodoState" />
+<VStack>
+	<Text value={state.title} />
+	<Text value={state.itmes} />
+	<For each={state.items} itemAs="todo">
+		<Checkbox checked={todo.done} />
+		<Text value={todo.nmae} />
+	</For>
+	<For each={state.title}>
+		<Text value={params.id} />
+	</For>
+</VStack>
+"#;
+		let diags = check_state(src, "todo", None, &schema, None).expect_err("typos");
+		let messages = diags
+			.iter()
+			.map(|diag| diag.message.as_str())
+			.collect::<Vec<_>>();
+		assert_eq!(
+			messages,
+			vec![
+				"unknown field `itmes` on `state`; known fields: title, items",
+				"unknown field `nmae` on `todo`; known fields: name, done",
+				"`For each` needs a list, but got a string",
+			]
+		);
+		assert_eq!(&src[diags[0].span.start..diags[0].span.end], "state.itmes");
+
+		let without_page = "<Text value={state.anything} />";
+		assert!(check_state(without_page, "x", None, &schema, None).is_ok());
+	}
+
+	#[test]
+	fn reports_unknown_functions_and_wrong_arity() {
+		let src = r#"
+<VStack>
+	<Text value={shout(state.name)} />
+	{upper(state.name, 2)}
+</VStack>
+"#;
+		let messages = compile(src, "bad")
+			.expect_err("compile should fail")
+			.into_iter()
+			.map(|diag| diag.message)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			messages,
+			vec![
+				"unknown function shout".to_string(),
+				"upper expects 1 argument(s), got 2".to_string(),
+			]
+		);
+	}
+
+	#[test]
+	fn render_block_inlines_components() {
+		let generated =
+			compile(r#"<Text value={state.count} />"#, "count").expect("compile should succeed");
+		let block = generated.render_block;
+		assert!(block.starts_with("{\n"), "{block}");
+		assert!(
+			block.contains("wgui::text(&*wgui::wui::builtins::WuiText::wui_text(&(state.count)))")
+		);
+
+		let card = r#"
+<Props><Prop name="title" type="string" default="Untitled" /></Props>
+<VStack><Text value={title} /><Children /></VStack>
+"#;
+		let src = r#"
+<Import name="Card" from="card" />
+<VStack><Card><Text value="body" /></Card></VStack>
+"#;
+		let generated =
+			compile_with_loader(src, "page", Some(Path::new("/t")), |_| Ok(card.to_string()))
+				.expect("compile should succeed");
+		let block = generated.render_block;
+		assert!(!block.contains("Card"), "{block}");
+		assert!(block.contains("let (title,) = (\"Untitled\",);"), "{block}");
+		assert!(block.contains("wgui::text(\"body\")"), "{block}");
+	}
 }
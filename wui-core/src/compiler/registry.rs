@@ -27,6 +27,41 @@ pub struct TagSchema {
 	pub props: &'static [PropSchema],
 }
 
+/// Props every widget accepts on top of its own: `class` and the key
+/// handlers.
+pub const COMMON_PROPS: &[PropSchema] = &[
+	PropSchema {
+		name: "class",
+		kind: PropKind::Value(ValueType::String),
+	},
+	PropSchema {
+		name: "onKeyDown",
+		kind: PropKind::Event(EventKind::KeyDown),
+	},
+	PropSchema {
+		name: "onKeyUp",
+		kind: PropKind::Event(EventKind::KeyUp),
+	},
+	PropSchema {
+		name: "key",
+		kind: PropKind::Value(ValueType::String),
+	},
+	PropSchema {
+		name: "preventDefault",
+		kind: PropKind::Value(ValueType::Bool),
+	},
+];
+
+impl TagSchema {
+	pub fn prop(&self, name: &str) -> Option<&'static PropSchema> {
+		self.all_props().find(|prop| prop.name == name)
+	}
+
+	pub fn all_props(&self) -> impl Iterator<Item = &'static PropSchema> {
+		self.props.iter().chain(COMMON_PROPS)
+	}
+}
+
 pub fn schema_for(tag: &str) -> Option<TagSchema> {
 	match tag {
 		"VStack" => Some(TagSchema {
@@ -335,6 +370,10 @@ pub fn schema_for(tag: &str) -> Option<TagSchema> {
 					name: "value",
 					kind: PropKind::Value(ValueType::Number),
 				},
+				PropSchema {
+					name: "bind:value",
+					kind: PropKind::Bind(ValueType::Number),
+				},
 				PropSchema {
 					name: "step",
 					kind: PropKind::Value(ValueType::Number),
@@ -421,6 +460,10 @@ pub fn schema_for(tag: &str) -> Option<TagSchema> {
 					name: "value",
 					kind: PropKind::Value(ValueType::String),
 				},
+				PropSchema {
+					name: "bind:value",
+					kind: PropKind::Bind(ValueType::String),
+				},
 				PropSchema {
 					name: "options",
 					kind: PropKind::Value(ValueType::Any),
@@ -696,19 +739,64 @@ pub fn schema_for(tag: &str) -> Option<TagSchema> {
 				},
 			],
 		}),
+		"Shortcut" => Some(TagSchema {
+			name: "Shortcut",
+			props: &[PropSchema {
+				name: "arg",
+				kind: PropKind::Value(ValueType::Number),
+			}],
+		}),
 		_ => None,
 	}
 }
 
+/// The client event a `bind:*` prop on `tag` listens to for new values.
+pub fn binding_event(tag: &str) -> Option<EventKind> {
+	match tag {
+		"TextInput" | "DatePicker" => Some(EventKind::TextChanged),
+		"Checkbox" => Some(EventKind::Click),
+		"Slider" => Some(EventKind::SliderChange),
+		"Select" => Some(EventKind::Select),
+		_ => None,
+	}
+}
+
+/// The key an event prop is registered under in `Item::handlers`, or `None`
+/// for change events, which are sent with the item's own id.
+pub fn handler_key(event: &str) -> Option<String> {
+	match event {
+		"onTextChanged" | "onSliderChange" | "onSelect" => None,
+		_ => {
+			let Some(rest) = event.strip_prefix("on").filter(|rest| {
+				rest.chars()
+					.next()
+					.is_some_and(|first| first.is_ascii_uppercase())
+			}) else {
+				// Custom component events are already bare names.
+				return Some(event.to_string());
+			};
+			let mut key = rest[..1].to_ascii_lowercase();
+			key.push_str(&rest[1..]);
+			Some(key)
+		}
+	}
+}
+
 pub fn is_structural(tag: &str) -> bool {
 	matches!(
 		tag,
 		"For"
-			| "If" | "Else"
-			| "Scope" | "Page"
-			| "Route" | "Switch"
+			| "If" | "ElseIf"
+			| "Else" | "Let"
+			| "Match" | "Scope"
+			| "Page" | "Route"
+			| "Switch"
 			| "Case" | "Import"
+			| "ErrorBoundary"
+			| "Fallback"
 			| "Children"
+			| "Props" | "Prop"
+			| "Slot" | "Style"
 	)
 }
 
@@ -842,9 +930,25 @@ fn layout_props() -> &'static [PropSchema] {
 			name: "overflow",
 			kind: PropKind::Value(ValueType::String),
 		},
+		PropSchema {
+			name: "arg",
+			kind: PropKind::Value(ValueType::Number),
+		},
 		PropSchema {
 			name: "onScrollNearBottom",
 			kind: PropKind::Event(EventKind::ScrollNearBottom),
 		},
+		PropSchema {
+			name: "onClick",
+			kind: PropKind::Event(EventKind::Click),
+		},
+		PropSchema {
+			name: "onPress",
+			kind: PropKind::Event(EventKind::Press),
+		},
+		PropSchema {
+			name: "onRelease",
+			kind: PropKind::Event(EventKind::Release),
+		},
 	]
 }
@@ -0,0 +1,136 @@
+//! What an imported component accepts: the props declared in its `<Props>`
+//! header, the named `<Slot>` placeholders in its body and whether it renders
+//! `<Children />`.
+//!
+//! ```text
+//! <Props>
+//!     <Prop name="title" type="string" required />
+//!     <Prop name="compact" type="bool" default=false />
+//! </Props>
+//! <VStack>
+//!     <Slot name="header"><Text value={title} /></Slot>
+//!     <Children />
+//! </VStack>
+//! ```
+
+use crate::ast::{AttrValue, Element, Node};
+use crate::compiler::registry::ValueType;
+use crate::diagnostic::Span;
+
+#[derive(Debug, Clone)]
+pub struct PropDecl {
+	pub name: String,
+	pub ty: ValueType,
+	pub required: bool,
+	pub default: Option<AttrValue>,
+	pub span: Span,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ComponentSignature {
+	/// `None` when the component has no `<Props>` header; any prop is then
+	/// accepted, as before typed props existed.
+	pub props: Option<Vec<PropDecl>>,
+	pub slots: Vec<String>,
+	pub children: bool,
+}
+
+impl ComponentSignature {
+	pub fn prop(&self, name: &str) -> Option<&PropDecl> {
+		self.props.as_ref()?.iter().find(|prop| prop.name == name)
+	}
+}
+
+pub fn signature(nodes: &[Node]) -> ComponentSignature {
+	let mut signature = ComponentSignature::default();
+	for node in nodes {
+		if let Node::Element(el) = node {
+			if el.name == "Props" {
+				let props = signature.props.get_or_insert_with(Vec::new);
+				props.extend(el.children.iter().filter_map(prop_decl));
+			}
+		}
+	}
+	collect_placeholders(nodes, &mut signature);
+	signature
+}
+
+fn collect_placeholders(nodes: &[Node], signature: &mut ComponentSignature) {
+	for node in nodes {
+		let Node::Element(el) = node else {
+			continue;
+		};
+		match el.name.as_str() {
+			"Props" => continue,
+			"Children" => signature.children = true,
+			"Slot" => {
+				if let Some(name) = string_attr(el, "name") {
+					if !signature.slots.contains(&name) {
+						signature.slots.push(name);
+					}
+				}
+			}
+			_ => {}
+		}
+		collect_placeholders(&el.children, signature);
+	}
+}
+
+fn prop_decl(node: &Node) -> Option<PropDecl> {
+	let Node::Element(el) = node else {
+		return None;
+	};
+	if el.name != "Prop" {
+		return None;
+	}
+	let name = string_attr(el, "name")?;
+	let ty = string_attr(el, "type")
+		.and_then(|ty| prop_type(&ty))
+		.unwrap_or(ValueType::Any);
+	let required = el
+		.attrs
+		.iter()
+		.any(|attr| attr.name == "required" && matches!(attr.value, AttrValue::Bool(true, _)));
+	let default = el
+		.attrs
+		.iter()
+		.find(|attr| attr.name == "default")
+		.map(|attr| attr.value.clone());
+	Some(PropDecl {
+		name,
+		ty,
+		required,
+		default,
+		span: el.span,
+	})
+}
+
+/// The `type` names a `<Prop>` can declare.
+pub fn prop_type(name: &str) -> Option<ValueType> {
+	match name {
+		"string" => Some(ValueType::String),
+		"number" => Some(ValueType::Number),
+		"bool" => Some(ValueType::Bool),
+		"any" => Some(ValueType::Any),
+		_ => None,
+	}
+}
+
+pub fn type_name(ty: ValueType) -> &'static str {
+	match ty {
+		ValueType::String => "string",
+		ValueType::Number => "number",
+		ValueType::Bool => "bool",
+		ValueType::Any => "any",
+	}
+}
+
+fn string_attr(el: &Element, name: &str) -> Option<String> {
+	el.attrs
+		.iter()
+		.find(|attr| attr.name == name)
+		.and_then(|attr| match &attr.value {
+			AttrValue::String(value, _) => Some(value.clone()),
+			_ => None,
+		})
+}
@@ -0,0 +1,176 @@
+use crate::ast::Expr;
+use crate::compiler::ir::{IrDocument, IrNode, IrProp};
+use crate::compiler::registry::ValueType;
+use crate::diagnostic::Diagnostic;
+use crate::schema::{FieldType, StateSchema};
+use std::collections::HashMap;
+
+/// Check every `state.*` path (and paths through `For` item variables) in
+/// `doc` against `state`. Roots the schema knows nothing about, such as
+/// `params` or component props, are left alone.
+pub fn check(doc: &IrDocument, schema: &StateSchema, state: &FieldType) -> Vec<Diagnostic> {
+	let mut checker = Checker {
+		schema,
+		diags: Vec::new(),
+	};
+	let env = HashMap::from([("state".to_string(), state.clone())]);
+	checker.nodes(&doc.nodes, &env);
+	let mut components = doc.components.iter().collect::<Vec<_>>();
+	components.sort_by(|a, b| a.0.cmp(b.0));
+	for (_, component) in components {
+		let mut env = env.clone();
+		for prop in &component.props {
+			env.insert(prop.name.clone(), prop_field_type(prop.ty));
+		}
+		checker.nodes(&component.body, &env);
+	}
+	checker.diags
+}
+
+type Env = HashMap<String, FieldType>;
+
+struct Checker<'a> {
+	schema: &'a StateSchema,
+	diags: Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+	fn nodes(&mut self, nodes: &[IrNode], env: &Env) {
+		for node in nodes {
+			self.node(node, env);
+		}
+	}
+
+	fn node(&mut self, node: &IrNode, env: &Env) {
+		match node {
+			IrNode::Widget(widget) => {
+				for prop in &widget.props {
+					match prop {
+						IrProp::Value { expr, .. } | IrProp::Bind { expr, .. } => {
+							self.expr(expr, env);
+						}
+						IrProp::Event { arg: Some(arg), .. } => {
+							self.expr(arg, env);
+						}
+						_ => {}
+					}
+				}
+				self.nodes(&widget.children, env);
+			}
+			IrNode::For(node) => {
+				let each = self.expr(&node.each, env);
+				let item = match each.map(strip_optional) {
+					Some(FieldType::List(item)) => *item,
+					Some(FieldType::Any) | None => FieldType::Any,
+					Some(other) => {
+						self.diags.push(Diagnostic::new(
+							format!("`For each` needs a list, but got {}", other.describe()),
+							node.each.span(),
+						));
+						FieldType::Any
+					}
+				};
+				let mut env = env.clone();
+				env.insert(node.item.clone(), item);
+				if let Some(index) = &node.index {
+					env.insert(index.clone(), FieldType::Number);
+				}
+				if let Some(key) = &node.key {
+					self.expr(key, &env);
+				}
+				self.nodes(&node.body, &env);
+			}
+			IrNode::If(node) => {
+				self.expr(&node.test, env);
+				self.nodes(&node.then_body, env);
+				self.nodes(&node.else_body, env);
+			}
+			IrNode::Let(node) => {
+				let ty = self.expr(&node.value, env).unwrap_or(FieldType::Any);
+				let mut env = env.clone();
+				env.insert(node.name.clone(), ty);
+				self.nodes(&node.body, &env);
+			}
+			IrNode::Match(node) => {
+				self.expr(&node.value, env);
+				for case in &node.cases {
+					self.nodes(&case.body, env);
+				}
+				self.nodes(&node.else_body, env);
+			}
+			IrNode::Scope(node) => self.nodes(&node.body, env),
+			IrNode::ErrorBoundary(node) => {
+				self.nodes(&node.body, env);
+				self.nodes(&node.fallback, env);
+			}
+			IrNode::Slot(node) => self.nodes(&node.body, env),
+			IrNode::Route(node) => self.nodes(&node.body, env),
+			IrNode::Switch(node) => {
+				for case in &node.cases {
+					self.nodes(&case.body, env);
+				}
+			}
+			IrNode::Children | IrNode::Text(_) => {}
+		}
+	}
+
+	/// Check `expr`, returning the type of a resolvable path.
+	fn expr(&mut self, expr: &Expr, env: &Env) -> Option<FieldType> {
+		match expr {
+			Expr::Literal(_, _) => None,
+			Expr::Path(parts, span) => {
+				let (root, rest) = parts.split_first()?;
+				let root_type = env.get(root)?;
+				match self.schema.resolve(root_type, root, rest) {
+					Ok(ty) => Some(ty),
+					Err(message) => {
+						self.diags.push(Diagnostic::new(message, *span));
+						None
+					}
+				}
+			}
+			Expr::Call { args, .. } => {
+				for arg in args {
+					self.expr(arg, env);
+				}
+				None
+			}
+			Expr::Unary { expr, .. } => {
+				self.expr(expr, env);
+				None
+			}
+			Expr::Binary { left, right, .. } | Expr::Coalesce { left, right, .. } => {
+				self.expr(left, env);
+				self.expr(right, env);
+				None
+			}
+			Expr::Ternary {
+				cond,
+				then_expr,
+				else_expr,
+				..
+			} => {
+				self.expr(cond, env);
+				self.expr(then_expr, env);
+				self.expr(else_expr, env);
+				None
+			}
+		}
+	}
+}
+
+fn prop_field_type(ty: ValueType) -> FieldType {
+	match ty {
+		ValueType::String => FieldType::String,
+		ValueType::Number => FieldType::Number,
+		ValueType::Bool => FieldType::Bool,
+		ValueType::Any => FieldType::Any,
+	}
+}
+
+fn strip_optional(ty: FieldType) -> FieldType {
+	match ty {
+		FieldType::Optional(inner) => strip_optional(*inner),
+		other => other,
+	}
+}
@@ -1,6 +1,9 @@
 use crate::ast::{AttrValue, Expr, Literal, Node};
-use crate::compiler::registry::{is_structural, schema_for, PropKind, ValueType};
-use crate::diagnostic::Diagnostic;
+use crate::builtins::{self, ReturnType};
+use crate::compiler::registry::{binding_event, is_structural, schema_for, PropKind, ValueType};
+use crate::compiler::signature::{prop_type, signature, type_name};
+use crate::diagnostic::{Diagnostic, Span};
+use crate::keys::KeyCombo;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
@@ -23,10 +26,14 @@ pub fn validate(
 	components: &HashMap<String, Vec<Node>>,
 	diags: &mut Vec<Diagnostic>,
 ) -> Option<ValidatedDocument> {
+	validate_else_chains(nodes, diags);
+	validate_stray_fallbacks(nodes, diags);
 	for node in nodes {
 		validate_node(node, components, diags);
 	}
 	for nodes in components.values() {
+		validate_else_chains(nodes, diags);
+		validate_stray_fallbacks(nodes, diags);
 		for node in nodes {
 			validate_node(node, components, diags);
 		}
@@ -55,11 +62,135 @@ fn validate_node(
 			} else {
 				validate_widget(el, diags);
 			}
+			for attr in &el.attrs {
+				if let AttrValue::Expr(expr) = &attr.value {
+					validate_expr(expr, diags);
+				}
+			}
+			if el.name != "Match" {
+				validate_else_chains(&el.children, diags);
+			}
+			if el.name != "ErrorBoundary" {
+				validate_stray_fallbacks(&el.children, diags);
+			}
 			for child in &el.children {
+				if let Node::Element(child_el) = child {
+					if child_el.name == "Props" {
+						diags.push(Diagnostic::new(
+							"Props must be at the top level of a component file",
+							child_el.span,
+						));
+					}
+				}
 				validate_node(child, components, diags);
 			}
 		}
-		Node::Text(_, _) | Node::Expr(_) => {}
+		Node::Expr(expr) => validate_expr(expr, diags),
+		Node::Text(_, _) => {}
+	}
+}
+
+/// `ElseIf` and `Else` only make sense directly after an `If` or `ElseIf`.
+fn validate_else_chains(nodes: &[Node], diags: &mut Vec<Diagnostic>) {
+	let mut in_chain = false;
+	for node in nodes {
+		let Node::Element(el) = node else {
+			in_chain = false;
+			continue;
+		};
+		match el.name.as_str() {
+			"If" => in_chain = true,
+			"ElseIf" | "Else" if !in_chain => {
+				diags.push(Diagnostic::new(
+					format!("{} must follow an If or ElseIf", el.name),
+					el.span,
+				));
+			}
+			"ElseIf" => {}
+			_ => in_chain = false,
+		}
+		if el.name == "Else" {
+			in_chain = false;
+		}
+	}
+}
+
+/// `Fallback` only makes sense directly inside an `ErrorBoundary`.
+fn validate_stray_fallbacks(nodes: &[Node], diags: &mut Vec<Diagnostic>) {
+	for node in nodes {
+		if let Node::Element(el) = node {
+			if el.name == "Fallback" {
+				diags.push(Diagnostic::new(
+					"Fallback must be inside an ErrorBoundary",
+					el.span,
+				));
+			}
+		}
+	}
+}
+
+fn validate_expr(expr: &Expr, diags: &mut Vec<Diagnostic>) {
+	match expr {
+		Expr::Literal(_, _) | Expr::Path(_, _) => {}
+		Expr::Call { name, args, span } => {
+			match builtins::lookup(name) {
+				None => diags.push(Diagnostic::new(format!("unknown function {name}"), *span)),
+				Some(builtin) if !builtin.accepts(args.len()) => diags.push(Diagnostic::new(
+					format!(
+						"{name} expects {} argument(s), got {}",
+						builtin.arity(),
+						args.len()
+					),
+					*span,
+				)),
+				Some(_) if name == "t" => validate_translation(args, *span, diags),
+				Some(_) if name == "url" => {
+					if !matches!(args.first(), Some(Expr::Literal(Literal::String(_), _))) {
+						diags.push(Diagnostic::new("url route must be a string literal", *span));
+					}
+				}
+				Some(_) => {}
+			}
+			for arg in args {
+				validate_expr(arg, diags);
+			}
+		}
+		Expr::Unary { expr, .. } => validate_expr(expr, diags),
+		Expr::Binary { left, right, .. } | Expr::Coalesce { left, right, .. } => {
+			validate_expr(left, diags);
+			validate_expr(right, diags);
+		}
+		Expr::Ternary {
+			cond,
+			then_expr,
+			else_expr,
+			..
+		} => {
+			validate_expr(cond, diags);
+			validate_expr(then_expr, diags);
+			validate_expr(else_expr, diags);
+		}
+	}
+}
+
+/// `t("key", "name", value, ...)`: a literal key, so `wgui check` can find
+/// it in the catalogs, then name/value pairs with literal names.
+fn validate_translation(args: &[Expr], span: Span, diags: &mut Vec<Diagnostic>) {
+	let is_string = |expr: &Expr| matches!(expr, Expr::Literal(Literal::String(_), _));
+	if !args.first().is_some_and(is_string) {
+		diags.push(Diagnostic::new("t key must be a string literal", span));
+	}
+	if args.len().is_multiple_of(2) {
+		diags.push(Diagnostic::new(
+			"t arguments must be name/value pairs after the key",
+			span,
+		));
+	}
+	if args.iter().skip(1).step_by(2).any(|name| !is_string(name)) {
+		diags.push(Diagnostic::new(
+			"t argument names must be string literals",
+			span,
+		));
 	}
 }
 
@@ -69,14 +200,87 @@ fn validate_structural(el: &crate::ast::Element, diags: &mut Vec<Diagnostic>) {
 			require_attr(el, "each", diags);
 			allow_only(el, &["each", "itemAs", "indexAs", "key"], diags);
 		}
-		"If" => {
+		"If" | "ElseIf" => {
 			require_attr(el, "test", diags);
 			allow_only(el, &["test"], diags);
 		}
-		"Else" => {
+		"Let" => {
+			require_attr(el, "name", diags);
+			require_attr(el, "value", diags);
+			require_string_attr(el, "name", diags);
+			allow_only(el, &["name", "value"], diags);
+			if let Some((name, span)) = string_attr(el, "name") {
+				if !is_valid_ident(name) || is_reserved_let_name(name) {
+					diags.push(Diagnostic::new(format!("invalid Let name {}", name), span));
+				}
+			}
+		}
+		"Match" => {
+			require_attr(el, "value", diags);
+			allow_only(el, &["value"], diags);
+			let mut seen: Vec<&str> = Vec::new();
+			for (index, child) in el.children.iter().enumerate() {
+				match child {
+					Node::Element(child_el) if child_el.name == "Case" => {
+						let Some((is, span)) = string_attr(child_el, "is") else {
+							diags.push(Diagnostic::new(
+								"Case in Match requires an is attribute",
+								child_el.span,
+							));
+							continue;
+						};
+						if seen.contains(&is) {
+							diags.push(Diagnostic::new(
+								format!("Match has more than one Case is=\"{}\"", is),
+								span,
+							));
+						}
+						seen.push(is);
+					}
+					Node::Element(child_el)
+						if child_el.name == "Else" && index + 1 == el.children.len() => {}
+					Node::Element(child_el) if child_el.name == "Else" => {
+						diags.push(Diagnostic::new(
+							"Else must be the last child of Match",
+							child_el.span,
+						));
+					}
+					_ => {
+						diags.push(Diagnostic::new(
+							"Match only allows Case and Else children",
+							el.span,
+						));
+					}
+				}
+			}
+		}
+		"Else" if !el.attrs.is_empty() => {
+			diags.push(Diagnostic::new("Else does not take attributes", el.span));
+		}
+		"ErrorBoundary" => {
 			if !el.attrs.is_empty() {
-				diags.push(Diagnostic::new("Else does not take attributes", el.span));
+				diags.push(Diagnostic::new(
+					"ErrorBoundary does not take attributes",
+					el.span,
+				));
 			}
+			let fallbacks = el.children.iter().filter(
+				|child| matches!(child, Node::Element(child_el) if child_el.name == "Fallback"),
+			);
+			for extra in fallbacks.skip(1) {
+				if let Node::Element(extra) = extra {
+					diags.push(Diagnostic::new(
+						"ErrorBoundary has more than one Fallback",
+						extra.span,
+					));
+				}
+			}
+		}
+		"Fallback" if !el.attrs.is_empty() => {
+			diags.push(Diagnostic::new(
+				"Fallback does not take attributes",
+				el.span,
+			));
 		}
 		"Scope" => {
 			require_attr(el, "name", diags);
@@ -118,13 +322,24 @@ fn validate_structural(el: &crate::ast::Element, diags: &mut Vec<Diagnostic>) {
 			}
 			for child in &el.children {
 				match child {
-					Node::Element(child_el) if child_el.name == "Case" => {}
+					Node::Element(child_el) if child_el.name == "Case" => {
+						if let Some(attr) = child_el.attrs.iter().find(|attr| attr.name == "is") {
+							diags.push(Diagnostic::new(
+								"Case is= only works inside Match",
+								attr.span,
+							));
+						}
+					}
 					_ => {
 						diags.push(Diagnostic::new("Switch only allows Case children", el.span));
 					}
 				}
 			}
 		}
+		"Case" if el.attrs.iter().any(|attr| attr.name == "is") => {
+			require_string_attr(el, "is", diags);
+			allow_only(el, &["is"], diags);
+		}
 		"Case" => {
 			let has_path = el.attrs.iter().any(|attr| attr.name == "path");
 			let has_route = el.attrs.iter().any(|attr| attr.name == "route");
@@ -165,6 +380,43 @@ fn validate_structural(el: &crate::ast::Element, diags: &mut Vec<Diagnostic>) {
 				diags.push(Diagnostic::new("Import does not take children", el.span));
 			}
 		}
+		"Props" => {
+			if !el.attrs.is_empty() {
+				diags.push(Diagnostic::new("Props does not take attributes", el.span));
+			}
+			for child in &el.children {
+				match child {
+					Node::Element(child_el) if child_el.name == "Prop" => {}
+					_ => {
+						diags.push(Diagnostic::new("Props only allows Prop children", el.span));
+					}
+				}
+			}
+		}
+		"Prop" => validate_prop_decl(el, diags),
+		"Slot" => {
+			require_attr(el, "name", diags);
+			require_string_attr(el, "name", diags);
+			allow_only(el, &["name"], diags);
+		}
+		"Style" => {
+			if !el.attrs.is_empty() {
+				diags.push(Diagnostic::new("Style does not take attributes", el.span));
+			}
+			for child in &el.children {
+				match child {
+					Node::Text(css, span) => {
+						if let Err(message) = crate::style::scope_css(css, "wui") {
+							diags.push(Diagnostic::new(
+								format!("invalid CSS in Style: {}", message),
+								*span,
+							));
+						}
+					}
+					_ => diags.push(Diagnostic::new("Style only allows CSS text", el.span)),
+				}
+			}
+		}
 		"Children" => {
 			if !el.attrs.is_empty() {
 				diags.push(Diagnostic::new(
@@ -188,8 +440,35 @@ fn validate_component_use(
 	let Some(component_nodes) = components.get(&el.name) else {
 		return;
 	};
-	let accepts_children = component_uses_children(component_nodes);
-	if !accepts_children && !el.children.is_empty() {
+	let signature = signature(component_nodes);
+	let mut filled = Vec::new();
+	let mut has_children = false;
+	for child in &el.children {
+		let Node::Element(fill) = child else {
+			has_children = true;
+			continue;
+		};
+		if fill.name != "Slot" {
+			has_children = true;
+			continue;
+		}
+		let Some((name, span)) = string_attr(fill, "name") else {
+			continue;
+		};
+		if !signature.slots.iter().any(|slot| slot == name) {
+			diags.push(Diagnostic::new(
+				format!("{} has no slot {}", el.name, name),
+				span,
+			));
+		} else if filled.contains(&name) {
+			diags.push(Diagnostic::new(
+				format!("slot {} is filled twice", name),
+				span,
+			));
+		}
+		filled.push(name);
+	}
+	if !signature.children && has_children {
 		diags.push(Diagnostic::new(
 			format!("{} does not render Children", el.name),
 			el.span,
@@ -205,20 +484,89 @@ fn validate_component_use(
 				format!("invalid value for {}", attr.name),
 				attr.span,
 			));
+			continue;
+		}
+		let Some(props) = &signature.props else {
+			continue;
+		};
+		match props.iter().find(|prop| prop.name == attr.name) {
+			None => {
+				let known = props
+					.iter()
+					.map(|prop| prop.name.as_str())
+					.collect::<Vec<_>>()
+					.join(", ");
+				diags.push(Diagnostic::new(
+					format!(
+						"unknown prop {} for {}; expected one of: {}",
+						attr.name, el.name, known
+					),
+					attr.span,
+				));
+			}
+			Some(prop) if !attr_value_matches(&attr.value, prop.ty) => {
+				diags.push(Diagnostic::new(
+					format!("{} expects a {}", attr.name, type_name(prop.ty)),
+					attr.span,
+				));
+			}
+			Some(_) => {}
+		}
+	}
+	for prop in signature.props.iter().flatten() {
+		if prop.required && !el.attrs.iter().any(|attr| attr.name == prop.name) {
+			diags.push(Diagnostic::new(
+				format!("{} requires prop {}", el.name, prop.name),
+				el.span,
+			));
 		}
 	}
 }
 
-fn component_uses_children(nodes: &[Node]) -> bool {
-	for node in nodes {
-		let Node::Element(el) = node else {
-			continue;
-		};
-		if el.name == "Children" || component_uses_children(&el.children) {
-			return true;
+fn validate_prop_decl(el: &crate::ast::Element, diags: &mut Vec<Diagnostic>) {
+	require_attr(el, "name", diags);
+	require_string_attr(el, "name", diags);
+	allow_only(el, &["name", "type", "required", "default"], diags);
+	if let Some((name, span)) = string_attr(el, "name") {
+		if !is_valid_ident(name) {
+			diags.push(Diagnostic::new(format!("invalid prop name {}", name), span));
+		}
+	}
+	let mut ty = ValueType::Any;
+	if let Some(attr) = el.attrs.iter().find(|attr| attr.name == "type") {
+		match &attr.value {
+			AttrValue::String(name, _) => match prop_type(name) {
+				Some(declared) => ty = declared,
+				None => diags.push(Diagnostic::new(
+					format!(
+						"unknown prop type {}; use string, number, bool or any",
+						name
+					),
+					attr.span,
+				)),
+			},
+			_ => diags.push(Diagnostic::new("type must be a string literal", attr.span)),
+		}
+	}
+	let required = el.attrs.iter().find(|attr| attr.name == "required");
+	if let Some(attr) = required {
+		if !matches!(attr.value, AttrValue::Bool(_, _)) {
+			diags.push(Diagnostic::new("required must be a bool", attr.span));
+		}
+	}
+	if let Some(attr) = el.attrs.iter().find(|attr| attr.name == "default") {
+		if required.is_some_and(|attr| matches!(attr.value, AttrValue::Bool(true, _))) {
+			diags.push(Diagnostic::new(
+				"a required prop cannot have a default",
+				attr.span,
+			));
+		} else if !attr_value_matches(&attr.value, ty) {
+			diags.push(Diagnostic::new(
+				format!("default must be a {}", type_name(ty)),
+				attr.span,
+			));
 		}
 	}
-	false
 }
 
 fn validate_widget(el: &crate::ast::Element, diags: &mut Vec<Diagnostic>) {
@@ -227,11 +575,9 @@ fn validate_widget(el: &crate::ast::Element, diags: &mut Vec<Diagnostic>) {
 		return;
 	};
 	for attr in &el.attrs {
-		let Some(prop) = schema.props.iter().find(|p| p.name == attr.name) else {
-			if attr.name == "class" {
-				if !attr_value_matches(&attr.value, crate::compiler::registry::ValueType::String) {
-					diags.push(Diagnostic::new("invalid value for class", attr.span));
-				}
+		let Some(prop) = schema.prop(&attr.name) else {
+			if let Some(event) = attr.name.strip_prefix("arg:") {
+				validate_event_arg(el, &schema, event, attr, diags);
 				continue;
 			}
 			if is_custom_component_tag(&el.name) && is_custom_event_attr(&attr.name) {
@@ -248,7 +594,20 @@ fn validate_widget(el: &crate::ast::Element, diags: &mut Vec<Diagnostic>) {
 			PropKind::Event(_) => {
 				validate_event_attr(attr, diags);
 			}
-			PropKind::Bind(value_type) | PropKind::Value(value_type) => {
+			PropKind::Bind(value_type) => {
+				if !is_state_path(&attr.value) {
+					diags.push(Diagnostic::new(
+						format!("{} must be a state path such as {{state.name}}", attr.name),
+						attr.span,
+					));
+				} else if !attr_value_matches(&attr.value, *value_type) {
+					diags.push(Diagnostic::new(
+						format!("invalid value for {}", attr.name),
+						attr.span,
+					));
+				}
+			}
+			PropKind::Value(value_type) => {
 				if !attr_value_matches(&attr.value, *value_type) {
 					diags.push(Diagnostic::new(
 						format!("invalid value for {}", attr.name),
@@ -258,7 +617,57 @@ fn validate_widget(el: &crate::ast::Element, diags: &mut Vec<Diagnostic>) {
 			}
 		}
 	}
-	check_bind_conflicts(el, diags);
+	check_bind_conflicts(el, &schema, diags);
+	validate_key_props(el, diags);
+}
+
+/// `key` and `preventDefault` configure the element's key handlers, and a
+/// `<Shortcut>` is nothing but a key handler.
+fn validate_key_props(el: &crate::ast::Element, diags: &mut Vec<Diagnostic>) {
+	let has_key_handler = el
+		.attrs
+		.iter()
+		.any(|attr| attr.name == "onKeyDown" || attr.name == "onKeyUp");
+	for attr in &el.attrs {
+		if attr.name != "key" && attr.name != "preventDefault" {
+			continue;
+		}
+		if !has_key_handler {
+			if el.name == "Shortcut" {
+				continue;
+			}
+			diags.push(Diagnostic::new(
+				format!("{} needs an onKeyDown or onKeyUp handler", attr.name),
+				attr.span,
+			));
+		} else if let (AttrValue::String(combo, _), "key") = (&attr.value, attr.name.as_str()) {
+			if let Err(err) = KeyCombo::parse(combo) {
+				diags.push(Diagnostic::new(
+					format!("invalid key combination: {err}"),
+					attr.span,
+				));
+			}
+		}
+	}
+	if el.name == "Shortcut" {
+		require_attr(el, "key", diags);
+		if !has_key_handler {
+			diags.push(Diagnostic::new(
+				"Shortcut needs an onKeyDown or onKeyUp handler",
+				el.span,
+			));
+		}
+		if !el.children.is_empty() {
+			diags.push(Diagnostic::new("Shortcut takes no children", el.span));
+		}
+	}
+}
+
+fn is_state_path(value: &AttrValue) -> bool {
+	matches!(
+		value,
+		AttrValue::Expr(Expr::Path(parts, _)) if parts.len() > 1 && parts[0] == "state"
+	)
 }
 
 fn is_custom_component_tag(name: &str) -> bool {
@@ -284,23 +693,65 @@ fn validate_event_attr(attr: &crate::ast::Attribute, diags: &mut Vec<Diagnostic>
 	}
 }
 
-fn check_bind_conflicts(el: &crate::ast::Element, diags: &mut Vec<Diagnostic>) {
-	let mut has_value = false;
-	let mut has_bind = false;
-	for attr in &el.attrs {
-		if attr.name == "value" {
-			has_value = true;
-		}
-		if attr.name.starts_with("bind:") {
-			has_bind = true;
-		}
+/// `arg:onClick={..}` gives one handler its own argument, overriding `arg`.
+fn validate_event_arg(
+	el: &crate::ast::Element,
+	schema: &crate::compiler::registry::TagSchema,
+	event: &str,
+	attr: &crate::ast::Attribute,
+	diags: &mut Vec<Diagnostic>,
+) {
+	let is_event = schema
+		.prop(event)
+		.is_some_and(|prop| matches!(prop.kind, PropKind::Event(_)));
+	if !is_event {
+		diags.push(Diagnostic::new(
+			format!("{} is not an event of {}", event, el.name),
+			attr.span,
+		));
+	} else if !el.attrs.iter().any(|candidate| candidate.name == event) {
+		diags.push(Diagnostic::new(
+			format!("{} has no {} handler", el.name, event),
+			attr.span,
+		));
+	} else if !attr_value_matches(&attr.value, ValueType::Number) {
+		diags.push(Diagnostic::new(
+			format!("invalid value for {}", attr.name),
+			attr.span,
+		));
 	}
-	if has_value && has_bind {
+}
+
+fn check_bind_conflicts(
+	el: &crate::ast::Element,
+	schema: &crate::compiler::registry::TagSchema,
+	diags: &mut Vec<Diagnostic>,
+) {
+	let Some(bind) = el.attrs.iter().find(|attr| attr.name.starts_with("bind:")) else {
+		return;
+	};
+	let bound = &bind.name["bind:".len()..];
+	if el.attrs.iter().any(|attr| attr.name == bound) {
 		diags.push(Diagnostic::new(
-			"cannot use value with bind:* on same element",
+			format!("cannot use {bound} with {} on same element", bind.name),
 			el.span,
 		));
 	}
+	let event = binding_event(&el.name);
+	for attr in &el.attrs {
+		let handles_event = schema.prop(&attr.name).is_some_and(
+			|prop| matches!(&prop.kind, PropKind::Event(kind) if Some(kind) == event.as_ref()),
+		);
+		if handles_event {
+			diags.push(Diagnostic::new(
+				format!(
+					"{} already handles {}; remove one of them",
+					bind.name, attr.name
+				),
+				attr.span,
+			));
+		}
+	}
 }
 
 fn require_attr(el: &crate::ast::Element, name: &str, diags: &mut Vec<Diagnostic>) {
@@ -324,10 +775,7 @@ fn require_string_attr(el: &crate::ast::Element, name: &str, diags: &mut Vec<Dia
 	}
 }
 
-fn string_attr<'a>(
-	el: &'a crate::ast::Element,
-	name: &str,
-) -> Option<(&'a str, crate::diagnostic::Span)> {
+fn string_attr<'a>(el: &'a crate::ast::Element, name: &str) -> Option<(&'a str, Span)> {
 	let attr = el.attrs.iter().find(|attr| attr.name == name)?;
 	match &attr.value {
 		AttrValue::String(value, _) => Some((value.as_str(), attr.span)),
@@ -335,7 +783,7 @@ fn string_attr<'a>(
 	}
 }
 
-fn validate_path_params(path: &str, span: crate::diagnostic::Span, diags: &mut Vec<Diagnostic>) {
+fn validate_path_params(path: &str, span: Span, diags: &mut Vec<Diagnostic>) {
 	for segment in path.split('/').filter(|seg| !seg.is_empty()) {
 		if segment == "*" || segment == "{*wildcard}" {
 			continue;
@@ -364,6 +812,12 @@ fn validate_path_params(path: &str, span: crate::diagnostic::Span, diags: &mut V
 	}
 }
 
+/// Names a `<Let>` can't bind: `state`, those Rust can't bind even as raw
+/// identifiers, and the `__` prefix of names generated code uses.
+fn is_reserved_let_name(name: &str) -> bool {
+	matches!(name, "state" | "_" | "self" | "Self" | "super" | "crate") || name.starts_with("__")
+}
+
 fn is_valid_ident(name: &str) -> bool {
 	let mut chars = name.chars();
 	let Some(first) = chars.next() else {
@@ -418,7 +872,18 @@ fn expr_type(expr: &Expr) -> ExprType {
 			Literal::Bool(_) => ExprType::Bool,
 			Literal::Null => ExprType::Null,
 		},
-		Expr::Call { .. } => ExprType::Unknown,
+		Expr::Call { name, args, .. } => {
+			match builtins::lookup(name).map(|builtin| builtin.returns) {
+				Some(ReturnType::String) => ExprType::String,
+				Some(ReturnType::Number) => ExprType::Number,
+				Some(ReturnType::Bool) => ExprType::Bool,
+				Some(ReturnType::FirstArg) => match args.first().map(expr_type) {
+					Some(ExprType::String) => ExprType::String,
+					_ => ExprType::Unknown,
+				},
+				Some(ReturnType::List) | None => ExprType::Unknown,
+			}
+		}
 		_ => ExprType::Unknown,
 	}
 }
@@ -0,0 +1,460 @@
+//! Lossless concrete syntax tree for `.wui` sources.
+//!
+//! Every byte of the input belongs to exactly one node: top-level nodes tile
+//! the whole source and an element's children tile the text between its
+//! opening and closing tags. Whitespace, `<!-- -->` comments and even input
+//! that does not parse are kept with their spans, so tools can rewrite a file
+//! without losing anything. [`crate::parser::Parser`] derives the AST from it.
+
+use crate::diagnostic::{Diagnostic, Span};
+
+#[derive(Debug, Clone)]
+pub struct Cst {
+	pub nodes: Vec<CstNode>,
+	pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Clone)]
+pub enum CstNode {
+	Element(CstElement),
+	/// Text between tags, including runs of whitespace only.
+	Text(Span),
+	/// A `{...}` expression, braces included.
+	Expr(Span),
+	/// A `<!-- ... -->` comment, delimiters included.
+	Comment(Span),
+	/// Input that could not be parsed, such as a stray closing tag.
+	Error(Span),
+}
+
+#[derive(Debug, Clone)]
+pub struct CstElement {
+	/// From `<` to the end of the closing tag, or of the opening tag when the
+	/// element self-closes.
+	pub span: Span,
+	pub name: Span,
+	/// `<Name ...>` or `<Name ... />`; whitespace between attributes lies in
+	/// this span but outside every attribute.
+	pub open: Span,
+	pub attrs: Vec<CstAttr>,
+	pub self_closing: bool,
+	pub children: Vec<CstNode>,
+	/// `</Name>`, missing for self-closing and unterminated elements.
+	pub close: Option<Span>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CstAttr {
+	pub span: Span,
+	pub name: Span,
+	/// The raw value as written: `"text"`, `{expr}` or a bare literal. `None`
+	/// for the `disabled` shorthand.
+	pub value: Option<Span>,
+}
+
+impl CstNode {
+	pub fn span(&self) -> Span {
+		match self {
+			CstNode::Element(el) => el.span,
+			CstNode::Text(span)
+			| CstNode::Expr(span)
+			| CstNode::Comment(span)
+			| CstNode::Error(span) => *span,
+		}
+	}
+}
+
+pub fn parse(src: &str) -> Cst {
+	let mut scanner = Scanner {
+		src,
+		pos: 0,
+		diagnostics: Vec::new(),
+	};
+	let nodes = scanner.nodes(false);
+	Cst {
+		nodes,
+		diagnostics: scanner.diagnostics,
+	}
+}
+
+struct Scanner<'a> {
+	src: &'a str,
+	pos: usize,
+	diagnostics: Vec<Diagnostic>,
+}
+
+impl Scanner<'_> {
+	/// Nodes up to the end of input or, `in_element`, up to a closing tag.
+	fn nodes(&mut self, in_element: bool) -> Vec<CstNode> {
+		let mut nodes = Vec::new();
+		while !self.eof() {
+			let start = self.pos;
+			if self.rest().starts_with("</") {
+				if in_element {
+					break;
+				}
+				self.skip_past('>');
+				self.error("unexpected closing tag", start);
+				nodes.push(CstNode::Error(self.span_from(start)));
+			} else if self.rest().starts_with("<!--") {
+				nodes.push(self.comment());
+			} else if self.rest().starts_with('<') {
+				nodes.push(self.element());
+			} else if self.rest().starts_with('{') {
+				self.pos = braced_expr_end(self.src, start + 1);
+				if self.eof() {
+					self.error("unterminated expression", self.pos);
+					nodes.push(CstNode::Error(self.span_from(start)));
+				} else {
+					self.pos += 1;
+					nodes.push(CstNode::Expr(self.span_from(start)));
+				}
+			} else {
+				self.pos += self.rest().find(['<', '{']).unwrap_or(self.rest().len());
+				nodes.push(CstNode::Text(self.span_from(start)));
+			}
+		}
+		nodes
+	}
+
+	/// Text up to `end`, for elements such as `<Style>` whose content is not
+	/// markup; CSS braces are not expressions.
+	fn raw_text(&mut self, end: &str) -> Vec<CstNode> {
+		let start = self.pos;
+		self.pos += self.rest().find(end).unwrap_or(self.rest().len());
+		if self.pos == start {
+			return Vec::new();
+		}
+		vec![CstNode::Text(self.span_from(start))]
+	}
+
+	fn comment(&mut self) -> CstNode {
+		let start = self.pos;
+		match self.rest().find("-->") {
+			Some(len) => {
+				self.pos += len + "-->".len();
+				CstNode::Comment(self.span_from(start))
+			}
+			None => {
+				self.pos = self.src.len();
+				self.error("unterminated comment", start);
+				CstNode::Error(self.span_from(start))
+			}
+		}
+	}
+
+	fn element(&mut self) -> CstNode {
+		let start = self.pos;
+		self.pos += 1;
+		self.skip_ws();
+		let Some(name) = self.ident() else {
+			self.error("expected tag name", start);
+			return CstNode::Error(self.span_from(start));
+		};
+		let mut attrs = Vec::new();
+		let self_closing = loop {
+			self.skip_ws();
+			if self.eof() {
+				self.error("expected character", self.pos);
+				let span = self.span_from(start);
+				return CstNode::Element(CstElement {
+					span,
+					name,
+					open: span,
+					attrs,
+					self_closing: false,
+					children: Vec::new(),
+					close: None,
+				});
+			}
+			if self.rest().starts_with("/>") {
+				self.pos += 2;
+				break true;
+			}
+			if self.rest().starts_with('>') {
+				self.pos += 1;
+				break false;
+			}
+			match self.attr() {
+				Some(attr) => attrs.push(attr),
+				None => {
+					let junk = self.pos;
+					let len = self
+						.rest()
+						.find(|ch: char| ch.is_whitespace() || ch == '>' || ch == '/')
+						.unwrap_or(self.rest().len())
+						.max(1);
+					self.pos += len;
+					self.error("expected attribute name", junk);
+				}
+			}
+		};
+		let open = self.span_from(start);
+		let mut element = CstElement {
+			span: open,
+			name,
+			open,
+			attrs,
+			self_closing,
+			children: Vec::new(),
+			close: None,
+		};
+		if self_closing {
+			return CstNode::Element(element);
+		}
+		element.children = if &self.src[name.start..name.end] == "Style" {
+			self.raw_text("</Style")
+		} else {
+			self.nodes(true)
+		};
+		if self.eof() {
+			let tag = &self.src[name.start..name.end];
+			self.error(format!("missing closing tag for {tag}"), start);
+		} else {
+			let close_start = self.pos;
+			self.pos += 2;
+			self.skip_ws();
+			let closing = self.ident();
+			self.skip_ws();
+			if self.rest().starts_with('>') {
+				self.pos += 1;
+			} else {
+				self.error("expected character", self.pos);
+			}
+			let matches = closing.is_some_and(|closing| {
+				self.src[closing.start..closing.end] == self.src[name.start..name.end]
+			});
+			if !matches {
+				self.error("mismatched closing tag", start);
+			}
+			element.close = Some(self.span_from(close_start));
+		}
+		element.span = self.span_from(start);
+		CstNode::Element(element)
+	}
+
+	fn attr(&mut self) -> Option<CstAttr> {
+		let start = self.pos;
+		let name = self.ident()?;
+		let before_eq = self.pos;
+		self.skip_ws();
+		if !self.rest().starts_with('=') {
+			self.pos = before_eq;
+			return Some(CstAttr {
+				span: self.span_from(start),
+				name,
+				value: None,
+			});
+		}
+		self.pos += 1;
+		self.skip_ws();
+		let value_start = self.pos;
+		if self.rest().starts_with('"') {
+			self.pos += 1;
+			loop {
+				match self.rest().find(['"', '\\']) {
+					Some(len) if self.rest()[len..].starts_with('\\') => {
+						self.pos += len + 1;
+						self.pos += self.rest().chars().next().map_or(0, char::len_utf8);
+					}
+					Some(len) => {
+						self.pos += len + 1;
+						break;
+					}
+					None => {
+						self.pos = self.src.len();
+						self.error("unterminated string", self.pos);
+						break;
+					}
+				}
+			}
+		} else if self.rest().starts_with('{') {
+			self.pos = braced_expr_end(self.src, value_start + 1);
+			if self.eof() {
+				self.error("unterminated expression", self.pos);
+			} else {
+				self.pos += 1;
+			}
+		} else {
+			self.pos += self
+				.rest()
+				.find(|ch: char| ch.is_whitespace() || ch == '>' || ch == '/')
+				.unwrap_or(self.rest().len());
+			if self.pos == value_start {
+				self.error("expected attribute value", value_start);
+			}
+		}
+		Some(CstAttr {
+			span: self.span_from(start),
+			name,
+			value: Some(self.span_from(value_start)),
+		})
+	}
+
+	fn ident(&mut self) -> Option<Span> {
+		let start = self.pos;
+		let first = self.rest().chars().next()?;
+		if !(first.is_ascii_alphabetic() || first == '_' || first == ':') {
+			return None;
+		}
+		self.pos += self
+			.rest()
+			.find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_' || ch == ':' || ch == '-'))
+			.unwrap_or(self.rest().len());
+		Some(self.span_from(start))
+	}
+
+	fn skip_ws(&mut self) {
+		let rest = self.rest();
+		self.pos += rest.len() - rest.trim_start().len();
+	}
+
+	fn skip_past(&mut self, target: char) {
+		self.pos += self
+			.rest()
+			.find(target)
+			.map_or(self.rest().len(), |len| len + target.len_utf8());
+	}
+
+	fn error(&mut self, message: impl Into<String>, start: usize) {
+		let span = Span::new(start, self.pos.max(start));
+		self.diagnostics.push(Diagnostic::new(message, span));
+	}
+
+	fn rest(&self) -> &str {
+		&self.src[self.pos..]
+	}
+
+	fn span_from(&self, start: usize) -> Span {
+		Span::new(start, self.pos)
+	}
+
+	fn eof(&self) -> bool {
+		self.pos >= self.src.len()
+	}
+}
+
+/// The offset of the `}` closing a braced expression whose body starts at
+/// `start`, or the end of `src` when it is unterminated.
+pub(crate) fn braced_expr_end(src: &str, start: usize) -> usize {
+	// Open string literals, template strings and `${` interpolations, so a
+	// `}` only ends the expression at the outermost level.
+	let mut nesting: Vec<char> = Vec::new();
+	let mut pos = start;
+	while let Some(ch) = src[pos..].chars().next() {
+		match (nesting.last(), ch) {
+			(Some('"' | '`'), '\\') => {
+				pos += 1;
+				if let Some(next) = src[pos..].chars().next() {
+					pos += next.len_utf8();
+				}
+				continue;
+			}
+			(Some('"'), '"') | (Some('`'), '`') => {
+				nesting.pop();
+			}
+			(Some('`'), '$') if src[pos..].starts_with("${") => {
+				nesting.push('{');
+				pos += 2;
+				continue;
+			}
+			(Some('"' | '`'), _) => {}
+			(_, '"' | '`' | '{') => nesting.push(ch),
+			(Some('{'), '}') => {
+				nesting.pop();
+			}
+			(None, '}') => break,
+			_ => {}
+		}
+		pos += ch.len_utf8();
+	}
+	pos
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Checks that `nodes` tile `start..end` without gaps or overlaps.
+	fn assert_tiles(nodes: &[CstNode], start: usize, end: usize) {
+		let mut pos = start;
+		for node in nodes {
+			let span = node.span();
+			assert_eq!(span.start, pos, "gap or overlap before {node:?}");
+			if let CstNode::Element(el) = node {
+				assert_eq!(el.open.start, span.start);
+				let inner_end = el.close.map_or(span.end, |close| close.start);
+				assert_tiles(&el.children, el.open.end.min(inner_end), inner_end);
+			}
+			pos = span.end;
+		}
+		assert_eq!(pos, end);
+	}
+
+	#[test]
+	fn keeps_every_byte_of_valid_input() {
+		let src = "<!-- page -->\n<VStack spacing=4  class=\"a \\\"b\\\"\">\n\t<Text value={`${state.n} }`} disabled />\n\tHello {state.name}!\n</VStack>\n";
+		let cst = parse(src);
+		assert!(cst.diagnostics.is_empty(), "{:?}", cst.diagnostics);
+		assert_tiles(&cst.nodes, 0, src.len());
+		let kinds = cst
+			.nodes
+			.iter()
+			.map(|node| match node {
+				CstNode::Element(_) => "element",
+				CstNode::Text(_) => "text",
+				CstNode::Expr(_) => "expr",
+				CstNode::Comment(_) => "comment",
+				CstNode::Error(_) => "error",
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(kinds, vec!["comment", "text", "element", "text"]);
+		let CstNode::Element(root) = &cst.nodes[2] else {
+			panic!("expected element");
+		};
+		let values = root
+			.attrs
+			.iter()
+			.map(|attr| &src[attr.value.unwrap().start..attr.value.unwrap().end])
+			.collect::<Vec<_>>();
+		assert_eq!(values, vec!["4", "\"a \\\"b\\\"\""]);
+		let CstNode::Element(text) = &root.children[1] else {
+			panic!("expected child element");
+		};
+		assert_eq!(
+			&src[text.open.start..text.open.end],
+			"<Text value={`${state.n} }`} disabled />"
+		);
+		assert!(text.self_closing && text.attrs[1].value.is_none());
+	}
+
+	#[test]
+	fn keeps_style_content_as_raw_text() {
+		let src = "<Style>\n.a { color: red }\n</Style>";
+		let cst = parse(src);
+		assert!(cst.diagnostics.is_empty(), "{:?}", cst.diagnostics);
+		assert_tiles(&cst.nodes, 0, src.len());
+		let CstNode::Element(style) = &cst.nodes[0] else {
+			panic!("expected element");
+		};
+		let [CstNode::Text(css)] = style.children.as_slice() else {
+			panic!("expected raw text, got {:?}", style.children);
+		};
+		assert_eq!(&src[css.start..css.end], "\n.a { color: red }\n");
+	}
+
+	#[test]
+	fn keeps_every_byte_of_broken_input() {
+		for src in [
+			"</Stray> <Text value=\"open",
+			"<VStack><Text value={state.x</VStack>",
+			"<VStack><!-- never closed",
+			"<A><B></A> < 3 {",
+			"<Button = text=\"x\" />",
+		] {
+			let cst = parse(src);
+			assert!(!cst.diagnostics.is_empty(), "{src}");
+			assert_tiles(&cst.nodes, 0, src.len());
+		}
+	}
+}
@@ -175,6 +175,9 @@ impl<'a> ExprParser<'a> {
 			self.expect_char(')')?;
 			return Ok(expr);
 		}
+		if let Some(expr) = self.parse_template_string()? {
+			return Ok(expr);
+		}
 		if let Some(value) = self.parse_string()? {
 			let span = Span::new(start, self.current_offset());
 			return Ok(Expr::Literal(Literal::String(value), span));
@@ -244,6 +247,61 @@ impl<'a> ExprParser<'a> {
 		Err(Diagnostic::new("unterminated string", self.span_here()))
 	}
 
+	/// `` `Hello ${name}!` `` becomes `concat("Hello ", name, "!")`.
+	fn parse_template_string(&mut self) -> Result<Option<Expr>, Diagnostic> {
+		self.skip_ws();
+		let start = self.current_offset();
+		if !self.consume_char('`') {
+			return Ok(None);
+		}
+		let mut args = Vec::new();
+		let mut text = String::new();
+		let mut text_start = self.current_offset();
+		while let Some(ch) = self.peek_char() {
+			if ch == '`' || self.peek_str("${") {
+				if !text.is_empty() {
+					let span = Span::new(text_start, self.current_offset());
+					args.push(Expr::Literal(
+						Literal::String(std::mem::take(&mut text)),
+						span,
+					));
+				}
+				if ch == '`' {
+					self.pos += 1;
+					return Ok(Some(Expr::Call {
+						name: crate::builtins::CONCAT.to_string(),
+						args,
+						span: Span::new(start, self.current_offset()),
+					}));
+				}
+				self.pos += 2;
+				args.push(self.parse_ternary()?);
+				self.skip_ws();
+				self.expect_char('}')?;
+				text_start = self.current_offset();
+				continue;
+			}
+			self.pos += ch.len_utf8();
+			if ch != '\\' {
+				text.push(ch);
+				continue;
+			}
+			let next = self
+				.peek_char()
+				.ok_or_else(|| Diagnostic::new("unterminated escape", self.span_here()))?;
+			self.pos += next.len_utf8();
+			match next {
+				'`' | '\\' | '$' => text.push(next),
+				'n' => text.push('\n'),
+				_ => return Err(Diagnostic::new("invalid escape", self.span_here())),
+			}
+		}
+		Err(Diagnostic::new(
+			"unterminated template string",
+			self.span_here(),
+		))
+	}
+
 	fn parse_literal(&mut self) -> Result<Option<Literal>, Diagnostic> {
 		self.skip_ws();
 		if self.peek_str("true") && self.is_delim(4) {
@@ -414,4 +472,19 @@ mod tests {
 			_ => panic!("expected call"),
 		}
 	}
+
+	#[test]
+	fn parse_template_string_as_concat() {
+		let expr = ExprParser::new("`Hi ${upper(name)}, \\${x}`", 0)
+			.parse()
+			.unwrap();
+		let Expr::Call { name, args, .. } = expr else {
+			panic!("expected call");
+		};
+		assert_eq!(name, "concat");
+		assert_eq!(args.len(), 3);
+		assert!(matches!(&args[0], Expr::Literal(Literal::String(text), _) if text == "Hi "));
+		assert!(matches!(&args[1], Expr::Call { name, .. } if name == "upper"));
+		assert!(matches!(&args[2], Expr::Literal(Literal::String(text), _) if text == ", ${x}"));
+	}
 }
@@ -0,0 +1,219 @@
+//! Canonical formatting for `.wui` templates, used by `wgui fmt` and the
+//! language server.
+//!
+//! Elements are indented with tabs, one node per line. Attributes stay on the
+//! tag's line while it fits in [`MAX_WIDTH`] columns and otherwise go one per
+//! line. Expressions are kept as written, comments are kept where they were and
+//! a blank line between two nodes survives as a single blank line.
+
+use crate::cst::{CstAttr, CstElement, CstNode};
+use crate::diagnostic::{Diagnostic, Span};
+use crate::parser::Parser;
+
+/// Line width, counting a tab as four columns.
+pub const MAX_WIDTH: usize = 100;
+
+const TAB_WIDTH: usize = 4;
+
+/// Formats `src`, or returns its parse errors. Formatting is idempotent.
+pub fn format(src: &str) -> Result<String, Vec<Diagnostic>> {
+	let file = Parser::new(src).parse();
+	if !file.diagnostics.is_empty() {
+		return Err(file.diagnostics);
+	}
+	let mut printer = Printer {
+		src,
+		out: String::new(),
+	};
+	printer.nodes(&file.cst, 0);
+	Ok(printer.out)
+}
+
+struct Printer<'a> {
+	src: &'a str,
+	out: String,
+}
+
+impl<'a> Printer<'a> {
+	fn nodes(&mut self, nodes: &[CstNode], depth: usize) {
+		let mut printed = false;
+		let mut blank = false;
+		for node in nodes {
+			let text = self.text(node.span());
+			let content = text.trim();
+			if let CstNode::Text(_) = node {
+				let leading = &text[..text.len() - text.trim_start().len()];
+				blank |= leading.matches('\n').count() > 1;
+				if content.is_empty() {
+					continue;
+				}
+			}
+			if blank && printed {
+				self.out.push('\n');
+			}
+			blank = false;
+			printed = true;
+			match node {
+				CstNode::Element(el) => self.element(el, depth),
+				CstNode::Text(_) => {
+					self.text_lines(content, depth);
+					let trailing = &text[text.trim_end().len()..];
+					blank = trailing.matches('\n').count() > 1;
+				}
+				CstNode::Expr(_) => {
+					let inner = content[1..content.len() - 1].trim();
+					self.line(depth, &format!("{{{inner}}}"));
+				}
+				CstNode::Comment(_) => {
+					let inner = &content["<!--".len()..content.len() - "-->".len()];
+					if inner.contains('\n') {
+						self.line(depth, content);
+					} else {
+						self.line(depth, &format!("<!-- {} -->", inner.trim()));
+					}
+				}
+				// Files with errors are never formatted.
+				CstNode::Error(_) => self.line(depth, content),
+			}
+		}
+	}
+
+	fn element(&mut self, el: &CstElement, depth: usize) {
+		let name = self.text(el.name);
+		let attrs = el
+			.attrs
+			.iter()
+			.map(|attr| self.attr(attr))
+			.collect::<Vec<_>>();
+		let has_content = el.children.iter().any(
+			|child| !matches!(child, CstNode::Text(span) if self.text(*span).trim().is_empty()),
+		);
+		let close = if has_content { ">" } else { " />" };
+		let single = format!("<{name}{}{close}", attrs.concat());
+		let fits = depth * TAB_WIDTH + single.chars().count() <= MAX_WIDTH;
+		if attrs.is_empty() || (fits && !attrs.iter().any(|attr| attr.contains('\n'))) {
+			self.line(depth, &single);
+		} else {
+			self.line(depth, &format!("<{name}"));
+			for attr in &attrs {
+				self.line(depth + 1, attr.trim_start());
+			}
+			self.line(depth, close.trim_start());
+		}
+		if has_content && name == "Style" {
+			for child in &el.children {
+				self.css_lines(self.text(child.span()), depth + 1);
+			}
+			self.line(depth, &format!("</{name}>"));
+		} else if has_content {
+			self.nodes(&el.children, depth + 1);
+			self.line(depth, &format!("</{name}>"));
+		}
+	}
+
+	/// The attribute with a leading space: `name="value"`, `name={expr}` or a
+	/// bare `name` for flags.
+	fn attr(&self, attr: &CstAttr) -> String {
+		let name = self.text(attr.name);
+		let Some(value) = attr.value.map(|span| self.text(span)) else {
+			return format!(" {name}");
+		};
+		match value
+			.strip_prefix('{')
+			.and_then(|value| value.strip_suffix('}'))
+		{
+			Some(inner) => format!(" {name}={{{}}}", inner.trim()),
+			None => format!(" {name}={value}"),
+		}
+	}
+
+	fn text_lines(&mut self, text: &str, depth: usize) {
+		let mut blank = false;
+		for line in text.lines().map(str::trim) {
+			if line.is_empty() {
+				blank = true;
+				continue;
+			}
+			if blank {
+				self.out.push('\n');
+				blank = false;
+			}
+			self.line(depth, line);
+		}
+	}
+
+	/// CSS from a `<Style>` block, indented by brace nesting.
+	fn css_lines(&mut self, css: &str, depth: usize) {
+		let mut nesting = 0usize;
+		for line in css.lines().map(str::trim).filter(|line| !line.is_empty()) {
+			let closes = line.starts_with('}');
+			self.line(depth + nesting - usize::from(closes && nesting > 0), line);
+			let opened = line.matches('{').count();
+			let closed = line.matches('}').count();
+			nesting = (nesting + opened).saturating_sub(closed);
+		}
+	}
+
+	fn line(&mut self, depth: usize, text: &str) {
+		for _ in 0..depth {
+			self.out.push('\t');
+		}
+		self.out.push_str(text);
+		self.out.push('\n');
+	}
+
+	fn text(&self, span: Span) -> &'a str {
+		&self.src[span.start..span.end]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn indents_with_tabs_and_normalizes_attributes() {
+		let src = "<VStack   spacing=4>\n  <Text value = \"a \\\"b\\\"\" />\n<Button text=\"Go\" onClick=\"Go\" disabled={ state.busy }></Button>\n</VStack>";
+		assert_eq!(
+			format(src).unwrap(),
+			"<VStack spacing=4>\n\t<Text value=\"a \\\"b\\\"\" />\n\t<Button text=\"Go\" onClick=\"Go\" disabled={state.busy} />\n</VStack>\n"
+		);
+	}
+
+	#[test]
+	fn wraps_long_attribute_lists() {
+		let src = r#"<VStack class="sidebar" width=260 minWidth=200 maxWidth=420 padding=12 spacing=10 overflow="auto" hresize=true><Text value="Hi" /></VStack>"#;
+		assert_eq!(
+			format(src).unwrap(),
+			"<VStack\n\tclass=\"sidebar\"\n\twidth=260\n\tminWidth=200\n\tmaxWidth=420\n\tpadding=12\n\tspacing=10\n\toverflow=\"auto\"\n\thresize=true\n>\n\t<Text value=\"Hi\" />\n</VStack>\n"
+		);
+	}
+
+	#[test]
+	fn keeps_comments_text_and_blank_lines() {
+		let src = "<!--header-->\n<VStack>\n    <Text value={(a + b) * 2} />\n\n\n    <!-- multi\n  line -->\n  Hello   there\n  <Text value=\"x\" /> <!-- trailing -->\n  {`${state.n} items`}\n<!--last-->\n</VStack>\n";
+		let formatted = format(src).unwrap();
+		assert_eq!(
+			formatted,
+			"<!-- header -->\n<VStack>\n\t<Text value={(a + b) * 2} />\n\n\t<!-- multi\n  line -->\n\tHello   there\n\t<Text value=\"x\" />\n\t<!-- trailing -->\n\t{`${state.n} items`}\n\t<!-- last -->\n</VStack>\n"
+		);
+		assert_eq!(format(&formatted).unwrap(), formatted);
+	}
+
+	#[test]
+	fn indents_style_blocks_by_nesting() {
+		let src = "<Style>\n.a { color: red; }\n@media (max-width: 600px) {\n.a {\ncolor: blue;\n}\n}\n</Style>\n<Text value=\"x\" />";
+		let formatted = format(src).unwrap();
+		assert_eq!(
+			formatted,
+			"<Style>\n\t.a { color: red; }\n\t@media (max-width: 600px) {\n\t\t.a {\n\t\t\tcolor: blue;\n\t\t}\n\t}\n</Style>\n<Text value=\"x\" />\n"
+		);
+		assert_eq!(format(&formatted).unwrap(), formatted);
+	}
+
+	#[test]
+	fn reports_parse_errors() {
+		let diags = format("<VStack><Text></VStack>").unwrap_err();
+		assert!(!diags.is_empty());
+	}
+}
@@ -0,0 +1,687 @@
+//! Message catalogs for the `t("key", ...)` built-in and the locale rules
+//! `format_number`, `format_date` and plural selection follow.
+//!
+//! Catalogs are written in a subset of [Fluent](https://projectfluent.org),
+//! one `i18n/<locale>.ftl` file per locale:
+//!
+//! ```text
+//! # Comments start with #.
+//! greeting = Hello, { $name }!
+//! cart-items = { $count ->
+//!     [0] Your cart is empty
+//!     [one] One item in your cart
+//!    *[other] { $count } items in your cart
+//! }
+//! ```
+//!
+//! Indented lines, and a line starting with `}`, continue the message above
+//! them. Placeables are `{ $var }`, string literals such as `{ "{" }` and
+//! selects on a variable, which match exact numbers first, then the CLDR
+//! plural category of the locale (see [`plural_category`]) and fall back to
+//! the `*` variant.
+
+use std::collections::BTreeMap;
+
+use crate::ast::{Expr, Literal};
+use crate::builtins::{self, WuiDate};
+use crate::diagnostic::Diagnostic;
+use crate::parser::Parser;
+
+/// A message argument.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Arg {
+	Text(String),
+	Number(f64),
+}
+
+impl From<&str> for Arg {
+	fn from(value: &str) -> Self {
+		Arg::Text(value.to_string())
+	}
+}
+
+impl From<String> for Arg {
+	fn from(value: String) -> Self {
+		Arg::Text(value)
+	}
+}
+
+impl From<f64> for Arg {
+	fn from(value: f64) -> Self {
+		Arg::Number(value)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogError {
+	/// 1-based line of the message the error is in.
+	pub line: usize,
+	pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Element {
+	Text(String),
+	Var(String),
+	Select {
+		var: String,
+		variants: Vec<(String, Vec<Element>)>,
+		default: usize,
+	},
+}
+
+/// The messages of one locale.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Catalog {
+	locale: String,
+	messages: BTreeMap<String, Vec<Element>>,
+}
+
+impl Catalog {
+	pub fn parse(locale: &str, src: &str) -> Result<Self, Vec<CatalogError>> {
+		let mut messages = BTreeMap::new();
+		let mut errors = Vec::new();
+		let mut current: Option<(usize, String, Vec<&str>)> = None;
+		let mut finish = |current: &mut Option<(usize, String, Vec<&str>)>,
+		                  errors: &mut Vec<CatalogError>| {
+			let Some((line, id, lines)) = current.take() else {
+				return;
+			};
+			let value = lines
+				.iter()
+				.map(|line| line.trim())
+				.skip_while(|line| line.is_empty())
+				.collect::<Vec<_>>()
+				.join("\n");
+			if messages.contains_key(&id) {
+				errors.push(CatalogError {
+					line,
+					message: format!("duplicate message {id}"),
+				});
+				return;
+			}
+			match parse_pattern(value.trim_end()) {
+				Ok(pattern) => {
+					messages.insert(id, pattern);
+				}
+				Err(message) => errors.push(CatalogError {
+					line,
+					message: format!("{id}: {message}"),
+				}),
+			}
+		};
+		for (index, line) in src.lines().enumerate() {
+			if line.trim().is_empty() {
+				continue;
+			}
+			// A select's closing brace conventionally sits in the first column.
+			if line.starts_with([' ', '\t', '}']) {
+				match current.as_mut() {
+					Some((_, _, lines)) => lines.push(line),
+					None => errors.push(CatalogError {
+						line: index + 1,
+						message: "indented line outside a message".to_string(),
+					}),
+				}
+				continue;
+			}
+			finish(&mut current, &mut errors);
+			if line.starts_with('#') {
+				continue;
+			}
+			let Some((id, value)) = line.split_once('=') else {
+				errors.push(CatalogError {
+					line: index + 1,
+					message: "expected `key = value`".to_string(),
+				});
+				continue;
+			};
+			let id = id.trim();
+			if !is_message_id(id) {
+				errors.push(CatalogError {
+					line: index + 1,
+					message: format!("invalid message key {id:?}"),
+				});
+				continue;
+			}
+			current = Some((index + 1, id.to_string(), vec![value]));
+		}
+		finish(&mut current, &mut errors);
+		if errors.is_empty() {
+			Ok(Catalog {
+				locale: locale.to_string(),
+				messages,
+			})
+		} else {
+			Err(errors)
+		}
+	}
+
+	pub fn locale(&self) -> &str {
+		&self.locale
+	}
+
+	pub fn contains(&self, key: &str) -> bool {
+		self.messages.contains_key(key)
+	}
+
+	pub fn keys(&self) -> impl Iterator<Item = &str> {
+		self.messages.keys().map(String::as_str)
+	}
+
+	/// The message `key` with `args` filled in, `None` if the catalog lacks
+	/// it. Arguments the message uses but `args` lacks show as `{$name}`.
+	pub fn format(&self, key: &str, args: &[(&str, Arg)]) -> Option<String> {
+		let pattern = self.messages.get(key)?;
+		let mut out = String::new();
+		self.write_pattern(pattern, args, &mut out);
+		Some(out)
+	}
+
+	fn write_pattern(&self, pattern: &[Element], args: &[(&str, Arg)], out: &mut String) {
+		let arg = |name: &str| {
+			args.iter()
+				.find(|(arg_name, _)| *arg_name == name)
+				.map(|(_, value)| value)
+		};
+		for element in pattern {
+			match element {
+				Element::Text(text) => out.push_str(text),
+				Element::Var(name) => match arg(name) {
+					Some(Arg::Text(text)) => out.push_str(text),
+					Some(Arg::Number(number)) => {
+						out.push_str(&format_number(&self.locale, *number, None))
+					}
+					None => {
+						out.push_str("{$");
+						out.push_str(name);
+						out.push('}');
+					}
+				},
+				Element::Select {
+					var,
+					variants,
+					default,
+				} => {
+					let chosen = match arg(var) {
+						Some(Arg::Number(number)) => variants
+							.iter()
+							.position(|(key, _)| key.parse::<f64>() == Ok(*number))
+							.or_else(|| {
+								let category = plural_category(&self.locale, *number);
+								variants.iter().position(|(key, _)| key == category)
+							}),
+						Some(Arg::Text(text)) => variants.iter().position(|(key, _)| key == text),
+						None => None,
+					};
+					self.write_pattern(&variants[chosen.unwrap_or(*default)].1, args, out);
+				}
+			}
+		}
+	}
+}
+
+fn is_message_id(id: &str) -> bool {
+	let mut chars = id.chars();
+	chars.next().is_some_and(|ch| ch.is_ascii_alphabetic())
+		&& chars.all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.'))
+}
+
+fn is_variable(name: &str) -> bool {
+	let mut chars = name.chars();
+	chars.next().is_some_and(|ch| ch.is_ascii_alphabetic())
+		&& chars.all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_'))
+}
+
+fn parse_pattern(src: &str) -> Result<Vec<Element>, String> {
+	let mut elements = Vec::new();
+	let mut text = String::new();
+	let mut rest = src;
+	while let Some(open) = rest.find(['{', '}']) {
+		if rest.as_bytes()[open] == b'}' {
+			return Err("unexpected }".to_string());
+		}
+		text.push_str(&rest[..open]);
+		let close = matching_brace(rest, open)?;
+		let placeable = parse_placeable(rest[open + 1..close].trim())?;
+		match placeable {
+			Element::Text(literal) => text.push_str(&literal),
+			placeable => {
+				if !text.is_empty() {
+					elements.push(Element::Text(std::mem::take(&mut text)));
+				}
+				elements.push(placeable);
+			}
+		}
+		rest = &rest[close + 1..];
+	}
+	text.push_str(rest);
+	if !text.is_empty() {
+		elements.push(Element::Text(text));
+	}
+	Ok(elements)
+}
+
+/// The `}` closing the `{` at `open`, skipping nested placeables and string
+/// literals.
+fn matching_brace(src: &str, open: usize) -> Result<usize, String> {
+	let mut depth = 0usize;
+	let mut in_string = false;
+	for (index, ch) in src[open..].char_indices() {
+		match ch {
+			'"' => in_string = !in_string,
+			_ if in_string => {}
+			'{' => depth += 1,
+			'}' => {
+				depth -= 1;
+				if depth == 0 {
+					return Ok(open + index);
+				}
+			}
+			_ => {}
+		}
+	}
+	Err("missing }".to_string())
+}
+
+fn parse_placeable(inner: &str) -> Result<Element, String> {
+	if let Some(literal) = inner
+		.strip_prefix('"')
+		.and_then(|rest| rest.strip_suffix('"'))
+	{
+		return Ok(Element::Text(literal.to_string()));
+	}
+	let (selector, variants) = match inner.split_once("->") {
+		Some((selector, variants)) => (selector.trim(), Some(variants)),
+		None => (inner, None),
+	};
+	let Some(var) = selector.strip_prefix('$').filter(|name| is_variable(name)) else {
+		return Err(format!("unsupported placeable {{ {selector} }}"));
+	};
+	let Some(variants) = variants else {
+		return Ok(Element::Var(var.to_string()));
+	};
+	let variants = parse_variants(variants)?;
+	let defaults = variants
+		.iter()
+		.enumerate()
+		.filter(|(_, (is_default, _, _))| *is_default)
+		.map(|(index, _)| index)
+		.collect::<Vec<_>>();
+	let [default] = defaults[..] else {
+		return Err(format!(
+			"select on ${var} needs exactly one default *[variant]"
+		));
+	};
+	Ok(Element::Select {
+		var: var.to_string(),
+		variants: variants
+			.into_iter()
+			.map(|(_, key, pattern)| (key, pattern))
+			.collect(),
+		default,
+	})
+}
+
+/// `[key] pattern` lines, each starting on its own line; `*` marks the
+/// default.
+fn parse_variants(src: &str) -> Result<Vec<(bool, String, Vec<Element>)>, String> {
+	let mut variants = Vec::new();
+	let mut rest = src.trim_start();
+	while !rest.is_empty() {
+		let (is_default, after_star) = match rest.strip_prefix('*') {
+			Some(after) => (true, after),
+			None => (false, rest),
+		};
+		let Some((key, after_key)) = after_star
+			.strip_prefix('[')
+			.and_then(|after| after.split_once(']'))
+		else {
+			return Err("expected [variant]".to_string());
+		};
+		let end = variant_end(after_key);
+		variants.push((
+			is_default,
+			key.trim().to_string(),
+			parse_pattern(after_key[..end].trim())?,
+		));
+		rest = after_key[end..].trim_start();
+	}
+	Ok(variants)
+}
+
+/// Where the variant value starting `src` ends: at the next line opening a
+/// variant outside nested placeables.
+fn variant_end(src: &str) -> usize {
+	let mut depth = 0usize;
+	for (index, ch) in src.char_indices() {
+		match ch {
+			'{' => depth += 1,
+			'}' => depth = depth.saturating_sub(1),
+			'\n' if depth == 0 => {
+				let next = src[index + 1..].trim_start();
+				if next.starts_with('[') || next.starts_with("*[") {
+					return index;
+				}
+			}
+			_ => {}
+		}
+	}
+	src.len()
+}
+
+fn language(locale: &str) -> String {
+	locale
+		.split(['-', '_'])
+		.next()
+		.unwrap_or_default()
+		.to_ascii_lowercase()
+}
+
+/// The CLDR plural category of `count` in `locale`: `zero`, `one`, `two`,
+/// `few`, `many` or `other`. Languages without rules here use English's.
+pub fn plural_category(locale: &str, count: f64) -> &'static str {
+	// CLDR operands: `n` the absolute value, `i` its integer digits, `v` the
+	// number of fraction digits shown and `f` those digits as a number.
+	let n = count.abs();
+	let shown = n.to_string();
+	let (i, v, f) = match shown.split_once('.') {
+		Some((int, frac)) => (
+			int.parse::<u64>().unwrap_or(0),
+			frac.len(),
+			frac.parse::<u64>().unwrap_or(0),
+		),
+		None => (n as u64, 0, 0),
+	};
+	let whole = v == 0;
+	let (i10, i100) = (i % 10, i % 100);
+	match language(locale).as_str() {
+		"ja" | "zh" | "ko" | "vi" | "th" | "id" | "ms" | "lo" | "my" => "other",
+		"fr" | "pt" if i <= 1 => "one",
+		"fr" | "pt" => "other",
+		"ru" | "uk" | "be" if whole && i10 == 1 && i100 != 11 => "one",
+		"ru" | "uk" | "be" if whole && (2..=4).contains(&i10) && !(12..=14).contains(&i100) => {
+			"few"
+		}
+		"ru" | "uk" | "be" if whole => "many",
+		"ru" | "uk" | "be" => "other",
+		"pl" if whole && i == 1 => "one",
+		"pl" if whole && (2..=4).contains(&i10) && !(12..=14).contains(&i100) => "few",
+		"pl" if whole => "many",
+		"pl" => "other",
+		"cs" | "sk" if whole && i == 1 => "one",
+		"cs" | "sk" if whole && (2..=4).contains(&i) => "few",
+		"cs" | "sk" if !whole => "many",
+		"cs" | "sk" => "other",
+		"hr" | "sr" | "bs" => {
+			let (f10, f100) = (f % 10, f % 100);
+			if (whole && i10 == 1 && i100 != 11) || (f10 == 1 && f100 != 11) {
+				"one"
+			} else if (whole && (2..=4).contains(&i10) && !(12..=14).contains(&i100))
+				|| ((2..=4).contains(&f10) && !(12..=14).contains(&f100))
+			{
+				"few"
+			} else {
+				"other"
+			}
+		}
+		"lt" if whole && i10 == 1 && !(11..=19).contains(&i100) => "one",
+		"lt" if whole && i10 >= 2 && !(11..=19).contains(&i100) => "few",
+		"lt" if !whole => "many",
+		"lt" => "other",
+		"ro" if whole && i == 1 => "one",
+		"ro" if !whole || i == 0 || (2..=19).contains(&i100) => "few",
+		"ro" => "other",
+		"sl" if whole && i100 == 1 => "one",
+		"sl" if whole && i100 == 2 => "two",
+		"sl" if !whole || (3..=4).contains(&i100) => "few",
+		"sl" => "other",
+		"he" if whole && i == 1 => "one",
+		"he" if whole && i == 2 => "two",
+		"he" => "other",
+		"ar" if !whole => "other",
+		"ar" if i == 0 => "zero",
+		"ar" if i == 1 => "one",
+		"ar" if i == 2 => "two",
+		"ar" if (3..=10).contains(&i100) => "few",
+		"ar" if (11..=99).contains(&i100) => "many",
+		"ar" => "other",
+		_ if whole && i == 1 => "one",
+		_ => "other",
+	}
+}
+
+/// `format_number` with the separators of `locale`: `1,234.5` in English,
+/// `1 234,5` (with a no-break space) in Finnish, `1.234,5` in German.
+pub fn format_number(locale: &str, value: f64, decimals: Option<f64>) -> String {
+	let (group, decimal) = match language(locale).as_str() {
+		"fi" | "sv" | "nb" | "nn" | "no" | "fr" | "cs" | "sk" | "pl" | "ru" | "uk" | "et"
+		| "lv" | "lt" | "hu" | "bg" => ("\u{a0}", ","),
+		"de" | "nl" | "es" | "it" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "hr" | "sl"
+		| "sr" => (".", ","),
+		_ => (",", "."),
+	};
+	builtins::format_number_with(value, decimals, group, decimal)
+}
+
+const FI_MONTHS: [&str; 12] = [
+	"tammikuuta",
+	"helmikuuta",
+	"maaliskuuta",
+	"huhtikuuta",
+	"toukokuuta",
+	"kesäkuuta",
+	"heinäkuuta",
+	"elokuuta",
+	"syyskuuta",
+	"lokakuuta",
+	"marraskuuta",
+	"joulukuuta",
+];
+
+const FI_SHORT_MONTHS: [&str; 12] = [
+	"tammik.", "helmik.", "maalisk.", "huhtik.", "toukok.", "kesäk.", "heinäk.", "elok.", "syysk.",
+	"lokak.", "marrask.", "jouluk.",
+];
+
+/// `format_date` with the month names of `locale`. Finnish and English have
+/// month names; other locales use the English ones.
+pub fn format_date<D: WuiDate + ?Sized>(locale: &str, value: &D, pattern: &str) -> String {
+	match language(locale).as_str() {
+		"fi" => builtins::format_date_with(value, pattern, &FI_MONTHS, &FI_SHORT_MONTHS),
+		_ => builtins::format_date(value, pattern),
+	}
+}
+
+/// The best of `available` locales for an `Accept-Language` header, `None`
+/// when nothing matches. Exact tags win over a shared language.
+pub fn negotiate<'a>(accept_language: &str, available: &[&'a str]) -> Option<&'a str> {
+	let mut ranges = accept_language
+		.split(',')
+		.filter_map(|range| {
+			let mut parts = range.split(';');
+			let tag = parts.next()?.trim();
+			let quality = parts
+				.find_map(|param| param.trim().strip_prefix("q="))
+				.map_or(Some(1.0), |q| q.trim().parse::<f64>().ok())?;
+			(!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+		})
+		.collect::<Vec<_>>();
+	ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+	ranges.into_iter().find_map(|(tag, _)| {
+		available
+			.iter()
+			.find(|locale| locale.eq_ignore_ascii_case(tag))
+			.or_else(|| {
+				available
+					.iter()
+					.find(|locale| tag != "*" && language(locale) == language(tag))
+			})
+			.copied()
+	})
+}
+
+/// Diagnostics for `t("key", ...)` calls in `source` whose key is missing
+/// from any of `catalogs`.
+pub fn check_keys(source: &str, catalogs: &[Catalog]) -> Vec<Diagnostic> {
+	let mut diags = Vec::new();
+	let mut check = |expr: &Expr| {
+		let Expr::Call { name, args, span } = expr else {
+			return;
+		};
+		if name != "t" {
+			return;
+		}
+		let Some(Expr::Literal(Literal::String(key), _)) = args.first() else {
+			return;
+		};
+		for catalog in catalogs.iter().filter(|catalog| !catalog.contains(key)) {
+			diags.push(Diagnostic::new(
+				format!("missing translation {key:?} in {}", catalog.locale()),
+				*span,
+			));
+		}
+	};
+	for node in &Parser::new(source).parse().nodes {
+		node.visit_exprs(&mut check);
+	}
+	diags
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const FI: &str = "# Finnish\n\
+		greeting = Hei, { $name }!\n\
+		cart-items = { $count ->\n    [0] Ostoskori on tyhjä\n    [one] Yksi tuote\n   *[other] { $count } tuotetta\n}\n\
+		multi =\n    first line\n    second { \"{\" } line\n";
+
+	#[test]
+	fn formats_messages_and_selects() {
+		let catalog = Catalog::parse("fi", FI).unwrap();
+		assert_eq!(
+			catalog.format("greeting", &[("name", "Aino".into())]),
+			Some("Hei, Aino!".to_string())
+		);
+		let items = |count: f64| catalog.format("cart-items", &[("count", count.into())]);
+		assert_eq!(items(0.0).unwrap(), "Ostoskori on tyhjä");
+		assert_eq!(items(1.0).unwrap(), "Yksi tuote");
+		assert_eq!(items(2500.0).unwrap(), "2\u{a0}500 tuotetta");
+		assert_eq!(
+			catalog.format("multi", &[]).unwrap(),
+			"first line\nsecond { line"
+		);
+		assert_eq!(catalog.format("greeting", &[]).unwrap(), "Hei, {$name}!");
+		assert_eq!(catalog.format("missing", &[]), None);
+		assert_eq!(
+			catalog.keys().collect::<Vec<_>>(),
+			["cart-items", "greeting", "multi"]
+		);
+	}
+
+	#[test]
+	fn reports_catalog_errors_with_lines() {
+		let errors = Catalog::parse(
+			"en",
+			"a = x\n  b\nnot a message\na = y\nc = { $n -> [one] x }\nd = { nope }",
+		)
+		.unwrap_err();
+		let errors = errors
+			.iter()
+			.map(|error| (error.line, error.message.as_str()))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			errors,
+			[
+				(3, "expected `key = value`"),
+				(4, "duplicate message a"),
+				(5, "c: select on $n needs exactly one default *[variant]"),
+				(6, "d: unsupported placeable { nope }"),
+			]
+		);
+	}
+
+	#[test]
+	fn formats_numbers_and_dates_per_locale() {
+		assert_eq!(format_number("en-US", 1234.5, Some(2.0)), "1,234.50");
+		assert_eq!(format_number("fi", -1234.5, None), "-1\u{a0}234,5");
+		assert_eq!(format_number("de-DE", 1234567.0, None), "1.234.567");
+		assert_eq!(
+			format_date("fi-FI", &1_700_000_000i64, "D. MMMM YYYY"),
+			"14. marraskuuta 2023"
+		);
+		assert_eq!(format_date("fi", "2024-02-29", "D MMM"), "29 helmik.");
+		assert_eq!(format_date("en", "2024-02-29", "D MMM"), "29 Feb");
+		assert_eq!(plural_category("fr", 0.0), "one");
+		assert_eq!(plural_category("en", 0.0), "other");
+	}
+
+	#[test]
+	fn selects_cldr_plural_categories() {
+		let categories = |locale: &str, counts: &[f64]| {
+			counts
+				.iter()
+				.map(|count| plural_category(locale, *count))
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(
+			categories("en", &[1.0, 2.0, 1.5]),
+			["one", "other", "other"]
+		);
+		assert_eq!(
+			categories("ru-RU", &[1.0, 21.0, 3.0, 5.0, 11.0, 12.0, 1.5]),
+			["one", "one", "few", "many", "many", "many", "other"]
+		);
+		assert_eq!(
+			categories("pl", &[1.0, 22.0, 12.0, 5.0, 21.0]),
+			["one", "few", "many", "many", "many"]
+		);
+		assert_eq!(
+			categories("cs", &[1.0, 3.0, 5.0, 2.5]),
+			["one", "few", "other", "many"]
+		);
+		assert_eq!(
+			categories("ar", &[0.0, 1.0, 2.0, 7.0, 11.0, 100.0]),
+			["zero", "one", "two", "few", "many", "other"]
+		);
+		assert_eq!(categories("ja", &[1.0]), ["other"]);
+	}
+
+	#[test]
+	fn reports_keys_missing_from_catalogs() {
+		let en = Catalog::parse("en", "greeting = Hi\nbye = Bye").unwrap();
+		let fi = Catalog::parse("fi", "greeting = Hei").unwrap();
+		let source = r#"<VStack gap={1}>
+	<Text value={t("greeting")} />
+	<Text value={state.done ? t("bye") : t("nope", "n", 1)} />
+</VStack>"#;
+		let diags = check_keys(source, &[en, fi]);
+		let messages = diags
+			.iter()
+			.map(|diag| diag.message.as_str())
+			.collect::<Vec<_>>();
+		assert_eq!(
+			messages,
+			[
+				"missing translation \"bye\" in fi",
+				"missing translation \"nope\" in en",
+				"missing translation \"nope\" in fi",
+			]
+		);
+		assert_eq!(
+			&source[diags[0].span.start..diags[0].span.end],
+			"t(\"bye\")"
+		);
+	}
+
+	#[test]
+	fn negotiates_accept_language() {
+		let available = ["en", "fi"];
+		assert_eq!(negotiate("fi-FI,fi;q=0.9,en;q=0.8", &available), Some("fi"));
+		assert_eq!(negotiate("sv;q=0.9, en-GB;q=0.5", &available), Some("en"));
+		assert_eq!(negotiate("en;q=0.2, fi;q=0.7", &available), Some("fi"));
+		assert_eq!(negotiate("de, fi;q=0", &available), None);
+		assert_eq!(negotiate("*", &available), None);
+	}
+}
@@ -1,4 +1,4 @@
-use crate::ast::{AttrValue, Element, Node};
+use crate::ast::{AttrValue, Element, Expr, Node};
 use crate::diagnostic::{Diagnostic, Span};
 use crate::parser::Parser;
 use std::collections::{HashMap, HashSet};
@@ -11,6 +11,12 @@ pub struct ImportResult {
 	pub nodes: Vec<Node>,
 	pub components: HashMap<String, Vec<Node>>,
 	pub source_files: Vec<PathBuf>,
+	/// The file each component was imported from. Spans in a component's
+	/// nodes are offsets into that file; content inlined by `<Import src>`
+	/// is spanned as the `<Import>` tag that pulled it in.
+	pub component_files: HashMap<String, PathBuf>,
+	/// The source of every imported file, keyed like `component_files`.
+	pub sources: HashMap<PathBuf, String>,
 }
 
 #[derive(Clone)]
@@ -25,6 +31,8 @@ struct ImportContext {
 	stack: Vec<PathBuf>,
 	source_files: Vec<PathBuf>,
 	source_files_set: HashSet<PathBuf>,
+	component_files: HashMap<String, PathBuf>,
+	sources: HashMap<PathBuf, String>,
 }
 
 impl ImportContext {
@@ -73,6 +81,8 @@ where
 			nodes,
 			components,
 			source_files: ctx.source_files,
+			component_files: ctx.component_files,
+			sources: ctx.sources,
 		})
 	} else {
 		Err(diags)
@@ -111,6 +121,8 @@ where
 						load_import(&path, module_name, el.span, ctx, loader, diags)
 					{
 						merge_components(components, imported.components, el.span, diags);
+						ctx.component_files
+							.insert(name.clone(), normalize_path(&path));
 						if components.insert(name.clone(), imported.nodes).is_some() {
 							diags.push(Diagnostic::new(
 								format!("duplicate import component {}", name),
@@ -131,7 +143,10 @@ where
 						load_import(&path, module_name, el.span, ctx, loader, diags)
 					{
 						merge_components(components, imported.components, el.span, diags);
-						out.extend(imported.nodes);
+						out.extend(imported.nodes.into_iter().map(|mut node| {
+							relocate(&mut node, el.span);
+							node
+						}));
 					}
 				}
 			}
@@ -170,6 +185,76 @@ fn merge_components(
 	}
 }
 
+/// Moves every span in `node` to `span`.
+fn relocate(node: &mut Node, span: Span) {
+	match node {
+		Node::Element(el) => {
+			el.span = span;
+			for attr in &mut el.attrs {
+				attr.span = span;
+				match &mut attr.value {
+					AttrValue::String(_, value)
+					| AttrValue::Number(_, value)
+					| AttrValue::Bool(_, value)
+					| AttrValue::Null(value) => *value = span,
+					AttrValue::Expr(expr) => relocate_expr(expr, span),
+				}
+			}
+			for child in &mut el.children {
+				relocate(child, span);
+			}
+		}
+		Node::Text(_, value) => *value = span,
+		Node::Expr(expr) => relocate_expr(expr, span),
+	}
+}
+
+fn relocate_expr(expr: &mut Expr, span: Span) {
+	match expr {
+		Expr::Literal(_, value) | Expr::Path(_, value) => *value = span,
+		Expr::Call {
+			args, span: value, ..
+		} => {
+			*value = span;
+			for arg in args {
+				relocate_expr(arg, span);
+			}
+		}
+		Expr::Unary {
+			expr, span: value, ..
+		} => {
+			*value = span;
+			relocate_expr(expr, span);
+		}
+		Expr::Binary {
+			left,
+			right,
+			span: value,
+			..
+		}
+		| Expr::Coalesce {
+			left,
+			right,
+			span: value,
+		} => {
+			*value = span;
+			relocate_expr(left, span);
+			relocate_expr(right, span);
+		}
+		Expr::Ternary {
+			cond,
+			then_expr,
+			else_expr,
+			span: value,
+		} => {
+			*value = span;
+			relocate_expr(cond, span);
+			relocate_expr(then_expr, span);
+			relocate_expr(else_expr, span);
+		}
+	}
+}
+
 fn import_name(el: &Element, diags: &mut Vec<Diagnostic>) -> Option<String> {
 	let attr = el.attrs.iter().find(|attr| attr.name == "name")?;
 	match &attr.value {
@@ -296,6 +381,7 @@ where
 	ctx.record_source(path);
 	ctx.stack.push(normalized.clone());
 	let parsed = Parser::new(&source).parse();
+	ctx.sources.insert(normalized.clone(), source);
 	diags.extend(parsed.diagnostics);
 	let mut components = HashMap::new();
 	let nodes = expand_nodes(
@@ -353,6 +439,10 @@ mod tests {
 		assert!(result.components.is_empty());
 		assert_eq!(result.source_files.len(), 1);
 		assert_eq!(result.source_files[0], partial);
+		let Node::Element(el) = &result.nodes[0] else {
+			panic!("expected the partial's Text");
+		};
+		assert_eq!(el.span, Span::new(0, src.len()));
 	}
 
 	#[test]
@@ -369,6 +459,9 @@ mod tests {
 		let result = resolve(src, "main", Some(&dir)).expect("resolve imports");
 		assert_eq!(result.nodes.len(), 1);
 		assert!(result.components.contains_key("AppLayout"));
-		assert_eq!(result.source_files, vec![layout]);
+		assert_eq!(result.source_files, vec![layout.clone()]);
+		let file = &result.component_files["AppLayout"];
+		assert_eq!(*file, normalize_path(&layout));
+		assert_eq!(result.sources[file], "<VStack><Children /></VStack>");
 	}
 }
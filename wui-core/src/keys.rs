@@ -0,0 +1,213 @@
+//! Key combinations such as `Ctrl+S` or `Shift+Enter`, as written in `key`
+//! attributes and matched against key events from the client.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+	pub ctrl: bool,
+	pub alt: bool,
+	pub shift: bool,
+	pub meta: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyCombo {
+	pub modifiers: Modifiers,
+	/// The canonical key name: an upper-case letter, a digit, `F1`..`F12` or
+	/// a named key such as `Enter`.
+	pub key: String,
+}
+
+const NAMED_KEYS: &[&str] = &[
+	"Enter",
+	"Escape",
+	"Tab",
+	"Space",
+	"Backspace",
+	"Delete",
+	"Insert",
+	"Home",
+	"End",
+	"PageUp",
+	"PageDown",
+	"ArrowUp",
+	"ArrowDown",
+	"ArrowLeft",
+	"ArrowRight",
+];
+
+impl KeyCombo {
+	/// Parses `Ctrl+Shift+K`-style combinations. Modifiers and key names are
+	/// case-insensitive; `Cmd` is an alias for `Meta` and `Esc` for `Escape`.
+	pub fn parse(spec: &str) -> Result<Self, String> {
+		if spec.trim().is_empty() {
+			return Err("empty key combination".to_string());
+		}
+		let mut modifiers = Modifiers::default();
+		let mut key = None;
+		for part in spec.split('+').map(str::trim) {
+			if part.is_empty() {
+				return Err(format!("missing key in {spec}"));
+			}
+			let flag = match part.to_ascii_lowercase().as_str() {
+				"ctrl" | "control" => Some(&mut modifiers.ctrl),
+				"alt" | "option" => Some(&mut modifiers.alt),
+				"shift" => Some(&mut modifiers.shift),
+				"meta" | "cmd" | "command" => Some(&mut modifiers.meta),
+				_ => None,
+			};
+			if let Some(flag) = flag {
+				if *flag {
+					return Err(format!("{part} is given twice"));
+				}
+				*flag = true;
+				continue;
+			}
+			if key.is_some() {
+				return Err(format!("{spec} names more than one key"));
+			}
+			key = Some(key_name(part).ok_or_else(|| format!("unknown key {part}"))?);
+		}
+		let key = key.ok_or_else(|| format!("missing key in {spec}"))?;
+		Ok(Self { modifiers, key })
+	}
+
+	/// Whether an event for `keycode` (`KeyboardEvent.code`, or `.key` when
+	/// the browser gives no code) with exactly `modifiers` held matches.
+	pub fn matches(&self, keycode: &str, modifiers: Modifiers) -> bool {
+		if modifiers != self.modifiers {
+			return false;
+		}
+		let key = self.key.as_str();
+		if key.len() == 1 {
+			let ch = key.as_bytes()[0];
+			if ch.is_ascii_alphabetic() {
+				return keycode.strip_prefix("Key") == Some(key)
+					|| keycode.eq_ignore_ascii_case(key);
+			}
+			return keycode.strip_prefix("Digit") == Some(key)
+				|| keycode.strip_prefix("Numpad") == Some(key)
+				|| keycode == key;
+		}
+		match key {
+			"Space" => keycode == "Space" || keycode == " ",
+			"Enter" => keycode == "Enter" || keycode == "NumpadEnter",
+			_ => keycode == key,
+		}
+	}
+}
+
+impl fmt::Display for KeyCombo {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let held = [
+			(self.modifiers.ctrl, "Ctrl"),
+			(self.modifiers.alt, "Alt"),
+			(self.modifiers.shift, "Shift"),
+			(self.modifiers.meta, "Meta"),
+		];
+		for (_, name) in held.iter().filter(|(on, _)| *on) {
+			write!(f, "{name}+")?;
+		}
+		f.write_str(&self.key)
+	}
+}
+
+fn key_name(part: &str) -> Option<String> {
+	if part.len() == 1 {
+		let ch = part.as_bytes()[0];
+		return ch
+			.is_ascii_alphanumeric()
+			.then(|| part.to_ascii_uppercase());
+	}
+	let lower = part.to_ascii_lowercase();
+	let alias = match lower.as_str() {
+		"esc" => Some("Escape"),
+		"up" => Some("ArrowUp"),
+		"down" => Some("ArrowDown"),
+		"left" => Some("ArrowLeft"),
+		"right" => Some("ArrowRight"),
+		"del" => Some("Delete"),
+		_ => None,
+	};
+	if let Some(alias) = alias {
+		return Some(alias.to_string());
+	}
+	if let Some(number) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+		return (1..=12).contains(&number).then(|| format!("F{number}"));
+	}
+	NAMED_KEYS
+		.iter()
+		.find(|name| name.eq_ignore_ascii_case(part))
+		.map(|name| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_and_normalizes_combinations() {
+		let combo = KeyCombo::parse("shift+ctrl+s").unwrap();
+		assert_eq!(combo.to_string(), "Ctrl+Shift+S");
+		assert_eq!(
+			KeyCombo::parse("Cmd+Esc").unwrap().to_string(),
+			"Meta+Escape"
+		);
+		assert_eq!(KeyCombo::parse("f5").unwrap().to_string(), "F5");
+		assert_eq!(
+			KeyCombo::parse("Enter").unwrap().modifiers,
+			Modifiers::default()
+		);
+	}
+
+	#[test]
+	fn rejects_malformed_combinations() {
+		assert_eq!(KeyCombo::parse("").unwrap_err(), "empty key combination");
+		assert_eq!(
+			KeyCombo::parse("Ctrl+").unwrap_err(),
+			"missing key in Ctrl+"
+		);
+		assert_eq!(
+			KeyCombo::parse("Ctrl+Shift").unwrap_err(),
+			"missing key in Ctrl+Shift"
+		);
+		assert_eq!(
+			KeyCombo::parse("A+B").unwrap_err(),
+			"A+B names more than one key"
+		);
+		assert_eq!(
+			KeyCombo::parse("Ctrl+Ctrl+S").unwrap_err(),
+			"Ctrl is given twice"
+		);
+		assert_eq!(
+			KeyCombo::parse("Ctrl+Hyper").unwrap_err(),
+			"unknown key Hyper"
+		);
+		assert_eq!(KeyCombo::parse("F13").unwrap_err(), "unknown key F13");
+	}
+
+	#[test]
+	fn matches_codes_and_keys_with_exact_modifiers() {
+		let save = KeyCombo::parse("Ctrl+S").unwrap();
+		let ctrl = Modifiers {
+			ctrl: true,
+			..Modifiers::default()
+		};
+		assert!(save.matches("KeyS", ctrl));
+		assert!(save.matches("s", ctrl));
+		assert!(!save.matches("KeyS", Modifiers::default()));
+		assert!(!save.matches(
+			"KeyS",
+			Modifiers {
+				shift: true,
+				..ctrl
+			}
+		));
+		let one = KeyCombo::parse("1").unwrap();
+		assert!(one.matches("Digit1", Modifiers::default()));
+		assert!(one.matches("Numpad1", Modifiers::default()));
+		let space = KeyCombo::parse("Space").unwrap();
+		assert!(space.matches(" ", Modifiers::default()));
+	}
+}
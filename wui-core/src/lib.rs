@@ -1,7 +1,14 @@
 pub mod ast;
+pub mod builtins;
 pub mod compiler;
+pub mod cst;
 pub mod diagnostic;
 pub mod expr;
+pub mod format;
+pub mod i18n;
 pub mod imports;
+pub mod keys;
 pub mod parser;
 pub mod routing;
+pub mod schema;
+pub mod style;
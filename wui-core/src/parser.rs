@@ -1,16 +1,19 @@
-use crate::ast::{AttrValue, Attribute, Element, Expr, Node};
+use crate::ast::{AttrValue, Attribute, Element, Expr, Literal, Node};
+use crate::cst::{self, CstAttr, CstElement, CstNode};
 use crate::diagnostic::{Diagnostic, Span};
 use crate::expr::ExprParser;
 
 #[derive(Debug)]
 pub struct ParsedFile {
 	pub nodes: Vec<Node>,
+	/// The lossless tree `nodes` was derived from, with comments and
+	/// whitespace.
+	pub cst: Vec<CstNode>,
 	pub diagnostics: Vec<Diagnostic>,
 }
 
 pub struct Parser<'a> {
 	src: &'a str,
-	pos: usize,
 	diagnostics: Vec<Diagnostic>,
 }
 
@@ -18,378 +21,142 @@ impl<'a> Parser<'a> {
 	pub fn new(src: &'a str) -> Self {
 		Self {
 			src,
-			pos: 0,
 			diagnostics: Vec::new(),
 		}
 	}
 
 	pub fn parse(mut self) -> ParsedFile {
-		let nodes = self.parse_nodes(None);
+		let cst = cst::parse(self.src);
+		self.diagnostics = cst.diagnostics;
+		let nodes = self.nodes(&cst.nodes);
 		ParsedFile {
 			nodes,
+			cst: cst.nodes,
 			diagnostics: self.diagnostics,
 		}
 	}
 
-	fn parse_nodes(&mut self, stop_tag: Option<&str>) -> Vec<Node> {
+	fn nodes(&mut self, cst: &[CstNode]) -> Vec<Node> {
 		let mut nodes = Vec::new();
-		loop {
-			self.skip_ws();
-			if self.eof() {
-				break;
-			}
-			if let Some(tag) = stop_tag {
-				if self.peek_str("</") && self.peek_str_at(tag, 2) {
-					break;
+		for node in cst {
+			match node {
+				CstNode::Element(el) => nodes.push(Node::Element(self.element(el))),
+				CstNode::Text(span) => {
+					// Leading whitespace is layout; text that is only
+					// whitespace is dropped.
+					let raw = self.text(*span);
+					let text = raw.trim_start();
+					if !text.is_empty() {
+						let start = span.end - text.len();
+						nodes.push(Node::Text(text.to_string(), Span::new(start, span.end)));
+					}
 				}
-			}
-			if self.peek_char() == Some('<') {
-				match self.parse_element() {
-					Some(node) => nodes.push(Node::Element(node)),
-					None => break,
-				}
-				continue;
-			}
-			if self.peek_char() == Some('{') {
-				if let Some(expr) = self.parse_expr_node() {
-					nodes.push(Node::Expr(expr));
-				}
-				continue;
-			}
-			if let Some(text) = self.parse_text() {
-				nodes.push(text);
-			} else {
-				break;
+				CstNode::Expr(span) => nodes.push(Node::Expr(self.braced_expr(*span))),
+				CstNode::Comment(_) | CstNode::Error(_) => {}
 			}
 		}
 		nodes
 	}
 
-	fn parse_element(&mut self) -> Option<Element> {
-		let start = self.pos;
-		self.expect_char('<')?;
-		if self.consume_char('/') {
-			self.diagnostics
-				.push(Diagnostic::new("unexpected closing tag", self.span_here()));
-			return None;
-		}
-		let name = self.parse_ident()?;
-		let attrs = self.parse_attributes();
-		self.skip_ws();
-		if self.consume_char('/') {
-			self.expect_char('>')?;
-			let span = Span::new(start, self.pos);
-			return Some(Element {
-				name,
-				attrs,
-				children: Vec::new(),
-				span,
-			});
-		}
-		self.expect_char('>')?;
-		let children = self.parse_nodes(Some(&name));
-		self.expect_char('<')?;
-		self.expect_char('/')?;
-		let closing = self.parse_ident().unwrap_or_default();
-		if closing != name {
-			let span = Span::new(start, self.pos);
-			self.diagnostics
-				.push(Diagnostic::new("mismatched closing tag", span));
-		}
-		self.skip_ws();
-		self.expect_char('>')?;
-		let span = Span::new(start, self.pos);
-		Some(Element {
-			name,
+	fn element(&mut self, el: &CstElement) -> Element {
+		let attrs = el.attrs.iter().map(|attr| self.attr(attr)).collect();
+		Element {
+			name: self.text(el.name).to_string(),
 			attrs,
-			children,
-			span,
-		})
-	}
-
-	fn parse_attributes(&mut self) -> Vec<Attribute> {
-		let mut attrs = Vec::new();
-		loop {
-			self.skip_ws();
-			if self.peek_char() == Some('>') || self.peek_str("/>") {
-				break;
-			}
-			let start = self.pos;
-			let Some(name) = self.parse_ident() else {
-				self.recover_attr();
-				continue;
-			};
-			self.skip_ws();
-			let value = if self.consume_char('=') {
-				self.skip_ws();
-				if self.peek_char() == Some('"') {
-					match self.parse_string() {
-						Some(value) => {
-							let span = Span::new(start, self.pos);
-							AttrValue::String(value, span)
-						}
-						None => AttrValue::String(String::new(), self.span_here()),
-					}
-				} else if self.peek_char() == Some('{') {
-					match self.parse_braced_expr() {
-						Some(expr) => AttrValue::Expr(expr),
-						None => AttrValue::Expr(Expr::Literal(
-							crate::ast::Literal::Null,
-							self.span_here(),
-						)),
-					}
-				} else if let Some(lit) = self.parse_bare_literal() {
-					lit
+			children: self.nodes(&el.children),
+			span: el.span,
+		}
+	}
+
+	fn attr(&mut self, attr: &CstAttr) -> Attribute {
+		let value = match attr.value {
+			None => AttrValue::Bool(true, attr.span),
+			Some(span) => {
+				let raw = self.text(span);
+				if raw.starts_with('"') {
+					let inner = Span::new(
+						span.start + 1,
+						span.end - usize::from(raw.len() > 1 && raw.ends_with('"')),
+					);
+					AttrValue::String(self.unescape(inner), inner)
+				} else if raw.starts_with('{') {
+					AttrValue::Expr(self.braced_expr(span))
 				} else {
-					self.diagnostics.push(Diagnostic::new(
-						"expected attribute value",
-						self.span_here(),
-					));
-					AttrValue::Bool(true, self.span_here())
+					self.bare_literal(span)
 				}
-			} else {
-				let span = Span::new(start, self.pos);
-				AttrValue::Bool(true, span)
-			};
-			let span = Span::new(start, self.pos);
-			attrs.push(Attribute { name, value, span });
-		}
-		attrs
-	}
-
-	fn parse_bare_literal(&mut self) -> Option<AttrValue> {
-		let start = self.pos;
-		if self.peek_str("true") && self.is_delim(4) {
-			self.pos += 4;
-			return Some(AttrValue::Bool(true, Span::new(start, self.pos)));
-		}
-		if self.peek_str("false") && self.is_delim(5) {
-			self.pos += 5;
-			return Some(AttrValue::Bool(false, Span::new(start, self.pos)));
-		}
-		if self.peek_str("null") && self.is_delim(4) {
-			self.pos += 4;
-			return Some(AttrValue::Null(Span::new(start, self.pos)));
-		}
-		let mut saw_digit = false;
-		let mut saw_dot = false;
-		let mut i = self.pos;
-		while let Some(ch) = self.peek_char_at(i) {
-			if ch.is_ascii_digit() {
-				saw_digit = true;
-				i += ch.len_utf8();
-				continue;
-			}
-			if ch == '.' && !saw_dot {
-				saw_dot = true;
-				i += ch.len_utf8();
-				continue;
-			}
-			break;
-		}
-		if saw_digit {
-			let slice = &self.src[self.pos..i];
-			if let Ok(value) = slice.parse::<f64>() {
-				self.pos = i;
-				return Some(AttrValue::Number(value, Span::new(start, self.pos)));
-			}
-		}
-		None
-	}
-
-	fn parse_text(&mut self) -> Option<Node> {
-		let start = self.pos;
-		let mut end = self.pos;
-		while let Some(ch) = self.peek_char() {
-			if ch == '<' || ch == '{' {
-				break;
-			}
-			self.pos += ch.len_utf8();
-			end = self.pos;
-		}
-		if end == start {
-			return None;
-		}
-		let text = self.src[start..end].to_string();
-		if text.trim().is_empty() {
-			return None;
-		}
-		Some(Node::Text(text, Span::new(start, end)))
-	}
-
-	fn parse_expr_node(&mut self) -> Option<Expr> {
-		self.parse_braced_expr()
-	}
-
-	fn parse_braced_expr(&mut self) -> Option<Expr> {
-		let start = self.pos;
-		if !self.consume_char('{') {
-			return None;
-		}
-		let expr_start = self.pos;
-		let mut in_string = false;
-		while let Some(ch) = self.peek_char() {
-			if ch == '"' {
-				in_string = !in_string;
-			}
-			if ch == '}' && !in_string {
-				break;
-			}
-			self.pos += ch.len_utf8();
-		}
-		if self.peek_char() != Some('}') {
-			self.diagnostics
-				.push(Diagnostic::new("unterminated expression", self.span_here()));
-			return None;
-		}
-		let expr_end = self.pos;
-		let expr_src = &self.src[expr_start..expr_end];
-		self.pos += 1;
-		match ExprParser::new(expr_src, expr_start).parse() {
-			Ok(expr) => Some(expr),
-			Err(diag) => {
-				self.diagnostics.push(diag);
-				self.recover_to('}');
-				Some(Expr::Literal(
-					crate::ast::Literal::Null,
-					Span::new(start, self.pos),
-				))
 			}
+		};
+		Attribute {
+			name: self.text(attr.name).to_string(),
+			value,
+			span: attr.span,
 		}
 	}
 
-	fn parse_string(&mut self) -> Option<String> {
-		if !self.consume_char('"') {
-			return None;
-		}
-		let mut out = String::new();
-		while let Some(ch) = self.peek_char() {
-			self.pos += ch.len_utf8();
-			match ch {
-				'"' => return Some(out),
-				'\\' => {
-					let next = self.peek_char()?;
-					self.pos += next.len_utf8();
-					match next {
-						'"' => out.push('"'),
-						'\\' => out.push('\\'),
-						'n' => out.push('\n'),
-						_ => {
-							self.diagnostics
-								.push(Diagnostic::new("invalid escape", self.span_here()));
-						}
+	fn bare_literal(&mut self, span: Span) -> AttrValue {
+		match self.text(span) {
+			"true" => AttrValue::Bool(true, span),
+			"false" => AttrValue::Bool(false, span),
+			"null" => AttrValue::Null(span),
+			raw => match raw.parse::<f64>() {
+				Ok(value) if raw.starts_with(|ch: char| ch.is_ascii_digit()) => {
+					AttrValue::Number(value, span)
+				}
+				_ => {
+					if !raw.is_empty() {
+						self.diagnostics
+							.push(Diagnostic::new("expected attribute value", span));
 					}
+					AttrValue::Bool(true, span)
 				}
-				_ => out.push(ch),
-			}
+			},
 		}
-		self.diagnostics
-			.push(Diagnostic::new("unterminated string", self.span_here()));
-		None
 	}
 
-	fn parse_ident(&mut self) -> Option<String> {
-		self.skip_ws();
-		let first = self.peek_char()?;
-		if !(first.is_ascii_alphabetic() || first == '_' || first == ':') {
-			return None;
-		}
-		let mut end = self.pos + first.len_utf8();
-		let iter = self.src[self.pos + first.len_utf8()..].char_indices();
-		for (offset, ch) in iter {
-			if ch.is_ascii_alphanumeric() || ch == '_' || ch == ':' || ch == '-' {
-				end = self.pos + first.len_utf8() + offset + ch.len_utf8();
-			} else {
-				break;
-			}
-		}
-		let ident = self.src[self.pos..end].to_string();
-		self.pos = end;
-		Some(ident)
-	}
-
-	fn recover_attr(&mut self) {
-		while let Some(ch) = self.peek_char() {
-			if ch.is_whitespace() || ch == '>' || ch == '/' {
-				break;
+	/// Parses the expression inside a `{...}` span; an unterminated or
+	/// malformed one becomes `null`.
+	fn braced_expr(&mut self, span: Span) -> Expr {
+		let end = if self.text(span).ends_with('}') {
+			span.end - 1
+		} else {
+			span.end
+		};
+		let expr_start = (span.start + 1).min(end);
+		match ExprParser::new(&self.src[expr_start..end], expr_start).parse() {
+			Ok(expr) => expr,
+			Err(diag) => {
+				if self.text(span).ends_with('}') {
+					self.diagnostics.push(diag);
+				}
+				Expr::Literal(Literal::Null, span)
 			}
-			self.pos += ch.len_utf8();
 		}
 	}
 
-	fn recover_to(&mut self, target: char) {
-		while let Some(ch) = self.peek_char() {
-			self.pos += ch.len_utf8();
-			if ch == target {
-				break;
+	fn unescape(&mut self, span: Span) -> String {
+		let mut out = String::new();
+		let mut chars = self.text(span).char_indices();
+		while let Some((offset, ch)) = chars.next() {
+			if ch != '\\' {
+				out.push(ch);
+				continue;
 			}
-		}
-	}
-
-	fn skip_ws(&mut self) {
-		while let Some(ch) = self.peek_char() {
-			if ch.is_whitespace() {
-				self.pos += ch.len_utf8();
-			} else {
-				break;
+			match chars.next() {
+				Some((_, '"')) => out.push('"'),
+				Some((_, '\\')) => out.push('\\'),
+				Some((_, 'n')) => out.push('\n'),
+				_ => {
+					let at = span.start + offset;
+					self.diagnostics
+						.push(Diagnostic::new("invalid escape", Span::new(at, at + 1)));
+				}
 			}
 		}
+		out
 	}
 
-	fn expect_char(&mut self, ch: char) -> Option<()> {
-		self.skip_ws();
-		if self.consume_char(ch) {
-			Some(())
-		} else {
-			self.diagnostics
-				.push(Diagnostic::new("expected character", self.span_here()));
-			None
-		}
-	}
-
-	fn consume_char(&mut self, ch: char) -> bool {
-		self.skip_ws();
-		if self.peek_char() == Some(ch) {
-			self.pos += ch.len_utf8();
-			true
-		} else {
-			false
-		}
-	}
-
-	fn peek_char(&self) -> Option<char> {
-		self.src[self.pos..].chars().next()
-	}
-
-	fn peek_char_at(&self, pos: usize) -> Option<char> {
-		self.src[pos..].chars().next()
-	}
-
-	fn peek_str(&self, s: &str) -> bool {
-		self.src[self.pos..].starts_with(s)
-	}
-
-	fn peek_str_at(&self, s: &str, offset: usize) -> bool {
-		self.src[self.pos + offset..].starts_with(s)
-	}
-
-	fn is_delim(&self, len: usize) -> bool {
-		let idx = self.pos + len;
-		if idx >= self.src.len() {
-			return true;
-		}
-		match self.src[idx..].chars().next() {
-			Some(ch) => !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '.'),
-			None => true,
-		}
-	}
-
-	fn span_here(&self) -> Span {
-		Span::new(self.pos, self.pos)
-	}
-
-	fn eof(&self) -> bool {
-		self.pos >= self.src.len()
+	fn text(&self, span: Span) -> &'a str {
+		&self.src[span.start..span.end]
 	}
 }
 
@@ -431,6 +198,37 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn derives_ast_without_comments_or_layout() {
+		let src = "<!-- head --><VStack>\n\t<!--a-->\n\t<Text value=\"h\\\"i\" />\n\tHi {state.name}\n</VStack>";
+		let file = Parser::new(src).parse();
+		assert!(file.diagnostics.is_empty());
+		assert_eq!(file.cst.len(), 2);
+		assert_eq!(file.nodes.len(), 1);
+		let Node::Element(root) = &file.nodes[0] else {
+			panic!("expected element");
+		};
+		assert_eq!(root.children.len(), 3);
+		let Node::Element(text) = &root.children[0] else {
+			panic!("expected child element");
+		};
+		match &text.attrs[0].value {
+			AttrValue::String(value, span) => {
+				assert_eq!(value, "h\"i");
+				assert_eq!(&src[span.start..span.end], "h\\\"i");
+			}
+			_ => panic!("expected string attribute"),
+		}
+		match &root.children[1] {
+			Node::Text(text, span) => {
+				assert_eq!(text, "Hi ");
+				assert_eq!(&src[span.start..span.end], "Hi ");
+			}
+			_ => panic!("expected text"),
+		}
+		assert!(matches!(root.children[2], Node::Expr(Expr::Path(_, _))));
+	}
+
 	#[test]
 	fn reports_mismatched_closing_tag() {
 		let file = Parser::new("<Text></Button>").parse();
@@ -1,4 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+#[cfg(any(test, feature = "scan"))]
+use std::{fs, io, path::Path};
+
+use crate::ast::{Expr, Literal};
+use crate::diagnostic::Diagnostic;
+use crate::parser::Parser;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct RouteScore {
@@ -35,6 +41,59 @@ impl RoutePattern {
 		&self.raw
 	}
 
+	/// Names of the parameter segments, in order.
+	pub fn param_names(&self) -> impl Iterator<Item = &str> {
+		self.segments
+			.iter()
+			.filter_map(|segment| param_name(segment))
+	}
+
+	/// The path this pattern matches with `params` in its parameter segments,
+	/// in order, each percent-encoded. A wildcard and whatever follows it are
+	/// left out.
+	pub fn url<S: AsRef<str>>(&self, params: &[S]) -> Result<String, String> {
+		let expected = self.param_names().count();
+		if params.len() != expected {
+			let noun = if expected == 1 {
+				"parameter"
+			} else {
+				"parameters"
+			};
+			return Err(format!(
+				"`{}` takes {expected} path {noun}, got {}",
+				self.raw,
+				params.len()
+			));
+		}
+		Ok(self.fill(params))
+	}
+
+	/// Like [`RoutePattern::url`] without the parameter count check: missing
+	/// params leave their segments empty and extra ones are ignored.
+	pub fn fill<S: AsRef<str>>(&self, params: &[S]) -> String {
+		let mut params = params.iter();
+		let mut url = String::new();
+		for segment in self
+			.segments
+			.iter()
+			.take_while(|segment| !is_wildcard(segment))
+		{
+			url.push('/');
+			match param_name(segment) {
+				Some(_) => {
+					if let Some(param) = params.next() {
+						url.push_str(&encode_segment(param.as_ref()));
+					}
+				}
+				None => url.push_str(segment),
+			}
+		}
+		if url.is_empty() {
+			url.push('/');
+		}
+		url
+	}
+
 	pub fn match_path(&self, path: &str) -> Option<RouteMatch> {
 		let path_parts = route_segments(path);
 		let mut params = HashMap::new();
@@ -59,7 +118,7 @@ impl RoutePattern {
 
 		for (route_seg, path_seg) in self.segments.iter().take(end).zip(path_parts.iter()) {
 			if let Some(name) = param_name(route_seg) {
-				params.insert(name.to_string(), (*path_seg).to_string());
+				params.insert(name.to_string(), decode_segment(path_seg));
 				dynamic_segments += 1;
 			} else if route_seg == path_seg {
 				static_segments += 1;
@@ -102,6 +161,230 @@ where
 	best.map(|(index, _)| index)
 }
 
+/// Named routes of a crate: the module path and pattern of each `#[route]`
+/// and `#[partial]` handler, e.g. `todos::show`. `wgui check` validates
+/// `url()` calls against it.
+#[derive(Debug, Clone, Default)]
+pub struct RouteTable {
+	routes: BTreeMap<String, RoutePattern>,
+}
+
+impl RouteTable {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn insert(&mut self, name: impl Into<String>, pattern: RoutePattern) {
+		self.routes.insert(name.into(), pattern);
+	}
+
+	/// The route `name` refers to: its function name, or as much of its module
+	/// path as tells it apart from routes with the same name elsewhere.
+	pub fn get(&self, name: &str) -> Result<&RoutePattern, String> {
+		let suffix = format!("::{name}");
+		let mut matches = self
+			.routes
+			.iter()
+			.filter(|(key, _)| *key == name || key.ends_with(&suffix));
+		let Some((_, pattern)) = matches.next() else {
+			return Err(format!("unknown route {name:?}"));
+		};
+		if matches.next().is_some() {
+			let candidates = self
+				.routes
+				.keys()
+				.filter(|key| *key == name || key.ends_with(&suffix))
+				.map(String::as_str)
+				.collect::<Vec<_>>();
+			return Err(format!(
+				"route {name:?} is ambiguous: qualify it as one of {}",
+				candidates.join(", ")
+			));
+		}
+		Ok(pattern)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.routes.is_empty()
+	}
+
+	/// Add every function in `source` with a `#[route]` or `#[partial]`
+	/// attribute, as items of the crate root.
+	#[cfg(any(test, feature = "scan"))]
+	pub fn scan_source(&mut self, source: &str) -> syn::Result<()> {
+		self.scan_module("", source)
+	}
+
+	/// Like [`RouteTable::scan_source`] for the source of `module`, e.g.
+	/// `"todos::api"`.
+	#[cfg(any(test, feature = "scan"))]
+	pub fn scan_module(&mut self, module: &str, source: &str) -> syn::Result<()> {
+		let file = syn::parse_file(source)?;
+		self.scan_items(module, &file.items);
+		Ok(())
+	}
+
+	#[cfg(any(test, feature = "scan"))]
+	fn scan_items(&mut self, module: &str, items: &[syn::Item]) {
+		for item in items {
+			match item {
+				syn::Item::Fn(item) => {
+					if let Some(path) = item.attrs.iter().find_map(route_attr_path) {
+						self.insert(
+							qualify(module, &item.sig.ident.to_string()),
+							RoutePattern::parse(&path),
+						);
+					}
+				}
+				syn::Item::Mod(item) => {
+					if let Some((_, items)) = &item.content {
+						self.scan_items(&qualify(module, &item.ident.to_string()), items);
+					}
+				}
+				_ => {}
+			}
+		}
+	}
+
+	/// Scan every `.rs` file below the crate source directory `dir`. Files
+	/// that fail to parse are skipped; the compiler reports those.
+	#[cfg(any(test, feature = "scan"))]
+	pub fn scan_dir(dir: &Path) -> io::Result<Self> {
+		let mut table = Self::new();
+		let mut pending = vec![dir.to_path_buf()];
+		while let Some(current) = pending.pop() {
+			for entry in fs::read_dir(&current)? {
+				let path = entry?.path();
+				if path.is_dir() {
+					pending.push(path);
+				} else if path.extension().is_some_and(|ext| ext == "rs") {
+					let source = fs::read_to_string(&path)?;
+					let _ = table.scan_module(&file_module(dir, &path), &source);
+				}
+			}
+		}
+		Ok(table)
+	}
+}
+
+#[cfg(any(test, feature = "scan"))]
+pub(crate) fn qualify(module: &str, name: &str) -> String {
+	if module.is_empty() {
+		name.to_string()
+	} else {
+		format!("{module}::{name}")
+	}
+}
+
+/// The module path of the source file `path` in the crate rooted at `dir`:
+/// `src/todos/mod.rs` and `src/todos.rs` are both `todos`.
+#[cfg(any(test, feature = "scan"))]
+pub(crate) fn file_module(dir: &Path, path: &Path) -> String {
+	let relative = path.strip_prefix(dir).unwrap_or(path).with_extension("");
+	let mut segments = relative
+		.iter()
+		.map(|segment| segment.to_string_lossy().into_owned())
+		.collect::<Vec<_>>();
+	if (segments.len() == 1 && matches!(segments[0].as_str(), "main" | "lib"))
+		|| segments.last().is_some_and(|last| last == "mod")
+	{
+		segments.pop();
+	}
+	segments.join("::")
+}
+
+/// The pattern of a `#[route("/path", ..)]` or `#[partial("/path", ..)]`
+/// attribute.
+#[cfg(any(test, feature = "scan"))]
+fn route_attr_path(attr: &syn::Attribute) -> Option<String> {
+	let name = attr.path().segments.last()?.ident.to_string();
+	if name != "route" && name != "partial" {
+		return None;
+	}
+	attr.parse_args_with(|input: syn::parse::ParseStream| {
+		let path = input.parse::<syn::LitStr>()?;
+		input.step(|cursor| {
+			let mut rest = *cursor;
+			while let Some((_, next)) = rest.token_tree() {
+				rest = next;
+			}
+			Ok(((), rest))
+		})?;
+		Ok(path.value())
+	})
+	.ok()
+}
+
+/// Report `url("name", ...)` calls in `source` that name no route in `routes`,
+/// name more than one, or pass the wrong number of path parameters.
+pub fn check_urls(source: &str, routes: &RouteTable) -> Vec<Diagnostic> {
+	let mut diags = Vec::new();
+	let mut check = |expr: &Expr| {
+		let Expr::Call { name, args, span } = expr else {
+			return;
+		};
+		if name != "url" {
+			return;
+		}
+		let Some(Expr::Literal(Literal::String(route), _)) = args.first() else {
+			return;
+		};
+		match routes.get(route) {
+			Ok(pattern) => {
+				if let Err(message) = pattern.url(&vec![""; args.len() - 1]) {
+					diags.push(Diagnostic::new(
+						format!("route {route:?}: {message}"),
+						*span,
+					));
+				}
+			}
+			Err(message) => diags.push(Diagnostic::new(message, *span)),
+		}
+	};
+	for node in &Parser::new(source).parse().nodes {
+		node.visit_exprs(&mut check);
+	}
+	diags
+}
+
+/// Percent-encodes everything but RFC 3986 unreserved characters, so `value`
+/// stays one path segment.
+pub fn encode_segment(value: &str) -> String {
+	let mut out = String::with_capacity(value.len());
+	for byte in value.bytes() {
+		if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+			out.push(byte as char);
+		} else {
+			out.push_str(&format!("%{byte:02X}"));
+		}
+	}
+	out
+}
+
+/// Reverses [`encode_segment`]. Malformed escapes are kept as written.
+pub fn decode_segment(segment: &str) -> String {
+	let bytes = segment.as_bytes();
+	let mut out = Vec::with_capacity(bytes.len());
+	let mut index = 0;
+	while index < bytes.len() {
+		let escaped = (bytes[index] == b'%')
+			.then(|| segment.get(index + 1..index + 3))
+			.flatten()
+			.and_then(|hex| u8::from_str_radix(hex, 16).ok());
+		match escaped {
+			Some(byte) => {
+				out.push(byte);
+				index += 3;
+			}
+			None => {
+				out.push(bytes[index]);
+				index += 1;
+			}
+		}
+	}
+	String::from_utf8_lossy(&out).into_owned()
+}
+
 fn route_segments(path: &str) -> Vec<&str> {
 	path.trim_matches('/')
 		.split('/')
@@ -183,6 +466,112 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn url_fills_and_encodes_params() {
+		let pattern = RoutePattern::parse("/users/:name/posts/{id}");
+		assert_eq!(
+			pattern.url(&["Ada Lovelace", "7"]).unwrap(),
+			"/users/Ada%20Lovelace/posts/7"
+		);
+		assert_eq!(
+			pattern.url(&["x"]).unwrap_err(),
+			"`/users/:name/posts/{id}` takes 2 path parameters, got 1"
+		);
+		assert_eq!(RoutePattern::parse("/").url::<&str>(&[]).unwrap(), "/");
+		assert_eq!(
+			RoutePattern::parse("/files/*").url::<&str>(&[]).unwrap(),
+			"/files"
+		);
+
+		let url = pattern.url(&["ä/b?", "1"]).unwrap();
+		let matched = pattern.match_path(&url).unwrap();
+		assert_eq!(matched.params["name"], "ä/b?");
+	}
+
+	#[test]
+	fn route_table_checks_url_calls() {
+		let mut routes = RouteTable::new();
+		routes
+			.scan_source(
+				r#"
+				#[route("/todos/:id", view)]
+				async fn todo(ctx: &Ctx<App>, id: u32) -> View { todo!() }
+
+				mod status {
+					#[wgui::partial("/devices/:peer/status")]
+					fn device_status(ctx: &Ctx<App>, peer: String) -> View { todo!() }
+				}
+
+				fn helper() {}
+				"#,
+			)
+			.unwrap();
+		assert_eq!(routes.get("todo").unwrap().raw(), "/todos/:id");
+		assert_eq!(
+			routes.get("device_status").unwrap().raw(),
+			"/devices/:peer/status"
+		);
+		assert_eq!(
+			routes.get("status::device_status").unwrap().raw(),
+			"/devices/:peer/status"
+		);
+		assert!(routes.get("helper").is_err());
+
+		let source = r#"
+<VStack>
+	<Link href={url("todo", state.id)} />
+	<Link href={url("todo")} />
+	<Link href={url("todos", 1)} />
+</VStack>
+"#;
+		let messages = check_urls(source, &routes)
+			.into_iter()
+			.map(|diag| diag.message)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			messages,
+			[
+				"route \"todo\": `/todos/:id` takes 1 path parameter, got 0",
+				"unknown route \"todos\"",
+			]
+		);
+	}
+
+	#[test]
+	fn route_table_reports_ambiguous_names() {
+		let mut routes = RouteTable::new();
+		let source = r#"
+			#[route("/", view)]
+			fn index(ctx: &Ctx<App>) -> View { todo!() }
+		"#;
+		routes.scan_module("todos", source).unwrap();
+		routes
+			.scan_module("users", &source.replace("\"/\"", "\"/users\""))
+			.unwrap();
+		assert_eq!(
+			routes.get("index").unwrap_err(),
+			"route \"index\" is ambiguous: qualify it as one of todos::index, users::index"
+		);
+		assert_eq!(routes.get("users::index").unwrap().raw(), "/users");
+		assert_eq!(
+			file_module(Path::new("src"), Path::new("src/todos/mod.rs")),
+			"todos"
+		);
+		assert_eq!(
+			file_module(Path::new("src"), Path::new("src/todos/api.rs")),
+			"todos::api"
+		);
+		assert_eq!(file_module(Path::new("src"), Path::new("src/main.rs")), "");
+
+		let diags = check_urls(r#"<Link href={url("index")} />"#, &routes);
+		assert_eq!(diags.len(), 1);
+		assert!(
+			diags[0].message.contains("ambiguous"),
+			"{}",
+			diags[0].message
+		);
+	}
+
 	#[test]
 	fn route_pattern_is_exact_without_wildcard() {
 		assert!(RoutePattern::parse("/posts/:id")
@@ -0,0 +1,613 @@
+//! Shapes of the Rust state types WUI templates render.
+//!
+//! A [`StateSchema`] maps model names to their fields. `WuiModel` and
+//! `WguiModel` derive [`WuiSchema`], which registers a model under its full
+//! path along with the models its fields use; debug builds check each
+//! `#[wgui_controller]` runtime template against it when it first loads.
+//!
+//! With the `scan` feature, [`StateSchema::scan_dir`] collects the same
+//! shapes from the structs deriving a model in the crate sources, by type
+//! name, which is what `#[wgui_controller]`, `#[route(.., view)]` and
+//! `wgui check` use to type-check templates before the program runs.
+
+use std::{
+	borrow::Cow,
+	collections::{HashMap, VecDeque},
+	marker::PhantomData,
+	rc::Rc,
+	sync::Arc,
+};
+#[cfg(any(test, feature = "scan"))]
+use std::{
+	collections::BTreeMap,
+	fs, io,
+	path::{Path, PathBuf},
+	sync::Mutex,
+	time::SystemTime,
+};
+
+#[cfg(any(test, feature = "scan"))]
+use crate::routing::{file_module, qualify};
+
+/// What a state value converts to, following `WuiValueConvert`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldType {
+	String,
+	Number,
+	Bool,
+	/// Maps, `serde_json::Value` and types the schema knows nothing about.
+	Any,
+	Optional(Box<FieldType>),
+	List(Box<FieldType>),
+	/// A named model, looked up in the [`StateSchema`].
+	Model(String),
+	/// An anonymous object, such as the state built by `view!({ .. })`.
+	Object(Vec<(String, FieldType)>),
+}
+
+#[cfg(any(test, feature = "scan"))]
+impl FieldType {
+	/// Map a Rust type, as written in source, to the value it renders as.
+	pub fn from_rust_type(ty: &str) -> Self {
+		syn::parse_str::<syn::Type>(ty)
+			.map(|ty| Self::from_syn(&ty))
+			.unwrap_or(Self::Any)
+	}
+
+	pub fn from_syn(ty: &syn::Type) -> Self {
+		match ty {
+			syn::Type::Reference(reference) => Self::from_syn(&reference.elem),
+			syn::Type::Paren(paren) => Self::from_syn(&paren.elem),
+			syn::Type::Group(group) => Self::from_syn(&group.elem),
+			syn::Type::Slice(slice) => Self::List(Box::new(Self::from_syn(&slice.elem))),
+			syn::Type::Array(array) => Self::List(Box::new(Self::from_syn(&array.elem))),
+			syn::Type::Path(path) if path.qself.is_none() => {
+				let Some(segment) = path.path.segments.last() else {
+					return Self::Any;
+				};
+				let first_arg = || match &segment.arguments {
+					syn::PathArguments::AngleBracketed(args) => {
+						args.args.iter().find_map(|arg| match arg {
+							syn::GenericArgument::Type(ty) => Some(Self::from_syn(ty)),
+							_ => None,
+						})
+					}
+					_ => None,
+				};
+				match segment.ident.to_string().as_str() {
+					"String" | "str" | "char" | "Cow" => Self::String,
+					"bool" => Self::Bool,
+					"u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32"
+					| "i64" | "i128" | "isize" | "f32" | "f64" => Self::Number,
+					"Option" => Self::Optional(Box::new(first_arg().unwrap_or(Self::Any))),
+					"Vec" | "VecDeque" => Self::List(Box::new(first_arg().unwrap_or(Self::Any))),
+					"Box" | "Rc" | "Arc" => first_arg().unwrap_or(Self::Any),
+					"HashMap" | "BTreeMap" | "Value" | "WuiValue" => Self::Any,
+					name => Self::Model(name.to_string()),
+				}
+			}
+			_ => Self::Any,
+		}
+	}
+}
+
+impl FieldType {
+	pub(crate) fn describe(&self) -> String {
+		match self {
+			Self::String => "a string".to_string(),
+			Self::Number => "a number".to_string(),
+			Self::Bool => "a bool".to_string(),
+			Self::Any => "a value".to_string(),
+			Self::Optional(inner) => inner.describe(),
+			Self::List(_) => "a list".to_string(),
+			Self::Model(name) => format!("`{name}`"),
+			Self::Object(_) => "an object".to_string(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelSchema {
+	pub name: String,
+	/// Module path of the type, e.g. `todos::model`; empty for the crate root
+	/// or when not known.
+	pub module: String,
+	pub fields: Vec<(String, FieldType)>,
+}
+
+impl ModelSchema {
+	pub fn new<'a>(name: &str, fields: impl IntoIterator<Item = (&'a str, FieldType)>) -> Self {
+		Self {
+			name: name.to_string(),
+			module: String::new(),
+			fields: fields
+				.into_iter()
+				.map(|(field, ty)| (field.to_string(), ty))
+				.collect(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StateSchema {
+	models: HashMap<String, ModelSchema>,
+	/// Names defined in more than one module, with each module. Scanned
+	/// models are named without a path, so these can't be told apart.
+	ambiguous: HashMap<String, Vec<String>>,
+}
+
+impl StateSchema {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn insert(&mut self, model: ModelSchema) {
+		if let Some(existing) = self.models.get(&model.name) {
+			if existing.module != model.module {
+				let modules = self
+					.ambiguous
+					.entry(model.name.clone())
+					.or_insert_with(|| vec![existing.module.clone()]);
+				if !modules.contains(&model.module) {
+					modules.push(model.module.clone());
+				}
+			}
+		}
+		self.models.insert(model.name.clone(), model);
+	}
+
+	/// The model named `name`, unless several modules define one.
+	pub fn model(&self, name: &str) -> Option<&ModelSchema> {
+		if self.ambiguous.contains_key(name) {
+			return None;
+		}
+		self.models.get(name)
+	}
+
+	pub fn contains(&self, name: &str) -> bool {
+		self.models.contains_key(name)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.models.is_empty()
+	}
+
+	/// Add every struct in `source` that derives `WuiModel` or `WguiModel`,
+	/// as items of the crate root.
+	#[cfg(any(test, feature = "scan"))]
+	pub fn scan_source(&mut self, source: &str) -> syn::Result<()> {
+		self.scan_module("", source)
+	}
+
+	/// Like [`StateSchema::scan_source`] for the source of `module`, e.g.
+	/// `"todos::model"`.
+	#[cfg(any(test, feature = "scan"))]
+	pub fn scan_module(&mut self, module: &str, source: &str) -> syn::Result<()> {
+		let file = syn::parse_file(source)?;
+		let mut models = Vec::new();
+		collect_models(module, &file.items, &mut models);
+		for model in models {
+			self.insert(model);
+		}
+		Ok(())
+	}
+
+	/// Scan every `.rs` file below the crate source directory `dir`. Files
+	/// that fail to parse are skipped; the compiler reports those. Each file
+	/// is parsed again only once it changes, so repeated scans (one per
+	/// macro invocation) stay cheap.
+	#[cfg(any(test, feature = "scan"))]
+	pub fn scan_dir(dir: &Path) -> io::Result<Self> {
+		static SCANNED: Mutex<BTreeMap<PathBuf, (SystemTime, Vec<ModelSchema>)>> =
+			Mutex::new(BTreeMap::new());
+
+		let mut schema = Self::new();
+		let mut pending = vec![dir.to_path_buf()];
+		while let Some(current) = pending.pop() {
+			for entry in fs::read_dir(&current)? {
+				let path = entry?.path();
+				if path.is_dir() {
+					pending.push(path);
+					continue;
+				}
+				if path.extension().is_none_or(|ext| ext != "rs") {
+					continue;
+				}
+				let modified = fs::metadata(&path)?.modified()?;
+				let mut scanned = SCANNED.lock().unwrap_or_else(|err| err.into_inner());
+				let models = match scanned.get(&path) {
+					Some((at, models)) if *at == modified => models.clone(),
+					_ => {
+						let source = fs::read_to_string(&path)?;
+						let mut models = Vec::new();
+						if let Ok(file) = syn::parse_file(&source) {
+							collect_models(&file_module(dir, &path), &file.items, &mut models);
+						}
+						scanned.insert(path, (modified, models.clone()));
+						models
+					}
+				};
+				for model in models {
+					schema.insert(model);
+				}
+			}
+		}
+		Ok(schema)
+	}
+
+	/// Follow `segments` from `root`, returning the type they end at.
+	/// `prefix` is the expression text so far, used in error messages.
+	pub fn resolve(
+		&self,
+		root: &FieldType,
+		prefix: &str,
+		segments: &[String],
+	) -> Result<FieldType, String> {
+		let mut current = root.clone();
+		let mut prefix = prefix.to_string();
+		for segment in segments {
+			while let FieldType::Optional(inner) = current {
+				current = *inner;
+			}
+			let fields = match &current {
+				FieldType::Any => return Ok(FieldType::Any),
+				// Same-named models from several modules can't be told apart
+				// by name; the schema `WuiSchema` derives checks those.
+				FieldType::Model(name) => match self.model(name) {
+					Some(model) => &model.fields,
+					None => return Ok(FieldType::Any),
+				},
+				FieldType::Object(fields) => fields,
+				other => {
+					return Err(format!(
+						"`{prefix}` is {}, it has no field `{segment}`",
+						other.describe()
+					))
+				}
+			};
+			let Some((_, ty)) = fields.iter().find(|(name, _)| name == segment) else {
+				let known = fields
+					.iter()
+					.map(|(name, _)| name.as_str())
+					.collect::<Vec<_>>()
+					.join(", ");
+				return Err(format!(
+					"unknown field `{segment}` on `{prefix}`; known fields: {known}"
+				));
+			};
+			current = ty.clone();
+			prefix.push('.');
+			prefix.push_str(segment);
+		}
+		Ok(current)
+	}
+}
+
+/// The shape of a state type. `#[derive(WuiModel)]` and `#[derive(WguiModel)]`
+/// implement it for models, naming them by full path so same-named models
+/// from different modules stay apart.
+pub trait WuiSchema {
+	/// Register the models this type uses in `schema` and return what it
+	/// renders as.
+	fn wui_field_type(schema: &mut StateSchema) -> FieldType;
+}
+
+macro_rules! leaf_schema {
+	($field_type:ident: $($ty:ty),*) => {
+		$(impl WuiSchema for $ty {
+			fn wui_field_type(_schema: &mut StateSchema) -> FieldType {
+				FieldType::$field_type
+			}
+		})*
+	};
+}
+
+leaf_schema!(String: String, str, char, Cow<'_, str>);
+leaf_schema!(Bool: bool);
+leaf_schema!(Number: u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+impl<T: WuiSchema + ?Sized> WuiSchema for &T {
+	fn wui_field_type(schema: &mut StateSchema) -> FieldType {
+		T::wui_field_type(schema)
+	}
+}
+
+impl<T: WuiSchema + ?Sized> WuiSchema for Box<T> {
+	fn wui_field_type(schema: &mut StateSchema) -> FieldType {
+		T::wui_field_type(schema)
+	}
+}
+
+impl<T: WuiSchema + ?Sized> WuiSchema for Rc<T> {
+	fn wui_field_type(schema: &mut StateSchema) -> FieldType {
+		T::wui_field_type(schema)
+	}
+}
+
+impl<T: WuiSchema + ?Sized> WuiSchema for Arc<T> {
+	fn wui_field_type(schema: &mut StateSchema) -> FieldType {
+		T::wui_field_type(schema)
+	}
+}
+
+impl<T: WuiSchema> WuiSchema for Option<T> {
+	fn wui_field_type(schema: &mut StateSchema) -> FieldType {
+		FieldType::Optional(Box::new(T::wui_field_type(schema)))
+	}
+}
+
+impl<T: WuiSchema> WuiSchema for Vec<T> {
+	fn wui_field_type(schema: &mut StateSchema) -> FieldType {
+		FieldType::List(Box::new(T::wui_field_type(schema)))
+	}
+}
+
+impl<T: WuiSchema> WuiSchema for VecDeque<T> {
+	fn wui_field_type(schema: &mut StateSchema) -> FieldType {
+		FieldType::List(Box::new(T::wui_field_type(schema)))
+	}
+}
+
+impl<T: WuiSchema> WuiSchema for [T] {
+	fn wui_field_type(schema: &mut StateSchema) -> FieldType {
+		FieldType::List(Box::new(T::wui_field_type(schema)))
+	}
+}
+
+impl<T: WuiSchema, const N: usize> WuiSchema for [T; N] {
+	fn wui_field_type(schema: &mut StateSchema) -> FieldType {
+		FieldType::List(Box::new(T::wui_field_type(schema)))
+	}
+}
+
+impl StateSchema {
+	/// The schema of `T` and the models it uses, with what `T` renders as.
+	pub fn of<T: WuiSchema + ?Sized>() -> (Self, FieldType) {
+		let mut schema = Self::new();
+		let state = T::wui_field_type(&mut schema);
+		(schema, state)
+	}
+}
+
+/// A derived model's field type, for the derive to pick [`DescribeWuiField`]
+/// when it implements [`WuiSchema`] and [`SkipWuiSchema`], which leaves it
+/// unchecked, otherwise.
+#[doc(hidden)]
+pub struct SchemaProbe<T: ?Sized>(PhantomData<fn() -> Box<T>>);
+
+impl<T: ?Sized> SchemaProbe<T> {
+	#[allow(clippy::new_without_default)]
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+#[doc(hidden)]
+pub trait DescribeWuiField {
+	fn wui_field_type(self, schema: &mut StateSchema) -> FieldType;
+}
+
+impl<T: WuiSchema + ?Sized> DescribeWuiField for SchemaProbe<T> {
+	fn wui_field_type(self, schema: &mut StateSchema) -> FieldType {
+		T::wui_field_type(schema)
+	}
+}
+
+#[doc(hidden)]
+pub trait SkipWuiSchema {
+	fn wui_field_type(self, schema: &mut StateSchema) -> FieldType;
+}
+
+impl<T: ?Sized> SkipWuiSchema for &SchemaProbe<T> {
+	fn wui_field_type(self, _schema: &mut StateSchema) -> FieldType {
+		FieldType::Any
+	}
+}
+
+/// The structs among `items` that derive a model, with nested modules.
+#[cfg(any(test, feature = "scan"))]
+fn collect_models(module: &str, items: &[syn::Item], out: &mut Vec<ModelSchema>) {
+	for item in items {
+		match item {
+			syn::Item::Struct(item) if derives_model(&item.attrs) => {
+				let syn::Fields::Named(fields) = &item.fields else {
+					continue;
+				};
+				out.push(ModelSchema {
+					name: item.ident.to_string(),
+					module: module.to_string(),
+					fields: fields
+						.named
+						.iter()
+						.filter_map(|field| {
+							let ident = field.ident.as_ref()?;
+							Some((ident.to_string(), FieldType::from_syn(&field.ty)))
+						})
+						.collect(),
+				});
+			}
+			syn::Item::Mod(item) => {
+				if let Some((_, items)) = &item.content {
+					collect_models(&qualify(module, &item.ident.to_string()), items, out);
+				}
+			}
+			_ => {}
+		}
+	}
+}
+
+#[cfg(any(test, feature = "scan"))]
+fn derives_model(attrs: &[syn::Attribute]) -> bool {
+	attrs.iter().any(|attr| {
+		if !attr.path().is_ident("derive") {
+			return false;
+		}
+		let mut found = false;
+		let _ =
+			attr.parse_nested_meta(|meta| {
+				if meta.path.segments.last().is_some_and(|segment| {
+					segment.ident == "WuiModel" || segment.ident == "WguiModel"
+				}) {
+					found = true;
+				}
+				Ok(())
+			});
+		found
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn scans_derived_models() {
+		let mut schema = StateSchema::new();
+		schema
+			.scan_source(
+				r#"
+				#[derive(Clone, WuiModel)]
+				struct TodoState {
+					items: Vec<TodoItem>,
+					filter: Option<String>,
+					count: usize,
+				}
+				mod nested {
+					#[derive(wgui::WguiModel)]
+					pub struct TodoItem { pub title: String, pub done: bool }
+				}
+				struct NotAModel { x: u32 }
+				"#,
+			)
+			.unwrap();
+
+		let todo = schema.model("TodoState").unwrap();
+		assert_eq!(
+			todo.fields,
+			vec![
+				(
+					"items".to_string(),
+					FieldType::List(Box::new(FieldType::Model("TodoItem".to_string())))
+				),
+				(
+					"filter".to_string(),
+					FieldType::Optional(Box::new(FieldType::String))
+				),
+				("count".to_string(), FieldType::Number),
+			]
+		);
+		assert!(schema.model("TodoItem").is_some());
+		assert!(schema.model("NotAModel").is_none());
+	}
+
+	#[test]
+	fn resolves_paths_and_explains_failures() {
+		let mut schema = StateSchema::new();
+		schema.insert(ModelSchema::new(
+			"State",
+			[
+				("user", FieldType::from_rust_type("Option<User>")),
+				("title", FieldType::from_rust_type("String")),
+			],
+		));
+		schema.insert(ModelSchema::new(
+			"User",
+			[("name", FieldType::from_rust_type("&'static str"))],
+		));
+		let root = FieldType::Model("State".to_string());
+		let path = |parts: &[&str]| {
+			parts
+				.iter()
+				.map(|part| part.to_string())
+				.collect::<Vec<_>>()
+		};
+
+		assert_eq!(
+			schema.resolve(&root, "state", &path(&["user", "name"])),
+			Ok(FieldType::String)
+		);
+		assert_eq!(
+			schema.resolve(&root, "state", &path(&["titel"])),
+			Err("unknown field `titel` on `state`; known fields: user, title".to_string())
+		);
+		assert_eq!(
+			schema.resolve(&root, "state", &path(&["title", "len"])),
+			Err("`state.title` is a string, it has no field `len`".to_string())
+		);
+	}
+
+	#[test]
+	fn leaves_models_defined_in_several_modules_unchecked() {
+		let mut schema = StateSchema::new();
+		let source = r#"
+			#[derive(WuiModel)]
+			struct Item { name: String }
+		"#;
+		schema.scan_module("todos", source).unwrap();
+		schema.scan_module("shop", source).unwrap();
+		schema
+			.scan_source("#[derive(WuiModel)] struct State { item: Item }")
+			.unwrap();
+		let root = FieldType::Model("State".to_string());
+
+		assert!(schema.model("Item").is_none());
+		assert_eq!(
+			schema.resolve(&root, "state", &["item".to_string(), "nmae".to_string()]),
+			Ok(FieldType::Any)
+		);
+	}
+
+	#[test]
+	fn describes_std_types_and_skips_unknown_ones() {
+		#[allow(unused_imports)]
+		use super::{DescribeWuiField as _, SkipWuiSchema as _};
+		struct Opaque;
+
+		let (_, state) = StateSchema::of::<Vec<Option<Box<str>>>>();
+		assert_eq!(
+			state,
+			FieldType::List(Box::new(FieldType::Optional(Box::new(FieldType::String))))
+		);
+		let mut schema = StateSchema::new();
+		assert_eq!(
+			SchemaProbe::<[u8; 4]>::new().wui_field_type(&mut schema),
+			FieldType::List(Box::new(FieldType::Number))
+		);
+		assert_eq!(
+			SchemaProbe::<Opaque>::new().wui_field_type(&mut schema),
+			FieldType::Any
+		);
+	}
+
+	#[test]
+	fn scan_dir_qualifies_models_and_rescans_changed_files() {
+		let suffix = SystemTime::now()
+			.duration_since(SystemTime::UNIX_EPOCH)
+			.unwrap()
+			.as_nanos();
+		let dir = std::env::temp_dir().join(format!("wui_schema_scan_test_{suffix}"));
+		fs::create_dir_all(dir.join("todos")).expect("create temp dir");
+		let model = dir.join("todos/model.rs");
+		fs::write(&model, "#[derive(WuiModel)] struct Todo { title: String }")
+			.expect("write model");
+
+		let schema = StateSchema::scan_dir(&dir).expect("scan");
+		let todo = schema.model("Todo").expect("Todo is scanned");
+		assert_eq!(todo.module, "todos::model");
+		assert_eq!(todo.fields, vec![("title".to_string(), FieldType::String)]);
+
+		fs::write(&model, "#[derive(WuiModel)] struct Todo { done: bool }").expect("rewrite model");
+		let later = SystemTime::now() + std::time::Duration::from_secs(5);
+		fs::File::options()
+			.write(true)
+			.open(&model)
+			.and_then(|file| file.set_modified(later))
+			.expect("touch model");
+
+		let schema = StateSchema::scan_dir(&dir).expect("rescan");
+		assert_eq!(
+			schema.model("Todo").expect("Todo is scanned").fields,
+			vec![("done".to_string(), FieldType::Bool)]
+		);
+	}
+}
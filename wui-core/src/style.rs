@@ -0,0 +1,225 @@
+//! `<Style>` blocks: CSS scoped to one template by a class that the compiler
+//! adds to every widget the template renders.
+//!
+//! Each selector gets the scope class on its last compound selector, so
+//! `.row > Text:hover` becomes `.row > Text:hover.wui-1a2b3c4d`. Rules nested
+//! in `@media`, `@supports`, `@container` and `@layer` are scoped too, other
+//! at-rules such as `@keyframes` are copied as written and `:global(...)`
+//! opts a selector out of scoping.
+
+/// The class scoping the styles of `key`, a template module or component
+/// name. It depends on the CSS too, so edited styles get a new class.
+pub fn scope_class(key: &str, css: &str) -> String {
+	let mut hash: u32 = 0x811c9dc5;
+	for byte in key.bytes().chain([0]).chain(css.bytes()) {
+		hash ^= u32::from(byte);
+		hash = hash.wrapping_mul(0x01000193);
+	}
+	format!("wui-{hash:08x}")
+}
+
+/// Rewrites `css` so its selectors only match elements with the `scope`
+/// class. Comments are dropped.
+pub fn scope_css(css: &str, scope: &str) -> Result<String, String> {
+	let css = strip_comments(css)?;
+	let mut out = String::new();
+	scope_rules(&css, scope, &mut out)?;
+	Ok(out)
+}
+
+fn scope_rules(src: &str, scope: &str, out: &mut String) -> Result<(), String> {
+	let mut rest = src.trim_start();
+	while !rest.is_empty() {
+		let Some(end) = find_top_level(rest, &['{', ';', '}']) else {
+			return Err(format!("expected {{ after {}", rest.trim()));
+		};
+		let prelude = rest[..end].trim();
+		match rest.as_bytes()[end] {
+			b'}' => return Err("unexpected }".to_string()),
+			b';' if prelude.starts_with('@') => {
+				out.push_str(prelude);
+				out.push_str(";\n");
+				rest = rest[end + 1..].trim_start();
+				continue;
+			}
+			b';' => return Err(format!("expected {{ after {prelude}")),
+			_ => {}
+		}
+		let close = matching_brace(rest, end)?;
+		let body = rest[end + 1..close].trim();
+		if let Some(at_rule) = prelude.strip_prefix('@') {
+			let name = at_rule
+				.split(|ch: char| !ch.is_ascii_alphanumeric() && ch != '-')
+				.next()
+				.unwrap_or_default();
+			if matches!(name, "media" | "supports" | "container" | "layer") {
+				out.push_str(prelude);
+				out.push_str(" {\n");
+				scope_rules(body, scope, out)?;
+				out.push_str("}\n");
+			} else {
+				out.push_str(prelude);
+				out.push_str(" { ");
+				out.push_str(body);
+				out.push_str(" }\n");
+			}
+		} else {
+			let selectors = split_top_level(prelude, ',')
+				.into_iter()
+				.map(|selector| scope_selector(selector.trim(), scope))
+				.collect::<Result<Vec<_>, _>>()?;
+			out.push_str(&selectors.join(", "));
+			out.push_str(" { ");
+			out.push_str(body);
+			out.push_str(" }\n");
+		}
+		rest = rest[close + 1..].trim_start();
+	}
+	Ok(())
+}
+
+fn scope_selector(selector: &str, scope: &str) -> Result<String, String> {
+	if selector.is_empty() {
+		return Err("empty selector".to_string());
+	}
+	if let Some(inner) = selector
+		.strip_prefix(":global(")
+		.and_then(|rest| rest.strip_suffix(')'))
+	{
+		return Ok(inner.trim().to_string());
+	}
+	// The last compound selector starts after the last combinator.
+	let mut depth = 0usize;
+	let mut last = 0;
+	for (index, ch) in selector.char_indices() {
+		match ch {
+			'(' | '[' => depth += 1,
+			')' | ']' => depth = depth.saturating_sub(1),
+			' ' | '\t' | '\n' | '\r' | '>' | '+' | '~' if depth == 0 => last = index + 1,
+			_ => {}
+		}
+	}
+	let insert = last + selector[last..].find("::").unwrap_or(selector.len() - last);
+	Ok(format!(
+		"{}.{scope}{}",
+		&selector[..insert],
+		&selector[insert..]
+	))
+}
+
+fn strip_comments(css: &str) -> Result<String, String> {
+	let mut out = String::with_capacity(css.len());
+	let mut rest = css;
+	while let Some(start) = find_top_level(rest, &['/']) {
+		if !rest[start..].starts_with("/*") {
+			out.push_str(&rest[..start + 1]);
+			rest = &rest[start + 1..];
+			continue;
+		}
+		out.push_str(&rest[..start]);
+		let Some(len) = rest[start + 2..].find("*/") else {
+			return Err("unterminated comment".to_string());
+		};
+		rest = &rest[start + 2 + len + 2..];
+	}
+	out.push_str(rest);
+	Ok(out)
+}
+
+/// The first of `targets` outside strings, parentheses and brackets.
+fn find_top_level(src: &str, targets: &[char]) -> Option<usize> {
+	let mut depth = 0usize;
+	let mut quote = None;
+	let mut escaped = false;
+	for (index, ch) in src.char_indices() {
+		if let Some(open) = quote {
+			match ch {
+				_ if escaped => escaped = false,
+				'\\' => escaped = true,
+				_ if ch == open => quote = None,
+				_ => {}
+			}
+			continue;
+		}
+		match ch {
+			'"' | '\'' => quote = Some(ch),
+			'(' | '[' => depth += 1,
+			')' | ']' => depth = depth.saturating_sub(1),
+			_ if depth == 0 && targets.contains(&ch) => return Some(index),
+			_ => {}
+		}
+	}
+	None
+}
+
+fn matching_brace(src: &str, open: usize) -> Result<usize, String> {
+	let mut depth = 0usize;
+	let mut offset = open;
+	while let Some(index) = find_top_level(&src[offset..], &['{', '}']) {
+		let index = offset + index;
+		if src.as_bytes()[index] == b'{' {
+			depth += 1;
+		} else {
+			depth -= 1;
+			if depth == 0 {
+				return Ok(index);
+			}
+		}
+		offset = index + 1;
+	}
+	Err("missing }".to_string())
+}
+
+fn split_top_level(src: &str, separator: char) -> Vec<&str> {
+	let mut parts = Vec::new();
+	let mut rest = src;
+	while let Some(index) = find_top_level(rest, &[separator]) {
+		parts.push(&rest[..index]);
+		rest = &rest[index + 1..];
+	}
+	parts.push(rest);
+	parts
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn scopes_the_last_compound_of_each_selector() {
+		let css = ".row > Text:hover, button::before { color: red; }\n/* note */\na[href=\"x y\"] { margin: 0 }";
+		assert_eq!(
+			scope_css(css, "s").unwrap(),
+			".row > Text:hover.s, button.s::before { color: red; }\na[href=\"x y\"].s { margin: 0 }\n"
+		);
+	}
+
+	#[test]
+	fn recurses_into_conditional_at_rules_only() {
+		let css = "@import url(\"a.css\");\n@media (max-width: 600px) { .a { b: c } }\n@keyframes spin { from { x: 0 } to { x: 1 } }\n:global(body) { margin: 0 }";
+		assert_eq!(
+			scope_css(css, "s").unwrap(),
+			"@import url(\"a.css\");\n@media (max-width: 600px) {\n.a.s { b: c }\n}\n@keyframes spin { from { x: 0 } to { x: 1 } }\nbody { margin: 0 }\n"
+		);
+	}
+
+	#[test]
+	fn reports_malformed_css() {
+		assert_eq!(scope_css(".a { b: c", "s").unwrap_err(), "missing }");
+		assert_eq!(scope_css(".a }", "s").unwrap_err(), "unexpected }");
+		assert_eq!(
+			scope_css("/* open", "s").unwrap_err(),
+			"unterminated comment"
+		);
+		assert_eq!(scope_css(", .a { }", "s").unwrap_err(), "empty selector");
+	}
+
+	#[test]
+	fn scope_class_depends_on_key_and_css() {
+		let class = scope_class("page", ".a {}");
+		assert!(class.starts_with("wui-") && class.len() == 12);
+		assert_eq!(class, scope_class("page", ".a {}"));
+		assert_ne!(class, scope_class("other", ".a {}"));
+		assert_ne!(class, scope_class("page", ".b {}"));
+	}
+}
@@ -1,5 +1,5 @@
 use proc_macro::TokenStream;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, ToTokens};
 use std::path::{Path, PathBuf};
 use syn::{
 	parse::{Parse, ParseStream},
@@ -86,6 +86,13 @@ fn derive_wui_value_convert(input: TokenStream, label: &str) -> TokenStream {
 			(#key.to_string(), wgui::wui::runtime::WuiValueConvert::to_wui_value(&self.#ident))
 		}
 	});
+	let visit_arms = named.iter().map(|field| {
+		let ident = field.ident.as_ref().unwrap();
+		let key = ident.to_string();
+		quote! {
+			#key => visit(&self.#ident)
+		}
+	});
 	let schema_fields = named.iter().map(|field| {
 		let ident = field.ident.as_ref().unwrap();
 		let key = ident.to_string();
@@ -98,6 +105,24 @@ fn derive_wui_value_convert(input: TokenStream, label: &str) -> TokenStream {
 		}
 	});
 
+	let setter_arms = named.iter().map(|field| {
+		let ident = field.ident.as_ref().unwrap();
+		let key = ident.to_string();
+		quote! {
+			#key => wgui::wui::runtime::SetterProbe(&mut self.#ident).set_wui_field(rest, value)
+		}
+	});
+	let wui_schema_fields = named.iter().map(|field| {
+		let key = field.ident.as_ref().unwrap().to_string();
+		let ty = &field.ty;
+		quote! {
+			(
+				#key.to_string(),
+				wgui::wui::schema::SchemaProbe::<#ty>::new().wui_field_type(schema),
+			)
+		}
+	});
+
 	let expanded = quote! {
 		impl wgui::wui::runtime::WuiValueConvert for #name {
 			fn to_wui_value(&self) -> wgui::wui::runtime::WuiValue {
@@ -105,6 +130,54 @@ fn derive_wui_value_convert(input: TokenStream, label: &str) -> TokenStream {
 					#(#entries),*
 				])
 			}
+
+			fn visit_wui_field(
+				&self,
+				name: &str,
+				visit: &mut dyn FnMut(&dyn wgui::wui::runtime::WuiValueProvider),
+			) {
+				match name {
+					#(#visit_arms,)*
+					_ => {}
+				}
+			}
+		}
+
+		impl wgui::wui::runtime::WuiValueSetter for #name {
+			fn set_wui_value(
+				&mut self,
+				path: &[::std::string::String],
+				value: wgui::wui::runtime::WuiValue,
+			) -> bool {
+				#[allow(unused_imports)]
+				use wgui::wui::runtime::{SetWuiField as _, SkipWuiField as _};
+				let ::std::option::Option::Some((first, rest)) = path.split_first() else {
+					return false;
+				};
+				match first.as_str() {
+					#(#setter_arms,)*
+					_ => false,
+				}
+			}
+		}
+
+		impl wgui::wui::schema::WuiSchema for #name {
+			fn wui_field_type(
+				schema: &mut wgui::wui::schema::StateSchema,
+			) -> wgui::wui::schema::FieldType {
+				#[allow(unused_imports)]
+				use wgui::wui::schema::{DescribeWuiField as _, SkipWuiSchema as _};
+				let name = concat!(module_path!(), "::", stringify!(#name));
+				if !schema.contains(name) {
+					let mut model = wgui::wui::schema::ModelSchema::new(name, []);
+					model.module = module_path!().to_string();
+					// Registered empty first so recursive models terminate.
+					schema.insert(model.clone());
+					model.fields = vec![#(#wui_schema_fields),*];
+					schema.insert(model);
+				}
+				wgui::wui::schema::FieldType::Model(name.to_string())
+			}
 		}
 
 		impl wgui::wui::runtime::WdbModel for #name {
@@ -142,6 +215,9 @@ enum TemplateMode {
 	Auto,
 	Runtime,
 	Compiled,
+	/// Renders through Rust generated from the template, inlined against the
+	/// concrete model type; the embedded template only decodes events.
+	Codegen,
 }
 
 impl Parse for WguiControllerArgs {
@@ -158,6 +234,7 @@ impl Parse for WguiControllerArgs {
 				args.mode = match value.value().as_str() {
 					"runtime" => TemplateMode::Runtime,
 					"compiled" => TemplateMode::Compiled,
+					"codegen" => TemplateMode::Codegen,
 					"auto" => TemplateMode::Auto,
 					other => {
 						return Err(syn::Error::new_spanned(
@@ -186,6 +263,7 @@ enum HandlerArg {
 	U32,
 	I32,
 	U32I32,
+	U32String,
 	String,
 	Json,
 }
@@ -243,6 +321,7 @@ fn expand_wgui_controller(
 	let mut handlers = Vec::new();
 	let mut fallback_event_handler: Option<FallbackEventHandler> = None;
 	let mut process_method: Option<syn::Ident> = None;
+	let mut binding_method: Option<syn::Ident> = None;
 	let mut http_handlers = Vec::new();
 
 	for item in &mut impl_block.items {
@@ -286,38 +365,45 @@ fn expand_wgui_controller(
 			.count();
 
 		match (&recv.reference, &recv.mutability) {
-			(Some(_), None) => {
-				if input_count == 0 {
-					if method.sig.asyncness.is_some() {
+			(Some(_), None) if input_count == 0 => {
+				if method.sig.asyncness.is_some() {
+					continue;
+				}
+				if let ReturnType::Type(_, ty) = &method.sig.output {
+					if matches!(**ty, Type::Tuple(_)) {
 						continue;
 					}
-					if let ReturnType::Type(_, ty) = &method.sig.output {
-						if matches!(**ty, Type::Tuple(_)) {
-							continue;
+					if method.sig.ident == "title" {
+						if title_method.is_some() {
+							return Err(syn::Error::new_spanned(
+								&method.sig.ident,
+								"wgui_controller allows only one title method",
+							));
 						}
-						if method.sig.ident == "title" {
-							if title_method.is_some() {
-								return Err(syn::Error::new_spanned(
-									&method.sig.ident,
-									"wgui_controller allows only one title method",
-								));
-							}
-							title_method =
-								Some((method.sig.ident.clone(), title_return_from_type(ty)?));
-						} else if method.sig.ident == "state" {
-							if state_method.is_some() {
-								return Err(syn::Error::new_spanned(
-									&method.sig.ident,
-									"wgui_controller allows only one state method",
-								));
-							}
-							state_method = Some((method.sig.ident.clone(), (**ty).clone()));
-						} else {
-							fallback_model_methods.push((method.sig.ident.clone(), (**ty).clone()));
+						title_method =
+							Some((method.sig.ident.clone(), title_return_from_type(ty)?));
+					} else if method.sig.ident == "state" {
+						if state_method.is_some() {
+							return Err(syn::Error::new_spanned(
+								&method.sig.ident,
+								"wgui_controller allows only one state method",
+							));
 						}
+						state_method = Some((method.sig.ident.clone(), (**ty).clone()));
+					} else {
+						fallback_model_methods.push((method.sig.ident.clone(), (**ty).clone()));
 					}
 				}
 			}
+			(Some(_), Some(_)) if input_count == 0 && returns_mut_ref(&method.sig.output) => {
+				if binding_method.is_some() {
+					return Err(syn::Error::new_spanned(
+						&method.sig.ident,
+						"wgui_controller allows only one &mut self method returning &mut state",
+					));
+				}
+				binding_method = Some(method.sig.ident.clone());
+			}
 			(Some(_), Some(_)) => {
 				let arg_type = method.sig.inputs.iter().find_map(|arg| match arg {
 					FnArg::Typed(pat) => Some(&*pat.ty),
@@ -410,16 +496,80 @@ fn expand_wgui_controller(
 	let template_fn = format_ident!("__wgui_template_for_{}", controller_ident);
 	let action_fn = format_ident!("__wgui_action_name_for_{}", controller_ident);
 	let module_name_fn = format_ident!("__wgui_module_name_for_{}", controller_ident);
-	let template_impl = if direct_item_render {
-		quote! {}
+	if !direct_item_render {
+		check_controller_template_state(
+			&args,
+			&controller_ident,
+			&module_name,
+			&model_type_ident,
+			binding_method.is_some(),
+		)?;
+	}
+	let compiled = if direct_item_render || args.mode == TemplateMode::Runtime {
+		None
 	} else {
-		template_impl_tokens(
+		Some(read_compiled_template(
 			&args,
 			&controller_ident.to_string(),
 			&module_name,
+		)?)
+	};
+	let template_impl = match &compiled {
+		Some(compiled) => template_impl_tokens(
+			args.mode,
+			compiled,
 			&module_name_fn,
 			&template_fn,
-		)?
+			&model_type,
+		),
+		None if direct_item_render => quote! {},
+		None => runtime_template_impl(&module_name_fn, &template_fn, &model_type, None),
+	};
+	let render_impl = match compiled
+		.as_ref()
+		.filter(|_| args.mode == TemplateMode::Codegen)
+	{
+		Some(compiled) => {
+			let block = compiled_render_tokens(compiled)?;
+			quote! {
+				fn render(&self) -> ::wgui::Item {
+					self.render_with_path("")
+				}
+
+				fn render_with_path(&self, path: &str) -> ::wgui::Item {
+					let model = self.#model_method_ident();
+					let state = &model;
+					let __path: &str = path;
+					#block
+				}
+
+				fn render_with_route(
+					&self,
+					route: &::wgui::wui::runtime::RouteContext,
+				) -> ::wgui::Item {
+					self.render_with_path(&route.path)
+				}
+			}
+		}
+		None => quote! {
+			fn render(&self) -> ::wgui::Item {
+				let model = self.#model_method_ident();
+				#template_fn().render(&model)
+			}
+
+			fn render_with_path(&self, path: &str) -> ::wgui::Item {
+				let model = self.#model_method_ident();
+				#template_fn().render_with_path(&model, path)
+			}
+
+			fn render_with_route(
+				&self,
+				route: &::wgui::wui::runtime::RouteContext,
+			) -> ::wgui::Item {
+				let model = self.#model_method_ident();
+				#template_fn().render_with_route(&model, route)
+			}
+		},
 	};
 	let title_impl = title_method.map(|(ident, return_type)| match return_type {
 		TitleReturn::String => quote! {
@@ -496,6 +646,18 @@ fn expand_wgui_controller(
 				quote! { #name => { self.#ident(arg, value); true } }
 			}
 		});
+	let u32_string_arms = handlers
+		.iter()
+		.filter(|handler| matches!(handler.arg, HandlerArg::U32String))
+		.map(|handler| {
+			let ident = &handler.ident;
+			let name = ident.to_string();
+			if handler.is_async {
+				quote! { #name => { self.#ident(arg, value).await; true } }
+			} else {
+				quote! { #name => { self.#ident(arg, value); true } }
+			}
+		});
 	let string_arms = handlers
 		.iter()
 		.filter(|handler| matches!(handler.arg, HandlerArg::String))
@@ -524,6 +686,20 @@ fn expand_wgui_controller(
 		})
 		.collect::<Vec<_>>();
 	let json_arms_ref = &json_arms;
+	let binding_apply = match &binding_method {
+		Some(ident) => quote! {
+			::wgui::wui::runtime::apply_binding(self.#ident(), &path, write)
+		},
+		None => {
+			let controller_name = controller_ident.to_string();
+			quote! {
+				{
+					let _ = write;
+					::wgui::wui::runtime::unbound_binding(#controller_name, &path)
+				}
+			}
+		}
+	};
 	let fallback_decode = if let Some(handler) = &fallback_event_handler {
 		let ident = &handler.ident;
 		if handler.is_async {
@@ -651,23 +827,7 @@ fn expand_wgui_controller(
 
 	#[::wgui::wui::runtime::async_trait]
 	impl ::wgui::wui::runtime::WuiController for #controller_ident {
-		fn render(&self) -> ::wgui::Item {
-			let model = self.#model_method_ident();
-			#template_fn().render(&model)
-		}
-
-		fn render_with_path(&self, path: &str) -> ::wgui::Item {
-			let model = self.#model_method_ident();
-			#template_fn().render_with_path(&model, path)
-		}
-
-		fn render_with_route(
-			&self,
-			route: &::wgui::wui::runtime::RouteContext,
-		) -> ::wgui::Item {
-			let model = self.#model_method_ident();
-			#template_fn().render_with_route(&model, route)
-		}
+		#render_impl
 
 		#title_impl
 		#http_impl
@@ -677,6 +837,10 @@ fn expand_wgui_controller(
 			#template_fn().title_for_path(path)
 		}
 
+		fn wui_template(&self) -> ::std::option::Option<&'static ::wgui::wui::runtime::Template> {
+			::std::option::Option::Some(#template_fn())
+		}
+
 		async fn handle(&mut self, event: &::wgui::ClientEvent) -> bool {
 			let Some(action) = #template_fn().decode(event) else {
 				#fallback_decode
@@ -724,6 +888,21 @@ fn expand_wgui_controller(
 							_ => false,
 						}
 					}
+					::wgui::wui::runtime::RuntimeAction::KeyDown { ref name, arg, key: value }
+					| ::wgui::wui::runtime::RuntimeAction::KeyUp { ref name, arg, key: value } => {
+						let action_name = #action_fn(name);
+						if let Some(arg) = arg {
+							match action_name.as_str() {
+								#(#u32_string_arms,)*
+								_ => false,
+							}
+						} else {
+							match action_name.as_str() {
+								#(#string_arms_ref,)*
+								_ => false,
+							}
+						}
+					}
 					::wgui::wui::runtime::RuntimeAction::Custom { ref name, payload, .. } => {
 						let action_name = #action_fn(name);
 						match action_name.as_str() {
@@ -731,6 +910,7 @@ fn expand_wgui_controller(
 							_ => false,
 						}
 					}
+					::wgui::wui::runtime::RuntimeAction::Bind { path, write } => #binding_apply,
 				}
 			}
 		}
@@ -883,46 +1063,46 @@ fn http_impl_tokens(handlers: &[HttpHandlerMethod]) -> proc_macro2::TokenStream
 }
 
 fn template_impl_tokens(
-	args: &WguiControllerArgs,
-	controller_name: &str,
-	fallback_module_name: &str,
+	mode: TemplateMode,
+	compiled: &CompiledTemplate,
 	module_name_fn: &proc_macro2::Ident,
 	template_fn: &proc_macro2::Ident,
-) -> syn::Result<proc_macro2::TokenStream> {
-	let runtime_impl = runtime_template_impl(module_name_fn, template_fn, None);
-	match args.mode {
-		TemplateMode::Runtime => Ok(runtime_impl),
-		TemplateMode::Compiled => compiled_template_impl(
-			args,
-			controller_name,
-			fallback_module_name,
-			template_fn,
-			None,
-		),
+	model_type: &Type,
+) -> proc_macro2::TokenStream {
+	match mode {
+		TemplateMode::Runtime => {
+			runtime_template_impl(module_name_fn, template_fn, model_type, None)
+		}
+		TemplateMode::Compiled | TemplateMode::Codegen => {
+			compiled_template_impl(compiled, template_fn, None)
+		}
 		TemplateMode::Auto => {
 			let runtime_impl = runtime_template_impl(
 				module_name_fn,
 				template_fn,
+				model_type,
 				Some(quote! { #[cfg(debug_assertions)] }),
 			);
 			let compiled_impl = compiled_template_impl(
-				args,
-				controller_name,
-				fallback_module_name,
+				compiled,
 				template_fn,
 				Some(quote! { #[cfg(not(debug_assertions))] }),
-			)?;
-			Ok(quote! {
+			);
+			quote! {
 				#runtime_impl
 				#compiled_impl
-			})
+			}
 		}
 	}
 }
 
+/// Loads the template on first use. Debug builds also check it against the
+/// schema the model's `WuiModel` derive describes, which sees the models
+/// the compile-time source scan can't.
 fn runtime_template_impl(
 	module_name_fn: &proc_macro2::Ident,
 	template_fn: &proc_macro2::Ident,
+	model_type: &Type,
 	cfg_attr: Option<proc_macro2::TokenStream>,
 ) -> proc_macro2::TokenStream {
 	let cfg_attr = cfg_attr.unwrap_or_default();
@@ -930,45 +1110,56 @@ fn runtime_template_impl(
 		#cfg_attr
 		#[allow(non_snake_case)]
 		fn #template_fn() -> &'static ::wgui::wui::runtime::Template {
-			static TEMPLATE: ::std::sync::OnceLock<::wgui::wui::runtime::Template> = ::std::sync::OnceLock::new();
-			TEMPLATE.get_or_init(|| {
-				let base_dir = ::std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/wui"));
-				let candidates = #module_name_fn();
-				let mut read_errors = ::std::vec::Vec::new();
-				for module_name in candidates {
-					let source_path = base_dir.join(format!("{}.wui", module_name));
-					let source = match ::std::fs::read_to_string(&source_path) {
-						::std::result::Result::Ok(source) => source,
-						::std::result::Result::Err(err) => {
-							read_errors.push(format!("{}: {}", source_path.display(), err));
-							continue;
+			static TEMPLATE: ::wgui::wui::hot_reload::TemplateSlot = ::wgui::wui::hot_reload::TemplateSlot::new();
+			if let ::std::option::Option::Some(template) = TEMPLATE.get() {
+				return template;
+			}
+			let base_dir = ::std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/wui"));
+			let candidates = #module_name_fn();
+			let mut read_errors = ::std::vec::Vec::new();
+			for module_name in candidates {
+				let source_path = base_dir.join(format!("{}.wui", module_name));
+				match TEMPLATE.get_or_load(&source_path, &module_name) {
+					::std::result::Result::Ok(template) => {
+						if cfg!(debug_assertions) {
+							#[allow(unused_imports)]
+							use ::wgui::wui::schema::{DescribeWuiField as _, SkipWuiSchema as _};
+							let mut schema = ::wgui::wui::schema::StateSchema::new();
+							let state = ::wgui::wui::schema::SchemaProbe::<#model_type>::new()
+								.wui_field_type(&mut schema);
+							let diags = template.check_state(&schema, &state);
+							if !diags.is_empty() {
+								panic!("wui template {} doesn't match its state: {:?}", module_name, diags)
+							}
 						}
-					};
-					return ::wgui::wui::runtime::Template::parse_with_dir(&source, &module_name, source_path.parent())
-						.unwrap_or_else(|diags| panic!("failed to parse wui template {}: {:?}", module_name, diags));
+						return template;
+					}
+					::std::result::Result::Err(::wgui::wui::runtime::TemplateLoadError::Io(err)) => {
+						read_errors.push(format!("{}: {}", source_path.display(), err));
+					}
+					::std::result::Result::Err(::wgui::wui::runtime::TemplateLoadError::Diagnostics(diags)) => {
+						panic!("failed to parse wui template {}: {:?}", module_name, diags)
+					}
 				}
-				panic!("failed to read wui template; tried {}", read_errors.join(", "))
-			})
+			}
+			panic!("failed to read wui template; tried {}", read_errors.join(", "))
 		}
 	}
 }
 
 fn compiled_template_impl(
-	args: &WguiControllerArgs,
-	controller_name: &str,
-	fallback_module_name: &str,
+	compiled: &CompiledTemplate,
 	template_fn: &proc_macro2::Ident,
 	cfg_attr: Option<proc_macro2::TokenStream>,
-) -> syn::Result<proc_macro2::TokenStream> {
-	let compiled = read_compiled_template(args, controller_name, fallback_module_name)?;
+) -> proc_macro2::TokenStream {
 	let cfg_attr = cfg_attr.unwrap_or_default();
-	let module_name = compiled.module_name;
-	let root_path = compiled.root_path;
-	let root_source = compiled.root_source;
+	let module_name = &compiled.module_name;
+	let root_path = &compiled.root_path;
+	let root_source = &compiled.root_source;
 	let sources = compiled.sources.iter().map(|(path, source)| {
 		quote! { (#path, #source) }
 	});
-	Ok(quote! {
+	quote! {
 		#cfg_attr
 		#[allow(non_snake_case)]
 		fn #template_fn() -> &'static ::wgui::wui::runtime::Template {
@@ -987,6 +1178,26 @@ fn compiled_template_impl(
 				.unwrap_or_else(|diags| panic!("failed to parse compiled wui template {}: {:?}", #module_name, diags))
 			})
 		}
+	}
+}
+
+/// The generated render of `compiled` as an expression over `state` and
+/// `__path` in scope.
+fn compiled_render_tokens(compiled: &CompiledTemplate) -> syn::Result<TokenStream2> {
+	render_block_tokens(&compiled.root_path, &compiled.render_block)
+}
+
+fn render_block_tokens(root_path: &str, render_block: &str) -> syn::Result<TokenStream2> {
+	let block = syn::parse_str::<syn::Block>(render_block).map_err(|err| {
+		syn::Error::new(
+			proc_macro2::Span::call_site(),
+			format!("{root_path}: generated render does not parse: {err}"),
+		)
+	})?;
+	Ok(quote! {
+		#[allow(unused, clippy::all)]
+		let __item: ::wgui::Item = #block;
+		__item
 	})
 }
 
@@ -995,6 +1206,7 @@ struct CompiledTemplate {
 	root_path: String,
 	root_source: String,
 	sources: Vec<(String, String)>,
+	render_block: String,
 }
 
 fn read_compiled_template(
@@ -1044,6 +1256,7 @@ fn read_compiled_template(
 			root_path: source_path.display().to_string(),
 			root_source: source,
 			sources,
+			render_block: generated.render_block,
 		});
 	}
 	Err(syn::Error::new(
@@ -1150,6 +1363,173 @@ fn normalize_template_path(path: &Path) -> PathBuf {
 	})
 }
 
+/// Type-check the controller's template against its model, as far as the
+/// crate's `WuiModel`/`WguiModel` structs describe it. Templates that are
+/// missing or fail to parse are left to the template loader to report.
+fn check_controller_template_state(
+	args: &WguiControllerArgs,
+	controller_ident: &syn::Ident,
+	fallback_module_name: &str,
+	model_type: &str,
+	has_binding_target: bool,
+) -> syn::Result<()> {
+	let controller_name = controller_ident.to_string();
+	let controller_name = controller_name.as_str();
+	let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR").map(PathBuf::from) else {
+		return Ok(());
+	};
+	let base_dir = manifest_dir.join("wui");
+	let candidates = template_candidates(
+		args.template.as_deref(),
+		&base_dir,
+		controller_name,
+		fallback_module_name,
+	);
+	let Some(module_name) = candidates
+		.into_iter()
+		.find(|module_name| base_dir.join(format!("{module_name}.wui")).is_file())
+	else {
+		return Ok(());
+	};
+	let source_path = base_dir.join(format!("{module_name}.wui"));
+	if !has_binding_target {
+		check_bindings_have_target(controller_ident, &source_path, &module_name)?;
+	}
+	check_template_state(
+		&manifest_dir,
+		&source_path,
+		&module_name,
+		Some(wui_core::schema::FieldType::Model(model_type.to_string())),
+	)
+}
+
+/// `bind:*` props write through a `&mut self` method returning `&mut` state;
+/// without one every write would be dropped.
+fn check_bindings_have_target(
+	controller_ident: &syn::Ident,
+	source_path: &Path,
+	module_name: &str,
+) -> syn::Result<()> {
+	let Ok(source) = std::fs::read_to_string(source_path) else {
+		return Ok(());
+	};
+	let source_path = normalize_template_path(source_path);
+	let Ok(generated) =
+		wui_core::compiler::compile_with_dir(&source, module_name, source_path.parent())
+	else {
+		return Ok(());
+	};
+	match generated
+		.actions
+		.iter()
+		.find_map(|action| action.binding.as_ref())
+	{
+		Some(path) => Err(syn::Error::new_spanned(
+			controller_ident,
+			format!(
+				"{} binds `state.{}`, but {controller_ident} has no `&mut self` method returning `&mut` state to write it to",
+				source_path.display(),
+				path.join(".")
+			),
+		)),
+		None => Ok(()),
+	}
+}
+
+fn check_template_state(
+	manifest_dir: &Path,
+	source_path: &Path,
+	module_name: &str,
+	state: Option<wui_core::schema::FieldType>,
+) -> syn::Result<()> {
+	let Ok(source) = std::fs::read_to_string(source_path) else {
+		return Ok(());
+	};
+	let source_path = normalize_template_path(source_path);
+	if wui_core::compiler::compile_with_dir(&source, module_name, source_path.parent()).is_err() {
+		return Ok(());
+	}
+	let Ok(schema) = wui_core::schema::StateSchema::scan_dir(&manifest_dir.join("src")) else {
+		return Ok(());
+	};
+	wui_core::compiler::check_state(&source, module_name, source_path.parent(), &schema, state)
+		.map_err(|diags| template_diagnostics_error(&source_path, &diags))
+}
+
+/// The state shape built by the `view!({ .. })` calls in a route handler,
+/// merged when there are several. `None` when the handler passes a model
+/// expression instead.
+fn view_state_shape(item_fn: &ItemFn) -> Option<wui_core::schema::FieldType> {
+	let mut objects = Vec::new();
+	collect_view_objects(item_fn.block.to_token_stream(), &mut objects);
+	let mut merged: Option<Vec<(String, wui_core::schema::FieldType)>> = None;
+	for object in objects {
+		let Ok(entries) = syn::parse::Parser::parse2(parse_view_object, object) else {
+			return None;
+		};
+		let fields = view_object_fields(&entries);
+		match &mut merged {
+			Some(merged) => merge_view_fields(merged, fields),
+			None => merged = Some(fields),
+		}
+	}
+	merged.map(wui_core::schema::FieldType::Object)
+}
+
+fn collect_view_objects(tokens: TokenStream2, out: &mut Vec<TokenStream2>) {
+	let tokens = tokens.into_iter().collect::<Vec<_>>();
+	for (index, token) in tokens.iter().enumerate() {
+		let proc_macro2::TokenTree::Group(group) = token else {
+			continue;
+		};
+		let is_view_call = index >= 2
+			&& matches!(&tokens[index - 2], proc_macro2::TokenTree::Ident(ident) if ident == "view")
+			&& matches!(&tokens[index - 1], proc_macro2::TokenTree::Punct(punct) if punct.as_char() == '!');
+		if is_view_call {
+			let mut inner = group.stream().into_iter();
+			if let (Some(proc_macro2::TokenTree::Group(object)), None) =
+				(inner.next(), inner.next())
+			{
+				if object.delimiter() == proc_macro2::Delimiter::Brace {
+					out.push(proc_macro2::TokenTree::Group(object).into());
+					continue;
+				}
+			}
+		}
+		collect_view_objects(group.stream(), out);
+	}
+}
+
+fn view_object_fields(
+	entries: &[(syn::Ident, ViewMacroValue)],
+) -> Vec<(String, wui_core::schema::FieldType)> {
+	entries
+		.iter()
+		.map(|(key, value)| {
+			let ty = match value {
+				ViewMacroValue::Object(entries) => {
+					wui_core::schema::FieldType::Object(view_object_fields(entries))
+				}
+				ViewMacroValue::Expr(_) => wui_core::schema::FieldType::Any,
+			};
+			(key.to_string(), ty)
+		})
+		.collect()
+}
+
+fn merge_view_fields(
+	into: &mut Vec<(String, wui_core::schema::FieldType)>,
+	fields: Vec<(String, wui_core::schema::FieldType)>,
+) {
+	for (name, ty) in fields {
+		match into.iter_mut().find(|(existing, _)| *existing == name) {
+			Some((_, existing)) if *existing != ty => *existing = wui_core::schema::FieldType::Any,
+			Some(_) => {}
+			None => into.push((name, ty)),
+		}
+	}
+}
+
 fn template_diagnostics_error(
 	path: &Path,
 	diags: &[wui_core::diagnostic::Diagnostic],
@@ -1217,12 +1597,10 @@ fn handler_two_args_from_method(method: &syn::ImplItemFn) -> Option<HandlerArg>
 	});
 	let first = types.next()?;
 	let second = types.next()?;
-	if matches!(handler_arg_from_type(first), Some(HandlerArg::U32))
-		&& matches!(handler_arg_from_type(second), Some(HandlerArg::I32))
-	{
-		Some(HandlerArg::U32I32)
-	} else {
-		None
+	match (handler_arg_from_type(first), handler_arg_from_type(second)) {
+		(Some(HandlerArg::U32), Some(HandlerArg::I32)) => Some(HandlerArg::U32I32),
+		(Some(HandlerArg::U32), Some(HandlerArg::String)) => Some(HandlerArg::U32String),
+		_ => None,
 	}
 }
 
@@ -1276,6 +1654,10 @@ fn title_return_from_type(ty: &Type) -> syn::Result<TitleReturn> {
 	}
 }
 
+fn returns_mut_ref(output: &ReturnType) -> bool {
+	matches!(output, ReturnType::Type(_, ty) if matches!(&**ty, Type::Reference(reference) if reference.mutability.is_some()))
+}
+
 fn is_client_event_ref(ty: &Type) -> bool {
 	let Type::Reference(reference) = ty else {
 		return false;
@@ -1450,6 +1832,8 @@ struct RouteArgs {
 	method: RouteMethod,
 	view: bool,
 	template: Option<String>,
+	codegen: bool,
+	ssr_fallback: bool,
 }
 
 #[derive(Default)]
@@ -1465,6 +1849,8 @@ impl Parse for RouteArgs {
 		let mut method = RouteMethod::default();
 		let mut view = false;
 		let mut template = None;
+		let mut codegen = false;
+		let mut ssr_fallback = false;
 		while !input.is_empty() {
 			input.parse::<Token![,]>()?;
 			if input.is_empty() {
@@ -1491,6 +1877,28 @@ impl Parse for RouteArgs {
 			} else if ident == "template" {
 				view = true;
 				template = Some(val.value());
+			} else if ident == "mode" {
+				codegen = match val.value().as_str() {
+					"codegen" => true,
+					"runtime" => false,
+					other => {
+						return Err(syn::Error::new_spanned(
+							val,
+							format!("unsupported route mode {other:?}; use runtime or codegen"),
+						))
+					}
+				};
+			} else if ident == "ssr" {
+				ssr_fallback = match val.value().as_str() {
+					"fallback" => true,
+					"wait" => false,
+					other => {
+						return Err(syn::Error::new_spanned(
+							val,
+							format!("unsupported ssr option {other:?}; use wait or fallback"),
+						))
+					}
+				};
 			} else {
 				return Err(syn::Error::new_spanned(
 					ident,
@@ -1503,11 +1911,17 @@ impl Parse for RouteArgs {
 			method,
 			view,
 			template,
+			codegen,
+			ssr_fallback,
 		})
 	}
 }
 
-fn expand_route(args: RouteArgs, item_fn: ItemFn, handle_kind: &str) -> syn::Result<TokenStream2> {
+fn expand_route(
+	args: RouteArgs,
+	mut item_fn: ItemFn,
+	handle_kind: &str,
+) -> syn::Result<TokenStream2> {
 	let fn_ident = item_fn.sig.ident.clone();
 	let fn_is_async = item_fn.sig.asyncness.is_some();
 	let return_type = match &item_fn.sig.output {
@@ -1523,30 +1937,84 @@ fn expand_route(args: RouteArgs, item_fn: ItemFn, handle_kind: &str) -> syn::Res
 	let route_const_ident = route_const_ident(&fn_ident, handle_kind);
 
 	let path_lit = &args.path;
-	let template_impl = if args.view {
+	if args.codegen && !args.view {
+		return Err(syn::Error::new_spanned(
+			&fn_ident,
+			"mode = \"codegen\" needs a route with a view",
+		));
+	}
+	let template_impl = if args.view && args.codegen {
+		let template_name = args
+			.template
+			.clone()
+			.unwrap_or_else(|| standard_route_template(&args.path));
+		let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+			.map(PathBuf::from)
+			.map_err(|err| syn::Error::new(proc_macro2::Span::call_site(), err))?;
+		let source_path = manifest_dir
+			.join("wui")
+			.join(format!("{template_name}.wui"));
+		check_template_state(&manifest_dir, &source_path, &template_name, None)?;
+		let source = std::fs::read_to_string(&source_path).map_err(|err| {
+			syn::Error::new(
+				proc_macro2::Span::call_site(),
+				format!(
+					"failed to read WUI template {}: {err}",
+					source_path.display()
+				),
+			)
+		})?;
+		let source_path = normalize_template_path(&source_path);
+		let generated =
+			wui_core::compiler::compile_with_dir(&source, &template_name, source_path.parent())
+				.map_err(|diags| template_diagnostics_error(&source_path, &diags))?;
+		let render =
+			render_block_tokens(&source_path.display().to_string(), &generated.render_block)?;
+		let bindings = generated
+			.route_bindings
+			.parse::<TokenStream2>()
+			.map_err(|err| syn::Error::new(proc_macro2::Span::call_site(), err))?;
+		rewrite_codegen_views(&mut item_fn.block, &ctx_ident, &render, &bindings)?;
+		quote! {}
+	} else if args.view {
 		let template_name = args
 			.template
 			.clone()
 			.unwrap_or_else(|| standard_route_template(&args.path));
+		if let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR").map(PathBuf::from) {
+			check_template_state(
+				&manifest_dir,
+				&manifest_dir
+					.join("wui")
+					.join(format!("{template_name}.wui")),
+				&template_name,
+				view_state_shape(&item_fn),
+			)?;
+		}
 		let template_fn = format_ident!("__wgui_template_for_{}", fn_ident);
 		quote! {
 			fn #template_fn() -> &'static wgui::wui::runtime::Template {
-				static TEMPLATE: ::std::sync::OnceLock<wgui::wui::runtime::Template> = ::std::sync::OnceLock::new();
-				TEMPLATE.get_or_init(|| {
-					let source_path = ::std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/wui"))
-						.join(format!("{}.wui", #template_name));
-					let source = ::std::fs::read_to_string(&source_path).unwrap_or_else(|err| {
+				static TEMPLATE: wgui::wui::hot_reload::TemplateSlot = wgui::wui::hot_reload::TemplateSlot::new();
+				if let ::std::option::Option::Some(template) = TEMPLATE.get() {
+					return template;
+				}
+				let source_path = ::std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/wui"))
+					.join(format!("{}.wui", #template_name));
+				match TEMPLATE.get_or_load(&source_path, #template_name) {
+					::std::result::Result::Ok(template) => template,
+					::std::result::Result::Err(wgui::wui::runtime::TemplateLoadError::Io(err)) => {
 						panic!("failed to read WUI template {}: {}", source_path.display(), err)
-					});
-					wgui::wui::runtime::Template::parse_with_dir(&source, #template_name, source_path.parent())
-						.unwrap_or_else(|diags| panic!("failed to parse WUI template {}: {:?}", #template_name, diags))
-				})
+					}
+					::std::result::Result::Err(wgui::wui::runtime::TemplateLoadError::Diagnostics(diags)) => {
+						panic!("failed to parse WUI template {}: {:?}", #template_name, diags)
+					}
+				}
 			}
 		}
 	} else {
 		quote! {}
 	};
-	let template_method = if args.view {
+	let template_method = if args.view && !args.codegen {
 		let template_fn = format_ident!("__wgui_template_for_{}", fn_ident);
 		quote! {
 			fn wui_template(&self) -> ::std::option::Option<&'static wgui::wui::runtime::Template> {
@@ -1556,6 +2024,38 @@ fn expand_route(args: RouteArgs, item_fn: ItemFn, handle_kind: &str) -> syn::Res
 	} else {
 		quote! {}
 	};
+	let ssr_method = if args.ssr_fallback {
+		quote! {
+			fn ssr_loading(&self) -> wgui::wui::route_handler::SsrLoading {
+				wgui::wui::route_handler::SsrLoading::Fallback
+			}
+		}
+	} else {
+		quote! {}
+	};
+	let route_name = fn_ident.to_string();
+	// `url` takes the path params in pattern order, typed like the handler's
+	// arguments.
+	let url_params = path_param_names
+		.iter()
+		.map(|name| {
+			let arg = param_args.iter().find_map(|arg| match arg {
+				RouteArg::Path { ident, ty } if ident == name => Some((ident.clone(), ty)),
+				_ => None,
+			});
+			match arg {
+				Some((ident, ty)) => (ident, quote! { #ty }),
+				None => (
+					format_ident!("{}", name),
+					quote! { impl ::std::fmt::Display },
+				),
+			}
+		})
+		.collect::<Vec<_>>();
+	let url_args = url_params.iter().map(|(ident, ty)| quote! { #ident: #ty });
+	let url_values = url_params
+		.iter()
+		.map(|(ident, _)| quote! { &#ident as &dyn ::std::fmt::Display });
 	let method_arm = match args.method {
 		RouteMethod::Get => quote! { wgui::wui::route_handler::HttpMethod::Get },
 		RouteMethod::Post => quote! { wgui::wui::route_handler::HttpMethod::Post },
@@ -1582,6 +2082,17 @@ fn expand_route(args: RouteArgs, item_fn: ItemFn, handle_kind: &str) -> syn::Res
 				Err(_) => return Box::pin(async { wgui::wui::route_handler::RouteResult::NotFound }),
 			};
 		},
+		RouteArg::Query { ident, ty } => quote! {
+			let #ident: #ty = match <#ty>::from_route(&ctx.route().unwrap_or_default()) {
+				Ok(value) => value,
+				Err(err) => {
+					let message = err.to_string();
+					return Box::pin(async move {
+						wgui::wui::route_handler::RouteResult::BadRequest(message)
+					});
+				}
+			};
+		},
 	});
 
 	let call_arg_idents = param_args.iter().map(|arg| {
@@ -1618,6 +2129,12 @@ fn expand_route(args: RouteArgs, item_fn: ItemFn, handle_kind: &str) -> syn::Res
 
 			#template_method
 
+			#ssr_method
+
+			fn name(&self) -> ::std::option::Option<&str> {
+				::std::option::Option::Some(::std::concat!(::std::module_path!(), "::", #route_name))
+			}
+
 			fn call(
 				self,
 				ctx: ::std::sync::Arc<wgui::wui::runtime::Ctx<#state_type, #db_type>>,
@@ -1635,11 +2152,141 @@ fn expand_route(args: RouteArgs, item_fn: ItemFn, handle_kind: &str) -> syn::Res
 
 		#[allow(non_upper_case_globals)]
 		pub(crate) const #route_const_ident: #marker_ident = #marker_ident;
+
+		#[allow(dead_code)]
+		pub(crate) mod #route_const_ident {
+			#[allow(unused_imports)]
+			use super::*;
+
+			/// This route's path with its parameters filled in, percent-encoded.
+			pub fn url(#(#url_args),*) -> ::std::string::String {
+				wgui::wui::route_handler::fill_url(#path_lit, &[#(#url_values),*])
+			}
+		}
 	};
 
 	Ok(expanded)
 }
 
+/// Replace each `view!(model)` in a codegen route handler with the inlined
+/// render of its template over `model`. Only the wgui macro is rewritten:
+/// `view!`, `wgui::view!` or `::wgui::view!`. The route is read from the
+/// context once, up front, so views inside a `move` future or closure own a
+/// copy rather than borrowing the context.
+fn rewrite_codegen_views(
+	block: &mut syn::Block,
+	ctx_ident: &syn::Ident,
+	render: &TokenStream2,
+	bindings: &TokenStream2,
+) -> syn::Result<()> {
+	let mut rewriter = CodegenViews {
+		render,
+		bindings,
+		rewrote: false,
+		error: None,
+	};
+	syn::visit_mut::VisitMut::visit_block_mut(&mut rewriter, block);
+	if let Some(error) = rewriter.error {
+		return Err(error);
+	}
+	if rewriter.rewrote {
+		block.stmts.insert(
+			0,
+			syn::parse_quote! {
+				let __wgui_route = #ctx_ident.route().unwrap_or_default();
+			},
+		);
+	}
+	Ok(())
+}
+
+struct CodegenViews<'a> {
+	render: &'a TokenStream2,
+	bindings: &'a TokenStream2,
+	rewrote: bool,
+	error: Option<syn::Error>,
+}
+
+impl CodegenViews<'_> {
+	fn is_view(mac: &syn::Macro) -> bool {
+		let segments = mac
+			.path
+			.segments
+			.iter()
+			.map(|segment| segment.ident.to_string())
+			.collect::<Vec<_>>();
+		match segments.as_slice() {
+			[name] => name == "view" && mac.path.leading_colon.is_none(),
+			[krate, name] => krate == "wgui" && name == "view",
+			_ => false,
+		}
+	}
+
+	fn render(&mut self, mac: &syn::Macro) -> syn::Expr {
+		let mut inner = mac.tokens.clone().into_iter();
+		if let (Some(proc_macro2::TokenTree::Group(object)), None) = (inner.next(), inner.next()) {
+			if object.delimiter() == proc_macro2::Delimiter::Brace {
+				self.error.get_or_insert_with(|| {
+					syn::Error::new_spanned(
+						mac,
+						"codegen views render a typed model; pass a model value instead of `view!({ .. })`",
+					)
+				});
+			}
+		}
+		self.rewrote = true;
+		let model = &mac.tokens;
+		let render = self.render;
+		let bindings = self.bindings;
+		syn::parse_quote! {
+			::wgui::View::untitled({
+				let __model = #model;
+				let state = &__model;
+				let __route: &::wgui::wui::runtime::RouteContext = &__wgui_route;
+				let __path: &str = &__route.path;
+				#bindings
+				#render
+			})
+		}
+	}
+
+	/// Visit a `move` future or closure, giving it its own copy of the route
+	/// if it renders a view.
+	fn visit_moving(&mut self, expr: &mut syn::Expr) {
+		let outer = std::mem::replace(&mut self.rewrote, false);
+		syn::visit_mut::visit_expr_mut(self, expr);
+		if self.rewrote {
+			*expr = syn::parse_quote! {{
+				let __wgui_route = __wgui_route.clone();
+				#expr
+			}};
+		}
+		self.rewrote |= outer;
+	}
+}
+
+impl syn::visit_mut::VisitMut for CodegenViews<'_> {
+	fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+		match expr {
+			syn::Expr::Macro(expr_macro) if Self::is_view(&expr_macro.mac) => {
+				*expr = self.render(&expr_macro.mac);
+			}
+			syn::Expr::Async(future) if future.capture.is_some() => self.visit_moving(expr),
+			syn::Expr::Closure(closure) if closure.capture.is_some() => self.visit_moving(expr),
+			_ => syn::visit_mut::visit_expr_mut(self, expr),
+		}
+	}
+
+	fn visit_stmt_mut(&mut self, stmt: &mut syn::Stmt) {
+		match stmt {
+			syn::Stmt::Macro(stmt_macro) if Self::is_view(&stmt_macro.mac) => {
+				*stmt = syn::Stmt::Expr(self.render(&stmt_macro.mac), stmt_macro.semi_token);
+			}
+			_ => syn::visit_mut::visit_stmt_mut(self, stmt),
+		}
+	}
+}
+
 /// Map a GET route to its conventional WUI template below `wui/pages`.
 /// Static collection routes use `index`; an identifier terminal uses `show`;
 /// and a static terminal after an identifier names the action page.
@@ -1772,19 +2419,36 @@ fn extract_ctx_generics(ty: &Type) -> syn::Result<(Type, Type)> {
 enum RouteArg {
 	Path { ident: syn::Ident, ty: Type },
 	Form { ident: syn::Ident, ty: Type },
+	Query { ident: syn::Ident, ty: Type },
 }
 
 impl RouteArg {
 	fn ident(&self) -> &syn::Ident {
 		match self {
-			Self::Path { ident, .. } | Self::Form { ident, .. } => ident,
+			Self::Path { ident, .. } | Self::Form { ident, .. } | Self::Query { ident, .. } => {
+				ident
+			}
 		}
 	}
 }
 
+/// Whether `ty` is `Query<T>` (or a path ending in it), decoded from the
+/// query string.
+fn is_query_type(ty: &Type) -> bool {
+	match ty {
+		Type::Path(path) => path
+			.path
+			.segments
+			.last()
+			.is_some_and(|segment| segment.ident == "Query"),
+		_ => false,
+	}
+}
+
 /// Extract every non-`ctx` argument. Arguments whose names match a `:name`
-/// path segment are decoded from `PathParams`; one remaining argument on a
-/// POST route is decoded from the URL-encoded request body.
+/// path segment are decoded from `PathParams`, and `Query<T>` arguments from
+/// the query string; one remaining argument on a POST route is decoded from
+/// the URL-encoded request body.
 fn extract_param_args(
 	sig: &Signature,
 	path_param_names: &[String],
@@ -1814,6 +2478,8 @@ fn extract_param_args(
 			.any(|name| name == &ident.to_string())
 		{
 			out.push(RouteArg::Path { ident, ty });
+		} else if is_query_type(&ty) {
+			out.push(RouteArg::Query { ident, ty });
 		} else {
 			if !matches!(method, RouteMethod::Post) {
 				return Err(syn::Error::new_spanned(
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use ropey::Rope;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tower_lsp::jsonrpc::Result as LspResult;
@@ -10,6 +11,7 @@ use tracing::info;
 
 use wgui::wui::ast::{AttrValue, Element, Node};
 use wgui::wui::compiler::registry::{schema_for, PropKind, ValueType};
+use wgui::wui::compiler::signature::{signature, type_name, ComponentSignature};
 use wgui::wui::diagnostic::{Diagnostic as WuiDiagnostic, Span};
 
 #[derive(Debug, Clone)]
@@ -60,6 +62,7 @@ impl LanguageServer for Backend {
 			hover_provider: Some(HoverProviderCapability::Simple(true)),
 			definition_provider: Some(OneOf::Left(true)),
 			rename_provider: Some(OneOf::Left(true)),
+			document_formatting_provider: Some(OneOf::Left(true)),
 			..Default::default()
 		};
 
@@ -125,8 +128,9 @@ impl LanguageServer for Backend {
 		let text = self.get_text(&uri).await?;
 		let offset = position_to_offset(&text, position);
 		let actions = collect_actions(&text);
+		let components = component_signatures(&text, base_dir(&uri).as_deref());
 
-		let items = completion_items(&text, offset, &actions);
+		let items = completion_items(&text, offset, &actions, &components);
 		Ok(Some(CompletionResponse::Array(items)))
 	}
 
@@ -135,7 +139,8 @@ impl LanguageServer for Backend {
 		let uri = params.text_document_position_params.text_document.uri;
 		let text = self.get_text(&uri).await?;
 		let offset = position_to_offset(&text, position);
-		let hover = hover_info(&text, offset);
+		let components = component_signatures(&text, base_dir(&uri).as_deref());
+		let hover = hover_info(&text, offset, &components);
 		Ok(hover)
 	}
 
@@ -194,13 +199,31 @@ impl LanguageServer for Backend {
 		}))
 	}
 
+	async fn formatting(
+		&self,
+		params: DocumentFormattingParams,
+	) -> LspResult<Option<Vec<TextEdit>>> {
+		let text = self.get_text(&params.text_document.uri).await?;
+		// Documents that do not parse are left alone; their diagnostics say why.
+		let Ok(formatted) = wgui::wui::format::format(&text) else {
+			return Ok(None);
+		};
+		if formatted == text {
+			return Ok(Some(Vec::new()));
+		}
+		Ok(Some(vec![TextEdit {
+			range: span_to_range(&text, 0, text.len()),
+			new_text: formatted,
+		}]))
+	}
+
 	async fn diagnostic(
 		&self,
 		params: DocumentDiagnosticParams,
 	) -> LspResult<DocumentDiagnosticReportResult> {
 		let uri = params.text_document.uri;
 		let text = self.get_text(&uri).await?;
-		let diagnostics = analyze(&text)
+		let diagnostics = analyze(&text, base_dir(&uri).as_deref())
 			.into_iter()
 			.map(|diag| to_lsp_diagnostic(&text, diag))
 			.collect::<Vec<_>>();
@@ -227,7 +250,7 @@ impl Backend {
 			}
 		};
 
-		let diagnostics = analyze(&text)
+		let diagnostics = analyze(&text, base_dir(uri).as_deref())
 			.into_iter()
 			.map(|diag| to_lsp_diagnostic(&text, diag))
 			.collect::<Vec<_>>();
@@ -246,11 +269,25 @@ impl Backend {
 	}
 }
 
-fn analyze(text: &str) -> Vec<WuiDiagnostic> {
-	let parsed = wgui::wui::parser::Parser::new(text).parse();
-	let mut diags = parsed.diagnostics;
-	let components = std::collections::HashMap::new();
-	let validated = wgui::wui::compiler::validate::validate(&parsed.nodes, &components, &mut diags);
+fn base_dir(uri: &Url) -> Option<PathBuf> {
+	let path = uri.to_file_path().ok()?;
+	path.parent().map(Path::to_path_buf)
+}
+
+fn analyze(text: &str, base_dir: Option<&Path>) -> Vec<WuiDiagnostic> {
+	// Documents on disk resolve their imports, so component call sites are
+	// checked against the imported components' props and slots.
+	let (nodes, components, mut diags) = match base_dir {
+		Some(base_dir) => match wgui::wui::imports::resolve(text, "main", Some(base_dir)) {
+			Ok(resolved) => (resolved.nodes, resolved.components, Vec::new()),
+			Err(diags) => return diags,
+		},
+		None => {
+			let parsed = wgui::wui::parser::Parser::new(text).parse();
+			(parsed.nodes, HashMap::new(), parsed.diagnostics)
+		}
+	};
+	let validated = wgui::wui::compiler::validate::validate(&nodes, &components, &mut diags);
 	if validated.is_none() {
 		return diags;
 	}
@@ -268,7 +305,57 @@ fn to_lsp_diagnostic(text: &str, diag: WuiDiagnostic) -> Diagnostic {
 	}
 }
 
-fn completion_items(text: &str, offset: usize, actions: &[ActionRef]) -> Vec<CompletionItem> {
+/// Signatures of the components `text` imports by name. Unlike [`analyze`]
+/// this tolerates a half-typed document, which is what completion sees.
+fn component_signatures(
+	text: &str,
+	base_dir: Option<&Path>,
+) -> HashMap<String, ComponentSignature> {
+	let mut out = HashMap::new();
+	let Some(base_dir) = base_dir else {
+		return out;
+	};
+	let parsed = wgui::wui::parser::Parser::new(text).parse();
+	let mut pending = parsed.nodes.iter().collect::<Vec<_>>();
+	while let Some(node) = pending.pop() {
+		let Node::Element(el) = node else {
+			continue;
+		};
+		pending.extend(&el.children);
+		if el.name != "Import" {
+			continue;
+		}
+		let attr = |name: &str| {
+			el.attrs
+				.iter()
+				.find(|attr| attr.name == name)
+				.and_then(|attr| match &attr.value {
+					AttrValue::String(value, _) => Some(value.clone()),
+					_ => None,
+				})
+		};
+		let (Some(name), Some(src)) = (attr("name"), attr("from").or_else(|| attr("src"))) else {
+			continue;
+		};
+		let mut path = base_dir.join(src);
+		if !path.exists() && path.extension().is_none() {
+			path.set_extension("wui");
+		}
+		let Ok(source) = std::fs::read_to_string(&path) else {
+			continue;
+		};
+		let component = wgui::wui::parser::Parser::new(&source).parse();
+		out.insert(name, signature(&component.nodes));
+	}
+	out
+}
+
+fn completion_items(
+	text: &str,
+	offset: usize,
+	actions: &[ActionRef],
+	components: &HashMap<String, ComponentSignature>,
+) -> Vec<CompletionItem> {
 	let mut items = Vec::new();
 	if is_in_expr(text, offset) {
 		items.extend(expr_completions());
@@ -278,9 +365,18 @@ fn completion_items(text: &str, offset: usize, actions: &[ActionRef]) -> Vec<Com
 		match tag_ctx {
 			TagContext::TagName => {
 				items.extend(tag_completions());
+				items.extend(components.keys().map(|name| CompletionItem {
+					label: name.clone(),
+					kind: Some(CompletionItemKind::CLASS),
+					detail: Some("component".to_string()),
+					..Default::default()
+				}));
 			}
 			TagContext::AttrName { tag } => {
 				items.extend(prop_completions(&tag));
+				if let Some(component) = components.get(&tag) {
+					items.extend(component_prop_completions(component));
+				}
 			}
 			TagContext::ActionValue => {
 				items.extend(action_completions(actions));
@@ -291,7 +387,11 @@ fn completion_items(text: &str, offset: usize, actions: &[ActionRef]) -> Vec<Com
 	items
 }
 
-fn hover_info(text: &str, offset: usize) -> Option<Hover> {
+fn hover_info(
+	text: &str,
+	offset: usize,
+	components: &HashMap<String, ComponentSignature>,
+) -> Option<Hover> {
 	let (start, end, word) = word_at(text, offset)?;
 	if let Some(tag_ctx) = tag_context(text, offset) {
 		match tag_ctx {
@@ -303,7 +403,11 @@ fn hover_info(text: &str, offset: usize) -> Option<Hover> {
 				});
 			}
 			TagContext::AttrName { tag } => {
-				if let Some(info) = prop_hover(&tag, &word) {
+				let info = prop_hover(&tag, &word).or_else(|| {
+					let prop = components.get(&tag)?.prop(&word)?;
+					Some(component_prop_detail(&prop.name, prop.ty, prop.required))
+				});
+				if let Some(info) = info {
 					return Some(Hover {
 						contents: HoverContents::Scalar(MarkedString::String(info)),
 						range: Some(span_to_range(text, start, end)),
@@ -323,12 +427,8 @@ fn hover_info(text: &str, offset: usize) -> Option<Hover> {
 }
 
 fn prop_hover(tag: &str, prop: &str) -> Option<String> {
-	if let Some(schema) = schema_for(tag) {
-		for def in schema.props {
-			if def.name == prop {
-				return Some(format!("{}: {}", def.name, prop_type_name(&def.kind)));
-			}
-		}
+	if let Some(def) = schema_for(tag).and_then(|schema| schema.prop(prop)) {
+		return Some(format!("{}: {}", def.name, prop_type_name(&def.kind)));
 	}
 	if let Some(prop_type) = structural_prop_type(tag, prop) {
 		return Some(format!("{}: {}", prop, prop_type));
@@ -359,10 +459,23 @@ fn structural_prop_type(tag: &str, prop: &str) -> Option<&'static str> {
 			"key" => Some("expr"),
 			_ => None,
 		},
-		"If" => match prop {
+		"If" | "ElseIf" => match prop {
 			"test" => Some("expr"),
 			_ => None,
 		},
+		"Let" => match prop {
+			"name" => Some("string"),
+			"value" => Some("expr"),
+			_ => None,
+		},
+		"Match" => match prop {
+			"value" => Some("expr"),
+			_ => None,
+		},
+		"Case" => match prop {
+			"is" => Some("string"),
+			_ => None,
+		},
 		"Scope" => match prop {
 			"name" => Some("string"),
 			_ => None,
@@ -377,6 +490,16 @@ fn structural_prop_type(tag: &str, prop: &str) -> Option<&'static str> {
 			"src" => Some("string"),
 			_ => None,
 		},
+		"Prop" => match prop {
+			"name" | "type" => Some("string"),
+			"required" => Some("bool"),
+			"default" => Some("expr"),
+			_ => None,
+		},
+		"Slot" => match prop {
+			"name" => Some("string"),
+			_ => None,
+		},
 		_ => None,
 	}
 }
@@ -396,7 +519,7 @@ fn tag_completions() -> Vec<CompletionItem> {
 fn prop_completions(tag: &str) -> Vec<CompletionItem> {
 	let mut items = Vec::new();
 	if let Some(schema) = schema_for(tag) {
-		for prop in schema.props {
+		for prop in schema.all_props() {
 			items.push(CompletionItem {
 				label: prop.name.to_string(),
 				kind: Some(CompletionItemKind::PROPERTY),
@@ -414,6 +537,28 @@ fn prop_completions(tag: &str) -> Vec<CompletionItem> {
 	items
 }
 
+fn component_prop_completions(component: &ComponentSignature) -> Vec<CompletionItem> {
+	component
+		.props
+		.iter()
+		.flatten()
+		.map(|prop| CompletionItem {
+			label: prop.name.clone(),
+			kind: Some(CompletionItemKind::PROPERTY),
+			detail: Some(component_prop_detail(&prop.name, prop.ty, prop.required)),
+			..Default::default()
+		})
+		.collect()
+}
+
+fn component_prop_detail(name: &str, ty: ValueType, required: bool) -> String {
+	if required {
+		format!("{}: {} (required)", name, type_name(ty))
+	} else {
+		format!("{}: {}", name, type_name(ty))
+	}
+}
+
 fn action_completions(actions: &[ActionRef]) -> Vec<CompletionItem> {
 	let mut items = Vec::new();
 	let mut seen = HashMap::new();
@@ -485,10 +630,9 @@ fn action_at_offset(actions: &[ActionRef], offset: usize) -> Option<String> {
 }
 
 fn is_event_prop(name: &str) -> bool {
-	matches!(
-		name,
-		"onClick" | "onTextChanged" | "onSliderChange" | "onSelect"
-	)
+	name.strip_prefix("on")
+		.and_then(|rest| rest.chars().next())
+		.is_some_and(|first| first.is_ascii_uppercase())
 }
 
 fn is_ident_char(ch: char) -> bool {
@@ -582,31 +726,32 @@ fn tag_context(text: &str, offset: usize) -> Option<TagContext> {
 }
 
 fn is_action_value(tag_slice: &str, offset_in_tag: usize) -> bool {
-	for event in ["onClick", "onTextChanged", "onSliderChange", "onSelect"] {
-		let needle = format!("{}=\"", event);
-		let Some(start_idx) = tag_slice[..offset_in_tag.min(tag_slice.len())].rfind(&needle) else {
-			continue;
-		};
-		let value_start = start_idx + needle.len();
-		let rest = &tag_slice[value_start..];
-		let Some(end_rel) = rest.find('"') else {
-			continue;
-		};
-		let value_end = value_start + end_rel;
-		if offset_in_tag >= value_start && offset_in_tag <= value_end {
-			return true;
-		}
+	let before = &tag_slice[..offset_in_tag.min(tag_slice.len())];
+	let Some(open) = before.rfind("=\"") else {
+		return false;
+	};
+	if before[open + 2..].contains('"') {
+		return false;
 	}
-	false
+	let name_start = before[..open]
+		.rfind(|ch: char| !is_ident_char(ch))
+		.map(|idx| idx + 1)
+		.unwrap_or(0);
+	is_event_prop(&before[name_start..open])
 }
 
 fn structural_props(tag: &str) -> &'static [&'static str] {
 	match tag {
 		"For" => &["each", "itemAs", "indexAs", "key"],
-		"If" => &["test"],
+		"If" | "ElseIf" => &["test"],
+		"Let" => &["name", "value"],
+		"Match" => &["value"],
+		"Case" => &["is"],
 		"Scope" => &["name"],
 		"Page" => &["route", "title", "state"],
 		"Import" => &["src"],
+		"Prop" => &["name", "type", "required", "default"],
+		"Slot" => &["name"],
 		_ => &[],
 	}
 }
@@ -621,12 +766,23 @@ fn all_tags() -> &'static [&'static str] {
 		"Checkbox",
 		"Slider",
 		"Image",
+		"Shortcut",
 		"For",
 		"If",
+		"ElseIf",
 		"Else",
+		"Let",
+		"Match",
+		"Case",
 		"Scope",
+		"ErrorBoundary",
+		"Fallback",
 		"Page",
 		"Import",
+		"Props",
+		"Prop",
+		"Slot",
+		"Style",
 	]
 }
 